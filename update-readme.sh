@@ -41,6 +41,16 @@ cargo run -- --help >> README.md
 cat << EOF >> README.md
 \`\`\`
 
+Invoices are created with the \`generate\` command, which supports the following parameters:
+
+\`\`\`
+EOF
+
+cargo run -- generate --help >> README.md
+
+cat << EOF >> README.md
+\`\`\`
+
 The invoice hours CSV file (also available at [xrechnung_cmd/examples/invoice-lines.csv](xrechnung_cmd/examples/invoice-lines.csv)) could look like this then:
 
 \`\`\`csv
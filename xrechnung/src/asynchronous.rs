@@ -0,0 +1,303 @@
+//! Asynchronous variants of reading the inputs and writing the invoices (with the feature `async`), for embedding the
+//! crate in asynchronous services (e.g., web services running on tokio) without blocking the threads of the executor
+//! on file I/O.
+//!
+//! The file operations run on a small pool of four threads of the crate, which wake the waiting tasks once the
+//! operations are finished. So the futures work with any executor and the crate does not depend on a particular
+//! runtime.
+//!
+//! Errors are returned as `Box<dyn Error + Send + Sync>`, so that they can be passed between the tasks of the executor.
+//! I/O errors are returned as they are, other errors keep their messages and sources.
+//!
+//! # Example
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! let config = xrechnung::asynchronous::load("config.toml", "Client Company").await?;
+//! let bill = xrechnung::data::Bill::new(
+//!     "2025-0001".to_string(),
+//!     chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+//!     None,
+//!     &config,
+//! );
+//! // the errors of the synchronous functions cannot be sent between threads, so only their messages are passed on
+//! let (xml_root, _) = xrechnung::create(config.supplier, config.buyer, bill, vec![])
+//!     .map_err(|error| error.to_string())?;
+//! xrechnung::asynchronous::write("invoice.xml", &xml_root).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, mpsc};
+use std::task::{Context, Poll, Waker};
+
+use crate::XmlElement;
+use crate::attachments::Attachment;
+use crate::config::Config;
+
+/// The number of threads that run the blocking operations.
+const WORKER_THREADS: usize = 4;
+
+/// A blocking operation that is run by one of the threads of the pool.
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The error of an asynchronous operation, which can be sent between threads.
+type AsyncError = Box<dyn Error + Send + Sync>;
+
+/// An error with the message and the sources of an error that cannot be sent between threads.
+#[derive(Debug)]
+struct OperationError {
+    message: String,
+    source: Option<Box<OperationError>>,
+}
+
+impl OperationError {
+    /// Returns an error with the message and the sources of the given error.
+    fn from_error(error: &dyn Error) -> Self {
+        OperationError {
+            message: error.to_string(),
+            source: error
+                .source()
+                .map(|source| Box::new(OperationError::from_error(source))),
+        }
+    }
+}
+
+impl fmt::Display for OperationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for OperationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+/// Converts the given error into one that can be sent between threads: I/O errors are kept as they are, other errors
+/// are replaced by errors with the same messages and sources.
+fn sendable(error: Box<dyn Error>) -> AsyncError {
+    match error.downcast::<std::io::Error>() {
+        Ok(error) => error,
+        Err(error) => Box::new(OperationError::from_error(error.as_ref())),
+    }
+}
+
+/// Returns the queue of the blocking operations, starting the threads of the pool that run them on first use.
+fn queue() -> &'static mpsc::Sender<Job> {
+    static QUEUE: OnceLock<mpsc::Sender<Job>> = OnceLock::new();
+    QUEUE.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..WORKER_THREADS {
+            let receiver = Arc::clone(&receiver);
+            // operations are run on the calling thread, if no thread of the pool could be started
+            let _ = std::thread::Builder::new()
+                .name(format!("xrechnung-io-{index}"))
+                .spawn(move || {
+                    loop {
+                        let job = receiver
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .recv();
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => break,
+                        }
+                    }
+                });
+        }
+        sender
+    })
+}
+
+/// The shared state of a blocking operation and the future waiting for it.
+struct State<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// A future for the result of a blocking operation that runs on a thread of the pool.
+struct Blocking<T> {
+    state: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Runs the given blocking operation on a thread of the pool and returns a future for its result.
+fn blocking<T: Send + 'static>(
+    operation: impl FnOnce() -> Result<T, AsyncError> + Send + 'static,
+) -> Blocking<Result<T, AsyncError>> {
+    let state = Arc::new(Mutex::new(State {
+        result: None,
+        waker: None,
+    }));
+    let shared = Arc::clone(&state);
+    let job: Job = Box::new(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(operation))
+            .unwrap_or_else(|_| Err("The file operation failed unexpectedly.".into()));
+        let mut state = shared.lock().unwrap_or_else(PoisonError::into_inner);
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    });
+    if let Err(mpsc::SendError(job)) = queue().send(job) {
+        job();
+    }
+    Blocking { state }
+}
+
+/// Loads the configuration for the given buyer from the given file, like [`config::load`][crate::config::load].
+pub async fn load(config_file: &str, buyer: &str) -> Result<Config, AsyncError> {
+    let (config_file, buyer) = (config_file.to_string(), buyer.to_string());
+    blocking(move || crate::config::load(&config_file, &buyer).map_err(sendable)).await
+}
+
+/// Reads the attachment from the file with the given name, like [`Attachment::from_file`].
+pub async fn read_attachment(
+    file_name: &str,
+    description: Option<&str>,
+) -> Result<Attachment, AsyncError> {
+    let (file_name, description) = (file_name.to_string(), description.map(str::to_string));
+    blocking(move || Attachment::from_file(&file_name, description.as_deref()).map_err(sendable))
+        .await
+}
+
+/// Writes an XRechnung XML structure to the file with the given name, like [`write`][crate::write]. The document is
+/// created right away, only writing the file is done on a thread of its own.
+pub async fn write(file_name: &str, root_element: &XmlElement) -> Result<(), AsyncError> {
+    let content = crate::to_xml_string(root_element).map_err(sendable)?;
+    let file_name = file_name.to_string();
+    blocking(move || {
+        crate::xml_writer::write_atomically(&file_name, |file| {
+            Ok(file.write_all(content.as_bytes())?)
+        })
+        .map_err(sendable)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    use crate::XmlElement;
+
+    /// Runs the given future to completion on the current thread.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct Unparker(std::thread::Thread);
+        impl Wake for Unparker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = Waker::from(Arc::new(Unparker(std::thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+            std::thread::park();
+        }
+    }
+
+    #[test]
+    fn test_load() {
+        // the futures can be moved between the threads of an executor
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&super::load("examples/config.toml", "acme"));
+
+        let config = block_on(super::load("examples/config.toml", "acme")).unwrap();
+        assert_eq!(config.buyer.name, "Client Company");
+        assert!(block_on(super::load("examples/config.toml", "nobody")).is_err());
+        assert!(block_on(super::read_attachment("missing.pdf", None)).is_err());
+
+        // many operations share the threads of the pool
+        let results: Vec<_> = (0..20)
+            .map(|_| super::load("examples/config.toml", "acme"))
+            .map(block_on)
+            .collect();
+        assert!(results.iter().all(Result::is_ok));
+    }
+
+    #[test]
+    fn test_errors() {
+        // the errors can be sent between threads and keep their type or at least their messages and sources
+        fn assert_send_sync<T: Send + Sync + ?Sized>(_: &T) {}
+        let error = block_on(super::load("missing.toml", "acme")).unwrap_err();
+        assert_send_sync(&error);
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let error = super::sendable(Box::new(io_error));
+        let io_error = error.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(io_error.kind(), std::io::ErrorKind::NotFound);
+
+        #[derive(Debug)]
+        struct Outer(std::io::Error);
+        impl std::fmt::Display for Outer {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "outer")
+            }
+        }
+        impl std::error::Error for Outer {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+        let error = super::sendable(Box::new(Outer(std::io::Error::other("inner"))));
+        assert_eq!(error.to_string(), "outer");
+        assert_eq!(error.source().unwrap().to_string(), "inner");
+    }
+
+    #[test]
+    fn test_write() {
+        let directory =
+            std::env::temp_dir().join(format!("xrechnung-async-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let file_name = directory.join("invoice.xml").to_string_lossy().into_owned();
+        let root = XmlElement::new(
+            "Invoice",
+            None,
+            Some(vec![XmlElement::new_leaf("ID", None, "2025-0001")]),
+        );
+
+        block_on(super::write(&file_name, &root)).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_name).unwrap(),
+            crate::to_xml_string(&root).unwrap()
+        );
+
+        let missing = directory.join("missing").join("invoice.xml");
+        let error = block_on(super::write(&missing.to_string_lossy(), &root)).unwrap_err();
+        let io_error = error.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(io_error.kind(), std::io::ErrorKind::NotFound);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
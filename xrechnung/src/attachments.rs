@@ -0,0 +1,225 @@
+//! Attachments of invoices (additional supporting documents, BG-24), e.g., the timesheet or the acceptance report for
+//! the billed services. The files are embedded into the invoice as `cac:AdditionalDocumentReference` elements with
+//! their Base64 encoded content.
+//!
+//! XRechnung only allows attachments of the following types, which are recognized by the extension of the files: PDF
+//! (`.pdf`), PNG (`.png`), JPEG (`.jpg`, `.jpeg`), CSV (`.csv`), Excel (`.xlsx`) and OpenDocument spreadsheets
+//! (`.ods`).
+//!
+//! # Example
+//! ```
+//! let attachment = xrechnung::attachments::Attachment {
+//!     id: "timesheet.csv".to_string(),
+//!     description: Some("Timesheet January 2025".to_string()),
+//!     mime_code: "text/csv".to_string(),
+//!     file_name: "timesheet.csv".to_string(),
+//!     content: b"date,hours\n2025-01-02,7.5\n".to_vec(),
+//! };
+//! let mut invoice = xrechnung::XmlElement::new("ubl:Invoice", None, None);
+//! xrechnung::attachments::embed(&mut invoice, &[attachment]);
+//! assert_eq!(invoice.children()[0].name(), "cac:AdditionalDocumentReference");
+//! ```
+
+use std::path::Path;
+
+use crate::XmlElement;
+use crate::xml_writer::XmlContentItem;
+
+/// The file extensions of the attachments allowed by XRechnung and their MIME codes.
+const MIME_CODES: [(&str, &str); 7] = [
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("csv", "text/csv"),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    ("ods", "application/vnd.oasis.opendocument.spreadsheet"),
+];
+
+/// The elements of an invoice that follow the additional document references in UBL.
+const FOLLOWING_ELEMENTS: [&str; 3] = [
+    "cac:ProjectReference",
+    "cac:AccountingSupplierParty",
+    "cac:AccountingCustomerParty",
+];
+
+/// A document that is attached to an invoice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    /// The identifier of the document (BT-122), e.g., the file name or the number of the timesheet.
+    pub id: String,
+
+    /// The description of the document (BT-123), if any.
+    pub description: Option<String>,
+
+    /// The MIME code of the document, e.g., "application/pdf".
+    pub mime_code: String,
+
+    /// The file name of the document.
+    pub file_name: String,
+
+    /// The content of the document.
+    pub content: Vec<u8>,
+}
+
+impl Attachment {
+    /// Reads the attachment from the file with the given name. The file name (without directories) is the identifier
+    /// of the document.
+    pub fn from_file(
+        file_name: &str,
+        description: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let name = Path::new(file_name)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| format!("The attachment '{file_name}' is no file."))?;
+        let mime_code = mime_code(&name).ok_or_else(|| {
+            format!(
+                "The attachment '{file_name}' has no file type allowed in XRechnung (PDF, PNG, JPEG, CSV, XLSX or ODS)."
+            )
+        })?;
+        let content = std::fs::read(file_name)
+            .map_err(|e| format!("Could not read the attachment '{file_name}': {e}"))?;
+
+        Ok(Attachment {
+            id: name.clone(),
+            description: description.map(str::to_string),
+            mime_code: mime_code.to_string(),
+            file_name: name,
+            content,
+        })
+    }
+
+    /// Returns the `cac:AdditionalDocumentReference` element for the attachment.
+    fn element(&self) -> XmlElement {
+        let mut reference = XmlElement::new(
+            "cac:AdditionalDocumentReference",
+            None,
+            Some(vec![XmlElement::new_leaf("cbc:ID", None, &self.id)]),
+        );
+        if let Some(description) = &self.description {
+            reference.push_child(XmlElement::new_leaf(
+                "cbc:DocumentDescription",
+                None,
+                description,
+            ));
+        }
+        reference.push_child(XmlElement::new(
+            "cac:Attachment",
+            None,
+            Some(vec![XmlElement::new_leaf(
+                "cbc:EmbeddedDocumentBinaryObject",
+                Some(vec![
+                    ("mimeCode", &self.mime_code),
+                    ("filename", &self.file_name),
+                ]),
+                &crate::base64::encode(&self.content),
+            )]),
+        ));
+        reference
+    }
+}
+
+/// Returns the MIME code for the file with the given name, if it is a type of attachment allowed by XRechnung.
+pub fn mime_code(file_name: &str) -> Option<&'static str> {
+    let extension = Path::new(file_name)
+        .extension()?
+        .to_string_lossy()
+        .to_lowercase();
+    MIME_CODES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map(|(_, mime_code)| *mime_code)
+}
+
+/// Embeds the attachments into the given invoice as created by the [`create`][crate::create] function, after the
+/// document references that are already in the invoice.
+pub fn embed(invoice: &mut XmlElement, attachments: &[Attachment]) {
+    if invoice.text().is_some() {
+        return;
+    }
+    let children = invoice.children();
+    let position = children
+        .iter()
+        .position(|child| FOLLOWING_ELEMENTS.contains(&child.name()))
+        .unwrap_or(children.len());
+    for (index, attachment) in attachments.iter().enumerate() {
+        invoice.insert_item(
+            position + index,
+            XmlContentItem::Element(attachment.element()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Attachment;
+    use crate::XmlElement;
+
+    #[test]
+    fn test_mime_code() {
+        assert_eq!(super::mime_code("timesheet.PDF"), Some("application/pdf"));
+        assert_eq!(super::mime_code("photo.jpeg"), Some("image/jpeg"));
+        assert_eq!(super::mime_code("notes.txt"), None);
+        assert_eq!(super::mime_code("README"), None);
+    }
+
+    #[test]
+    fn test_embed() {
+        let mut invoice = XmlElement::new(
+            "ubl:Invoice",
+            None,
+            Some(vec![
+                XmlElement::new_leaf("cbc:ID", None, "2025-0001"),
+                XmlElement::new("cac:AccountingSupplierParty", None, None),
+            ]),
+        );
+        let attachment = |id: &str, description: Option<&str>| Attachment {
+            id: id.to_string(),
+            description: description.map(str::to_string),
+            mime_code: "text/csv".to_string(),
+            file_name: format!("{id}.csv"),
+            content: b"a,b".to_vec(),
+        };
+        super::embed(
+            &mut invoice,
+            &[
+                attachment("timesheet", Some("Timesheet")),
+                attachment("report", None),
+            ],
+        );
+
+        let names: Vec<&str> = invoice.children().iter().map(XmlElement::name).collect();
+        assert_eq!(
+            names,
+            [
+                "cbc:ID",
+                "cac:AdditionalDocumentReference",
+                "cac:AdditionalDocumentReference",
+                "cac:AccountingSupplierParty"
+            ]
+        );
+
+        let mut xml = quick_xml::Writer::new(Vec::new());
+        invoice.children()[1].write(&mut xml).unwrap();
+        assert_eq!(
+            String::from_utf8(xml.into_inner()).unwrap(),
+            "<cac:AdditionalDocumentReference><cbc:ID>timesheet</cbc:ID><cbc:DocumentDescription>Timesheet\
+             </cbc:DocumentDescription><cac:Attachment><cbc:EmbeddedDocumentBinaryObject mimeCode=\"text/csv\" \
+             filename=\"timesheet.csv\">YSxi</cbc:EmbeddedDocumentBinaryObject></cac:Attachment>\
+             </cac:AdditionalDocumentReference>"
+        );
+    }
+
+    #[test]
+    fn test_from_file() {
+        let attachment = Attachment::from_file("examples/config.toml", None)
+            .unwrap_err()
+            .to_string();
+        assert!(attachment.contains("no file type allowed in XRechnung"));
+        assert!(Attachment::from_file("missing.pdf", None).is_err());
+    }
+}
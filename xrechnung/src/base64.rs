@@ -0,0 +1,40 @@
+//! Base64 encoding (RFC 4648) of binary data, e.g., for the attachments of invoices and emails.
+
+/// The alphabet of the standard encoding.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Returns the given data encoded as Base64 with padding, without line breaks.
+pub fn encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => encoded.push(ALPHABET[(bits >> (18 - 6 * index) & 0x3f) as usize] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_encode() {
+        // the test vectors of RFC 4648
+        assert_eq!(super::encode(b""), "");
+        assert_eq!(super::encode(b"f"), "Zg==");
+        assert_eq!(super::encode(b"fo"), "Zm8=");
+        assert_eq!(super::encode(b"foo"), "Zm9v");
+        assert_eq!(super::encode(b"foob"), "Zm9vYg==");
+        assert_eq!(super::encode(b"fooba"), "Zm9vYmE=");
+        assert_eq!(super::encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(super::encode(&[0xfb, 0xff]), "+/8=");
+    }
+}
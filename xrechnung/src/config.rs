@@ -17,13 +17,40 @@
 #![doc=include_str!("../examples/config.toml")]
 //! ```
 //!
-//! The file must contain one supplier and at least one buyer.
+//! The file must contain one supplier and at least one buyer. Instead of TOML, the configuration can also be given in
+//! YAML or JSON format with the same structure, as determined by the extension of the file (`.yaml`, `.yml` or
+//! `.json`).
+//!
+//! Parts of the configuration can be kept in separate files, which are included with a list of paths relative to the
+//! including file, e.g., `include = ["buyers/*.toml"]`. This way, the supplier data can be kept in a shared base file
+//! and every buyer in a file of its own. Tables of the included files are merged, lists (like the buyers) are
+//! appended, and the values of the including file take precedence over the ones of the included files.
+//!
+//! With the feature `encrypted`, configuration files can also be encrypted with age (e.g., `config.toml.age`) or sops
+//! (e.g., `config.sops.yaml`), see the `decrypt` module for details.
+//!
+//! String values can refer to environment variables with placeholders like `${SUPPLIER_IBAN}`, so that sensitive data
+//! does not need to be stored in the configuration file itself.
 
-use serde::Deserialize;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::currency::Currency;
+#[cfg(feature = "encrypted")]
+use crate::decrypt;
+use crate::identifiers;
+use crate::json;
+use crate::lines::Rounding;
+use crate::money;
+use crate::numbering::Numbering;
+use crate::yaml;
 
 /// Address data for the supplier and buyer.
-#[derive(Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Address {
     /// The first line of the address. It usually contains the street name and number or a P.O. box.
     pub address_line: String,
@@ -39,8 +66,20 @@ pub struct Address {
     pub country_code: String,
 }
 
+impl Address {
+    /// Creates an address from the given address line, city, post code and country code.
+    pub fn new(address_line: &str, city: &str, post_code: &str, country_code: &str) -> Self {
+        Address {
+            address_line: address_line.to_string(),
+            city: city.to_string(),
+            post_code: post_code.to_string(),
+            country_code: country_code.to_string(),
+        }
+    }
+}
+
 /// Supplier data (name, tax data, contact, bank account) for the invoice.
-#[derive(Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Supplier {
     /// The company name of the supplier.
     pub name: String,
@@ -65,14 +104,147 @@ pub struct Supplier {
 
     /// The BIC (Bank Identifier Code) of the supplier. Matching the bank account that is determined by the IBAN field.
     pub bic: String,
+
+    /// Further bank accounts of the supplier by name (optional), e.g., for invoices in other currencies. An account is
+    /// used instead of the one given by the IBAN and BIC fields, if it is selected for the buyer or the invoice, or if
+    /// it is the first one with the currency of the invoice.
+    #[serde(default)]
+    pub accounts: BTreeMap<String, BankAccount>,
+
+    /// The ways in which the invoices can be paid (optional). A transfer to the bank account of the supplier is used,
+    /// if not given.
+    #[serde(default)]
+    pub payment_means: Vec<PaymentMeans>,
+
+    /// The managing director(s) of the supplier's company (optional), e.g., "Hans Muster".
+    pub managing_director: Option<String>,
+
+    /// The court at which the supplier's company is registered (optional), e.g., "Amtsgericht Example City".
+    pub register_court: Option<String>,
+
+    /// The number of the supplier's company in the commercial register (optional), e.g., "HRB 12345".
+    pub register_number: Option<String>,
+
+    /// The website of the supplier (optional), e.g., "https://supplier.example.com".
+    pub website: Option<String>,
+}
+
+impl Supplier {
+    /// Creates a supplier with the given mandatory data and without any of the optional data, which can be set with
+    /// the struct update syntax, e.g., `Supplier { website: Some(...), ..Supplier::new(...) }`.
+    pub fn new(
+        name: &str,
+        tax_identification: &str,
+        address: Address,
+        phone: &str,
+        email: &str,
+        iban: &str,
+        bic: &str,
+    ) -> Self {
+        Supplier {
+            name: name.to_string(),
+            tax_identification: tax_identification.to_string(),
+            address,
+            phone: phone.to_string(),
+            email: email.to_string(),
+            iban: iban.to_string(),
+            bic: bic.to_string(),
+            accounts: BTreeMap::new(),
+            payment_means: Vec::new(),
+            managing_director: None,
+            register_court: None,
+            register_number: None,
+            website: None,
+        }
+    }
+}
+
+/// A way in which the invoices can be paid, as given in the configuration with a `type` field, e.g., `type =
+/// "sepa_instant"`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PaymentMeans {
+    /// Transfer to the bank account of the supplier (payment means code 42).
+    BankTransfer,
+
+    /// SEPA credit transfer to the bank account of the supplier (payment means code 58).
+    SepaCreditTransfer,
+
+    /// SEPA instant credit transfer to the bank account of the supplier (payment means code 58, as there is no code
+    /// of its own, with the text "SEPA Instant Credit Transfer").
+    SepaInstant,
+
+    /// Payment by bank card (payment means code 48) or credit card (payment means code 54).
+    Card {
+        /// Whether the card is a credit card.
+        #[serde(default)]
+        credit: bool,
+
+        /// The last four to six digits of the number of the card (the complete number must not be given).
+        number: String,
+
+        /// The network of the card, e.g., "VISA" or "mastercard".
+        network: String,
+
+        /// The name of the holder of the card (optional).
+        holder: Option<String>,
+    },
+
+    /// Payment via an online payment service (payment means code 68), e.g., PayPal.
+    Online {
+        /// The name of the payment service, e.g., "PayPal".
+        service: String,
+
+        /// The account of the supplier at the payment service, e.g., an email address.
+        account: String,
+    },
+}
+
+/// A further bank account of the supplier.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BankAccount {
+    /// The IBAN (International Bank Account Number) of the bank account.
+    pub iban: String,
+
+    /// The BIC (Bank Identifier Code) of the bank account.
+    pub bic: String,
+
+    /// The currency of the bank account as ISO 4217 code, e.g., "USD" (optional).
+    pub currency: Option<Currency>,
+}
+
+/// A named rate for invoice lines, e.g., for a specific kind of service.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Rate {
+    /// The hourly rate for invoice lines with the name of this rate.
+    pub hourly_rate: f32,
+
+    /// The name that is shown on the invoice for lines with the name of this rate (optional). The name of the invoice
+    /// line is kept, if not given.
+    pub name: Option<String>,
+}
+
+/// Settings for the invoice lines of a project of a buyer, e.g., the position of the buyer's order that the project is
+/// billed on.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Project {
+    /// The line number of the buyer's purchase order for the invoice lines of the project (optional).
+    pub order_line_id: Option<String>,
+
+    /// The accounting cost code (e.g., cost center) of the buyer for the invoice lines of the project (optional).
+    pub accounting_cost: Option<String>,
 }
 
 /// Buyer data (name, tax data, contact, reference number) for the invoice.
-#[derive(Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Buyer {
     /// The company name of the buyer.
     pub name: String,
 
+    /// Short names that can be used instead of the company name to select the buyer, e.g., "acme" (optional).
+    #[serde(default)]
+    pub alias: Vec<String>,
+
     /// The tax identification (e.g., vat number) of the buyer. This is a unique identifier assigned to the buyer by
     /// the tax office.
     pub tax_identification: String,
@@ -87,80 +259,1246 @@ pub struct Buyer {
     // Can be order number, internal project number or contact of buyer or even N/A.
     pub reference: String,
 
+    /// The email address that invoices are sent to by email (optional), e.g., the address of the accounts payable
+    /// department. Defaults to the email address of the buyer.
+    pub invoice_email: Option<String>,
+
+    /// The Leitweg-ID of a public-sector buyer in Germany (e.g., "04011000-1234512345-06"), which is used as buyer
+    /// reference instead of the `reference` field for routing the invoice to the right office (optional).
+    pub leitweg_id: Option<String>,
+
+    /// Whether the buyer is a German public-sector authority (B2G), for which a valid Leitweg-ID is mandatory.
+    #[serde(default)]
+    pub public_sector: bool,
+
+    /// The identifier of the contact of the buyer in sevDesk (optional), needed for exporting invoices to sevDesk.
+    pub sevdesk_contact: Option<String>,
+
+    /// The channel via which invoices are submitted to the buyer (optional). The invoicing portals of the German
+    /// federal administration (ZRE and OZG-RE) accept invoices via Peppol with the Leitweg-ID as participant identifier
+    /// (endpoint scheme "0204") and via their email gateways (as `invoice_email`). Defaults to Peppol.
+    pub submission: Option<Submission>,
+
+    /// The scheme of the electronic address of the buyer, to which the invoice is delivered, as code of the EAS code
+    /// list (optional), e.g., "EM" for an email address, "0204" for a Leitweg-ID or "0088" for a GLN as Peppol ID. The
+    /// email address of the buyer is used, if neither the scheme nor the endpoint are given.
+    pub endpoint_scheme: Option<String>,
+
+    /// The electronic address of the buyer in the endpoint scheme (optional). It defaults to the email address of the
+    /// buyer for the scheme "EM" and to the Leitweg-ID of the buyer for the scheme "0204".
+    pub endpoint: Option<String>,
+
+    /// Notes that are added to every invoice for this buyer (optional), e.g., a reference to the framework agreement.
+    /// The notes can contain the same placeholders as the invoice lines.
+    #[serde(default)]
+    pub notes: Vec<String>,
+
+    /// The number of the buyer's purchase order that the invoices for this buyer refer to (optional).
+    pub order_reference: Option<String>,
+
+    /// The identifier of the contract with the buyer that the invoices for this buyer refer to (optional).
+    pub contract_reference: Option<String>,
+
+    /// The accounting cost code (e.g., cost center) of the buyer that the invoices for this buyer should be booked on
+    /// (optional).
+    pub accounting_cost: Option<String>,
+
     /// After how many days invoices for this buyer are due. This is used to calculated the due date of the invoice
     /// based on the issue date.
     pub due_after_days: i16,
+
+    /// Whether due dates that fall on a weekend or a public holiday are moved to the next business day.
+    #[serde(default)]
+    pub due_on_business_day: bool,
+
+    /// The text of the payment terms for this buyer, e.g., "Zahlbar innerhalb von 14 Tagen ohne Abzug" (optional).
+    /// The text can contain the same placeholders as the invoice lines, e.g., "Payable until {due_date}".
+    pub payment_terms: Option<String>,
+
+    /// The policy for rounding the invoiced quantities to billing increments, as agreed with the buyer (optional).
+    pub rounding: Option<Rounding>,
+
+    /// The minimum billable quantity in hours per invoice line (e.g., a minimum of one hour per call-out), as agreed
+    /// with the buyer (optional).
+    pub minimum_quantity: Option<f32>,
+
+    /// Whether lines that were raised to the minimum billable quantity get a note about the adjustment.
+    #[serde(default)]
+    pub note_minimum_quantity: bool,
+
+    /// The name of the bank account of the supplier for the invoices of this buyer (optional), as given in the
+    /// accounts of the supplier.
+    pub account: Option<String>,
+
+    /// The hourly rate for invoice lines of this buyer that do not specify a rate (optional). Overrides the global
+    /// default hourly rate.
+    pub default_hourly_rate: Option<f32>,
+
+    /// The currency of the invoices for this buyer as ISO 4217 code, e.g., "CHF" (optional). Overrides the global
+    /// currency.
+    pub currency: Option<Currency>,
+
+    /// The language of the invoices for this buyer as ISO 639-1 code, e.g., "de" (optional). It determines the
+    /// language of the texts that are generated for the invoice, like the month names of placeholders. English is
+    /// used, if not given or not supported.
+    pub language: Option<String>,
+
+    /// Translations of the names of invoice lines into the language of the buyer (optional), e.g., `Development =
+    /// "Entwicklung"`. Invoice lines whose name matches a translation are shown with the translated name.
+    #[serde(default)]
+    pub translations: BTreeMap<String, String>,
+
+    /// Named rates for this buyer (optional). Invoice lines whose name matches the name of a rate get its hourly rate
+    /// (if they do not specify a rate) and its display name.
+    #[serde(default)]
+    pub rates: BTreeMap<String, Rate>,
+
+    /// Settings per project for this buyer (optional). Invoice lines of a project get its references, if they do not
+    /// specify them.
+    #[serde(default)]
+    pub projects: BTreeMap<String, Project>,
+}
+
+impl Buyer {
+    /// Creates a buyer with the given mandatory data and without any of the optional data (like the configuration
+    /// file does for a buyer that only has the mandatory fields), which can be set with the struct update syntax.
+    pub fn new(
+        name: &str,
+        tax_identification: &str,
+        address: Address,
+        email: &str,
+        reference: &str,
+        due_after_days: i16,
+    ) -> Self {
+        Buyer {
+            name: name.to_string(),
+            alias: Vec::new(),
+            tax_identification: tax_identification.to_string(),
+            address,
+            email: email.to_string(),
+            reference: reference.to_string(),
+            invoice_email: None,
+            leitweg_id: None,
+            public_sector: false,
+            sevdesk_contact: None,
+            submission: None,
+            endpoint_scheme: None,
+            endpoint: None,
+            notes: Vec::new(),
+            order_reference: None,
+            contract_reference: None,
+            accounting_cost: None,
+            due_after_days,
+            due_on_business_day: false,
+            payment_terms: None,
+            rounding: None,
+            minimum_quantity: None,
+            note_minimum_quantity: false,
+            account: None,
+            default_hourly_rate: None,
+            currency: None,
+            language: None,
+            translations: BTreeMap::new(),
+            rates: BTreeMap::new(),
+            projects: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the scheme and the value of the electronic address of the buyer, to which the invoice is delivered.
+    ///
+    /// The function returns an error in case that the scheme is no code of the EAS code list, or if no endpoint is
+    /// given for a scheme without default endpoint.
+    pub fn endpoint(&self) -> Result<(&str, &str), String> {
+        let scheme = self.endpoint_scheme.as_deref().unwrap_or("EM");
+        if scheme != "EM" && !(scheme.len() == 4 && scheme.chars().all(|c| c.is_ascii_digit())) {
+            return Err(format!(
+                "the endpoint scheme '{scheme}' is no code of the EAS code list (like 'EM' or '0204')"
+            ));
+        }
+
+        match (scheme, &self.endpoint, &self.leitweg_id) {
+            (_, Some(endpoint), _) => Ok((scheme, endpoint)),
+            ("EM", None, _) => Ok((scheme, &self.email)),
+            ("0204", None, Some(leitweg_id)) => Ok((scheme, leitweg_id)),
+            _ => Err(format!(
+                "an endpoint is needed for the endpoint scheme '{scheme}'"
+            )),
+        }
+    }
+}
+
+/// The channel via which invoices are submitted to a buyer.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Submission {
+    /// Via the Peppol network, to the electronic address of the buyer.
+    Peppol,
+
+    /// By email, to the invoice email address of the buyer.
+    Email,
 }
 
 /// The complete configuration as deserialized from the configuration file.
-/// This includes all available buyers and is reduced to the Config struct before returned to the caller.
-#[derive(Deserialize)]
-struct CompleteConfig {
+/// This includes all available buyers and is reduced to the Config struct by [`load`] for creating an invoice.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CompleteConfig {
     /// The currency used for the invoice, e.g., "EUR", "USD", etc.
-    pub currency: String,
+    pub currency: Currency,
 
     /// The VAT percentage applied to the invoice total.
     pub vat_percent: f32,
 
+    /// The way in which the amounts of the invoices are rounded to cents (optional), "half_up" (the default) or
+    /// "half_even".
+    #[serde(default)]
+    pub amount_rounding: money::Rounding,
+
+    /// The hourly rate for invoice lines that do not specify a rate (optional).
+    pub default_hourly_rate: Option<f32>,
+
+    /// The public holidays in ISO 8601 format (YYYY-MM-DD), which are no business days for due dates (optional).
+    #[serde(default)]
+    pub holidays: Vec<String>,
+
     /// The supplier data for the invoice.
     pub supplier: Supplier,
 
     /// A list of buyers for the invoice creation. Only one will be used for any invoice.
     pub buyer: Vec<Buyer>,
+
+    /// The scheme for the automatic invoice numbers (optional).
+    pub numbering: Option<Numbering>,
+
+    /// The mail server for sending invoices by email (optional).
+    pub smtp: Option<Smtp>,
+
+    /// The Peppol access point for submitting invoices (optional).
+    pub peppol: Option<Peppol>,
+
+    /// The settings for exporting invoices to sevDesk (optional).
+    pub sevdesk: Option<Sevdesk>,
+}
+
+/// The settings for creating the invoices in sevDesk, whose contacts are given per buyer (`sevdesk_contact`).
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Sevdesk {
+    /// The identifier of the sevDesk user that is the contact person of the supplier for the invoices.
+    pub contact_person: String,
+
+    /// The identifier of the sevDesk unit of the invoice positions (optional). Defaults to "9" for hours.
+    pub unity: Option<String>,
+}
+
+/// The REST API of a Peppol access point, via which invoices are submitted to the Peppol network.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Peppol {
+    /// The URL of the API endpoint of the access point to which the documents are posted, e.g.,
+    /// "https://ap.example.com/api/v1/documents".
+    pub url: String,
+
+    /// The token for the authentication at the access point (optional), best given as environment variable
+    /// placeholder like `${PEPPOL_TOKEN}`.
+    pub token: Option<String>,
+
+    /// The Peppol participant identifier of the supplier as `<scheme>:<identifier>`, e.g., "9930:DE123456789".
+    pub sender: String,
+}
+
+/// The mail server and the texts for sending invoices by email.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Smtp {
+    /// The URL of the mail server, e.g., "smtps://mail.example.com" or "smtp://mail.example.com:587" (which requires
+    /// STARTTLS).
+    pub url: String,
+
+    /// The user name for logging in to the mail server (optional).
+    pub user: Option<String>,
+
+    /// The password for logging in to the mail server (optional), best given as environment variable placeholder like
+    /// `${SMTP_PASSWORD}`.
+    pub password: Option<String>,
+
+    /// The sender address of the emails (optional). Defaults to the email address of the supplier.
+    pub from: Option<String>,
+
+    /// The subject of the emails (optional), with the placeholders `{invoice_number}`, `{issue_date}`, `{due_date}`,
+    /// `{amount}`, `{buyer}` and `{supplier}`.
+    pub subject: Option<String>,
+
+    /// The text of the emails (optional), with the same placeholders as the subject.
+    pub body: Option<String>,
 }
 
 /// The reduced configuration for the invoice creation that in contrast to the CompleteConfig struct only contains the
 /// about the one buyer for whom the invoice should be created.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Config {
     /// The currency used for the invoice, e.g., "EUR", "USD", etc.
-    pub currency: String,
+    pub currency: Currency,
 
     /// The VAT percentage applied to the invoice total.
     pub vat_percent: f32,
 
+    /// The way in which the amounts of the invoices are rounded to cents.
+    pub amount_rounding: money::Rounding,
+
+    /// The hourly rate for invoice lines that do not specify a rate, either as configured for the buyer or globally.
+    pub default_hourly_rate: Option<f32>,
+
+    /// The public holidays, which are no business days for due dates.
+    pub holidays: Vec<NaiveDate>,
+
     /// The supplier data for the invoice.
     pub supplier: Supplier,
 
     /// The buyer data for the invoice. Selected from all buyers in the CompleteConfig struct.
     pub buyer: Buyer,
+
+    /// The scheme for the automatic invoice numbers, if configured.
+    pub numbering: Option<Numbering>,
+
+    /// The mail server for sending invoices by email, if configured.
+    pub smtp: Option<Smtp>,
+
+    /// The Peppol access point for submitting invoices, if configured.
+    pub peppol: Option<Peppol>,
+
+    /// The settings for exporting invoices to sevDesk, if configured.
+    pub sevdesk: Option<Sevdesk>,
+}
+
+/// The file names that are searched for in the default locations of the configuration file.
+const DEFAULT_FILE_NAMES: [&str; 4] = ["config.toml", "config.yaml", "config.yml", "config.json"];
+
+/// Returns the configuration file in the first of the default locations that contains one, for the case that no
+/// configuration file is given explicitly. The locations are searched in the following order:
+///
+/// 1. `$XDG_CONFIG_HOME/xrechnung/` (if the environment variable is set)
+/// 2. `~/.config/xrechnung/`
+/// 3. the current directory, where the file needs to be called `xrechnung.toml` (or `.yaml`, `.yml`, `.json`)
+///
+/// In the first two locations, the file needs to be called `config.toml` (or `.yaml`, `.yml`, `.json`).
+pub fn find_default() -> Option<PathBuf> {
+    default_locations(
+        std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+        std::env::var_os("HOME").map(PathBuf::from),
+    )
+    .into_iter()
+    .find(|path| path.is_file())
+}
+
+/// Returns the location for a new configuration file, i.e., `config.toml` in `$XDG_CONFIG_HOME/xrechnung/` or in
+/// `~/.config/xrechnung/`, if the environment variables are set.
+pub fn default_location() -> Option<PathBuf> {
+    default_locations(
+        std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+        std::env::var_os("HOME").map(PathBuf::from),
+    )
+    .into_iter()
+    .next()
+    .filter(|path| path.is_absolute())
+}
+
+/// Returns the candidates for the configuration file in the order in which they are searched.
+fn default_locations(xdg_config_home: Option<PathBuf>, home: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut directories = Vec::new();
+    directories.extend(xdg_config_home.filter(|path| path.is_absolute()));
+    directories.extend(home.map(|home| home.join(".config")));
+
+    let mut locations: Vec<PathBuf> = directories
+        .iter()
+        .flat_map(|directory| {
+            DEFAULT_FILE_NAMES
+                .iter()
+                .map(move |name| directory.join("xrechnung").join(name))
+        })
+        .collect();
+    locations.extend(
+        DEFAULT_FILE_NAMES
+            .iter()
+            .map(|name| PathBuf::from(name.replace("config", "xrechnung"))),
+    );
+    locations
+}
+
+/// Converts a parsed JSON or YAML value into a TOML value, so that it can be deserialized the same way as a TOML
+/// configuration file. Members without value (null) are left out, as TOML has no null value.
+fn to_toml(value: json::Value) -> toml::Value {
+    match value {
+        json::Value::Null => toml::Value::String(String::new()),
+        json::Value::Bool(value) => toml::Value::Boolean(value),
+        json::Value::Number(number) => match number.parse::<i64>() {
+            Ok(integer) => toml::Value::Integer(integer),
+            Err(_) => toml::Value::Float(number.parse().unwrap_or(f64::NAN)),
+        },
+        json::Value::String(value) => toml::Value::String(value),
+        json::Value::Array(values) => toml::Value::Array(values.into_iter().map(to_toml).collect()),
+        json::Value::Object(members) => toml::Value::Table(
+            members
+                .into_iter()
+                .filter(|(_, value)| *value != json::Value::Null)
+                .map(|(key, value)| (key, to_toml(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// The maximum depth of nested includes, which prevents endless recursion for files that include each other.
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Reads the configuration file with the given path (in the format determined by its extension) together with all the
+/// files that it includes.
+///
+/// The included files are given as `include` list of paths relative to the including file, where the file names can
+/// contain the wildcards `*` and `?` (e.g., `include = ["buyers/*.toml"]`). They are merged in the given order, before
+/// the values of the including file are merged on top of them.
+fn read_value(path: &Path, depth: usize) -> Result<toml::Value, Box<dyn std::error::Error>> {
+    let content = read_content(path)?;
+    #[cfg(feature = "encrypted")]
+    let format_path = decrypt::format_path(path);
+    #[cfg(not(feature = "encrypted"))]
+    let format_path = path.to_path_buf();
+    let extension = format_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+
+    let mut value = match extension.as_deref() {
+        Some("json") => to_toml(json::parse(&content)?),
+        Some("yaml" | "yml") => to_toml(yaml::parse(&content)?),
+        _ => toml::Value::Table(toml::from_str(&content)?),
+    };
+
+    let includes = match value
+        .as_table_mut()
+        .and_then(|table| table.remove("include"))
+    {
+        None => return Ok(value),
+        Some(toml::Value::Array(includes)) => includes,
+        Some(include) => vec![include],
+    };
+    if depth >= MAX_INCLUDE_DEPTH {
+        return Err(format!(
+            "The configuration file '{}' is included too deeply (are there circular includes?).",
+            path.display()
+        )
+        .into());
+    }
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut merged = toml::Value::Table(toml::Table::new());
+    for include in includes {
+        let pattern = include.as_str().ok_or(format!(
+            "The includes of the configuration file '{}' must be paths.",
+            path.display()
+        ))?;
+        for included in expand_include(&directory.join(pattern))? {
+            merge(&mut merged, read_value(&included, depth + 1)?);
+        }
+    }
+    merge(&mut merged, value);
+    Ok(merged)
+}
+
+/// Reads the content of the given configuration file, which is decrypted first, if it is encrypted (with the feature
+/// `encrypted`).
+fn read_content(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    #[cfg(feature = "encrypted")]
+    if let Some(content) = decrypt::decrypt(path) {
+        return content;
+    }
+
+    Ok(fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Could not read the configuration file '{}': {error}",
+            path.display()
+        )
+    })?)
+}
+
+/// Returns the files matching the given path of an include, whose file name can contain the wildcards `*` (any
+/// characters) and `?` (one character). The matches are sorted by name. A path without wildcards is returned as is.
+fn expand_include(path: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let pattern = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    if !pattern.contains(['*', '?']) {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let entries = fs::read_dir(if directory.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        directory
+    })
+    .map_err(|error| {
+        format!(
+            "Could not read the included directory '{}': {error}",
+            directory.display()
+        )
+    })?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_file()
+            && entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| matches_wildcard(pattern, name))
+        {
+            files.push(directory.join(entry.file_name()));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Returns whether the given name matches the pattern with the wildcards `*` and `?`.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+
+    // matches[j] is whether the processed part of the pattern matches the first j characters of the name
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for p in pattern.chars() {
+        let mut next = vec![p == '*' && matches[0]; 1];
+        for j in 1..=name.len() {
+            next.push(match p {
+                '*' => next[j - 1] || matches[j],
+                '?' => matches[j - 1],
+                c => matches[j - 1] && c == name[j - 1],
+            });
+        }
+        matches = next;
+    }
+    matches[name.len()]
+}
+
+/// Merges the given value into the base value: tables are merged recursively, lists are appended (e.g., the buyers
+/// from several files) and all other values replace the ones in the base value.
+fn merge(base: &mut toml::Value, value: toml::Value) {
+    match (base, value) {
+        (toml::Value::Table(base), toml::Value::Table(table)) => {
+            for (key, value) in table {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (toml::Value::Array(base), toml::Value::Array(values)) => base.extend(values),
+        (base, value) => *base = value,
+    }
+}
+
+/// Replaces the placeholders `${NAME}` in all string values of the configuration with the values of the environment
+/// variables of the same name, as returned by `lookup`. A literal `$` can be written as `$$`.
+///
+/// The function returns an error in case that a placeholder is not terminated or refers to a variable that is not set.
+fn interpolate(
+    value: &mut toml::Value,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match value {
+        toml::Value::String(text) if text.contains('$') => {
+            let mut result = String::new();
+            let mut rest = text.as_str();
+            while let Some(index) = rest.find('$') {
+                result.push_str(&rest[..index]);
+                rest = &rest[index + 1..];
+                if let Some(after) = rest.strip_prefix('$') {
+                    result.push('$');
+                    rest = after;
+                } else if let Some(after) = rest.strip_prefix('{') {
+                    let end = after.find('}').ok_or(format!(
+                        "Unterminated placeholder in the configuration value '{text}'."
+                    ))?;
+                    let name = &after[..end];
+                    let variable = lookup(name).ok_or(format!(
+                        "The environment variable '{name}' used in the configuration file is not set."
+                    ))?;
+                    result.push_str(&variable);
+                    rest = &after[end + 1..];
+                } else {
+                    result.push('$');
+                }
+            }
+            result.push_str(rest);
+            *text = result;
+        }
+        toml::Value::Array(values) => {
+            for value in values {
+                interpolate(value, lookup)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, value) in table.iter_mut() {
+                interpolate(value, lookup)?;
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Checks that no two buyers share a name or an alias, as the buyer for an invoice could not be selected unambiguously
+/// otherwise. Names and aliases are compared case-insensitively.
+///
+/// The function returns an error listing all names and aliases that are used by more than one buyer.
+fn check_unique_buyers(buyers: &[Buyer]) -> Result<(), Box<dyn std::error::Error>> {
+    // the indices of the buyers using a name or an alias, keyed by the lowercase name or alias
+    let mut owners: BTreeMap<String, (&str, Vec<usize>)> = BTreeMap::new();
+    for (index, buyer) in buyers.iter().enumerate() {
+        for name in std::iter::once(&buyer.name).chain(&buyer.alias) {
+            let (_, indices) = owners
+                .entry(name.to_lowercase())
+                .or_insert((name, Vec::new()));
+            if indices.last() != Some(&index) {
+                indices.push(index);
+            }
+        }
+    }
+
+    let duplicates: Vec<String> = owners
+        .values()
+        .filter(|(_, indices)| indices.len() > 1)
+        .map(|(name, indices)| {
+            let names: Vec<&str> = indices
+                .iter()
+                .map(|&index| buyers[index].name.as_str())
+                .collect();
+            format!("'{name}' (used by {})", names.join(", "))
+        })
+        .collect();
+    if duplicates.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "The following buyer names or aliases are not unique in the configuration file: {}.",
+            duplicates.join("; ")
+        )
+        .into())
+    }
+}
+
+/// Returns the names of the buyers whose name or alias is most similar to the given name (at most three, the most
+/// similar first), for suggesting them if no buyer with the given name exists.
+fn similar_buyers(name: &str, buyers: &[Buyer]) -> Vec<String> {
+    let name = name.to_lowercase();
+    let mut similar: Vec<(f64, &str)> = buyers
+        .iter()
+        .filter_map(|buyer| {
+            let similarity = std::iter::once(&buyer.name)
+                .chain(&buyer.alias)
+                .map(|candidate| strsim::jaro_winkler(&name, &candidate.to_lowercase()))
+                .fold(0.0, f64::max);
+            (similarity > 0.8).then_some((similarity, buyer.name.as_str()))
+        })
+        .collect();
+    similar.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    similar
+        .into_iter()
+        .take(3)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Returns the names of the fields of a struct that derives `Deserialize`, as given to the deserializer. This keeps the
+/// detection of unknown fields in sync with the structs of the configuration.
+fn field_names<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    /// A deserializer that only records the fields of the struct that is deserialized and fails otherwise.
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> serde::Deserializer<'de> for FieldNames<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: serde::de::Visitor<'de>>(
+            self,
+            _: V,
+        ) -> Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: serde::de::Visitor<'de>>(
+            self,
+            _: &'static str,
+            fields: &'static [&'static str],
+            _: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("fields recorded"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+            unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// Returns the fields of the payment means of the given type (besides the `type` field itself), or `None` for unknown
+/// types, which are reported when deserializing the configuration.
+fn payment_means_fields(payment_type: &str) -> Option<&'static [&'static str]> {
+    match payment_type {
+        "bank_transfer" | "sepa_credit_transfer" | "sepa_instant" => Some(&[]),
+        "card" => Some(&["credit", "number", "network", "holder"]),
+        "online" => Some(&["service", "account"]),
+        _ => None,
+    }
+}
+
+/// Adds a problem for every key of the given table that is not one of the given fields to the problems, with a
+/// suggestion of the most similar field, if there is one.
+fn check_fields(value: &toml::Value, path: &str, fields: &[&str], problems: &mut Vec<Problem>) {
+    let Some(table) = value.as_table() else {
+        return;
+    };
+    for key in table.keys().filter(|key| !fields.contains(&key.as_str())) {
+        let suggestion = fields
+            .iter()
+            .map(|field| (strsim::jaro_winkler(key, field), field))
+            .filter(|(similarity, _)| *similarity > 0.8)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b));
+        problems.push(Problem {
+            path: match path {
+                "" => key.clone(),
+                _ => format!("{path}.{key}"),
+            },
+            message: match suggestion {
+                Some((_, field)) => format!("unknown field (did you mean '{field}'?)"),
+                None => "unknown field".to_string(),
+            },
+        });
+    }
+}
+
+/// Returns the entries of the given table of tables (like the bank accounts by name), if there is one.
+fn entries(value: Option<&toml::Value>) -> impl Iterator<Item = (&String, &toml::Value)> {
+    value
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|table| table.iter())
+}
+
+/// Returns a problem for every currency code in the configuration that is no ISO 4217 code and replaces it with the
+/// code "XXX" (no currency), so that the rest of the configuration can still be deserialized and validated.
+fn invalid_currencies(value: &mut toml::Value) -> Vec<Problem> {
+    let mut currencies: Vec<(String, &mut toml::Value)> = Vec::new();
+    for (key, value) in value.as_table_mut().into_iter().flatten() {
+        match (key.as_str(), value) {
+            ("currency", currency) => currencies.push((key.clone(), currency)),
+            ("supplier", supplier) => {
+                let accounts = supplier
+                    .get_mut("accounts")
+                    .and_then(toml::Value::as_table_mut);
+                for (name, account) in accounts.into_iter().flatten() {
+                    if let Some(currency) = account.get_mut("currency") {
+                        currencies.push((format!("supplier.accounts.{name}.currency"), currency));
+                    }
+                }
+            }
+            ("buyer", toml::Value::Array(buyers)) => {
+                for (index, buyer) in buyers.iter_mut().enumerate() {
+                    if let Some(currency) = buyer.get_mut("currency") {
+                        currencies.push((format!("buyer[{index}].currency"), currency));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut problems = Vec::new();
+    for (path, currency) in currencies {
+        if let Some(Err(message)) = currency.as_str().map(Currency::new) {
+            problems.push(Problem { path, message });
+            *currency = toml::Value::String("XXX".to_string());
+        }
+    }
+    problems
+}
+
+/// Returns a problem for every field in the configuration that is unknown, e.g., because its name is misspelled and it
+/// would be ignored otherwise.
+fn unknown_fields(value: &toml::Value) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    check_fields(value, "", field_names::<CompleteConfig>(), &mut problems);
+
+    if let Some(supplier) = value.get("supplier") {
+        check_fields(
+            supplier,
+            "supplier",
+            field_names::<Supplier>(),
+            &mut problems,
+        );
+        if let Some(address) = supplier.get("address") {
+            check_fields(
+                address,
+                "supplier.address",
+                field_names::<Address>(),
+                &mut problems,
+            );
+        }
+        for (name, account) in entries(supplier.get("accounts")) {
+            let path = format!("supplier.accounts.{name}");
+            check_fields(account, &path, field_names::<BankAccount>(), &mut problems);
+        }
+        let payment_means = supplier
+            .get("payment_means")
+            .and_then(toml::Value::as_array);
+        for (index, payment_means) in payment_means.into_iter().flatten().enumerate() {
+            let payment_type = payment_means.get("type").and_then(toml::Value::as_str);
+            if let Some(fields) = payment_type.and_then(payment_means_fields) {
+                let fields: Vec<&str> = std::iter::once("type")
+                    .chain(fields.iter().copied())
+                    .collect();
+                let path = format!("supplier.payment_means[{index}]");
+                check_fields(payment_means, &path, &fields, &mut problems);
+            }
+        }
+    }
+
+    if let Some(numbering) = value.get("numbering") {
+        check_fields(
+            numbering,
+            "numbering",
+            field_names::<Numbering>(),
+            &mut problems,
+        );
+    }
+    if let Some(smtp) = value.get("smtp") {
+        check_fields(smtp, "smtp", field_names::<Smtp>(), &mut problems);
+    }
+    if let Some(peppol) = value.get("peppol") {
+        check_fields(peppol, "peppol", field_names::<Peppol>(), &mut problems);
+    }
+    if let Some(sevdesk) = value.get("sevdesk") {
+        check_fields(sevdesk, "sevdesk", field_names::<Sevdesk>(), &mut problems);
+    }
+
+    let buyers = value.get("buyer").and_then(toml::Value::as_array);
+    for (index, buyer) in buyers.into_iter().flatten().enumerate() {
+        let path = format!("buyer[{index}]");
+        check_fields(buyer, &path, field_names::<Buyer>(), &mut problems);
+        if let Some(address) = buyer.get("address") {
+            check_fields(
+                address,
+                &format!("{path}.address"),
+                field_names::<Address>(),
+                &mut problems,
+            );
+        }
+        if let Some(rounding) = buyer.get("rounding") {
+            check_fields(
+                rounding,
+                &format!("{path}.rounding"),
+                field_names::<Rounding>(),
+                &mut problems,
+            );
+        }
+        for (name, rate) in entries(buyer.get("rates")) {
+            check_fields(
+                rate,
+                &format!("{path}.rates.{name}"),
+                field_names::<Rate>(),
+                &mut problems,
+            );
+        }
+        for (name, project) in entries(buyer.get("projects")) {
+            check_fields(
+                project,
+                &format!("{path}.projects.{name}"),
+                field_names::<Project>(),
+                &mut problems,
+            );
+        }
+    }
+
+    problems
+}
+
+/// Reads the configuration file with all included files and replaces the placeholders for environment variables.
+fn read_config(filename: &str) -> Result<toml::Value, Box<dyn std::error::Error>> {
+    let mut value = read_value(Path::new(filename), 0)?;
+    interpolate(&mut value, &|name| std::env::var(name).ok())?;
+    Ok(value)
+}
+
+/// Reads the configuration file with all included files, replaces the placeholders for environment variables and
+/// deserializes the complete configuration with all buyers.
+///
+/// The function returns an error listing all unknown fields, in case that the configuration contains any.
+fn load_complete(filename: &str) -> Result<CompleteConfig, Box<dyn std::error::Error>> {
+    let value = read_config(filename)?;
+    let problems = unknown_fields(&value);
+    if !problems.is_empty() {
+        return Err(format!(
+            "The configuration file '{filename}' contains unknown fields: {}.",
+            problems
+                .iter()
+                .map(Problem::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        )
+        .into());
+    }
+    Ok(value.try_into()?)
+}
+
+/// Loads the configuration from the given file with all buyers, e.g., for listing the buyers.
+///
+/// The function returns an error in case that the config file could not be loaded or deserialized, if it refers to an
+/// environment variable that is not set or if buyers share a name or an alias.
+pub fn load_all(filename: &str) -> Result<CompleteConfig, Box<dyn std::error::Error>> {
+    let complete_config = load_complete(filename)?;
+    check_unique_buyers(&complete_config.buyer)?;
+    Ok(complete_config)
+}
+
+/// Checks that a public-sector buyer has a Leitweg-ID, and that the Leitweg-ID of the buyer is valid, if given.
+/// Checks that invoices for public-sector buyers submitted via Peppol are addressed to their Leitweg-ID, as the German
+/// invoicing portals (ZRE and OZG-RE) route them by it.
+fn check_submission(buyer: &Buyer) -> Result<(), String> {
+    match (buyer.public_sector, buyer.submission, buyer.endpoint()) {
+        (true, Some(Submission::Peppol), Ok((scheme, _))) if scheme != "0204" => Err(format!(
+            "invoices for public-sector buyers are submitted via Peppol to their Leitweg-ID (endpoint scheme '0204', \
+             not '{scheme}')"
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn check_leitweg_id(buyer: &Buyer) -> Result<(), String> {
+    match &buyer.leitweg_id {
+        Some(leitweg_id) => identifiers::check_leitweg_id(leitweg_id),
+        None if buyer.public_sector => {
+            Err("public-sector buyers need a Leitweg-ID as buyer reference".to_string())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Checks that the minimum billable quantity of the given buyer (if any) is greater than zero.
+fn check_minimum_quantity(buyer: &Buyer) -> Result<(), String> {
+    match buyer.minimum_quantity {
+        Some(minimum) if minimum.is_nan() || minimum <= 0.0 => Err(format!(
+            "the minimum quantity {minimum} needs to be greater than zero"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Returns whether the given code looks like a language code of ISO 639-1, e.g., "de".
+fn is_language_code(code: &str) -> bool {
+    code.len() == 2 && code.chars().all(|c| c.is_ascii_lowercase())
 }
 
 /// Loads the configuration from the given file and returns a Config struct that can be used to create an invoice.
 ///
-/// * `filename`   - The path to the configuration file in TOML format.
+/// * `filename`   - The path to the configuration file in TOML, YAML or JSON format (determined by the extension).
 /// * `buyer_name` - The name of the buyer for whom the invoice should be created. The value must match the name
-///                  attribute of exactly one Buyer in the configuration file.
+///   attribute (or one of the aliases) of exactly one Buyer in the configuration file.
 ///
-/// The function returns an error in case that the config file could not be loaded or deserialized or if no buyer with
-/// the name `buyer_name` could be found in the configuration file.
+/// The function returns an error in case that the config file could not be loaded or deserialized, if it refers to an
+/// environment variable that is not set, if buyers share a name or an alias or if no buyer with the name `buyer_name` could be found in the configuration
+/// file. The error message suggests the buyers with similar names in the latter case.
 pub fn load(filename: &str, buyer_name: &str) -> Result<Config, Box<dyn std::error::Error>> {
-    let config_toml = fs::read_to_string(filename)?;
-    let complete_config: CompleteConfig = toml::from_str(&config_toml)?;
+    let complete_config = load_complete(filename)?;
 
-    // filter buyer from the config file by the given supplier name
-    let matching_supplier: Option<Buyer> = complete_config
-        .buyer
-        .into_iter()
-        .filter(|x| x.name == buyer_name)
-        .collect::<Vec<_>>()
-        .pop(); // get the first element (assuming that there is no more than one match)
+    // a buyer must be identified unambiguously by its name or any of its aliases
+    check_unique_buyers(&complete_config.buyer)?;
+
+    // the buyer can be selected by its name or by one of its aliases
+    let mut buyers = complete_config.buyer;
+    let position = buyers.iter().position(|x| {
+        x.name == buyer_name
+            || x.alias
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(buyer_name))
+    });
 
     // we can only continue, if a supplier with the given name was found in the config file
-    let matching_supplier = matching_supplier.ok_or(format!(
-        "Could not find buyer '{buyer_name}' in the configuration file."
-    ))?;
+    let matching_supplier = match position {
+        Some(position) => buyers.swap_remove(position),
+        None => {
+            return Err(match similar_buyers(buyer_name, &buyers).as_slice() {
+                [] => format!("Could not find buyer '{buyer_name}' in the configuration file."),
+                suggestions => format!(
+                    "Could not find buyer '{buyer_name}' in the configuration file (did you mean {}?).",
+                    suggestions
+                        .iter()
+                        .map(|suggestion| format!("'{suggestion}'"))
+                        .collect::<Vec<_>>()
+                        .join(" or ")
+                ),
+            }
+            .into());
+        }
+    };
+
+    // the buyer's currency takes precedence over the global one
+    let currency = matching_supplier
+        .currency
+        .clone()
+        .unwrap_or(complete_config.currency);
+
+    if let Err(message) = check_leitweg_id(&matching_supplier)
+        .and(matching_supplier.endpoint().map(|_| ()))
+        .and(check_minimum_quantity(&matching_supplier))
+    {
+        return Err(format!("The buyer '{buyer_name}' is invalid: {message}.").into());
+    }
+
+    if let Some(language) = &matching_supplier.language
+        && !is_language_code(language)
+    {
+        return Err(format!(
+            "The language '{language}' of buyer '{buyer_name}' is no ISO 639-1 code (like 'de' or 'en')."
+        )
+        .into());
+    }
+
+    let holidays = complete_config
+        .holidays
+        .iter()
+        .map(|holiday| {
+            NaiveDate::parse_from_str(holiday, "%Y-%m-%d").map_err(|_| {
+                format!("The holiday '{holiday}' is no date in the format YYYY-MM-DD.")
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     // create the particular config for the given buyer (not returning all buyers)
-    let config = Config {
-        currency: complete_config.currency,
+    let mut config = Config {
+        currency,
         vat_percent: complete_config.vat_percent,
+        amount_rounding: complete_config.amount_rounding,
+        holidays,
+        default_hourly_rate: matching_supplier
+            .default_hourly_rate
+            .or(complete_config.default_hourly_rate),
         supplier: complete_config.supplier,
         buyer: matching_supplier,
+        numbering: complete_config.numbering,
+        smtp: complete_config.smtp,
+        peppol: complete_config.peppol,
+        sevdesk: complete_config.sevdesk,
     };
 
+    // the bank account is either selected for the buyer or matches the currency of the invoice
+    let account = config.buyer.account.clone().or_else(|| {
+        config
+            .supplier
+            .accounts
+            .iter()
+            .find(|(_, account)| account.currency.as_ref() == Some(&config.currency))
+            .map(|(name, _)| name.clone())
+    });
+    if let Some(account) = account {
+        config.select_account(&account)?;
+    }
+
     Ok(config)
 }
 
+impl Config {
+    /// Selects the bank account with the given name from the accounts of the supplier for the invoice, replacing the
+    /// IBAN and BIC of the supplier.
+    ///
+    /// The function returns an error in case that the supplier has no account with the given name.
+    pub fn select_account(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let account = self.supplier.accounts.get(name).ok_or_else(|| {
+            format!(
+                "Could not find the bank account '{name}' in the accounts of the supplier (available: {}).",
+                self.supplier
+                    .accounts
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+        self.supplier.iban = account.iban.clone();
+        self.supplier.bic = account.bic.clone();
+        Ok(())
+    }
+}
+
+/// A problem in the configuration file as found by [`validate_config`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Problem {
+    /// The path of the field with the problem, e.g., `buyer[1].address.country_code`.
+    pub path: String,
+
+    /// The description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Validates the configuration in the given file (with all buyers) beyond what is needed for loading it: the checksum
+/// of the IBAN, the formats of the BIC, the VAT identification numbers and the email addresses, as well as the country,
+/// currency and language codes and the uniqueness of the buyer names. Unknown fields (e.g., misspelled ones) are
+/// reported as problems as well, with a suggestion of the field that was probably meant.
+///
+/// The function returns all problems that were found (none for a valid configuration) or an error in case that the
+/// configuration file could not be loaded or deserialized at all.
+pub fn validate_config(filename: &str) -> Result<Vec<Problem>, Box<dyn std::error::Error>> {
+    let mut value = read_config(filename)?;
+    let mut problems = unknown_fields(&value);
+    problems.extend(invalid_currencies(&mut value));
+    let config: CompleteConfig = value.try_into()?;
+
+    let mut check = |path: String, result: Result<(), String>| {
+        if let Err(message) = result {
+            problems.push(Problem { path, message });
+        }
+    };
+    let check_country = |code: &str| match identifiers::is_country_code(code) {
+        true => Ok(()),
+        false => Err(format!("'{code}' is no ISO 3166-1 (Alpha-2) country code")),
+    };
+
+    check(
+        "vat_percent".to_string(),
+        match (0.0..=100.0).contains(&config.vat_percent) {
+            true => Ok(()),
+            false => Err(format!("{} is not between 0 and 100", config.vat_percent)),
+        },
+    );
+
+    let supplier = &config.supplier;
+    check(
+        "supplier.tax_identification".to_string(),
+        identifiers::check_vat_id(&supplier.tax_identification),
+    );
+    check(
+        "supplier.address.country_code".to_string(),
+        check_country(&supplier.address.country_code),
+    );
+    check(
+        "supplier.email".to_string(),
+        identifiers::check_email(&supplier.email),
+    );
+    check(
+        "supplier.iban".to_string(),
+        identifiers::check_iban(&supplier.iban),
+    );
+    check(
+        "supplier.bic".to_string(),
+        identifiers::check_bic(&supplier.bic),
+    );
+    for (name, account) in &supplier.accounts {
+        let path = |field: &str| format!("supplier.accounts.{name}.{field}");
+        check(path("iban"), identifiers::check_iban(&account.iban));
+        check(path("bic"), identifiers::check_bic(&account.bic));
+    }
+
+    for (index, buyer) in config.buyer.iter().enumerate() {
+        let path = |field: &str| format!("buyer[{index}].{field}");
+        check(
+            path("tax_identification"),
+            identifiers::check_vat_id(&buyer.tax_identification),
+        );
+        check(
+            path("address.country_code"),
+            check_country(&buyer.address.country_code),
+        );
+        check(path("email"), identifiers::check_email(&buyer.email));
+        if let Some(invoice_email) = &buyer.invoice_email {
+            check(
+                path("invoice_email"),
+                identifiers::check_email(invoice_email),
+            );
+        }
+        check(path("leitweg_id"), check_leitweg_id(buyer));
+        check(path("minimum_quantity"), check_minimum_quantity(buyer));
+        check(path("endpoint"), buyer.endpoint().map(|_| ()));
+        check(path("submission"), check_submission(buyer));
+        if let Some(account) = &buyer.account {
+            check(
+                path("account"),
+                match supplier.accounts.contains_key(account) {
+                    true => Ok(()),
+                    false => Err(format!("'{account}' is no bank account of the supplier")),
+                },
+            );
+        }
+        if let Some(language) = &buyer.language {
+            check(
+                path("language"),
+                match is_language_code(language) {
+                    true => Ok(()),
+                    false => Err(format!("'{language}' is no ISO 639-1 language code")),
+                },
+            );
+        }
+    }
+
+    check(
+        "buyer".to_string(),
+        check_unique_buyers(&config.buyer).map_err(|error| error.to_string()),
+    );
+    if let Some(numbering) = &config.numbering {
+        check("numbering.scheme".to_string(), numbering.check_scheme());
+    }
+    if let Some(smtp) = &config.smtp {
+        check(
+            "smtp.url".to_string(),
+            match smtp.url.starts_with("smtp://") || smtp.url.starts_with("smtps://") {
+                true => Ok(()),
+                false => Err(format!("'{}' is no smtp:// or smtps:// URL", smtp.url)),
+            },
+        );
+        if let Some(from) = &smtp.from {
+            check("smtp.from".to_string(), identifiers::check_email(from));
+        }
+    }
+    if let Some(peppol) = &config.peppol {
+        check(
+            "peppol.url".to_string(),
+            match peppol.url.starts_with("https://") {
+                true => Ok(()),
+                false => Err(format!("'{}' is no HTTPS URL", peppol.url)),
+            },
+        );
+        check(
+            "peppol.sender".to_string(),
+            match peppol.sender.split_once(':') {
+                Some((scheme, identifier))
+                    if scheme.len() == 4
+                        && scheme.chars().all(|c| c.is_ascii_digit())
+                        && !identifier.is_empty() =>
+                {
+                    Ok(())
+                }
+                _ => Err(format!(
+                    "'{}' is no participant identifier like '9930:DE123456789'",
+                    peppol.sender
+                )),
+            },
+        );
+    }
+
+    Ok(problems)
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -176,6 +1514,37 @@ mod tests {
         assert_eq!(config.buyer.email, "mail@client2.example.com");
     }
 
+    #[test]
+    fn test_default_hourly_rate() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        assert_eq!(config.default_hourly_rate, Some(100.0));
+
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert_eq!(config.default_hourly_rate, Some(110.0));
+    }
+
+    #[test]
+    fn test_rates() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let rate = &config.buyer.rates["development"];
+        assert_eq!(rate.hourly_rate, 95.0);
+        assert_eq!(rate.name.as_deref(), Some("Software Development"));
+
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert!(config.buyer.rates.is_empty());
+    }
+
+    #[test]
+    fn test_projects() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let project = &config.buyer.projects["Website"];
+        assert_eq!(project.order_line_id.as_deref(), Some("10"));
+        assert_eq!(project.accounting_cost.as_deref(), Some("4711"));
+
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert!(config.buyer.projects.is_empty());
+    }
+
     #[test]
     fn test_error_on_missing_file() {
         assert!(crate::config::load("examples/config_nonexistent.toml", "Client Company").is_err());
@@ -185,4 +1554,522 @@ mod tests {
     fn test_error_on_missing_buyer() {
         assert!(crate::config::load("examples/config.toml", "Wrong Company").is_err());
     }
+
+    #[test]
+    fn test_load_yaml_and_json_config_files() {
+        for filename in ["examples/config.yaml", "examples/config.json"] {
+            let config = crate::config::load(filename, "Client Company").unwrap();
+            assert_eq!(config.currency, "EUR");
+            assert_eq!(config.vat_percent, 19.0);
+            assert_eq!(config.default_hourly_rate, Some(100.0));
+            assert_eq!(config.supplier.name, "Hans Muster");
+            assert_eq!(config.supplier.address.city, "Example City");
+            assert_eq!(config.buyer.name, "Client Company");
+            assert_eq!(config.buyer.due_after_days, 20);
+            assert_eq!(config.buyer.rates["development"].hourly_rate, 95.0);
+            assert!(config.buyer.minimum_quantity.is_none());
+        }
+    }
+
+    #[test]
+    fn test_interpolate_environment_variables() {
+        let lookup = |name: &str| (name == "IBAN").then(|| "DE02120300000000202051".to_string());
+
+        let mut value: toml::Value =
+            toml::from_str("iban = \"${IBAN}\"\nnote = \"Pay $$5 to ${IBAN}.\"\nlist = [\"$x\"]")
+                .unwrap();
+        super::interpolate(&mut value, &lookup).unwrap();
+        assert_eq!(value["iban"].as_str(), Some("DE02120300000000202051"));
+        assert_eq!(
+            value["note"].as_str(),
+            Some("Pay $5 to DE02120300000000202051.")
+        );
+        assert_eq!(value["list"][0].as_str(), Some("$x"));
+
+        let mut value = toml::Value::String("${BIC}".to_string());
+        assert_eq!(
+            super::interpolate(&mut value, &lookup)
+                .unwrap_err()
+                .to_string(),
+            "The environment variable 'BIC' used in the configuration file is not set."
+        );
+        let mut value = toml::Value::String("${IBAN".to_string());
+        assert!(super::interpolate(&mut value, &lookup).is_err());
+    }
+
+    #[test]
+    fn test_load_with_includes() {
+        let config = crate::config::load("examples/layered/config.toml", "Client Company").unwrap();
+        assert_eq!(config.supplier.name, "Hans Muster");
+        assert_eq!(config.supplier.iban, "DE43345678111111111100");
+        assert_eq!(config.buyer.email, "mail@client1.example.com");
+
+        // the including file overrides the values of the included files
+        assert_eq!(config.vat_percent, 19.0);
+
+        let config = crate::config::load("examples/layered/config.toml", "Another Client").unwrap();
+        assert_eq!(config.buyer.due_after_days, 30);
+    }
+
+    #[test]
+    fn test_matches_wildcard() {
+        assert!(super::matches_wildcard("*.toml", "client.toml"));
+        assert!(super::matches_wildcard("client?.toml", "client1.toml"));
+        assert!(super::matches_wildcard("*", ""));
+        assert!(!super::matches_wildcard("*.toml", "client.yaml"));
+        assert!(!super::matches_wildcard("client?.toml", "client.toml"));
+    }
+
+    #[test]
+    fn test_default_locations() {
+        use std::path::PathBuf;
+
+        let locations = super::default_locations(
+            Some(PathBuf::from("/xdg")),
+            Some(PathBuf::from("/home/user")),
+        );
+        assert_eq!(locations.len(), 12);
+        assert_eq!(locations[0], PathBuf::from("/xdg/xrechnung/config.toml"));
+        assert_eq!(
+            locations[4],
+            PathBuf::from("/home/user/.config/xrechnung/config.toml")
+        );
+        assert_eq!(locations[8], PathBuf::from("xrechnung.toml"));
+        assert_eq!(locations[11], PathBuf::from("xrechnung.json"));
+
+        // relative paths in XDG_CONFIG_HOME are invalid and ignored according to the specification
+        let locations = super::default_locations(Some(PathBuf::from("xdg")), None);
+        assert_eq!(locations[0], PathBuf::from("xrechnung.toml"));
+    }
+
+    #[test]
+    fn test_buyer_currency() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        assert_eq!(config.currency, "EUR");
+
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert_eq!(config.currency, "CHF");
+
+        // invalid currency codes are rejected when loading the configuration
+        let error = crate::config::load("examples/invalid-config.toml", "Client Company")
+            .err()
+            .unwrap();
+        assert!(
+            error
+                .to_string()
+                .contains("'EURO' is no ISO 4217 currency code")
+        );
+    }
+
+    #[test]
+    fn test_buyer_language() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        assert!(config.buyer.language.is_none());
+        assert!(config.buyer.translations.is_empty());
+
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert_eq!(config.buyer.language.as_deref(), Some("de"));
+        assert_eq!(config.buyer.translations["support"], "Wartung und Support");
+    }
+
+    #[test]
+    fn test_payment_terms() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        assert_eq!(
+            config.buyer.payment_terms.as_deref(),
+            Some("Payable without deduction until {due_date}.")
+        );
+
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert!(config.buyer.payment_terms.is_none());
+    }
+
+    #[test]
+    fn test_buyer_references() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        assert_eq!(
+            config.buyer.notes,
+            vec!["Services according to the framework agreement."]
+        );
+        assert_eq!(config.buyer.order_reference.as_deref(), Some("PO-2025-17"));
+        assert_eq!(
+            config.buyer.contract_reference.as_deref(),
+            Some("FA-2024-3")
+        );
+        assert_eq!(config.buyer.accounting_cost.as_deref(), Some("CC-100"));
+
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert!(config.buyer.notes.is_empty());
+        assert!(config.buyer.order_reference.is_none());
+    }
+
+    #[test]
+    fn test_buyer_alias() {
+        let config = crate::config::load("examples/config.toml", "acme").unwrap();
+        assert_eq!(config.buyer.name, "Client Company");
+
+        let config = crate::config::load("examples/config.toml", "ACME").unwrap();
+        assert_eq!(config.buyer.name, "Client Company");
+    }
+
+    #[test]
+    fn test_suggest_similar_buyers() {
+        let error = crate::config::load("examples/config.toml", "Client Compnay")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Could not find buyer 'Client Compnay' in the configuration file (did you mean 'Client Company'?)."
+        );
+
+        let error = crate::config::load("examples/config.toml", "Wrong Company")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Could not find buyer 'Wrong Company' in the configuration file."
+        );
+    }
+
+    #[test]
+    fn test_error_on_duplicate_buyers() {
+        let buyer = |name: &str, alias: &[&str]| super::Buyer {
+            name: name.to_string(),
+            alias: alias.iter().map(|alias| alias.to_string()).collect(),
+            ..crate::config::load("examples/config.toml", "Client Company")
+                .unwrap()
+                .buyer
+        };
+
+        assert!(super::check_unique_buyers(&[buyer("A", &["a", "x"]), buyer("B", &["y"])]).is_ok());
+        assert_eq!(
+            super::check_unique_buyers(&[buyer("A", &["x"]), buyer("B", &["X"]), buyer("A", &[])])
+                .unwrap_err()
+                .to_string(),
+            "The following buyer names or aliases are not unique in the configuration file: \
+             'A' (used by A, A); 'x' (used by A, B)."
+        );
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert!(
+            crate::config::validate_config("examples/config.toml")
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            crate::config::validate_config("examples/config.json")
+                .unwrap()
+                .is_empty()
+        );
+
+        let problems = crate::config::validate_config("examples/invalid-config.toml").unwrap();
+        let problems: Vec<String> = problems.iter().map(|problem| problem.to_string()).collect();
+        assert_eq!(
+            problems,
+            vec![
+                "currency: 'EURO' is no ISO 4217 currency code",
+                "supplier.tax_identification: 'DE12345678' does not match the format of VAT identification numbers \
+                 of 'DE' (e.g., 'DE111111111')",
+                "supplier.iban: 'DE12345678111111111100' has an invalid checksum",
+                "supplier.bic: 'DABCDEF' is no BIC (expected 8 or 11 upper case letters or digits, like \
+                 'DEUTDEFFXXX')",
+                "buyer[0].address.country_code: 'Germany' is no ISO 3166-1 (Alpha-2) country code",
+                "buyer[0].email: 'mail.client.example.com' is no email address",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_leitweg_id() {
+        let mut buyer = crate::config::load("examples/config.toml", "Client Company")
+            .unwrap()
+            .buyer;
+        assert!(super::check_leitweg_id(&buyer).is_ok());
+
+        buyer.public_sector = true;
+        assert_eq!(
+            super::check_leitweg_id(&buyer).unwrap_err(),
+            "public-sector buyers need a Leitweg-ID as buyer reference"
+        );
+
+        buyer.leitweg_id = Some("04011000-1234512345-06".to_string());
+        assert!(super::check_leitweg_id(&buyer).is_ok());
+
+        buyer.leitweg_id = Some("04011000-1234512345".to_string());
+        assert!(super::check_leitweg_id(&buyer).is_err());
+    }
+
+    #[test]
+    fn test_minimum_quantity() {
+        let mut buyer = crate::config::load("examples/config.toml", "Client Company")
+            .unwrap()
+            .buyer;
+        assert!(super::check_minimum_quantity(&buyer).is_ok());
+        buyer.minimum_quantity = Some(0.5);
+        assert!(super::check_minimum_quantity(&buyer).is_ok());
+        for minimum in [0.0, -1.0, f32::NAN] {
+            buyer.minimum_quantity = Some(minimum);
+            assert!(super::check_minimum_quantity(&buyer).is_err());
+        }
+
+        // buyers with an invalid minimum quantity cannot be loaded and are reported by the validation
+        let directory = std::env::temp_dir().join("xrechnung_minimum_quantity_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        let config_file = directory.join("config.toml");
+        let content = std::fs::read_to_string("examples/config.toml")
+            .unwrap()
+            .replace(
+                "due_after_days = 20",
+                "due_after_days = 20\nminimum_quantity = 0.0",
+            );
+        std::fs::write(&config_file, content).unwrap();
+        let config_file = config_file.to_str().unwrap();
+
+        let Err(error) = crate::config::load(config_file, "Client Company") else {
+            panic!("the configuration with a minimum quantity of zero was loaded");
+        };
+        assert_eq!(
+            error.to_string(),
+            "The buyer 'Client Company' is invalid: the minimum quantity 0 needs to be greater than zero."
+        );
+        let problems: Vec<String> = crate::config::validate_config(config_file)
+            .unwrap()
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+        assert_eq!(
+            problems,
+            ["buyer[0].minimum_quantity: the minimum quantity 0 needs to be greater than zero"]
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_submission() {
+        let mut buyer = crate::config::load("examples/config.toml", "Client Company")
+            .unwrap()
+            .buyer;
+        buyer.public_sector = true;
+        buyer.leitweg_id = Some("04011000-1234512345-06".to_string());
+        assert!(super::check_submission(&buyer).is_ok());
+
+        buyer.submission = Some(super::Submission::Peppol);
+        assert!(super::check_submission(&buyer).is_err());
+
+        buyer.endpoint_scheme = Some("0204".to_string());
+        assert!(super::check_submission(&buyer).is_ok());
+
+        buyer.endpoint_scheme = None;
+        buyer.submission = Some(super::Submission::Email);
+        assert!(super::check_submission(&buyer).is_ok());
+    }
+
+    #[test]
+    fn test_bank_accounts() {
+        // default account of the supplier
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        assert_eq!(config.supplier.iban, "DE43345678111111111100");
+
+        // account matching the currency of the buyer
+        let mut config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert_eq!(config.supplier.iban, "CH9300762011623852957");
+        assert_eq!(config.supplier.bic, "POFICHBEXXX");
+
+        config.select_account("usd").unwrap();
+        assert_eq!(config.supplier.iban, "GB33BUKB20201555555555");
+        assert!(config.select_account("unknown").is_err());
+    }
+
+    #[test]
+    fn test_payment_means() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        assert!(matches!(
+            config.supplier.payment_means.as_slice(),
+            [
+                super::PaymentMeans::SepaCreditTransfer,
+                super::PaymentMeans::Online { service, .. }
+            ] if service == "PayPal"
+        ));
+
+        let config = crate::config::load("examples/config.json", "Client Company").unwrap();
+        assert!(config.supplier.payment_means.is_empty());
+    }
+
+    #[test]
+    fn test_holidays() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        assert_eq!(
+            config.holidays.first(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 1).as_ref()
+        );
+        assert!(config.buyer.due_on_business_day);
+
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        assert!(!config.buyer.due_on_business_day);
+    }
+
+    #[test]
+    fn test_buyer_endpoint() {
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let buyer = &mut config.buyer;
+        assert_eq!(
+            buyer.endpoint().unwrap(),
+            ("EM", "mail@client1.example.com")
+        );
+
+        buyer.endpoint = Some("invoices@client1.example.com".to_string());
+        assert_eq!(
+            buyer.endpoint().unwrap(),
+            ("EM", "invoices@client1.example.com")
+        );
+
+        // there is no default endpoint for a Peppol ID
+        buyer.endpoint = None;
+        buyer.endpoint_scheme = Some("0088".to_string());
+        assert!(buyer.endpoint().is_err());
+
+        buyer.endpoint_scheme = Some("0204".to_string());
+        assert!(buyer.endpoint().is_err());
+        buyer.leitweg_id = Some("04011000-1234512345-06".to_string());
+        assert_eq!(
+            buyer.endpoint().unwrap(),
+            ("0204", "04011000-1234512345-06")
+        );
+
+        buyer.endpoint_scheme = Some("XX".to_string());
+        assert!(buyer.endpoint().is_err());
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let value: toml::Value = toml::from_str(
+            r#"
+currency = "EUR"
+vat_percent = 19.0
+vat_percnet = 7.0
+
+[supplier]
+tax_identifcation = "DE123456789"
+accounts.chf = { iban = "CH9300762011623852957", bic = "POFICHBEXXX", curency = "CHF" }
+payment_means = [{ type = "online", service = "PayPal", acount = "pay@example.com" }, { type = "sepa_instant" }]
+
+[supplier.address]
+city = "Example City"
+
+[[buyer]]
+name = "Client Company"
+colour = "blue"
+address = { zip = "54321" }
+rounding = { increment = 0.25 }
+rates.support = { hourly_rate = 75.0, nmae = "Support" }
+"#,
+        )
+        .unwrap();
+
+        let problems: Vec<String> = super::unknown_fields(&value)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "vat_percnet: unknown field (did you mean 'vat_percent'?)",
+                "supplier.tax_identifcation: unknown field (did you mean 'tax_identification'?)",
+                "supplier.accounts.chf.curency: unknown field (did you mean 'currency'?)",
+                "supplier.payment_means[0].acount: unknown field (did you mean 'account'?)",
+                "buyer[0].colour: unknown field",
+                "buyer[0].address.zip: unknown field",
+                "buyer[0].rates.support.nmae: unknown field (did you mean 'name'?)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_on_unknown_fields() {
+        let directory = std::env::temp_dir().join("xrechnung_unknown_fields_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        let config_file = directory.join("config.toml");
+        let content = std::fs::read_to_string("examples/config.toml")
+            .unwrap()
+            .replace(
+                "tax_identification = \"DE123456789\"",
+                "tax_identifcation = \"DE123456789\"",
+            )
+            .replace(
+                "due_after_days = 30",
+                "due_after_days = 30\ndue_afterdays = 14",
+            );
+        std::fs::write(&config_file, content).unwrap();
+        let config_file = config_file.to_str().unwrap();
+
+        let Err(error) = crate::config::load(config_file, "Client Company") else {
+            panic!("the configuration with unknown fields was loaded");
+        };
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "The configuration file '{config_file}' contains unknown fields: supplier.tax_identifcation: unknown \
+                 field (did you mean 'tax_identification'?); buyer[1].due_afterdays: unknown field (did you mean \
+                 'due_after_days'?)."
+            )
+        );
+        // the unknown fields are reported as problems, if the configuration can be deserialized nevertheless
+        let content = std::fs::read_to_string(config_file)
+            .unwrap()
+            .replace("tax_identifcation", "tax_identification");
+        std::fs::write(config_file, content).unwrap();
+        let problems = crate::config::validate_config(config_file).unwrap();
+        assert_eq!(
+            problems[0].to_string(),
+            "buyer[1].due_afterdays: unknown field (did you mean 'due_after_days'?)"
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_constructors() {
+        let address = super::Address::new("Kundenweg 2", "Kundenstadt", "54321", "DE");
+        let buyer: super::Buyer = toml::from_str(
+            r#"
+            name = "Client Company"
+            tax_identification = "DE987654321"
+            email = "billing@client.example"
+            reference = "PO-2025-17"
+            due_after_days = 14
+            address = { address_line = "Kundenweg 2", city = "Kundenstadt", post_code = "54321", country_code = "DE" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            super::Buyer::new(
+                "Client Company",
+                "DE987654321",
+                address.clone(),
+                "billing@client.example",
+                "PO-2025-17",
+                14
+            ),
+            buyer
+        );
+
+        let supplier = super::Supplier {
+            website: Some("https://muster.example".to_string()),
+            ..super::Supplier::new(
+                "Hans Muster",
+                "DE123456789",
+                address,
+                "+49 123 456789",
+                "hans@muster.example",
+                "DE02120300000000202051",
+                "BYLADEM1001",
+            )
+        };
+        assert_eq!(supplier.clone(), supplier);
+        assert!(format!("{supplier:?}").contains("website: Some(\"https://muster.example\")"));
+        assert!(supplier.payment_means.is_empty() && supplier.accounts.is_empty());
+    }
 }
@@ -0,0 +1,143 @@
+//! Validation of currency codes according to ISO 4217 "Codes for the representation of currencies".
+//!
+//! The currencies of invoices are given as [`Currency`], which can only hold valid codes. Invalid codes (e.g., "EUr"
+//! or "EURO") are already rejected when loading the configuration, as validators reject invoices with them.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// The alphabetic codes of the currencies (and funds) in ISO 4217, sorted alphabetically.
+const CODES: [&str; 182] = [
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR",
+    "XOF", "XPD", "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+];
+
+/// Returns whether the given code is an alphabetic currency code of ISO 4217 (e.g., "EUR" or "CHF").
+pub fn is_valid(code: &str) -> bool {
+    CODES.binary_search(&code).is_ok()
+}
+
+/// A currency, given by its alphabetic code of ISO 4217 (e.g., "EUR").
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(String);
+
+impl Currency {
+    /// Returns the currency with the given code.
+    ///
+    /// The function returns an error in case that the code is no alphabetic currency code of ISO 4217 (the codes are
+    /// upper case, so "eur" is no valid code either).
+    pub fn new(code: &str) -> Result<Self, String> {
+        match is_valid(code) {
+            true => Ok(Currency(code.to_string())),
+            false => Err(format!("'{code}' is no ISO 4217 currency code")),
+        }
+    }
+
+    /// Returns the code of the currency, e.g., "EUR".
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Currency::new(code)
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Currency::new(&code)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.0
+    }
+}
+
+impl AsRef<str> for Currency {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Currency {
+    fn eq(&self, code: &str) -> bool {
+        self.0 == code
+    }
+}
+
+impl PartialEq<&str> for Currency {
+    fn eq(&self, code: &&str) -> bool {
+        self.0 == *code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_is_valid() {
+        assert!(super::CODES.is_sorted());
+        assert!(super::is_valid("EUR"));
+        assert!(super::is_valid("CHF"));
+        assert!(super::is_valid("USD"));
+        assert!(!super::is_valid("eur"));
+        assert!(!super::is_valid("EURO"));
+        assert!(!super::is_valid("ABC"));
+    }
+
+    #[test]
+    fn test_currency() {
+        let currency = super::Currency::new("CHF").unwrap();
+        assert_eq!(currency, "CHF");
+        assert_eq!(currency.to_string(), "CHF");
+        assert_eq!("EUR".parse::<super::Currency>().unwrap().code(), "EUR");
+        assert_eq!(
+            super::Currency::new("EUr").unwrap_err(),
+            "'EUr' is no ISO 4217 currency code"
+        );
+
+        #[derive(serde::Deserialize, serde::Serialize)]
+        struct Invoice {
+            currency: super::Currency,
+        }
+        let invoice: Invoice = toml::from_str("currency = \"USD\"").unwrap();
+        assert_eq!(invoice.currency, "USD");
+        assert_eq!(toml::to_string(&invoice).unwrap(), "currency = \"USD\"\n");
+        let error = toml::from_str::<Invoice>("currency = \"EURO\"")
+            .err()
+            .unwrap();
+        assert!(
+            error
+                .message()
+                .contains("'EURO' is no ISO 4217 currency code")
+        );
+    }
+}
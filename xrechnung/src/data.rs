@@ -1,11 +1,32 @@
 //! Data structures representing an invoice. The XRechnung format is created from these structures by the `xml_bill`
 //! module.
 
-use crate::config::Config;
-use chrono::{Days, NaiveDate};
-use serde::Deserialize;
+use crate::config::{Buyer, Config, Supplier};
+use crate::currency::Currency;
+use crate::money::Rounding;
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The names of the months in German, for texts of bills in German language.
+const GERMAN_MONTHS: [&str; 12] = [
+    "Januar",
+    "Februar",
+    "März",
+    "April",
+    "Mai",
+    "Juni",
+    "Juli",
+    "August",
+    "September",
+    "Oktober",
+    "November",
+    "Dezember",
+];
 
 /// Definition of a period for the invoice (e.g., billing period).
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Period {
     /// The start date of the period.
     pub start: NaiveDate,
@@ -14,35 +35,230 @@ pub struct Period {
     pub end: NaiveDate,
 }
 
+impl Period {
+    /// Creates a period from the given start and end date.
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        Period { start, end }
+    }
+
+    /// Returns the calendar month that contains the given date, e.g., 2025-01-01 to 2025-01-31 for 2025-01-15.
+    pub fn month_of(date: NaiveDate) -> Self {
+        let start = date.with_day(1).unwrap_or(date);
+        let end = start
+            .checked_add_months(Months::new(1))
+            .and_then(|next_month| next_month.pred_opt())
+            .unwrap_or(start);
+        Period { start, end }
+    }
+
+    /// Returns the calendar month before the one that contains the given date, e.g., 2024-12-01 to 2024-12-31 for
+    /// 2025-01-15.
+    pub fn previous_month(date: NaiveDate) -> Self {
+        let start = Period::month_of(date).start;
+        Period::month_of(start.pred_opt().unwrap_or(start))
+    }
+
+    /// Returns the period from the earliest start to the latest end of all given invoice lines that have a date, or
+    /// `None` if none of the lines has a date.
+    ///
+    /// The function returns an error in case that the period of one of the lines cannot be determined.
+    pub fn from_lines(
+        lines: &[InvoiceHoursElement],
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let mut range: Option<Period> = None;
+        for line in lines {
+            if let Some(period) = line.period()? {
+                range = Some(match range {
+                    Some(range) => Period {
+                        start: range.start.min(period.start),
+                        end: range.end.max(period.end),
+                    },
+                    None => period,
+                });
+            }
+        }
+
+        Ok(range)
+    }
+
+    /// Returns whether the given date lies within the period (including its start and end date).
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// Returns whether the given period lies completely within the period.
+    pub fn contains_period(&self, period: &Period) -> bool {
+        self.contains(period.start) && self.contains(period.end)
+    }
+}
+
+/// The VAT category of an invoice, which determines the tax rate and the notes that are required by law. It is
+/// serialized as snake case name, e.g., `"reverse_charge"` or `{ exempt = "<reason>" }`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxCategory {
+    /// Standard rated with the VAT percentage of the invoice (code "S").
+    #[default]
+    Standard,
+
+    /// Reverse charge, where the buyer is liable for the VAT, e.g., for services to companies in other EU member
+    /// states (code "AE").
+    ReverseCharge,
+
+    /// Exempt from VAT for the given reason, e.g., "Kleinunternehmer gemäß § 19 UStG" (code "E").
+    Exempt(String),
+
+    /// Intra-community supply to a company in another EU member state, which is exempt from VAT (code "K").
+    IntraCommunity,
+}
+
+impl TaxCategory {
+    /// Returns the code of the category in the UNTDID 5305 code list.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TaxCategory::Standard => "S",
+            TaxCategory::ReverseCharge => "AE",
+            TaxCategory::Exempt(_) => "E",
+            TaxCategory::IntraCommunity => "K",
+        }
+    }
+
+    /// Returns the reason why no VAT is charged in the given language (ISO 639-1 code, English if not supported), or
+    /// `None` for the standard rated category. The reason has to be stated on the invoice.
+    pub fn exemption_reason(&self, language: Option<&str>) -> Option<String> {
+        let german = language == Some("de");
+        match self {
+            TaxCategory::Standard => None,
+            TaxCategory::ReverseCharge if german => {
+                Some("Steuerschuldnerschaft des Leistungsempfängers".to_string())
+            }
+            TaxCategory::ReverseCharge => Some(
+                "Reverse charge: the recipient of the service is liable for the VAT".to_string(),
+            ),
+            TaxCategory::Exempt(reason) => Some(reason.clone()),
+            TaxCategory::IntraCommunity if german => {
+                Some("Steuerfreie innergemeinschaftliche Lieferung".to_string())
+            }
+            TaxCategory::IntraCommunity => {
+                Some("Intra-community supply exempt from VAT".to_string())
+            }
+        }
+    }
+}
+
+/// A discount for early payment ("Skonto") that the buyer may deduct, if the invoice is paid within the given number of
+/// days after the issue date.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CashDiscount {
+    /// The discount in percent of the invoice total, e.g., 2.0.
+    pub percent: f32,
+
+    /// The number of days after the issue date within which the discount may be deducted.
+    pub days: u16,
+}
+
+/// Formats the discount in the syntax of XRechnung for the payment terms, e.g., `#SKONTO#TAGE=14#PROZENT=2.00#`.
+impl fmt::Display for CashDiscount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#SKONTO#TAGE={}#PROZENT={:.2}#", self.days, self.percent)
+    }
+}
+
+/// Parses a comma-separated list of discounts for early payment in the format `<percent>%:<days>d`, e.g.,
+/// "3%:7d,2%:14d" for 3 % within 7 days and 2 % within 14 days.
+///
+/// The function returns an error in case that an entry does not match the format, or if a percentage is not between 0
+/// and 100 or a number of days is zero.
+pub fn parse_cash_discounts(input: &str) -> Result<Vec<CashDiscount>, String> {
+    input
+        .split(',')
+        .map(|entry| {
+            let entry = entry.trim();
+            let error = || {
+                format!(
+                    "'{entry}' is no discount in the format <percent>%:<days>d (e.g., \"2%:14d\")"
+                )
+            };
+            let (percent, days) = entry.split_once(':').ok_or_else(error)?;
+            let percent: f32 = percent
+                .trim()
+                .strip_suffix('%')
+                .and_then(|percent| percent.trim().parse().ok())
+                .ok_or_else(error)?;
+            let days: u16 = days
+                .trim()
+                .strip_suffix('d')
+                .and_then(|days| days.trim().parse().ok())
+                .ok_or_else(error)?;
+
+            if !(percent > 0.0 && percent < 100.0) {
+                return Err(format!("the discount {percent} % is not between 0 and 100"));
+            }
+            if days == 0 {
+                return Err(format!(
+                    "the discount of '{entry}' needs to be granted for at least one day"
+                ));
+            }
+            Ok(CashDiscount { percent, days })
+        })
+        .collect()
+}
+
 /// Data structure containing the metadata of an invoice (bill).
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Bill {
     /// The unique number of the invoice (as required by law).
     pub number: String,
 
     /// The currency of the invoice, e.g., EUR.
-    pub currency: String,
+    pub currency: Currency,
 
     /// The VAT percentage applied to the invoice total.
     pub vat_percent: f32,
 
+    /// The VAT category of the invoice. The VAT percentage only applies to the standard rated category.
+    #[serde(default)]
+    pub tax_category: TaxCategory,
+
     /// The issue date of the invoice.
     pub issue_date: NaiveDate,
 
     /// The due date of the invoice.
     pub due_date: NaiveDate,
 
+    /// The way in which the amounts of the invoice are rounded to cents.
+    #[serde(default)]
+    pub amount_rounding: Rounding,
+
+    /// The discounts for early payment that the buyer may deduct, if any.
+    #[serde(default)]
+    pub cash_discounts: Vec<CashDiscount>,
+
     /// The billing period for the invoice, if applicable.
     pub period: Option<Period>,
+
+    /// The language of the texts that are generated for the invoice as ISO 639-1 code (e.g., "de"), if not English.
+    pub language: Option<String>,
 }
 
 /// Data structure representing an invoice line item for hours worked.
 /// From a list of those items, the billable amount for the invoice is calculated.
-#[derive(Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct InvoiceHoursElement {
+    /// The identifier of the line item (e.g., the ID of a timesheet entry), if applicable. The line items are numbered
+    /// sequentially, if not given. The identifiers must be unique within the invoice.
+    pub id: Option<String>,
+
     /// The name / description of the line item, e.g., "Development", "Consulting", etc.
     pub name: String,
 
-    /// The quantity of hours worked for this line item.
+    /// A longer description of the line item, if applicable (e.g., the tasks that were worked on), which keeps the
+    /// name of the line item short.
+    pub description: Option<String>,
+
+    /// The quantity of hours worked for this line item. When deserialized, the quantity can also be given as a string
+    /// with minutes (e.g., "90m") or as a duration (e.g., "1:30"), as accepted by [`parse_hours`].
+    #[serde(deserialize_with = "deserialize_hours")]
     pub quantity: f32,
 
     /// The hourly rate for this line item in the specified currency.
@@ -50,6 +266,174 @@ pub struct InvoiceHoursElement {
 
     /// The date of the line item in ISO 8601 format (YYYY-MM-DD), if applicable.
     pub date: Option<String>,
+
+    /// A textual note for the line item, if applicable (e.g., the reason for an adjusted quantity).
+    pub note: Option<String>,
+
+    /// The first day of the period of the line item in ISO 8601 format (YYYY-MM-DD), if the line item spans more
+    /// than a single day. Takes precedence over the `date` field.
+    pub start_date: Option<String>,
+
+    /// The last day of the period of the line item in ISO 8601 format (YYYY-MM-DD). Only allowed together with the
+    /// `start_date` field. The period ends on the start date, if not given.
+    pub end_date: Option<String>,
+
+    /// The line number of the buyer's purchase order that this line item refers to, if applicable. Buyers use it to
+    /// match invoice lines against the lines of their order.
+    pub order_line_id: Option<String>,
+
+    /// The accounting cost code (e.g., cost center) of the buyer that this line item should be booked on, if
+    /// applicable.
+    pub accounting_cost: Option<String>,
+
+    /// The project that the line item belongs to, if applicable. It can be used to aggregate line items per project.
+    pub project: Option<String>,
+}
+
+/// A complete invoice with the supplier, the buyer, the metadata and the line items, e.g., as loaded from a single
+/// JSON or TOML document for templating or archiving invoices. The XRechnung structure for it is created by passing
+/// the fields to the [`create`][crate::create] function.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Invoice {
+    /// The supplier that issues the invoice.
+    pub supplier: Supplier,
+
+    /// The buyer that the invoice is addressed to.
+    pub buyer: Buyer,
+
+    /// The metadata of the invoice.
+    pub bill: Bill,
+
+    /// The line items of the invoice.
+    #[serde(default)]
+    pub lines: Vec<InvoiceHoursElement>,
+}
+
+/// Parses a quantity of hours from the given string and returns it as decimal hours.
+///
+/// Besides decimal hours (e.g., "1.5" or "1.5h"), the string can contain minutes (e.g., "90m" or "90min"), hours and
+/// minutes (e.g., "1h30m") or a duration in the format "HH:MM" (e.g., "1:30"), as exported by most time trackers.
+///
+/// The function returns an error in case that the string does not match any of these formats.
+pub fn parse_hours(input: &str) -> Result<f32, String> {
+    let error = || {
+        format!(
+            "Invalid quantity '{input}': expected decimal hours (e.g., '1.5'), minutes (e.g., '90m') or a duration (e.g., '1:30')."
+        )
+    };
+
+    let value = input.trim();
+    let number = |value: &str| value.trim().parse::<f32>().map_err(|_| error());
+
+    if let Some((hours, minutes)) = value.split_once(':') {
+        // duration in the format HH:MM
+        let hours: u32 = hours.trim().parse().map_err(|_| error())?;
+        let minutes: u32 = minutes.trim().parse().map_err(|_| error())?;
+        if minutes >= 60 {
+            return Err(error());
+        }
+        Ok(hours as f32 + minutes as f32 / 60.0)
+    } else if let Some(minutes) = value
+        .strip_suffix("min")
+        .or_else(|| value.strip_suffix('m'))
+    {
+        match minutes.split_once('h') {
+            // hours and minutes, e.g., 1h30m
+            Some((hours, minutes)) => Ok(number(hours)? + number(minutes)? / 60.0),
+            None => Ok(number(minutes)? / 60.0),
+        }
+    } else if let Some(hours) = value.strip_suffix('h') {
+        number(hours)
+    } else {
+        number(value)
+    }
+}
+
+/// Deserializes a quantity of hours either from a number or from a string as accepted by [`parse_hours`].
+fn deserialize_hours<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    struct HoursVisitor;
+
+    impl Visitor<'_> for HoursVisitor {
+        type Value = f32;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a quantity of hours as number or as string like '90m' or '1:30'")
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<f32, E> {
+            Ok(value as f32)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<f32, E> {
+            Ok(value as f32)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<f32, E> {
+            Ok(value as f32)
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<f32, E> {
+            parse_hours(value).map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_any(HoursVisitor)
+}
+
+impl InvoiceHoursElement {
+    /// Creates a line item with the given name, quantity of hours and hourly rate, without any of the optional data,
+    /// which can be set with the struct update syntax.
+    pub fn new(name: &str, quantity: f32, hourly_rate: f32) -> Self {
+        InvoiceHoursElement {
+            name: name.to_string(),
+            quantity,
+            hourly_rate,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the period of the line item as determined by either the `start_date` and `end_date` fields or by the
+    /// `date` field, if applicable.
+    ///
+    /// The function returns an error in case that one of the dates cannot be parsed, if only an end date is given or
+    /// if the end date lies before the start date.
+    pub fn period(&self) -> Result<Option<Period>, Box<dyn std::error::Error>> {
+        let (start, end) = match (&self.start_date, &self.end_date, &self.date) {
+            (Some(start), end, _) => (start, end.as_ref().unwrap_or(start)),
+            (None, Some(_), _) => {
+                return Err(
+                    format!("Line '{}' has an end date, but no start date.", self.name).into(),
+                );
+            }
+            (None, None, Some(date)) => (date, date),
+            (None, None, None) => return Ok(None),
+        };
+
+        let period = Period {
+            start: NaiveDate::parse_from_str(start, "%Y-%m-%d")?,
+            end: NaiveDate::parse_from_str(end, "%Y-%m-%d")?,
+        };
+
+        if period.end < period.start {
+            return Err(format!("Line '{}' ends before it starts.", self.name).into());
+        }
+
+        Ok(Some(period))
+    }
+}
+
+/// Calculates the due date of a bill from the issue date: the bill is due the configured amount of days after the issue
+/// date, moved to the next business day (not on a weekend or public holiday), if configured for the buyer.
+fn due_date(issue_date: NaiveDate, config: &Config) -> NaiveDate {
+    let mut due_date = issue_date + Days::new(config.buyer.due_after_days as u64);
+    if config.buyer.due_on_business_day {
+        while matches!(due_date.weekday(), Weekday::Sat | Weekday::Sun)
+            || config.holidays.contains(&due_date)
+        {
+            due_date = due_date + Days::new(1);
+        }
+    }
+    due_date
 }
 
 impl Bill {
@@ -63,12 +447,309 @@ impl Bill {
             number,
             currency: config.currency.clone(),
             vat_percent: config.vat_percent,
-            issue_date: issue_date,
+            tax_category: TaxCategory::Standard,
+            issue_date,
 
-            // bill is due configured amount of days after issue date
-            due_date: (issue_date + Days::new(config.buyer.due_after_days as u64)),
+            due_date: due_date(issue_date, config),
+            amount_rounding: config.amount_rounding,
+            cash_discounts: Vec::new(),
 
             period,
+            language: config.buyer.language.clone(),
+        }
+    }
+
+    /// Returns the VAT percentage that is charged for the invoice, which is zero for all but the standard rated
+    /// category.
+    pub fn tax_percent(&self) -> f32 {
+        match self.tax_category {
+            TaxCategory::Standard => self.vat_percent,
+            _ => 0.0,
+        }
+    }
+
+    /// Replaces the placeholders in the given text with the metadata of the bill and the name of the buyer. Texts
+    /// like "Consulting services {month} {year}" can be used for item names and notes that are the same for every
+    /// invoice then. The supported placeholders are:
+    ///
+    /// - `{month}`: name of the month of the billing period (or of the issue date without period) in the language of
+    ///   the bill, e.g., "January" or "Januar"
+    /// - `{year}`: year of the billing period (or of the issue date without period)
+    /// - `{period}`: billing period, e.g., "2025-01-01 to 2025-01-31" (or the issue date without period)
+    /// - `{number}`: number of the invoice
+    /// - `{issue_date}`: issue date of the invoice
+    /// - `{due_date}`: due date of the invoice
+    /// - `{buyer}`: name of the buyer
+    ///
+    /// Other texts in braces are kept unchanged. Month names and the text of the period are given in German, if that is
+    /// the language of the bill, and in English otherwise.
+    pub fn expand_placeholders(&self, text: &str, buyer: &str) -> String {
+        if !text.contains('{') {
+            return text.to_string();
         }
+
+        let german = self.language.as_deref() == Some("de");
+        let (month, period) = match &self.period {
+            Some(period) => (
+                period.start,
+                format!(
+                    "{} {} {}",
+                    period.start,
+                    if german { "bis" } else { "to" },
+                    period.end
+                ),
+            ),
+            None => (self.issue_date, self.issue_date.to_string()),
+        };
+        let month_name = if german {
+            GERMAN_MONTHS[month.month0() as usize].to_string()
+        } else {
+            month.format("%B").to_string()
+        };
+
+        text.replace("{month}", &month_name)
+            .replace("{year}", &month.format("%Y").to_string())
+            .replace("{period}", &period)
+            .replace("{number}", &self.number)
+            .replace("{issue_date}", &self.issue_date.to_string())
+            .replace("{due_date}", &self.due_date.to_string())
+            .replace("{buyer}", buyer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bill, CashDiscount, InvoiceHoursElement, Period, TaxCategory, parse_hours};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_parse_hours() {
+        assert_eq!(parse_hours("1.5"), Ok(1.5));
+        assert_eq!(parse_hours("2h"), Ok(2.0));
+        assert_eq!(parse_hours("90m"), Ok(1.5));
+        assert_eq!(parse_hours("45 min"), Ok(0.75));
+        assert_eq!(parse_hours("1h30m"), Ok(1.5));
+        assert_eq!(parse_hours("1:30"), Ok(1.5));
+        assert_eq!(parse_hours(" 0:15 "), Ok(0.25));
+    }
+
+    #[test]
+    fn test_error_on_invalid_hours() {
+        assert!(parse_hours("1:75").is_err());
+        assert!(parse_hours("abc").is_err());
+        assert!(parse_hours("").is_err());
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        let mut bill = Bill {
+            number: "2025-0001".to_string(),
+            currency: "EUR".parse().unwrap(),
+            vat_percent: 19.0,
+            tax_category: TaxCategory::Standard,
+            issue_date: NaiveDate::from_ymd_opt(2025, 2, 3).unwrap(),
+            due_date: NaiveDate::from_ymd_opt(2025, 2, 17).unwrap(),
+            cash_discounts: Vec::new(),
+            amount_rounding: Default::default(),
+            period: Some(Period {
+                start: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            }),
+            language: None,
+        };
+
+        assert_eq!(
+            bill.expand_placeholders("Consulting services {month} {year} ({period})", "Client"),
+            "Consulting services January 2025 (2025-01-01 to 2025-01-31)"
+        );
+        assert_eq!(
+            bill.expand_placeholders(
+                "Invoice {number} for {buyer} of {issue_date} due {due_date} {unknown}",
+                "Client"
+            ),
+            "Invoice 2025-0001 for Client of 2025-02-03 due 2025-02-17 {unknown}"
+        );
+
+        bill.language = Some("de".to_string());
+        assert_eq!(
+            bill.expand_placeholders("Beratung {month} {year} ({period})", "Client"),
+            "Beratung Januar 2025 (2025-01-01 bis 2025-01-31)"
+        );
+
+        bill.language = None;
+        bill.period = None;
+        assert_eq!(
+            bill.expand_placeholders("{month} {year}: {period}", "Client"),
+            "February 2025: 2025-02-03"
+        );
+    }
+
+    #[test]
+    fn test_due_date_on_business_day() {
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2025, 12, day).unwrap();
+
+        // 20 days after 2025-12-06 is Friday, 2025-12-26, which is a holiday, followed by the weekend
+        assert_eq!(super::due_date(date(6), &config), date(29));
+
+        // 20 days after 2025-12-01 is Sunday, 2025-12-21
+        assert_eq!(super::due_date(date(1), &config), date(22));
+
+        config.buyer.due_on_business_day = false;
+        assert_eq!(super::due_date(date(1), &config), date(21));
+    }
+
+    #[test]
+    fn test_tax_category() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        let mut bill = Bill::new("2025-0001".to_string(), date, None, &config);
+        assert_eq!(bill.tax_percent(), 19.0);
+
+        bill.tax_category = TaxCategory::ReverseCharge;
+        assert_eq!(bill.tax_percent(), 0.0);
+        assert_eq!(bill.tax_category.code(), "AE");
+        assert_eq!(
+            bill.tax_category.exemption_reason(Some("de")).as_deref(),
+            Some("Steuerschuldnerschaft des Leistungsempfängers")
+        );
+        assert_eq!(TaxCategory::Standard.exemption_reason(None), None);
+    }
+
+    #[test]
+    fn test_parse_cash_discounts() {
+        let discounts = super::parse_cash_discounts("3%:7d, 2.5 % : 14d").unwrap();
+        assert_eq!(
+            discounts,
+            [
+                CashDiscount {
+                    percent: 3.0,
+                    days: 7
+                },
+                CashDiscount {
+                    percent: 2.5,
+                    days: 14
+                }
+            ]
+        );
+        assert_eq!(discounts[1].to_string(), "#SKONTO#TAGE=14#PROZENT=2.50#");
+
+        assert!(super::parse_cash_discounts("3%").is_err());
+        assert!(super::parse_cash_discounts("3:7").is_err());
+        assert!(super::parse_cash_discounts("120%:7d").is_err());
+        assert!(super::parse_cash_discounts("2%:0d").is_err());
+        assert!(super::parse_cash_discounts("").is_err());
+    }
+
+    #[test]
+    fn test_invoice_round_trip() {
+        let document = r#"
+            [supplier]
+            name = "Hans Muster"
+            tax_identification = "DE123456789"
+            phone = "+49 123 456789"
+            email = "hans@muster.example"
+            iban = "DE02120300000000202051"
+            bic = "BYLADEM1001"
+            address = { address_line = "Musterstraße 1", city = "Musterstadt", post_code = "12345", country_code = "DE" }
+
+            [buyer]
+            name = "Client Company"
+            tax_identification = "DE987654321"
+            email = "billing@client.example"
+            reference = "PO-2025-17"
+            due_after_days = 14
+            rounding = { increment = 0.25, mode = "nearest" }
+            address = { address_line = "Kundenweg 2", city = "Kundenstadt", post_code = "54321", country_code = "DE" }
+
+            [bill]
+            number = "2025-0001"
+            currency = "EUR"
+            vat_percent = 19.0
+            tax_category = { exempt = "Kleinunternehmer gemäß § 19 UStG" }
+            issue_date = "2025-01-31"
+            due_date = "2025-02-14"
+            cash_discounts = [{ percent = 2.0, days = 7 }]
+            period = { start = "2025-01-01", end = "2025-01-31" }
+
+            [[lines]]
+            name = "Development"
+            quantity = "1:30"
+            hourly_rate = 100.0
+            date = "2025-01-02"
+        "#;
+
+        let invoice: super::Invoice = toml::from_str(document).unwrap();
+        assert_eq!(invoice.buyer.rounding.as_ref().unwrap().increment, 0.25);
+        assert_eq!(
+            invoice.bill.tax_category,
+            TaxCategory::Exempt("Kleinunternehmer gemäß § 19 UStG".to_string())
+        );
+        assert_eq!(
+            invoice.bill.period.as_ref().unwrap().end,
+            NaiveDate::from_ymd_opt(2025, 1, 31).unwrap()
+        );
+        assert_eq!(invoice.lines[0].quantity, 1.5);
+
+        // the emitted document is loaded to the same invoice
+        let emitted = toml::to_string(&invoice).unwrap();
+        let reloaded: super::Invoice = toml::from_str(&emitted).unwrap();
+        assert_eq!(reloaded, invoice);
+
+        // the optional fields of the bill have defaults
+        let bill: Bill = toml::from_str(
+            "number = \"2025-0002\"\ncurrency = \"EUR\"\nvat_percent = 19.0\n\
+             issue_date = \"2025-02-28\"\ndue_date = \"2025-03-14\"",
+        )
+        .unwrap();
+        assert_eq!(bill.tax_category, TaxCategory::Standard);
+        assert!(bill.cash_discounts.is_empty() && bill.period.is_none());
+    }
+
+    #[test]
+    fn test_period() {
+        let date = |month: u32, day: u32| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let january = Period::new(date(1, 1), date(1, 31));
+        assert_eq!(Period::month_of(date(1, 15)), january);
+        assert_eq!(Period::month_of(date(1, 31)), january);
+        assert_eq!(
+            Period::month_of(date(2, 10)),
+            Period::new(date(2, 1), date(2, 28))
+        );
+        assert_eq!(Period::previous_month(date(2, 28)), january);
+        assert_eq!(
+            Period::previous_month(date(1, 1)),
+            Period::new(
+                NaiveDate::from_ymd_opt(2024, 12, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()
+            )
+        );
+
+        assert!(january.contains(date(1, 1)));
+        assert!(january.contains(date(1, 31)));
+        assert!(!january.contains(date(2, 1)));
+        assert!(january.contains_period(&Period::new(date(1, 6), date(1, 10))));
+        assert!(!january.contains_period(&Period::new(date(1, 27), date(2, 2))));
+
+        let line = |date: Option<&str>, start_date: Option<&str>, end_date: Option<&str>| {
+            InvoiceHoursElement {
+                date: date.map(str::to_string),
+                start_date: start_date.map(str::to_string),
+                end_date: end_date.map(str::to_string),
+                ..InvoiceHoursElement::new("Development", 1.0, 100.0)
+            }
+        };
+        let mut lines = vec![
+            line(Some("2025-01-10"), None, None),
+            line(None, None, None),
+            line(None, Some("2025-01-06"), Some("2025-01-08")),
+        ];
+        assert_eq!(
+            Period::from_lines(&lines).unwrap(),
+            Some(Period::new(date(1, 6), date(1, 10)))
+        );
+        assert_eq!(Period::from_lines(&lines[1..2]).unwrap(), None);
+        lines.push(line(Some("2025-13-01"), None, None));
+        assert!(Period::from_lines(&lines).is_err());
     }
 }
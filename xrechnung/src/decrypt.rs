@@ -0,0 +1,114 @@
+//! Decryption of configuration files that are encrypted with [age](https://age-encryption.org) or
+//! [sops](https://getsops.io), so that bank details and other sensitive data need not be stored in plain text. The
+//! files are decrypted by running the `age` or `sops` tools, which need to be installed.
+//!
+//! Files encrypted with age are recognized by the extension `.age` after the extension of the format (e.g.,
+//! `config.toml.age`). They are decrypted with the identity file given by the environment variable
+//! `XRECHNUNG_AGE_IDENTITY`, or otherwise with a passphrase that age prompts for. Files encrypted with sops are
+//! recognized by `.sops` before the extension of the format (e.g., `config.sops.yaml`). They are decrypted with the
+//! keys that sops finds itself (e.g., via `SOPS_AGE_KEY_FILE`).
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The environment variable with the path to the age identity file for decrypting configuration files.
+const AGE_IDENTITY_VARIABLE: &str = "XRECHNUNG_AGE_IDENTITY";
+
+/// Returns the command for decrypting the given file, if it is encrypted.
+fn decryption_command(
+    path: &Path,
+    identity: Option<String>,
+) -> Option<(&'static str, Vec<String>)> {
+    let name = path.file_name()?.to_str()?.to_lowercase();
+    let file = path.to_string_lossy().into_owned();
+
+    if name.ends_with(".age") {
+        let mut args = vec!["--decrypt".to_string()];
+        if let Some(identity) = identity {
+            args.extend(["--identity".to_string(), identity]);
+        }
+        args.push(file);
+        Some(("age", args))
+    } else if name.contains(".sops.") {
+        Some(("sops", vec!["--decrypt".to_string(), file]))
+    } else {
+        None
+    }
+}
+
+/// Returns the path that determines the format of the given (possibly encrypted) file, i.e., the path without the
+/// extension `.age` of encrypted files.
+pub fn format_path(path: &Path) -> PathBuf {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("age") => path.with_extension(""),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Decrypts the given file, if it is encrypted, and returns its content. Returns `None` for files that are not
+/// encrypted.
+///
+/// The function returns an error in case that the decryption tool could not be run or failed.
+pub fn decrypt(path: &Path) -> Option<Result<String, Box<dyn std::error::Error>>> {
+    let (program, args) = decryption_command(path, std::env::var(AGE_IDENTITY_VARIABLE).ok())?;
+    Some(run(program, &args, path))
+}
+
+fn run(program: &str, args: &[String], path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    // the standard input and error stay connected to the terminal, so that the tools can prompt for passphrases
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|error| {
+            format!(
+                "Could not run {program} to decrypt the configuration file '{}': {error}",
+                path.display()
+            )
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "Could not decrypt the configuration file '{}' with {program}.",
+            path.display()
+        )
+        .into());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_decryption_command() {
+        let (program, args) =
+            super::decryption_command(Path::new("config.toml.age"), Some("key.txt".to_string()))
+                .unwrap();
+        assert_eq!(program, "age");
+        assert_eq!(
+            args,
+            ["--decrypt", "--identity", "key.txt", "config.toml.age"]
+        );
+
+        let (program, args) =
+            super::decryption_command(Path::new("config.sops.yaml"), None).unwrap();
+        assert_eq!(program, "sops");
+        assert_eq!(args, ["--decrypt", "config.sops.yaml"]);
+
+        assert!(super::decryption_command(Path::new("config.toml"), None).is_none());
+    }
+
+    #[test]
+    fn test_format_path() {
+        assert_eq!(
+            super::format_path(Path::new("dir/config.yaml.age")),
+            PathBuf::from("dir/config.yaml")
+        );
+        assert_eq!(
+            super::format_path(Path::new("config.sops.json")),
+            PathBuf::from("config.sops.json")
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Warnings about invoices, i.e., problems that do not prevent creating an invoice, but which are likely mistakes in the
+//! invoice lines. The warnings are returned by the [`create`][crate::create] function together with the invoice:
+//!
+//! - lines with an amount of zero (e.g., a quantity or rate that was forgotten)
+//! - lines without a date, while other lines of the invoice have one
+//! - lines whose hourly rate is less than half or more than twice the median rate of the invoice (for invoices with at
+//!   least three lines)
+
+use std::fmt;
+
+use crate::data::InvoiceHoursElement;
+
+/// The minimum number of invoice lines for determining the usual hourly rate of an invoice.
+const MIN_LINES_FOR_RATES: usize = 3;
+
+/// The factor by which the hourly rate of a line may differ from the usual rate of the invoice without a warning.
+const RATE_FACTOR: f32 = 2.0;
+
+/// A problem of an invoice that does not prevent creating it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    /// The identifier of the invoice line with the problem, if the problem concerns a single line.
+    pub line: Option<String>,
+
+    /// The description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Checks the given invoice lines, which are identified by the given identifiers, and returns the warnings about them.
+pub(crate) fn check_lines(ids: &[String], lines: &[InvoiceHoursElement]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut warn = |id: &str, message: String| {
+        warnings.push(Warning {
+            line: Some(id.to_string()),
+            message,
+        })
+    };
+
+    let has_date = |line: &InvoiceHoursElement| line.date.is_some() || line.start_date.is_some();
+    let any_dates = lines.iter().any(has_date);
+
+    let mut rates: Vec<f32> = lines
+        .iter()
+        .map(|line| line.hourly_rate)
+        .filter(|rate| *rate > 0.0)
+        .collect();
+    rates.sort_by(f32::total_cmp);
+    let median = (rates.len() >= MIN_LINES_FOR_RATES).then(|| rates[rates.len() / 2]);
+
+    for (id, line) in ids.iter().zip(lines) {
+        let name = &line.name;
+        if line.quantity * line.hourly_rate == 0.0 {
+            warn(
+                id,
+                format!("The invoice line {id} ('{name}') has an amount of zero."),
+            );
+        }
+        if any_dates && !has_date(line) {
+            warn(
+                id,
+                format!(
+                    "The invoice line {id} ('{name}') has no date, unlike other lines of the invoice."
+                ),
+            );
+        }
+        if let Some(median) = median
+            && line.hourly_rate > 0.0
+            && (line.hourly_rate > median * RATE_FACTOR || line.hourly_rate * RATE_FACTOR < median)
+        {
+            warn(
+                id,
+                format!(
+                    "The hourly rate {:.2} of the invoice line {id} ('{name}') differs strongly from the usual rate \
+                     {median:.2} of the invoice.",
+                    line.hourly_rate
+                ),
+            );
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::InvoiceHoursElement;
+
+    #[test]
+    fn test_check_lines() {
+        let line = |quantity: f32, hourly_rate: f32, date: Option<&str>| InvoiceHoursElement {
+            name: "Development".to_string(),
+            quantity,
+            hourly_rate,
+            date: date.map(str::to_string),
+            ..Default::default()
+        };
+        let ids: Vec<String> = (1..=4).map(|id| id.to_string()).collect();
+
+        // consistent lines give no warnings
+        let lines = vec![
+            line(2.0, 100.0, Some("2025-01-02")),
+            line(3.0, 110.0, Some("2025-01-03")),
+            line(1.0, 95.0, Some("2025-01-06")),
+        ];
+        assert!(super::check_lines(&ids, &lines).is_empty());
+
+        let lines = vec![
+            line(2.0, 100.0, Some("2025-01-02")),
+            line(0.0, 110.0, Some("2025-01-03")),
+            line(1.0, 100.0, None),
+            line(1.0, 1000.0, Some("2025-01-07")),
+        ];
+        let warnings: Vec<String> = super::check_lines(&ids, &lines)
+            .iter()
+            .map(|warning| format!("{}: {warning}", warning.line.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                "2: The invoice line 2 ('Development') has an amount of zero.",
+                "3: The invoice line 3 ('Development') has no date, unlike other lines of the invoice.",
+                "4: The hourly rate 1000.00 of the invoice line 4 ('Development') differs strongly from the usual \
+                 rate 110.00 of the invoice."
+            ]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Checks of the format of identifiers in the configuration, like IBAN, BIC, VAT identification numbers, country codes
+//! and email addresses. The checks only cover the syntax (and the checksum of IBANs), but not whether the identifiers
+//! actually exist.
+
+/// The country codes of ISO 3166-1 (Alpha-2), sorted alphabetically.
+const COUNTRY_CODES: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// The formats of the VAT identification numbers per country prefix (after the prefix), where `9` stands for a digit,
+/// `@` for a letter, `*` for a letter or digit and all other characters for themselves.
+const VAT_ID_FORMATS: [(&str, &[&str]); 31] = [
+    ("AT", &["U99999999"]),
+    ("BE", &["0999999999", "1999999999"]),
+    ("BG", &["999999999", "9999999999"]),
+    (
+        "CH",
+        &[
+            "E999999999",
+            "E999999999MWST",
+            "E999999999TVA",
+            "E999999999IVA",
+        ],
+    ),
+    ("CY", &["99999999@"]),
+    ("CZ", &["99999999", "999999999", "9999999999"]),
+    ("DE", &["999999999"]),
+    ("DK", &["99999999"]),
+    ("EE", &["999999999"]),
+    ("EL", &["999999999"]),
+    ("ES", &["*9999999*"]),
+    ("FI", &["99999999"]),
+    ("FR", &["**999999999"]),
+    ("GB", &["999999999", "999999999999", "GD999", "HA999"]),
+    ("HR", &["99999999999"]),
+    ("HU", &["99999999"]),
+    ("IE", &["9*99999@", "9*99999@@"]),
+    ("IT", &["99999999999"]),
+    ("LT", &["999999999", "999999999999"]),
+    ("LU", &["99999999"]),
+    ("LV", &["99999999999"]),
+    ("MT", &["99999999"]),
+    ("NL", &["999999999B99"]),
+    ("NO", &["999999999MVA"]),
+    ("PL", &["9999999999"]),
+    ("PT", &["999999999"]),
+    (
+        "RO",
+        &[
+            "99",
+            "999",
+            "9999",
+            "99999",
+            "999999",
+            "9999999",
+            "99999999",
+            "999999999",
+            "9999999999",
+        ],
+    ),
+    ("SE", &["999999999999"]),
+    ("SI", &["99999999"]),
+    ("SK", &["9999999999"]),
+    ("XI", &["999999999", "999999999999", "GD999", "HA999"]),
+];
+
+/// Returns whether the given code is a country code of ISO 3166-1 (Alpha-2), e.g., "DE".
+pub fn is_country_code(code: &str) -> bool {
+    COUNTRY_CODES.binary_search(&code).is_ok()
+}
+
+/// Checks the format and the checksum of the given IBAN (International Bank Account Number). Spaces are ignored.
+pub fn check_iban(iban: &str) -> Result<(), String> {
+    let iban: String = iban.chars().filter(|c| *c != ' ').collect();
+    let valid_format = (15..=34).contains(&iban.len())
+        && iban.chars().take(2).all(|c| c.is_ascii_uppercase())
+        && iban.chars().skip(2).take(2).all(|c| c.is_ascii_digit())
+        && iban
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    if !valid_format {
+        return Err(format!(
+            "'{iban}' is no IBAN (expected country code, check digits and up to 30 letters or digits)"
+        ));
+    }
+    if !is_country_code(&iban[..2]) {
+        return Err(format!("'{iban}' has an unknown country code"));
+    }
+
+    // the checksum is calculated over the rearranged IBAN, with letters replaced by the numbers 10 to 35
+    let remainder = iban[4..]
+        .chars()
+        .chain(iban[..4].chars())
+        .fold(0, |remainder, c| {
+            let value = c.to_digit(36).unwrap_or_default();
+            let factor = if value < 10 { 10 } else { 100 };
+            (remainder * factor + value) % 97
+        });
+    if remainder != 1 {
+        return Err(format!("'{iban}' has an invalid checksum"));
+    }
+    Ok(())
+}
+
+/// Checks the format of the given BIC (Business Identifier Code): bank code, country code, location code and optional
+/// branch code.
+pub fn check_bic(bic: &str) -> Result<(), String> {
+    let valid_format = (bic.len() == 8 || bic.len() == 11)
+        && bic.chars().take(4).all(|c| c.is_ascii_uppercase())
+        && bic
+            .chars()
+            .skip(4)
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    if !valid_format {
+        return Err(format!(
+            "'{bic}' is no BIC (expected 8 or 11 upper case letters or digits, like 'DEUTDEFFXXX')"
+        ));
+    }
+    if !is_country_code(&bic[4..6]) {
+        return Err(format!("'{bic}' has an unknown country code"));
+    }
+    Ok(())
+}
+
+/// Checks the format of the given tax identification, if it is a VAT identification number with the prefix of a
+/// European country (e.g., "DE123456789"). Other tax identifications (like national tax numbers) are not checked.
+pub fn check_vat_id(vat_id: &str) -> Result<(), String> {
+    let vat_id: String = vat_id.chars().filter(|c| *c != ' ').collect();
+    let Some((prefix, formats)) = VAT_ID_FORMATS
+        .iter()
+        .find(|(prefix, _)| vat_id.starts_with(prefix))
+    else {
+        return Ok(());
+    };
+
+    let number = &vat_id[prefix.len()..];
+    if formats.iter().any(|format| matches_format(format, number)) {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{vat_id}' does not match the format of VAT identification numbers of '{prefix}' (e.g., '{prefix}{}')",
+            formats[0].replace('9', "1").replace(['@', '*'], "A")
+        ))
+    }
+}
+
+/// Returns whether the given value matches the format as used for the VAT identification numbers.
+fn matches_format(format: &str, value: &str) -> bool {
+    format.len() == value.len()
+        && format.chars().zip(value.chars()).all(|(f, c)| match f {
+            '9' => c.is_ascii_digit(),
+            '@' => c.is_ascii_uppercase(),
+            '*' => c.is_ascii_uppercase() || c.is_ascii_digit(),
+            f => f == c,
+        })
+}
+
+/// Checks the format and the check digits of the given Leitweg-ID, the identifier of German public-sector buyers for
+/// routing invoices: a coarse address of 2 to 12 digits, an optional fine address of up to 30 letters or digits and two
+/// check digits, separated by hyphens (e.g., "04011000-1234512345-06").
+pub fn check_leitweg_id(leitweg_id: &str) -> Result<(), String> {
+    let parts: Vec<&str> = leitweg_id.split('-').collect();
+    let (coarse, fine, check_digits) = match parts.as_slice() {
+        [coarse, check_digits] => (*coarse, "", *check_digits),
+        [coarse, fine, check_digits] => (*coarse, *fine, *check_digits),
+        _ => ("", "", ""),
+    };
+    let valid_format = (2..=12).contains(&coarse.len())
+        && coarse.chars().all(|c| c.is_ascii_digit())
+        && fine.len() <= 30
+        && fine.chars().all(|c| c.is_ascii_alphanumeric())
+        && check_digits.len() == 2
+        && check_digits.chars().all(|c| c.is_ascii_digit());
+    if !valid_format || (parts.len() == 3 && fine.is_empty()) {
+        return Err(format!(
+            "'{leitweg_id}' is no Leitweg-ID (expected coarse address, optional fine address and check digits, like \
+             '04011000-1234512345-06')"
+        ));
+    }
+
+    // the check digits are calculated according to ISO 7064 (Mod 97-10), with letters replaced by 10 to 35
+    let remainder = coarse
+        .chars()
+        .chain(fine.chars())
+        .chain(check_digits.chars())
+        .fold(0, |remainder, c| {
+            let value = c.to_digit(36).unwrap_or_default();
+            let factor = if value < 10 { 10 } else { 100 };
+            (remainder * factor + value) % 97
+        });
+    if remainder != 1 {
+        return Err(format!("'{leitweg_id}' has invalid check digits"));
+    }
+    Ok(())
+}
+
+/// Checks the format of the given email address (a local part, an `@` and a domain with at least one dot).
+pub fn check_email(email: &str) -> Result<(), String> {
+    let valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.contains(char::is_whitespace)
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("'{email}' is no email address"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_country_codes() {
+        assert!(super::COUNTRY_CODES.is_sorted());
+        assert!(super::is_country_code("DE"));
+        assert!(super::is_country_code("CH"));
+        assert!(!super::is_country_code("EL"));
+        assert!(!super::is_country_code("de"));
+    }
+
+    #[test]
+    fn test_check_iban() {
+        assert!(super::check_iban("DE02120300000000202051").is_ok());
+        assert!(super::check_iban("DE02 1203 0000 0000 2020 51").is_ok());
+        assert!(super::check_iban("GB33BUKB20201555555555").is_ok());
+        assert_eq!(
+            super::check_iban("DE03120300000000202051").unwrap_err(),
+            "'DE03120300000000202051' has an invalid checksum"
+        );
+        assert!(super::check_iban("DE0212030000").is_err());
+        assert!(super::check_iban("XX02120300000000202051").is_err());
+    }
+
+    #[test]
+    fn test_check_bic() {
+        assert!(super::check_bic("DEUTDEFF").is_ok());
+        assert!(super::check_bic("DEUTDEFF500").is_ok());
+        assert!(super::check_bic("DEUTDEF").is_err());
+        assert!(super::check_bic("DEUTXXFF500").is_err());
+        assert!(super::check_bic("deutdeff").is_err());
+    }
+
+    #[test]
+    fn test_check_vat_id() {
+        assert!(super::check_vat_id("DE123456789").is_ok());
+        assert!(super::check_vat_id("ATU12345678").is_ok());
+        assert!(super::check_vat_id("NL123456789B01").is_ok());
+        assert!(super::check_vat_id("CHE123456789MWST").is_ok());
+        assert!(super::check_vat_id("12/345/67890").is_ok()); // national tax number
+        assert_eq!(
+            super::check_vat_id("DE12345678").unwrap_err(),
+            "'DE12345678' does not match the format of VAT identification numbers of 'DE' (e.g., 'DE111111111')"
+        );
+        assert!(super::check_vat_id("ATU1234567").is_err());
+    }
+
+    #[test]
+    fn test_check_email() {
+        assert!(super::check_email("mail@client.example.com").is_ok());
+        assert!(super::check_email("mail.example.com").is_err());
+        assert!(super::check_email("mail@localhost").is_err());
+        assert!(super::check_email("a b@example.com").is_err());
+    }
+
+    #[test]
+    fn test_check_leitweg_id() {
+        assert!(super::check_leitweg_id("04011000-1234512345-06").is_ok());
+        assert!(super::check_leitweg_id("991-33333TEST-33").is_ok());
+        assert!(super::check_leitweg_id("04011000-12345-03").is_ok());
+        assert_eq!(
+            super::check_leitweg_id("04011000-1234512345-07").unwrap_err(),
+            "'04011000-1234512345-07' has invalid check digits"
+        );
+        assert!(super::check_leitweg_id("04011000").is_err());
+        assert!(super::check_leitweg_id("A4011000-1234512345-06").is_err());
+        assert!(super::check_leitweg_id("04011000--06").is_err());
+    }
+}
@@ -0,0 +1,344 @@
+//! A minimal parser for JSON documents (RFC 8259), as needed for reading configuration files and invoice lines. Values
+//! are written as compact JSON documents with their [`Display`][std::fmt::Display] implementation.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A value of a JSON document. Numbers are kept as they were written in the document, and the members of objects are
+/// kept in the order of the document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Returns the value of the member with the given key, if this is an object that has such a member.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Returns the string, if this is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Returns the number, if this is a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements, if this is an array.
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the given string as JSON string, with quotes and escaped special characters.
+fn write_string(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in string.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Number(number) => f.write_str(number),
+            Value::String(string) => write_string(f, string),
+            Value::Array(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                f.write_str("]")
+            }
+            Value::Object(members) => {
+                f.write_str("{")?;
+                for (index, (name, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+/// Parses the given JSON document.
+pub fn parse(document: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: document.chars().peekable(),
+        line: 1,
+    };
+
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    match parser.chars.peek().copied() {
+        None => Ok(value),
+        Some(c) => Err(parser.error(&format!("unexpected character '{c}' after the document"))),
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("Invalid JSON in line {}: {message}.", self.line)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        for c in expected.chars() {
+            if self.next() != Some(c) {
+                return Err(self.error(&format!("expected '{expected}'")));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('n') => self.expect("null").map(|_| Value::Null),
+            Some('t') => self.expect("true").map(|_| Value::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Value::Bool(false)),
+            Some('"') => self.parse_string().map(Value::String),
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_object(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) => Err(self.error(&format!("unexpected character '{c}'"))),
+            None => Err(self.error("unexpected end of the document")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, String> {
+        let mut number = String::new();
+        while let Some(c) = self
+            .chars
+            .peek()
+            .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            number.push(*c);
+            self.next();
+        }
+
+        match number.parse::<f64>() {
+            Ok(_) => Ok(Value::Number(number)),
+            Err(_) => Err(self.error(&format!("invalid number '{number}'"))),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.next() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('/') => string.push('/'),
+                    Some('b') => string.push('\u{8}'),
+                    Some('f') => string.push('\u{c}'),
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some('u') => {
+                        let high = self.parse_hex()?;
+                        let code = if (0xD800..0xDC00).contains(&high) {
+                            // characters outside the basic multilingual plane are encoded as surrogate pairs
+                            self.expect("\\u")?;
+                            let low = self.parse_hex()?;
+                            0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+                        } else {
+                            high
+                        };
+                        string.push(
+                            char::from_u32(code)
+                                .ok_or(self.error("invalid unicode escape sequence"))?,
+                        );
+                    }
+                    _ => return Err(self.error("invalid escape sequence")),
+                },
+                Some(c) => string.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn parse_hex(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or(self.error("invalid unicode escape sequence"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_array(&mut self) -> Result<Value, String> {
+        self.expect("[")?;
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => (),
+                Some(']') => return Ok(Value::Array(values)),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, String> {
+        self.expect("{")?;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(":")?;
+            members.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => (),
+                Some('}') => return Ok(Value::Object(members)),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Value;
+
+    #[test]
+    fn test_parse() {
+        let value = super::parse(
+            r#"[{"name": "Café \"Service\"", "quantity": 1.5e0, "note": null, "paid": false}, []]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value,
+            Value::Array(vec![
+                Value::Object(vec![
+                    (
+                        "name".to_string(),
+                        Value::String("Café \"Service\"".to_string())
+                    ),
+                    ("quantity".to_string(), Value::Number("1.5e0".to_string())),
+                    ("note".to_string(), Value::Null),
+                    ("paid".to_string(), Value::Bool(false)),
+                ]),
+                Value::Array(vec![]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(super::parse("[1, 2").is_err());
+        assert!(super::parse("{\"a\" 1}").is_err());
+        assert!(super::parse("[1] 2").is_err());
+        assert_eq!(
+            super::parse("[\n1,\n x]").unwrap_err(),
+            "Invalid JSON in line 3: unexpected character 'x'."
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let value = super::parse(r#"{"name": "Service", "quantity": 2.5, "lines": [1]}"#).unwrap();
+        assert_eq!(value.get("name").and_then(Value::as_str), Some("Service"));
+        assert_eq!(value.get("quantity").and_then(Value::as_f64), Some(2.5));
+        assert_eq!(
+            value
+                .get("lines")
+                .and_then(Value::as_array)
+                .map(<[Value]>::len),
+            Some(1)
+        );
+        assert!(value.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_display() {
+        let document = r#"{"a":[1,2.50,true,null],"b":"x \"y\"\n\\ \u0001","c":{}}"#;
+        let value = super::parse(document).unwrap();
+        assert_eq!(value.to_string(), document);
+        assert_eq!(super::parse(&value.to_string()).unwrap(), value);
+    }
+}
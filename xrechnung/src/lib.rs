@@ -16,6 +16,13 @@
 //!     - it is planned to extend the crate to support other items like travel or hardware expenses as well
 //! - invoices are only created in the UBL format
 //!
+//! # Features
+//!
+//! - `async`: asynchronous variants of loading the configuration, reading attachments and writing invoices in the
+//!   `asynchronous` module, whose file I/O runs on a small thread pool of the crate. The futures work with any executor
+//!   (e.g., tokio), without a dependency on a particular runtime.
+//! - `encrypted`: configuration files encrypted with age or sops, which are decrypted with the installed tools.
+//!
 //! # Example
 //! ```
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -42,17 +49,24 @@
 //!         quantity: 7.0,
 //!         hourly_rate: 110.0,
 //!         date: Some("2025-01-02".to_string()),
+//!         ..Default::default()
 //!     },
 //!     xrechnung::data::InvoiceHoursElement {
 //!         name: "Another Service".to_string(),
 //!         quantity: 6.5,
 //!         hourly_rate: 110.0,
 //!         date: Some("2025-01-03".to_string()),
+//!         ..Default::default()
 //!     },
 //! ];
 //!
 //! // create XML structure for the invoice from the supplier, buyer, invoice metadata and invoice hours
-//! let xml_root = xrechnung::create(config.supplier, config.buyer, bill, invoice_hours)?;
+//! let (xml_root, warnings) = xrechnung::create(config.supplier, config.buyer, bill, invoice_hours)?;
+//!
+//! // warnings (e.g., about lines with an amount of zero) do not prevent creating the invoice, but should be checked
+//! for warning in &warnings {
+//!     eprintln!("Warning: {warning}");
+//! }
 //!
 //! // finally write the XML structure to a file
 //! xrechnung::write("invoice.xml", &xml_root)?;
@@ -69,10 +83,27 @@
 #![doc=include_str!("../examples/config.toml")]
 //! ```
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod attachments;
+pub mod base64;
 pub mod config;
+pub mod currency;
 pub mod data;
+#[cfg(feature = "encrypted")]
+mod decrypt;
+pub mod diagnostics;
+pub mod identifiers;
+pub mod json;
+pub mod lines;
+pub mod money;
+pub mod numbering;
+pub mod totals;
+pub mod validate;
 mod xml_bill;
+mod xml_reader;
 mod xml_writer;
+mod yaml;
 
 pub use xml_bill::create;
-pub use xml_writer::write;
+pub use xml_writer::{XmlContentItem, XmlElement, to_xml_string, write, write_to};
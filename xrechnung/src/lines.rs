@@ -0,0 +1,823 @@
+//! Processing steps for invoice lines that can be applied before the XML structure is created from them (e.g., the
+//! aggregation of raw time entries into fewer invoice lines).
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Project;
+use crate::data::{InvoiceHoursElement, Period};
+
+/// A source of invoice lines, e.g., a file with the lines or an import from a time tracking service, from which the
+/// [`create`][crate::create] function reads the lines of the invoice. It is implemented for vectors, arrays and slices
+/// of lines, so that lines that are already in memory can be given directly.
+pub trait InvoiceLineSource {
+    /// Reads the invoice lines from the source.
+    ///
+    /// The function returns an error in case that the lines cannot be read (e.g., because a file cannot be parsed).
+    fn read_lines(self) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>>;
+}
+
+impl InvoiceLineSource for Vec<InvoiceHoursElement> {
+    fn read_lines(self) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+        Ok(self)
+    }
+}
+
+impl<const N: usize> InvoiceLineSource for [InvoiceHoursElement; N] {
+    fn read_lines(self) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+        Ok(self.into())
+    }
+}
+
+impl InvoiceLineSource for &[InvoiceHoursElement] {
+    fn read_lines(self) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+        Ok(self.to_vec())
+    }
+}
+
+/// The ways in which invoice lines can be merged by the [`aggregate`] function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aggregation {
+    /// One line per day (and hourly rate), joining the names of all merged lines.
+    Day,
+
+    /// One line per item name (and hourly rate), spanning the period of all merged lines.
+    Name,
+
+    /// One line per project (and hourly rate), named after the project and spanning the period of all merged lines.
+    /// Lines without a project are merged by their item name.
+    Project,
+}
+
+impl FromStr for Aggregation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Aggregation::Day),
+            "name" => Ok(Aggregation::Name),
+            "project" => Ok(Aggregation::Project),
+            _ => Err(format!(
+                "Unknown aggregation '{s}' (possible values: day, name, project)."
+            )),
+        }
+    }
+}
+
+/// Merges the given invoice lines according to the given aggregation mode by summing up their quantities. Lines with
+/// different hourly rates are never merged.
+///
+/// The merged lines keep the order in which their first line appeared in the input. Optional fields like the order
+/// line reference are only kept, if they are equal for all merged lines.
+///
+/// The function returns an error in case that the period of one of the lines cannot be determined.
+pub fn aggregate(
+    lines: Vec<InvoiceHoursElement>,
+    aggregation: Aggregation,
+) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+    // the hourly rate is part of every key, as lines with different rates cannot be summed up
+    let groups = match aggregation {
+        Aggregation::Day => group_by(lines, |line| {
+            let period = line.period()?.map(|period| (period.start, period.end));
+            Ok((period, line.hourly_rate.to_bits()))
+        })?,
+        Aggregation::Name => group_by(lines, |line| {
+            Ok((line.name.clone(), line.hourly_rate.to_bits()))
+        })?,
+        Aggregation::Project => group_by(lines, |line| {
+            let name = line.project.as_ref().unwrap_or(&line.name);
+            Ok((name.clone(), line.hourly_rate.to_bits()))
+        })?,
+    };
+
+    groups
+        .into_iter()
+        .map(|group| merge(group, aggregation))
+        .collect()
+}
+
+/// Policy for rounding the quantities of invoice lines to billing increments (e.g., to quarter hours).
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Rounding {
+    /// The billing increment in hours to which the quantities are rounded, e.g., 0.25 for quarter hours.
+    pub increment: f32,
+
+    /// The direction in which the quantities are rounded.
+    #[serde(default)]
+    pub mode: RoundingMode,
+
+    /// Whether each line is rounded on its own or the total of each day.
+    #[serde(default)]
+    pub per: RoundingScope,
+}
+
+/// The direction in which quantities are rounded to the billing increment.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    /// Round up to the next increment (e.g., 0.3 hours become 0.5 hours for an increment of 0.25).
+    #[default]
+    Up,
+
+    /// Round down to the previous increment.
+    Down,
+
+    /// Round to the nearest increment.
+    Nearest,
+}
+
+/// The quantities to which a rounding policy is applied.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingScope {
+    /// Every invoice line is rounded on its own.
+    #[default]
+    Entry,
+
+    /// The total of each day (per hourly rate) is rounded and distributed over the lines of the day in multiples of
+    /// the increment, without giving any line a negative quantity. Lines without a date are rounded on their own.
+    Day,
+}
+
+/// Tolerance for the inaccuracy of floating point numbers, so that exact multiples of the increment are not rounded any
+/// further.
+const EPSILON: f64 = 1e-4;
+
+impl Rounding {
+    /// Rounds the given quantity to the billing increment of this policy.
+    pub fn round(&self, quantity: f32) -> f32 {
+        let increments = self.round_increments(quantity as f64 / self.increment as f64);
+        (increments * self.increment as f64) as f32
+    }
+
+    /// Rounds the given number of increments to a whole number in the direction of this policy.
+    fn round_increments(&self, increments: f64) -> f64 {
+        match self.mode {
+            RoundingMode::Up => (increments - EPSILON).ceil(),
+            RoundingMode::Down => (increments + EPSILON).floor(),
+            RoundingMode::Nearest => increments.round(),
+        }
+    }
+
+    /// Rounds the total quantity of the lines with the given indices and distributes it over these lines in multiples
+    /// of the increment: every line keeps the whole increments of its quantity and the increments that remain of the
+    /// rounded total are given to the lines with the largest remainders. So no line gets a negative quantity.
+    fn round_total(&self, lines: &mut [InvoiceHoursElement], indices: &[usize]) {
+        let increments: Vec<f64> = indices
+            .iter()
+            .map(|index| lines[*index].quantity as f64 / self.increment as f64)
+            .collect();
+        let total = self.round_increments(increments.iter().sum());
+        let mut whole: Vec<f64> = increments
+            .iter()
+            .map(|increments| (increments + EPSILON).floor().max(0.0))
+            .collect();
+
+        // the lines with the largest remainders come first (and the earlier lines for equal remainders)
+        let mut order: Vec<usize> = (0..indices.len()).collect();
+        order.sort_by(|a, b| {
+            let remainder = |position: usize| increments[position] - whole[position];
+            remainder(*b).total_cmp(&remainder(*a))
+        });
+        let remaining = (total - whole.iter().sum::<f64>()).round().max(0.0) as usize;
+        for position in order.into_iter().take(remaining) {
+            whole[position] += 1.0;
+        }
+
+        for (index, whole) in indices.iter().zip(whole) {
+            lines[*index].quantity = (whole * self.increment as f64) as f32;
+        }
+    }
+}
+
+/// Rounds the quantities of the given invoice lines according to the given rounding policy.
+///
+/// The function returns an error in case that the increment of the policy is not positive or, when rounding per day,
+/// the period of one of the lines cannot be determined.
+pub fn round_quantities(
+    lines: &mut [InvoiceHoursElement],
+    rounding: &Rounding,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if rounding.increment <= 0.0 {
+        return Err("The rounding increment needs to be greater than zero.".into());
+    }
+
+    match rounding.per {
+        RoundingScope::Entry => {
+            for line in lines.iter_mut() {
+                line.quantity = rounding.round(line.quantity);
+            }
+        }
+        RoundingScope::Day => {
+            // group the lines per day and hourly rate, as moving hours between lines with different rates would change
+            // the amount of the invoice
+            let mut days: HashMap<(NaiveDate, u32), Vec<usize>> = HashMap::new();
+            for (index, line) in lines.iter_mut().enumerate() {
+                match line.period()? {
+                    Some(period) => days
+                        .entry((period.start, line.hourly_rate.to_bits()))
+                        .or_default()
+                        .push(index),
+                    None => line.quantity = rounding.round(line.quantity),
+                }
+            }
+
+            for indices in days.into_values() {
+                rounding.round_total(lines, &indices);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Raises the quantity of all invoice lines below the given minimum billable quantity (e.g., a minimum of one hour per
+/// call-out) to that minimum. Lines without a positive quantity (e.g., corrections) are kept as they are.
+///
+/// * `lines`    - The invoice lines whose quantities should be adjusted.
+/// * `minimum`  - The minimum billable quantity in hours.
+/// * `add_note` - Whether the adjustment should be noted on the adjusted lines, together with the recorded quantity.
+/// * `language` - The language of the notes as ISO 639-1 code (English if not supported), like the language of the bill.
+pub fn apply_minimum_quantity(
+    lines: &mut [InvoiceHoursElement],
+    minimum: f32,
+    add_note: bool,
+    language: Option<&str>,
+) {
+    let german = language == Some("de");
+    for line in lines
+        .iter_mut()
+        .filter(|line| 0.0 < line.quantity && line.quantity < minimum)
+    {
+        if add_note {
+            let adjustment = if german {
+                format!(
+                    "Mindestabrechnungsmenge von {minimum:.2} Stunden angewendet (erfasst: {:.2} Stunden).",
+                    line.quantity
+                )
+            } else {
+                format!(
+                    "Minimum billable quantity of {minimum:.2} hours applied (recorded: {:.2} hours).",
+                    line.quantity
+                )
+            };
+
+            line.note = Some(match &line.note {
+                Some(note) => format!("{note} {adjustment}"),
+                None => adjustment,
+            });
+        }
+
+        line.quantity = minimum;
+    }
+}
+
+/// Sorts the given invoice lines chronologically by the start of their period and lines on the same date by their
+/// name. The sorting is stable, so lines with equal date and name keep their order. Lines without a date are moved to
+/// the end.
+///
+/// The function returns an error in case that the period of one of the lines cannot be determined.
+pub fn sort_by_date(
+    lines: &mut Vec<InvoiceHoursElement>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut keyed = Vec::with_capacity(lines.len());
+    for line in lines.drain(..) {
+        // lines without a period are ordered after all lines with a period
+        let start = line.period()?.map(|period| period.start);
+        keyed.push(((start.is_none(), start), line));
+    }
+
+    keyed.sort_by(|(a_key, a), (b_key, b)| a_key.cmp(b_key).then_with(|| a.name.cmp(&b.name)));
+    lines.extend(keyed.into_iter().map(|(_, line)| line));
+
+    Ok(())
+}
+
+/// Returns the period from the earliest start to the latest end of all given invoice lines that have a date, or
+/// `None` if none of the lines has a date, like [`Period::from_lines`].
+///
+/// The function returns an error in case that the period of one of the lines cannot be determined.
+pub fn date_range(
+    lines: &[InvoiceHoursElement],
+) -> Result<Option<Period>, Box<dyn std::error::Error>> {
+    Period::from_lines(lines)
+}
+
+/// Returns the indices of the given invoice lines whose date (or period) does not lie completely within the given
+/// period, e.g., to catch lines of the previous month in the invoice of this month. Lines without a date are never
+/// outside of the period.
+///
+/// The function returns an error in case that the period of one of the lines cannot be determined.
+pub fn outside_period(
+    lines: &[InvoiceHoursElement],
+    period: &Period,
+) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+    let mut indices = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(line_period) = line.period()?
+            && !period.contains_period(&line_period)
+        {
+            indices.push(index);
+        }
+    }
+
+    Ok(indices)
+}
+
+/// Applies the settings of the projects to the invoice lines of the projects: the order line reference and accounting
+/// cost of a project are set on its lines that do not specify them.
+pub fn apply_project_settings(
+    lines: &mut [InvoiceHoursElement],
+    projects: &BTreeMap<String, Project>,
+) {
+    for line in lines {
+        let Some(project) = line.project.as_ref().and_then(|name| projects.get(name)) else {
+            continue;
+        };
+
+        if line.order_line_id.is_none() {
+            line.order_line_id = project.order_line_id.clone();
+        }
+        if line.accounting_cost.is_none() {
+            line.accounting_cost = project.accounting_cost.clone();
+        }
+    }
+}
+
+/// Groups the invoice lines by their project, so that the lines of each project follow each other. The projects are
+/// ordered by their first appearance and the lines of a project keep their order. Lines without project come last.
+pub fn group_by_project(lines: &mut [InvoiceHoursElement]) {
+    let mut projects: Vec<Option<String>> = Vec::new();
+    for line in lines.iter() {
+        if line.project.is_some() && !projects.contains(&line.project) {
+            projects.push(line.project.clone());
+        }
+    }
+
+    lines.sort_by_key(|line| {
+        projects
+            .iter()
+            .position(|project| *project == line.project)
+            .unwrap_or(projects.len())
+    });
+}
+
+/// Returns the duplicates among the given invoice lines, i.e., lines with the same date (or period), name, quantity and
+/// rate as an earlier line, which usually indicates an accidental double export. Each duplicate is returned as the
+/// index of the line together with the index of the first line that it duplicates.
+pub fn find_duplicates(lines: &[InvoiceHoursElement]) -> Vec<(usize, usize)> {
+    let mut first: HashMap<_, usize> = HashMap::new();
+    let mut duplicates = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let key = (
+            &line.date,
+            &line.start_date,
+            &line.end_date,
+            &line.name,
+            line.quantity.to_bits(),
+            line.hourly_rate.to_bits(),
+        );
+        match first.get(&key) {
+            Some(original) => duplicates.push((index, *original)),
+            None => {
+                first.insert(key, index);
+            }
+        }
+    }
+
+    duplicates
+}
+
+/// Removes the duplicates (as determined by [`find_duplicates`]) from the given invoice lines, keeping the first line
+/// of each group of identical lines. Returns the number of removed lines.
+pub fn remove_duplicates(lines: &mut Vec<InvoiceHoursElement>) -> usize {
+    let mut duplicate = vec![false; lines.len()];
+    let mut count = 0;
+    for (index, _) in find_duplicates(lines) {
+        duplicate[index] = true;
+        count += 1;
+    }
+
+    let mut index = 0;
+    lines.retain(|_| {
+        index += 1;
+        !duplicate[index - 1]
+    });
+
+    count
+}
+
+/// Splits the given lines into groups with equal keys, keeping the order of the first occurrence of each key.
+fn group_by<K: Eq + Hash>(
+    lines: Vec<InvoiceHoursElement>,
+    key: impl Fn(&InvoiceHoursElement) -> Result<K, Box<dyn std::error::Error>>,
+) -> Result<Vec<Vec<InvoiceHoursElement>>, Box<dyn std::error::Error>> {
+    let mut indices: HashMap<K, usize> = HashMap::new();
+    let mut groups: Vec<Vec<InvoiceHoursElement>> = Vec::new();
+
+    for line in lines {
+        let index = *indices.entry(key(&line)?).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(line);
+    }
+
+    Ok(groups)
+}
+
+/// Returns the value of an optional field, if it is equal for all given lines.
+fn common_value(
+    lines: &[InvoiceHoursElement],
+    field: impl Fn(&InvoiceHoursElement) -> &Option<String>,
+) -> Option<String> {
+    let first = field(&lines[0]);
+    if lines.iter().all(|line| field(line) == first) {
+        first.clone()
+    } else {
+        None
+    }
+}
+
+/// Merges a (non-empty) group of lines into a single line.
+fn merge(
+    lines: Vec<InvoiceHoursElement>,
+    aggregation: Aggregation,
+) -> Result<InvoiceHoursElement, Box<dyn std::error::Error>> {
+    // the merged line spans from the earliest start to the latest end of all lines with a period
+    let period = date_range(&lines)?;
+
+    let name = match aggregation {
+        Aggregation::Day => {
+            // list each distinct name only once, in the order of their appearance
+            let mut names: Vec<&str> = Vec::new();
+            for line in &lines {
+                if !names.contains(&line.name.as_str()) {
+                    names.push(&line.name);
+                }
+            }
+            names.join(", ")
+        }
+        Aggregation::Name => lines[0].name.clone(),
+        Aggregation::Project => lines[0].project.clone().unwrap_or(lines[0].name.clone()),
+    };
+
+    let (date, start_date, end_date) = match period {
+        Some(period) if period.start == period.end => (Some(period.start.to_string()), None, None),
+        Some(period) => (
+            None,
+            Some(period.start.to_string()),
+            Some(period.end.to_string()),
+        ),
+        None => (None, None, None),
+    };
+
+    Ok(InvoiceHoursElement {
+        // identifiers of merged lines cannot be kept, as they need to be unique
+        id: if lines.len() == 1 {
+            lines[0].id.clone()
+        } else {
+            None
+        },
+        name,
+        description: common_value(&lines, |line| &line.description),
+        quantity: lines.iter().map(|line| line.quantity).sum(),
+        hourly_rate: lines[0].hourly_rate,
+        date,
+        start_date,
+        end_date,
+        note: common_value(&lines, |line| &line.note),
+        order_line_id: common_value(&lines, |line| &line.order_line_id),
+        accounting_cost: common_value(&lines, |line| &line.accounting_cost),
+        project: common_value(&lines, |line| &line.project),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Aggregation, InvoiceLineSource, Rounding, RoundingMode, RoundingScope, aggregate,
+        apply_minimum_quantity, apply_project_settings, date_range, find_duplicates,
+        group_by_project, outside_period, remove_duplicates, round_quantities, sort_by_date,
+    };
+    use crate::data::InvoiceHoursElement;
+
+    fn line(name: &str, project: Option<&str>, date: &str, quantity: f32) -> InvoiceHoursElement {
+        InvoiceHoursElement {
+            name: name.to_string(),
+            quantity,
+            hourly_rate: 100.0,
+            date: Some(date.to_string()),
+            project: project.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn example_lines() -> Vec<InvoiceHoursElement> {
+        vec![
+            line("Development", Some("Alpha"), "2025-01-02", 2.0),
+            line("Meeting", Some("Alpha"), "2025-01-02", 0.5),
+            line("Development", Some("Beta"), "2025-01-03", 3.0),
+            line("Development", None, "2025-01-02", 1.5),
+        ]
+    }
+
+    #[test]
+    fn test_aggregate_by_day() {
+        let lines = aggregate(example_lines(), Aggregation::Day).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].name, "Development, Meeting");
+        assert_eq!(lines[0].quantity, 4.0);
+        assert_eq!(lines[0].date.as_deref(), Some("2025-01-02"));
+        assert_eq!(lines[1].quantity, 3.0);
+    }
+
+    #[test]
+    fn test_aggregate_by_name() {
+        let lines = aggregate(example_lines(), Aggregation::Name).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].name, "Development");
+        assert_eq!(lines[0].quantity, 6.5);
+        assert_eq!(lines[0].start_date.as_deref(), Some("2025-01-02"));
+        assert_eq!(lines[0].end_date.as_deref(), Some("2025-01-03"));
+        assert_eq!(lines[0].project, None);
+    }
+
+    #[test]
+    fn test_aggregate_by_project() {
+        let lines = aggregate(example_lines(), Aggregation::Project).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].name, "Alpha");
+        assert_eq!(lines[0].quantity, 2.5);
+        assert_eq!(lines[1].name, "Beta");
+        assert_eq!(lines[2].name, "Development");
+    }
+
+    #[test]
+    fn test_different_rates_are_not_merged() {
+        let mut lines = example_lines();
+        lines[1].hourly_rate = 80.0;
+
+        let lines = aggregate(lines, Aggregation::Day).unwrap();
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn test_sort_by_date() {
+        let mut lines = example_lines();
+        lines.push(InvoiceHoursElement {
+            name: "Travel".to_string(),
+            ..Default::default()
+        });
+        lines.insert(0, line("Support", None, "2025-01-03", 1.0));
+
+        sort_by_date(&mut lines).unwrap();
+        let order: Vec<_> = lines
+            .iter()
+            .map(|line| (line.name.as_str(), line.quantity))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("Development", 2.0),
+                ("Development", 1.5),
+                ("Meeting", 0.5),
+                ("Development", 3.0),
+                ("Support", 1.0),
+                ("Travel", 0.0),
+            ]
+        );
+
+        let range = date_range(&lines).unwrap().unwrap();
+        assert_eq!(range.start.to_string(), "2025-01-02");
+        assert_eq!(range.end.to_string(), "2025-01-03");
+    }
+
+    #[test]
+    fn test_round_quantities_per_entry() {
+        let rounding = Rounding {
+            increment: 0.25,
+            mode: RoundingMode::Up,
+            per: RoundingScope::Entry,
+        };
+
+        let mut lines = example_lines();
+        lines[0].quantity = 2.1;
+        lines[1].quantity = 0.25;
+        round_quantities(&mut lines, &rounding).unwrap();
+
+        assert_eq!(lines[0].quantity, 2.25);
+        assert_eq!(lines[1].quantity, 0.25);
+        assert_eq!(lines[3].quantity, 1.5);
+    }
+
+    #[test]
+    fn test_round_quantities_per_day() {
+        let rounding = Rounding {
+            increment: 1.0,
+            mode: RoundingMode::Nearest,
+            per: RoundingScope::Day,
+        };
+
+        let mut lines = example_lines();
+        lines[1].quantity = 0.75;
+        round_quantities(&mut lines, &rounding).unwrap();
+
+        // the total of 4.25 hours on the 2nd is rounded to 4.0, which is distributed in whole hours, giving the
+        // remaining hour to the line with the largest remainder
+        assert_eq!(lines[0].quantity, 2.0);
+        assert_eq!(lines[1].quantity, 1.0);
+        assert_eq!(lines[3].quantity, 1.0);
+        assert_eq!(lines[2].quantity, 3.0);
+
+        // rounding down never leads to negative quantities
+        let rounding = Rounding {
+            mode: RoundingMode::Down,
+            ..rounding
+        };
+        let mut lines = vec![
+            line("Development", None, "2025-01-02", 1.8),
+            line("Meeting", None, "2025-01-02", 0.1),
+        ];
+        round_quantities(&mut lines, &rounding).unwrap();
+        assert_eq!(lines[0].quantity, 1.0);
+        assert_eq!(lines[1].quantity, 0.0);
+
+        // the hours of lines with different rates are rounded separately
+        let rounding = Rounding {
+            mode: RoundingMode::Up,
+            ..rounding
+        };
+        let mut lines = vec![
+            line("Development", None, "2025-01-02", 0.4),
+            line("Meeting", None, "2025-01-02", 0.4),
+            line("Support", None, "2025-01-02", 0.3),
+        ];
+        lines[2].hourly_rate = 150.0;
+        round_quantities(&mut lines, &rounding).unwrap();
+        let quantities: Vec<f32> = lines.iter().map(|line| line.quantity).collect();
+        assert_eq!(quantities, [1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_apply_minimum_quantity() {
+        let mut lines = example_lines();
+        apply_minimum_quantity(&mut lines, 1.0, true, None);
+
+        assert_eq!(lines[0].quantity, 2.0);
+        assert_eq!(lines[0].note, None);
+        assert_eq!(lines[1].quantity, 1.0);
+        assert_eq!(
+            lines[1].note.as_deref(),
+            Some("Minimum billable quantity of 1.00 hours applied (recorded: 0.50 hours).")
+        );
+
+        // the note is in the language of the bill
+        let mut lines = example_lines();
+        apply_minimum_quantity(&mut lines, 1.0, true, Some("de"));
+        assert_eq!(
+            lines[1].note.as_deref(),
+            Some("Mindestabrechnungsmenge von 1.00 Stunden angewendet (erfasst: 0.50 Stunden).")
+        );
+
+        // corrections and lines without hours are not raised to the minimum
+        let mut lines = example_lines();
+        lines[0].quantity = -2.0;
+        lines[1].quantity = 0.0;
+        apply_minimum_quantity(&mut lines, 1.0, true, None);
+        assert_eq!(lines[0].quantity, -2.0);
+        assert_eq!(lines[1].quantity, 0.0);
+        assert!(lines.iter().all(|line| line.note.is_none()));
+    }
+
+    #[test]
+    fn test_outside_period() {
+        let mut lines = example_lines();
+        lines.push(line("Development", None, "2024-12-31", 1.0));
+        lines[2].start_date = Some("2025-01-03".to_string());
+        lines[2].end_date = Some("2025-02-01".to_string());
+        lines[3].date = None;
+
+        let period = crate::data::Period {
+            start: chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            end: chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        };
+        assert_eq!(outside_period(&lines, &period).unwrap(), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut lines = example_lines();
+        lines.push(line("Development", Some("Alpha"), "2025-01-02", 2.0));
+        lines.push(line("Meeting", Some("Alpha"), "2025-01-03", 0.5));
+        lines.push(line("Development", None, "2025-01-02", 2.0));
+        lines.push(line("Meeting", None, "2025-01-02", 0.5));
+
+        assert_eq!(find_duplicates(&lines), vec![(4, 0), (6, 0), (7, 1)]);
+
+        assert_eq!(remove_duplicates(&mut lines), 3);
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[4].date.as_deref(), Some("2025-01-03"));
+        assert!(find_duplicates(&lines).is_empty());
+    }
+
+    #[test]
+    fn test_apply_project_settings() {
+        let projects = std::collections::BTreeMap::from([(
+            "Alpha".to_string(),
+            crate::config::Project {
+                order_line_id: Some("10".to_string()),
+                accounting_cost: Some("4711".to_string()),
+            },
+        )]);
+
+        let mut lines = example_lines();
+        lines[1].order_line_id = Some("20".to_string());
+        apply_project_settings(&mut lines, &projects);
+
+        assert_eq!(lines[0].order_line_id.as_deref(), Some("10"));
+        assert_eq!(lines[0].accounting_cost.as_deref(), Some("4711"));
+        assert_eq!(lines[1].order_line_id.as_deref(), Some("20"));
+        assert_eq!(lines[1].accounting_cost.as_deref(), Some("4711"));
+        assert_eq!(lines[2].order_line_id, None);
+        assert_eq!(lines[3].order_line_id, None);
+    }
+
+    #[test]
+    fn test_group_by_project() {
+        let mut lines = example_lines();
+        lines.insert(0, line("Support", None, "2025-01-01", 1.0));
+        lines.push(line("Review", Some("Alpha"), "2025-01-04", 1.0));
+        group_by_project(&mut lines);
+
+        let names: Vec<(&str, Option<&str>)> = lines
+            .iter()
+            .map(|line| (line.name.as_str(), line.project.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Development", Some("Alpha")),
+                ("Meeting", Some("Alpha")),
+                ("Review", Some("Alpha")),
+                ("Development", Some("Beta")),
+                ("Support", None),
+                ("Development", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_sources() {
+        // a source of lines as it could be implemented by an integration of an input format
+        struct Timesheet(&'static str);
+        impl InvoiceLineSource for Timesheet {
+            fn read_lines(self) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+                self.0
+                    .lines()
+                    .map(|entry| {
+                        let (date, hours) = entry.split_once(' ').ok_or("Invalid entry.")?;
+                        Ok(line("Development", None, date, hours.parse()?))
+                    })
+                    .collect()
+            }
+        }
+
+        let lines = example_lines();
+        assert_eq!(lines.as_slice().read_lines().unwrap(), lines);
+        assert_eq!(lines.clone().read_lines().unwrap(), lines);
+        assert_eq!(
+            [line("Meeting", None, "2025-01-02", 0.5)]
+                .read_lines()
+                .unwrap()[0]
+                .name,
+            "Meeting"
+        );
+
+        let timesheet = Timesheet("2025-01-02 2.5\n2025-01-03 4");
+        let lines = timesheet.read_lines().unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].quantity, 4.0);
+
+        // invoices can be created from any source, errors of reading the source are returned
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let bill = || {
+            crate::data::Bill::new(
+                "2025-0001".to_string(),
+                chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+                None,
+                &config,
+            )
+        };
+        let (supplier, buyer) = (config.supplier.clone(), config.buyer.clone());
+        let timesheet = Timesheet("2025-01-02 2.5");
+        assert!(crate::create(supplier.clone(), buyer.clone(), bill(), timesheet).is_ok());
+        let error = crate::create(supplier, buyer, bill(), Timesheet("2025-01-02")).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid entry.");
+    }
+}
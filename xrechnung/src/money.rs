@@ -0,0 +1,195 @@
+//! Amounts of money in a currency, which are rounded to two decimals (cents) as required for the amounts of invoices.
+//!
+//! How amounts are rounded is given explicitly by a [`Rounding`] mode, which can be configured with the field
+//! `amount_rounding` of the configuration (e.g., `amount_rounding = "half_even"`):
+//!
+//! - half up (the default), i.e., the commercial rounding where halves are rounded away from zero (2.675 becomes 2.68)
+//! - half even, i.e., the banker's rounding where halves are rounded to the even cent (2.665 becomes 2.66)
+//!
+//! # Example
+//! ```
+//! use xrechnung::money::{Money, Rounding};
+//!
+//! let currency: xrechnung::currency::Currency = "EUR".parse().unwrap();
+//! let amount = Money::new(2.665, &currency, Rounding::HalfUp);
+//! assert_eq!(amount.to_string(), "2.67 EUR");
+//! assert_eq!(Money::new(2.665, &currency, Rounding::HalfEven).decimal(), "2.66");
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use crate::currency::Currency;
+
+/// The way in which amounts are rounded to cents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /// Halves are rounded away from zero, e.g., 2.665 becomes 2.67 and -2.665 becomes -2.67.
+    #[default]
+    HalfUp,
+
+    /// Halves are rounded to the even cent, e.g., 2.665 becomes 2.66 and 2.675 becomes 2.68.
+    HalfEven,
+}
+
+impl Rounding {
+    /// Returns the given amount rounded to cents.
+    pub fn cents(self, amount: f64) -> i64 {
+        // the amount is rounded to millionths first, so that the inaccuracy of floating point numbers does not move
+        // exact halves (like 2.675, which is 2.67499999... as floating point number) below or above the half
+        let millionths = (amount * 1_000_000.0).round() as i64;
+        let (cents, remainder) = (millionths.div_euclid(10_000), millionths.rem_euclid(10_000));
+        match (remainder.cmp(&5_000), self) {
+            (Ordering::Less, _) => cents,
+            (Ordering::Greater, _) => cents + 1,
+            (Ordering::Equal, Rounding::HalfUp) if millionths < 0 => cents,
+            (Ordering::Equal, Rounding::HalfUp) => cents + 1,
+            (Ordering::Equal, Rounding::HalfEven) => cents + cents.rem_euclid(2),
+        }
+    }
+}
+
+/// Returns the given number as 64 bit floating point number with the decimals that it is shown with (e.g., 33.33
+/// instead of 33.33000183105469), so that calculations with it are not affected by the inaccuracy of the 32 bit
+/// floating point numbers of the invoice lines.
+pub(crate) fn decimal(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(value as f64)
+}
+
+/// An amount of money in a currency, rounded to cents.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Money {
+    cents: i64,
+    currency: Currency,
+}
+
+impl Money {
+    /// Returns the given amount in the given currency, rounded to cents with the given rounding mode.
+    pub fn new(amount: f64, currency: &Currency, rounding: Rounding) -> Self {
+        Money::from_cents(rounding.cents(amount), currency)
+    }
+
+    /// Returns the given number of cents in the given currency.
+    pub fn from_cents(cents: i64, currency: &Currency) -> Self {
+        Money {
+            cents,
+            currency: currency.clone(),
+        }
+    }
+
+    /// Returns an amount of zero in the given currency.
+    pub fn zero(currency: &Currency) -> Self {
+        Money::from_cents(0, currency)
+    }
+
+    /// Returns the amount in cents.
+    pub fn cents(&self) -> i64 {
+        self.cents
+    }
+
+    /// Returns the amount, e.g., 12.34.
+    pub fn amount(&self) -> f64 {
+        self.cents as f64 / 100.0
+    }
+
+    /// Returns the currency of the amount.
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// Returns the given percentage of the amount, rounded to cents with the given rounding mode.
+    pub fn percentage(&self, percent: f64, rounding: Rounding) -> Self {
+        Money::new(self.amount() * percent / 100.0, &self.currency, rounding)
+    }
+
+    /// Returns the amount with two decimals and without currency, as written into invoices, e.g., "12.34".
+    pub fn decimal(&self) -> String {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let cents = self.cents.unsigned_abs();
+        format!("{sign}{}.{:02}", cents / 100, cents % 100)
+    }
+}
+
+/// Formats the amount with its currency, e.g., "12.34 EUR".
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.decimal(), self.currency)
+    }
+}
+
+/// Adds two amounts, which need to be in the same currency.
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        assert_eq!(
+            self.currency, other.currency,
+            "Cannot add amounts in different currencies."
+        );
+        Money::from_cents(self.cents + other.cents, &self.currency)
+    }
+}
+
+/// Subtracts two amounts, which need to be in the same currency.
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        assert_eq!(
+            self.currency, other.currency,
+            "Cannot subtract amounts in different currencies."
+        );
+        Money::from_cents(self.cents - other.cents, &self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Money, Rounding};
+    use crate::currency::Currency;
+
+    #[test]
+    fn test_rounding() {
+        let cents = |amount: f64| {
+            (
+                Rounding::HalfUp.cents(amount),
+                Rounding::HalfEven.cents(amount),
+            )
+        };
+        assert_eq!(cents(2.675), (268, 268));
+        assert_eq!(cents(2.665), (267, 266));
+        assert_eq!(cents(0.125), (13, 12));
+        assert_eq!(cents(2.6649), (266, 266));
+        assert_eq!(cents(2.6651), (267, 267));
+        assert_eq!(cents(-2.665), (-267, -266));
+        assert_eq!(cents(-2.675), (-268, -268));
+        assert_eq!(cents(1767.15), (176715, 176715));
+    }
+
+    #[test]
+    fn test_money() {
+        let currency: Currency = "EUR".parse().unwrap();
+        let amount = Money::new(8.3325, &currency, Rounding::HalfUp);
+        assert_eq!(amount.cents(), 833);
+        assert_eq!(amount.decimal(), "8.33");
+        assert_eq!(amount.to_string(), "8.33 EUR");
+        assert_eq!(
+            (amount.clone() + Money::from_cents(2, &currency)).decimal(),
+            "8.35"
+        );
+        assert_eq!((Money::zero(&currency) - amount).decimal(), "-8.33");
+        assert_eq!(
+            Money::new(-0.05, &currency, Rounding::HalfUp).decimal(),
+            "-0.05"
+        );
+
+        // 19 % of 24.99 are 4.7481
+        let amount = Money::new(24.99, &currency, Rounding::HalfUp);
+        assert_eq!(amount.percentage(19.0, Rounding::HalfUp).decimal(), "4.75");
+        assert_eq!(super::decimal(33.33) * 0.25, 8.3325);
+    }
+}
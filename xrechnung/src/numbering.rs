@@ -0,0 +1,358 @@
+//! Automatic numbering of invoices, so that the invoice numbers are unique and without gaps.
+//!
+//! The invoice numbers follow a scheme that is configured in the `[numbering]` section of the configuration file, e.g.,
+//! `scheme = "{year}-{counter:03}"` for numbers like "2025-001". The scheme can contain the following placeholders:
+//!
+//! - `{year}`: the year of the issue date (e.g., "2025")
+//! - `{month}`: the month of the issue date with two digits (e.g., "01")
+//! - `{counter}`: the counter of the invoices, optionally with a minimum number of digits (e.g., `{counter:03}`)
+//!
+//! The counter starts again at 1 with each new year or month, if the scheme contains the year or month. The last
+//! allocated counter is stored in a state file, which is `invoice-numbers.toml` next to the configuration file, if not
+//! configured otherwise with `state_file` (relative to the configuration file).
+//!
+//! # Example
+//! ```
+//! let numbering = xrechnung::numbering::Numbering {
+//!     scheme: "RE-{year}-{counter:04}".to_string(),
+//!     state_file: None,
+//! };
+//! let issue_date = chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+//! let (number, counter) = numbering.next(None, issue_date).unwrap();
+//! assert_eq!(number, "RE-2025-0001");
+//! let (number, _) = numbering.next(Some(&counter), issue_date).unwrap();
+//! assert_eq!(number, "RE-2025-0002");
+//! ```
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long [`lock`] waits for another run to release the lock of the state file.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default name of the state file, next to the configuration file.
+const DEFAULT_STATE_FILE: &str = "invoice-numbers.toml";
+
+/// The configuration of the automatic invoice numbers.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Numbering {
+    /// The scheme of the invoice numbers, e.g., "{year}-{counter:03}".
+    pub scheme: String,
+
+    /// The file that stores the last allocated counter, relative to the configuration file (optional).
+    pub state_file: Option<String>,
+}
+
+/// The state of the invoice numbering: the last allocated counter and the period (year or month) it belongs to.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Counter {
+    /// The year (e.g., "2025") or month (e.g., "2025-01") of the counter, depending on the scheme. Empty for schemes
+    /// without year and month.
+    pub period: String,
+
+    /// The last allocated counter.
+    pub counter: u32,
+}
+
+impl Numbering {
+    /// Returns the path of the state file for the given configuration file.
+    pub fn state_file(&self, config_file: &str) -> PathBuf {
+        let directory = Path::new(config_file).parent().unwrap_or(Path::new(""));
+        directory.join(self.state_file.as_deref().unwrap_or(DEFAULT_STATE_FILE))
+    }
+
+    /// Checks that the scheme contains the counter and only known placeholders.
+    pub fn check_scheme(&self) -> Result<(), String> {
+        format_number(&self.scheme, NaiveDate::default(), 1).map(|_| ())
+    }
+
+    /// Returns the period of the counter for the given issue date, i.e., the year or month, if the scheme contains them.
+    fn period(&self, issue_date: NaiveDate) -> String {
+        if self.scheme.contains("{month}") {
+            issue_date.format("%Y-%m").to_string()
+        } else if self.scheme.contains("{year}") {
+            issue_date.format("%Y").to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Returns the next invoice number for the given issue date after the given last allocated counter, together with
+    /// the new state of the numbering (which needs to be stored once the invoice was created).
+    pub fn next(
+        &self,
+        last: Option<&Counter>,
+        issue_date: NaiveDate,
+    ) -> Result<(String, Counter), String> {
+        let period = self.period(issue_date);
+        let counter = match last {
+            Some(last) if last.period == period => last.counter + 1,
+            // a counter of a later period means that the issue date lies before already issued invoices
+            Some(last) if last.period > period => {
+                return Err(format!(
+                    "Invoices were already numbered for {} (after the issue date {issue_date}).",
+                    last.period
+                ));
+            }
+            _ => 1,
+        };
+        let number = format_number(&self.scheme, issue_date, counter)?;
+        Ok((number, Counter { period, counter }))
+    }
+}
+
+/// Formats an invoice number according to the given scheme.
+fn format_number(scheme: &str, issue_date: NaiveDate, counter: u32) -> Result<String, String> {
+    let mut number = String::new();
+    let mut rest = scheme;
+    let mut has_counter = false;
+    while let Some(start) = rest.find('{') {
+        number.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| {
+            format!("The placeholder at the end of the numbering scheme '{scheme}' is not closed.")
+        })?;
+        let placeholder = &rest[start + 1..start + end];
+        match placeholder.split_once(':') {
+            None if placeholder == "year" => number.push_str(&issue_date.format("%Y").to_string()),
+            None if placeholder == "month" => number.push_str(&issue_date.format("%m").to_string()),
+            None if placeholder == "counter" => {
+                number.push_str(&counter.to_string());
+                has_counter = true;
+            }
+            Some(("counter", digits)) => {
+                let digits: usize = digits.parse().map_err(|_| {
+                    format!("The number of digits '{digits}' in the numbering scheme '{scheme}' is no number.")
+                })?;
+                number.push_str(&format!("{counter:0digits$}"));
+                has_counter = true;
+            }
+            _ => {
+                return Err(format!(
+                    "The numbering scheme '{scheme}' contains the unknown placeholder '{{{placeholder}}}' (known: \
+                     {{year}}, {{month}}, {{counter}})."
+                ));
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    number.push_str(rest);
+
+    if !has_counter {
+        return Err(format!(
+            "The numbering scheme '{scheme}' needs to contain the placeholder {{counter}}."
+        ));
+    }
+    Ok(number)
+}
+
+/// Reads the state of the invoice numbering from the given state file, which does not exist before the first invoice.
+pub fn read_counter(state_file: &Path) -> Result<Option<Counter>, Box<dyn std::error::Error>> {
+    if !state_file.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(state_file)?;
+    let counter = toml::from_str(&content).map_err(|e| {
+        format!(
+            "Could not parse the invoice numbering state file '{}': {e}",
+            state_file.display()
+        )
+    })?;
+    Ok(Some(counter))
+}
+
+/// Writes the state of the invoice numbering to the given state file. The file is replaced at once, so that it is never
+/// left incomplete.
+pub fn write_counter(
+    state_file: &Path,
+    counter: &Counter,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let content = toml::to_string(counter)?;
+    crate::xml_writer::write_atomically(&state_file.to_string_lossy(), |file| {
+        Ok(std::io::Write::write_all(file, content.as_bytes())?)
+    })
+}
+
+/// An exclusive lock of the state file of the invoice numbering, which is released when it is dropped.
+#[derive(Debug)]
+pub struct Lock {
+    path: PathBuf,
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Locks the given state file of the invoice numbering, so that concurrent runs do not allocate the same invoice number.
+/// The lock needs to be held from reading the counter with [`read_counter`] until the new counter is written with
+/// [`write_counter`]. The lock is a file next to the state file (with the additional extension `.lock`), which is
+/// created exclusively.
+///
+/// The function waits for a lock of another run to be released and returns an error in case that it is not released
+/// within ten seconds.
+pub fn lock(state_file: &Path) -> Result<Lock, Box<dyn std::error::Error>> {
+    lock_with_timeout(state_file, LOCK_TIMEOUT)
+}
+
+fn lock_with_timeout(
+    state_file: &Path,
+    timeout: Duration,
+) -> Result<Lock, Box<dyn std::error::Error>> {
+    let mut path = state_file.as_os_str().to_owned();
+    path.push(".lock");
+    let path = PathBuf::from(path);
+
+    let start = Instant::now();
+    loop {
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(_) => return Ok(Lock { path }),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {
+                if start.elapsed() >= timeout {
+                    return Err(format!(
+                        "The invoice numbering state file '{}' is locked by another run (remove '{}' if no other run \
+                         is active).",
+                        state_file.display(),
+                        path.display()
+                    )
+                    .into());
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(error) => {
+                return Err(format!(
+                    "Could not lock the file '{}': {error}",
+                    state_file.display()
+                )
+                .into());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counter, Numbering};
+    use chrono::NaiveDate;
+
+    fn numbering(scheme: &str) -> Numbering {
+        Numbering {
+            scheme: scheme.to_string(),
+            state_file: None,
+        }
+    }
+
+    fn date(year: i32, month: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, 15).unwrap()
+    }
+
+    #[test]
+    fn test_next() {
+        let yearly = numbering("{year}-{counter:03}");
+        let (number, counter) = yearly.next(None, date(2025, 1)).unwrap();
+        assert_eq!(number, "2025-001");
+        assert_eq!(
+            counter,
+            Counter {
+                period: "2025".to_string(),
+                counter: 1
+            }
+        );
+        let (number, counter) = yearly.next(Some(&counter), date(2025, 12)).unwrap();
+        assert_eq!(number, "2025-002");
+
+        // the counter starts again in a new year
+        let (number, counter) = yearly.next(Some(&counter), date(2026, 1)).unwrap();
+        assert_eq!(number, "2026-001");
+        assert!(yearly.next(Some(&counter), date(2025, 12)).is_err());
+
+        let monthly = numbering("R{year}{month}/{counter}");
+        let (number, counter) = monthly.next(None, date(2025, 3)).unwrap();
+        assert_eq!(number, "R202503/1");
+        let (number, _) = monthly.next(Some(&counter), date(2025, 4)).unwrap();
+        assert_eq!(number, "R202504/1");
+
+        let continuous = numbering("{counter:05}");
+        let last = Counter {
+            period: String::new(),
+            counter: 41,
+        };
+        assert_eq!(
+            continuous.next(Some(&last), date(2030, 1)).unwrap().0,
+            "00042"
+        );
+    }
+
+    #[test]
+    fn test_invalid_scheme() {
+        assert!(numbering("{year}-{counter:03}").check_scheme().is_ok());
+        assert!(numbering("{year}").check_scheme().is_err());
+        assert!(numbering("{year}").next(None, date(2025, 1)).is_err());
+        assert!(
+            numbering("{year}-{count}")
+                .next(None, date(2025, 1))
+                .is_err()
+        );
+        assert!(numbering("{counter:x}").next(None, date(2025, 1)).is_err());
+        assert!(numbering("{counter").next(None, date(2025, 1)).is_err());
+    }
+
+    #[test]
+    fn test_state_file() {
+        assert_eq!(
+            numbering("{counter}").state_file("config/config.toml"),
+            std::path::Path::new("config/invoice-numbers.toml")
+        );
+        assert_eq!(
+            numbering("{counter}").state_file("config.toml"),
+            std::path::Path::new("invoice-numbers.toml")
+        );
+
+        let directory =
+            std::env::temp_dir().join(format!("xrechnung-numbering-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let state_file = directory.join("numbers.toml");
+        assert_eq!(super::read_counter(&state_file).unwrap(), None);
+        let counter = Counter {
+            period: "2025".to_string(),
+            counter: 7,
+        };
+        super::write_counter(&state_file, &counter).unwrap();
+        assert_eq!(super::read_counter(&state_file).unwrap(), Some(counter));
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_lock() {
+        let directory =
+            std::env::temp_dir().join(format!("xrechnung-numbering-lock-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let state_file = directory.join("numbers.toml");
+
+        let lock = super::lock(&state_file).unwrap();
+        assert!(directory.join("numbers.toml.lock").exists());
+        let timeout = std::time::Duration::from_millis(100);
+        assert!(super::lock_with_timeout(&state_file, timeout).is_err());
+
+        // the lock is released when it is dropped, also while another run waits for it
+        let waiting = std::thread::spawn({
+            let state_file = state_file.clone();
+            move || super::lock(&state_file).is_ok()
+        });
+        std::thread::sleep(timeout);
+        drop(lock);
+        assert!(waiting.join().unwrap());
+        assert!(!directory.join("numbers.toml.lock").exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
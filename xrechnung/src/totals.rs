@@ -0,0 +1,186 @@
+//! Calculation of the amounts of an invoice, i.e., the net amounts of the invoice lines, the VAT breakdown and the
+//! document totals. The [`create`][crate::create] function writes exactly these amounts into the invoice, so that they
+//! can be shown (e.g., in a preview) or checked without reading them from the XML structure.
+//!
+//! All amounts are rounded to cents with the [rounding mode][crate::money::Rounding] of the bill. The totals are
+//! calculated from the rounded amounts, so that they add up as required by the business rules of EN 16931 (e.g., the
+//! sum of the line amounts is the total of the lines).
+//!
+//! # Example
+//! ```
+//! let config = xrechnung::config::load("examples/config.toml", "Client Company").unwrap();
+//! let bill = xrechnung::data::Bill::new(
+//!     "2025-0001".to_string(),
+//!     chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+//!     None,
+//!     &config,
+//! );
+//! let lines = vec![xrechnung::data::InvoiceHoursElement::new("Development", 7.5, 100.0)];
+//!
+//! let totals = xrechnung::totals::calculate(&bill, &lines);
+//! assert_eq!(totals.line_amounts[0].decimal(), "750.00");
+//! assert_eq!(totals.tax_amount.decimal(), "142.50");
+//! assert_eq!(totals.payable_amount.to_string(), "892.50 EUR");
+//! ```
+
+use crate::data::{Bill, InvoiceHoursElement, TaxCategory};
+use crate::money::{self, Money};
+use std::ops::Add;
+
+/// The VAT breakdown of an invoice for a VAT category and rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaxSubtotal {
+    /// The VAT category.
+    pub category: TaxCategory,
+
+    /// The VAT rate in percent, which is zero for all categories but the standard rated one.
+    pub percent: f32,
+
+    /// The sum of the amounts that are taxed with the category and rate.
+    pub taxable_amount: Money,
+
+    /// The VAT amount of the category and rate.
+    pub tax_amount: Money,
+}
+
+/// The amounts of an invoice.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Totals {
+    /// The net amounts of the invoice lines, in the order of the lines.
+    pub line_amounts: Vec<Money>,
+
+    /// The VAT breakdown per VAT category and rate.
+    pub subtotals: Vec<TaxSubtotal>,
+
+    /// The sum of the net amounts of the invoice lines.
+    pub line_extension_amount: Money,
+
+    /// The sum of the allowances on document level.
+    pub allowance_total_amount: Money,
+
+    /// The sum of the charges on document level.
+    pub charge_total_amount: Money,
+
+    /// The total amount of the invoice without VAT.
+    pub tax_exclusive_amount: Money,
+
+    /// The total VAT amount of the invoice.
+    pub tax_amount: Money,
+
+    /// The total amount of the invoice with VAT.
+    pub tax_inclusive_amount: Money,
+
+    /// The amount that has already been paid.
+    pub prepaid_amount: Money,
+
+    /// The amount by which the payable amount is rounded.
+    pub payable_rounding_amount: Money,
+
+    /// The amount that is due for payment.
+    pub payable_amount: Money,
+}
+
+/// Calculates the amounts of the invoice with the given metadata and invoice lines.
+pub fn calculate(bill: &Bill, lines: &[InvoiceHoursElement]) -> Totals {
+    let (currency, rounding) = (&bill.currency, bill.amount_rounding);
+    let line_amounts: Vec<Money> = lines
+        .iter()
+        .map(|line| {
+            let amount = money::decimal(line.quantity) * money::decimal(line.hourly_rate);
+            Money::new(amount, currency, rounding)
+        })
+        .collect();
+    let line_extension_amount = line_amounts
+        .iter()
+        .cloned()
+        .fold(Money::zero(currency), Add::add);
+
+    // there are no allowances or charges on document level yet
+    let (allowance_total_amount, charge_total_amount) =
+        (Money::zero(currency), Money::zero(currency));
+    let tax_exclusive_amount = line_extension_amount.clone() - allowance_total_amount.clone()
+        + charge_total_amount.clone();
+
+    let percent = bill.tax_percent();
+    let subtotals = vec![TaxSubtotal {
+        category: bill.tax_category.clone(),
+        percent,
+        taxable_amount: tax_exclusive_amount.clone(),
+        tax_amount: tax_exclusive_amount.percentage(money::decimal(percent), rounding),
+    }];
+    let tax_amount = subtotals
+        .iter()
+        .map(|subtotal| subtotal.tax_amount.clone())
+        .fold(Money::zero(currency), Add::add);
+    let tax_inclusive_amount = tax_exclusive_amount.clone() + tax_amount.clone();
+
+    let (prepaid_amount, payable_rounding_amount) = (Money::zero(currency), Money::zero(currency));
+    Totals {
+        line_amounts,
+        subtotals,
+        line_extension_amount,
+        allowance_total_amount,
+        charge_total_amount,
+        tax_exclusive_amount,
+        tax_amount,
+        payable_amount: tax_inclusive_amount.clone() - prepaid_amount.clone()
+            + payable_rounding_amount.clone(),
+        tax_inclusive_amount,
+        prepaid_amount,
+        payable_rounding_amount,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{Bill, InvoiceHoursElement, TaxCategory};
+    use crate::money::{Money, Rounding};
+
+    #[test]
+    fn test_calculate() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let mut bill = Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            None,
+            &config,
+        );
+        let lines = vec![
+            InvoiceHoursElement::new("Development", 0.25, 33.33),
+            InvoiceHoursElement::new("Development", 0.25, 33.33),
+            InvoiceHoursElement::new("Development", 0.25, 33.33),
+        ];
+
+        // the line amounts are rounded before they are summed up (to 24.99 instead of 25.00)
+        let totals = super::calculate(&bill, &lines);
+        let line_amounts: Vec<String> = totals.line_amounts.iter().map(Money::decimal).collect();
+        assert_eq!(line_amounts, ["8.33", "8.33", "8.33"]);
+        assert_eq!(totals.line_extension_amount.decimal(), "24.99");
+        assert_eq!(totals.tax_exclusive_amount.decimal(), "24.99");
+        assert_eq!(totals.subtotals.len(), 1);
+        assert_eq!(totals.subtotals[0].taxable_amount.decimal(), "24.99");
+        assert_eq!(totals.subtotals[0].tax_amount.decimal(), "4.75");
+        assert_eq!(totals.tax_inclusive_amount.decimal(), "29.74");
+        assert_eq!(totals.payable_amount.decimal(), "29.74");
+
+        // the amounts are rounded with the rounding mode of the bill (0.5 hours at 0.25 are 0.125)
+        let lines = vec![InvoiceHoursElement::new("Development", 0.5, 0.25)];
+        assert_eq!(
+            super::calculate(&bill, &lines).line_amounts[0].decimal(),
+            "0.13"
+        );
+        bill.amount_rounding = Rounding::HalfEven;
+        assert_eq!(
+            super::calculate(&bill, &lines).line_amounts[0].decimal(),
+            "0.12"
+        );
+
+        bill.tax_category = TaxCategory::ReverseCharge;
+        let lines = vec![InvoiceHoursElement::new("Development", 7.5, 100.0)];
+        let totals = super::calculate(&bill, &lines);
+        assert_eq!(totals.subtotals[0].category, TaxCategory::ReverseCharge);
+        assert_eq!(totals.subtotals[0].percent, 0.0);
+        assert_eq!(totals.tax_amount.decimal(), "0.00");
+        assert_eq!(totals.payable_amount.decimal(), "750.00");
+    }
+}
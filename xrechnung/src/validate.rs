@@ -0,0 +1,1170 @@
+//! Validation of invoices against the business rules of EN 16931, so that invoices that would be rejected by the
+//! receiver can be found before they are sent.
+//!
+//! The rules are checked on the XML structure of an invoice in UBL syntax, as created by [`create`][crate::create] or
+//! read with [`XmlElement::parse`]. Elements are identified by their local names (without namespace prefix). The
+//! following rules are implemented:
+//!
+//! - the presence of the mandatory fields of the invoice, the seller, the buyer and the invoice lines (BR-01 to BR-16,
+//!   BR-21 to BR-26) and of the payment due date or terms for positive amounts (BR-CO-25)
+//! - the plausibility of the invoice and line periods (BR-29, BR-30) and item prices (BR-27)
+//! - the calculation of the document totals (BR-CO-10, BR-CO-13 to BR-CO-16) and of the VAT breakdown for the
+//!   standard rated category (BR-S-08, BR-S-09)
+//!
+//! Invoices that declare to follow the XRechnung specification (by their specification identifier) are also checked
+//! against the error rules of the German CIUS (BR-DE-1 to BR-DE-11, BR-DE-14 to BR-DE-20 and BR-DE-23 to BR-DE-25),
+//! e.g., the mandatory contact information of the seller, the buyer reference and the payment account for credit
+//! transfers. The rules that only lead to warnings are not checked.
+//!
+//! The data of an invoice can also be checked before the XML structure is created, with the [`check`] function. It
+//! checks the rules that concern the data given by the supplier, the buyer, the bill and the invoice lines, e.g., the
+//! mandatory fields, the periods, the country codes and the data needed for the VAT category.
+//!
+//! # Example
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let invoice = xrechnung::XmlElement::parse(&std::fs::read_to_string("invoice.xml")?)?;
+//! for violation in xrechnung::validate::validate(&invoice) {
+//!     println!("{violation}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::XmlElement;
+use crate::config::{Buyer, Supplier};
+use crate::data::{Bill, InvoiceHoursElement, TaxCategory};
+
+/// A violation of a business rule by an invoice.
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    /// The identifier of the violated rule, e.g., "BR-CO-10".
+    pub rule: &'static str,
+
+    /// The path of the element that violates the rule (or that is missing), e.g., `/Invoice/InvoiceLine[2]/ID`, or of
+    /// the field of the invoice data for violations found by [`check`], e.g., `lines[1].name`.
+    pub path: String,
+
+    /// The description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.rule, self.path, self.message)
+    }
+}
+
+/// Returns the name of the element without namespace prefix.
+fn local_name(element: &XmlElement) -> &str {
+    element.name().rsplit(':').next().unwrap_or_default()
+}
+
+/// Returns the child elements with the given local name.
+fn children<'a>(element: &'a XmlElement, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+    element
+        .children()
+        .iter()
+        .filter(move |child| local_name(child) == name)
+}
+
+/// Returns the first element at the given path of local names below the given element.
+fn find<'a>(element: &'a XmlElement, path: &[&'a str]) -> Option<&'a XmlElement> {
+    path.iter()
+        .try_fold(element, |element, name| children(element, name).next())
+}
+
+/// Returns the trimmed text of the element at the given path, if it exists and is not empty.
+fn text<'a>(element: &'a XmlElement, path: &[&'a str]) -> Option<&'a str> {
+    find(element, path)
+        .and_then(XmlElement::text)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+/// Returns the number at the given path, if it exists and is a number.
+fn number(element: &XmlElement, path: &[&str]) -> Option<f64> {
+    text(element, path).and_then(|text| text.parse().ok())
+}
+
+/// Returns the given amount in cents, for comparing amounts with two decimals exactly.
+fn cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+/// Collects the violations of the rules.
+struct Validator {
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    fn report(&mut self, rule: &'static str, path: String, message: String) {
+        self.violations.push(Violation {
+            rule,
+            path,
+            message,
+        });
+    }
+
+    /// Reports a violation, if the element at the given path has no text.
+    fn require_text(
+        &mut self,
+        rule: &'static str,
+        element: &XmlElement,
+        base: &str,
+        path: &[&str],
+        description: &str,
+    ) {
+        if text(element, path).is_none() {
+            let message = format!("The {description} is missing.");
+            self.report(rule, format!("{base}/{}", path.join("/")), message);
+        }
+    }
+
+    /// Reports a violation, if the element at the given path does not exist.
+    fn require_element(
+        &mut self,
+        rule: &'static str,
+        element: &XmlElement,
+        base: &str,
+        path: &[&str],
+        description: &str,
+    ) {
+        if find(element, path).is_none() {
+            let message = format!("The {description} is missing.");
+            self.report(rule, format!("{base}/{}", path.join("/")), message);
+        }
+    }
+
+    /// Reports a violation, if the end date of the period at the given path lies before its start date.
+    fn check_period(&mut self, rule: &'static str, element: &XmlElement, base: &str) {
+        if let (Some(start), Some(end)) = (
+            text(element, &["InvoicePeriod", "StartDate"]),
+            text(element, &["InvoicePeriod", "EndDate"]),
+        ) && end < start
+        {
+            self.report(
+                rule,
+                format!("{base}/InvoicePeriod/EndDate"),
+                format!("The end date {end} of the period lies before its start date {start}."),
+            );
+        }
+    }
+
+    /// Reports a violation, if the amount at the given path differs from the expected amount.
+    fn check_amount(
+        &mut self,
+        rule: &'static str,
+        invoice: &XmlElement,
+        path: &[&str],
+        expected: f64,
+        description: &str,
+    ) {
+        if let Some(amount) = number(invoice, path)
+            && cents(amount) != cents(expected)
+        {
+            self.report(
+                rule,
+                format!("/Invoice/{}", path.join("/")),
+                format!("The amount {amount:.2} differs from {description} ({expected:.2})."),
+            );
+        }
+    }
+}
+
+/// Validates the given invoice in UBL syntax against the business rules of EN 16931 (see the module documentation for
+/// the implemented rules) and returns all violations that were found (none for a valid invoice).
+pub fn validate(invoice: &XmlElement) -> Vec<Violation> {
+    let mut validator = Validator {
+        violations: Vec::new(),
+    };
+    let root = "/Invoice";
+
+    let mandatory = [
+        (
+            "BR-01",
+            &["CustomizationID"][..],
+            "specification identifier",
+        ),
+        ("BR-02", &["ID"], "invoice number"),
+        ("BR-03", &["IssueDate"], "invoice issue date"),
+        ("BR-04", &["InvoiceTypeCode"], "invoice type code"),
+        ("BR-05", &["DocumentCurrencyCode"], "invoice currency code"),
+        (
+            "BR-06",
+            &[
+                "AccountingSupplierParty",
+                "Party",
+                "PartyLegalEntity",
+                "RegistrationName",
+            ],
+            "seller name",
+        ),
+        (
+            "BR-07",
+            &[
+                "AccountingCustomerParty",
+                "Party",
+                "PartyLegalEntity",
+                "RegistrationName",
+            ],
+            "buyer name",
+        ),
+    ];
+    for (rule, path, description) in mandatory {
+        validator.require_text(rule, invoice, root, path, description);
+    }
+
+    for (party, address_rule, country_rule, role) in [
+        ("AccountingSupplierParty", "BR-08", "BR-09", "seller"),
+        ("AccountingCustomerParty", "BR-10", "BR-11", "buyer"),
+    ] {
+        let address = [party, "Party", "PostalAddress"];
+        validator.require_element(
+            address_rule,
+            invoice,
+            root,
+            &address,
+            &format!("postal address of the {role}"),
+        );
+        if find(invoice, &address).is_some() {
+            validator.require_text(
+                country_rule,
+                invoice,
+                root,
+                &[
+                    party,
+                    "Party",
+                    "PostalAddress",
+                    "Country",
+                    "IdentificationCode",
+                ],
+                &format!("country code of the {role}"),
+            );
+        }
+    }
+
+    let totals = [
+        (
+            "BR-12",
+            "LineExtensionAmount",
+            "sum of the invoice line net amounts",
+        ),
+        (
+            "BR-13",
+            "TaxExclusiveAmount",
+            "invoice total amount without VAT",
+        ),
+        (
+            "BR-14",
+            "TaxInclusiveAmount",
+            "invoice total amount with VAT",
+        ),
+        ("BR-15", "PayableAmount", "amount due for payment"),
+    ];
+    for (rule, name, description) in totals {
+        validator.require_text(
+            rule,
+            invoice,
+            root,
+            &["LegalMonetaryTotal", name],
+            description,
+        );
+    }
+
+    validator.check_period("BR-29", invoice, root);
+
+    // the invoice lines
+    let lines: Vec<&XmlElement> = children(invoice, "InvoiceLine").collect();
+    if lines.is_empty() {
+        validator.report(
+            "BR-16",
+            format!("{root}/InvoiceLine"),
+            "The invoice has no invoice line.".to_string(),
+        );
+    }
+    for (index, line) in lines.iter().enumerate() {
+        let base = format!("{root}/InvoiceLine[{}]", index + 1);
+        let line_fields = [
+            ("BR-21", &["ID"][..], "invoice line identifier"),
+            ("BR-22", &["InvoicedQuantity"], "invoiced quantity"),
+            ("BR-24", &["LineExtensionAmount"], "invoice line net amount"),
+            ("BR-25", &["Item", "Name"], "item name"),
+            ("BR-26", &["Price", "PriceAmount"], "item net price"),
+        ];
+        for (rule, path, description) in line_fields {
+            validator.require_text(rule, line, &base, path, description);
+        }
+        if let Some(quantity) = find(line, &["InvoicedQuantity"])
+            && quantity.attribute("unitCode").is_none_or(str::is_empty)
+        {
+            validator.report(
+                "BR-23",
+                format!("{base}/InvoicedQuantity/@unitCode"),
+                "The unit of measure of the invoiced quantity is missing.".to_string(),
+            );
+        }
+        if let Some(price) = number(line, &["Price", "PriceAmount"])
+            && price < 0.0
+        {
+            validator.report(
+                "BR-27",
+                format!("{base}/Price/PriceAmount"),
+                format!("The item net price {price:.2} is negative."),
+            );
+        }
+        validator.check_period("BR-30", line, &base);
+    }
+
+    // the payment due date or terms are needed for invoices with an amount due
+    if number(invoice, &["LegalMonetaryTotal", "PayableAmount"]).is_some_and(|amount| amount > 0.0)
+        && text(invoice, &["DueDate"]).is_none()
+        && text(invoice, &["PaymentTerms", "Note"]).is_none()
+    {
+        validator.report(
+            "BR-CO-25",
+            format!("{root}/DueDate"),
+            "Either the payment due date or the payment terms are needed for a positive amount due.".to_string(),
+        );
+    }
+
+    check_totals(&mut validator, invoice, &lines);
+    if text(invoice, &["CustomizationID"])
+        .is_some_and(|id| id.contains("urn:xeinkauf.de:kosit:xrechnung"))
+    {
+        check_xrechnung(&mut validator, invoice);
+    }
+    validator.violations
+}
+
+/// Checks the data of an invoice against the business rules of EN 16931 and XRechnung that concern the data itself,
+/// independent of the XML structure that is created from it, and returns all violations that were found (none for
+/// valid data). The paths of the violations are the fields of the data, e.g., `supplier.address.city`.
+pub fn check(
+    bill: &Bill,
+    supplier: &Supplier,
+    buyer: &Buyer,
+    lines: &[InvoiceHoursElement],
+) -> Vec<Violation> {
+    let mut validator = Validator {
+        violations: Vec::new(),
+    };
+    let mut require = |rule: &'static str, path: &str, value: &str, description: &str| {
+        if value.trim().is_empty() {
+            validator.report(
+                rule,
+                path.to_string(),
+                format!("The {description} is missing."),
+            );
+        }
+    };
+
+    require("BR-02", "bill.number", &bill.number, "invoice number");
+    require("BR-06", "supplier.name", &supplier.name, "seller name");
+    require("BR-07", "buyer.name", &buyer.name, "buyer name");
+    for (party, address, rule, role) in [
+        ("supplier", &supplier.address, "BR-09", "seller"),
+        ("buyer", &buyer.address, "BR-11", "buyer"),
+    ] {
+        require(
+            rule,
+            &format!("{party}.address.country_code"),
+            &address.country_code,
+            &format!("country code of the {role}"),
+        );
+    }
+    let mandatory = [
+        (
+            "BR-DE-3",
+            "supplier.address.city",
+            &supplier.address.city,
+            "city of the seller",
+        ),
+        (
+            "BR-DE-4",
+            "supplier.address.post_code",
+            &supplier.address.post_code,
+            "post code of the seller",
+        ),
+        (
+            "BR-DE-6",
+            "supplier.phone",
+            &supplier.phone,
+            "contact telephone number of the seller",
+        ),
+        (
+            "BR-DE-7",
+            "supplier.email",
+            &supplier.email,
+            "contact email address of the seller",
+        ),
+        (
+            "BR-DE-8",
+            "buyer.address.city",
+            &buyer.address.city,
+            "city of the buyer",
+        ),
+        (
+            "BR-DE-9",
+            "buyer.address.post_code",
+            &buyer.address.post_code,
+            "post code of the buyer",
+        ),
+        (
+            "BR-DE-16",
+            "supplier.tax_identification",
+            &supplier.tax_identification,
+            "VAT identifier of the seller",
+        ),
+    ];
+    for (rule, path, value, description) in mandatory {
+        require(rule, path, value, description);
+    }
+    if buyer.leitweg_id.is_none() {
+        require(
+            "BR-DE-15",
+            "buyer.reference",
+            &buyer.reference,
+            "buyer reference",
+        );
+    }
+
+    // the codes (the currency is always a valid code)
+    for (party, address) in [("supplier", &supplier.address), ("buyer", &buyer.address)] {
+        let code = &address.country_code;
+        if !code.trim().is_empty() && !crate::identifiers::is_country_code(code) {
+            validator.report(
+                "BR-CL-14",
+                format!("{party}.address.country_code"),
+                format!("The country code '{code}' is no ISO 3166-1 (Alpha-2) country code."),
+            );
+        }
+    }
+
+    // the VAT category
+    let buyer_vat_rule = match &bill.tax_category {
+        TaxCategory::Standard if bill.vat_percent <= 0.0 => {
+            validator.report(
+                "BR-S-05",
+                "bill.vat_percent".to_string(),
+                format!(
+                    "The VAT rate {} % of the standard rated category is not greater than zero.",
+                    bill.vat_percent
+                ),
+            );
+            None
+        }
+        TaxCategory::Exempt(reason) if reason.trim().is_empty() => {
+            validator.report(
+                "BR-E-10",
+                "bill.tax_category".to_string(),
+                "The VAT exemption reason is missing.".to_string(),
+            );
+            None
+        }
+        TaxCategory::ReverseCharge => Some("BR-AE-02"),
+        TaxCategory::IntraCommunity => Some("BR-IC-02"),
+        _ => None,
+    };
+    if let Some(rule) = buyer_vat_rule
+        && buyer.tax_identification.trim().is_empty()
+    {
+        validator.report(
+            rule,
+            "buyer.tax_identification".to_string(),
+            format!(
+                "The VAT identifier of the buyer is missing, which is needed for the VAT category '{}'.",
+                bill.tax_category.code()
+            ),
+        );
+    }
+
+    // the periods and the invoice lines
+    if let Some(period) = &bill.period
+        && period.end < period.start
+    {
+        validator.report(
+            "BR-29",
+            "bill.period.end".to_string(),
+            format!(
+                "The end date {} of the period lies before its start date {}.",
+                period.end, period.start
+            ),
+        );
+    }
+    if lines.is_empty() {
+        validator.report(
+            "BR-16",
+            "lines".to_string(),
+            "The invoice has no invoice line.".to_string(),
+        );
+    }
+    for (index, line) in lines.iter().enumerate() {
+        if line.name.trim().is_empty() {
+            validator.report(
+                "BR-25",
+                format!("lines[{index}].name"),
+                "The item name is missing.".to_string(),
+            );
+        }
+        if line.hourly_rate < 0.0 {
+            validator.report(
+                "BR-27",
+                format!("lines[{index}].hourly_rate"),
+                format!("The item net price {:.2} is negative.", line.hourly_rate),
+            );
+        }
+        if let Err(error) = line.period() {
+            validator.report(
+                "BR-30",
+                format!("lines[{index}]"),
+                format!("The period of the invoice line is invalid: {error}"),
+            );
+        }
+    }
+    validator.violations
+}
+
+/// The allowed invoice type codes of XRechnung (BR-DE-17).
+const XRECHNUNG_TYPE_CODES: [&str; 8] = ["326", "380", "384", "389", "381", "875", "876", "877"];
+
+/// The VAT categories that require a VAT identifier of the seller or its tax representative (BR-DE-16).
+const CATEGORIES_WITH_SELLER_VAT_ID: [&str; 8] = ["S", "Z", "E", "AE", "K", "G", "L", "M"];
+
+/// Returns whether the given line of the payment terms is a valid cash discount ("Skonto") in the format of
+/// XRechnung, e.g., `#SKONTO#TAGE=14#PROZENT=2.00#` or `#SKONTO#TAGE=7#PROZENT=3.00#BASISBETRAG=1000.00#`.
+fn is_cash_discount(line: &str) -> bool {
+    let is_number = |value: &str| !value.is_empty() && value.chars().all(|c| c.is_ascii_digit());
+    let is_amount = |value: &str| {
+        value.split_once('.').is_some_and(|(integer, decimals)| {
+            is_number(integer) && is_number(decimals) && decimals.len() == 2
+        })
+    };
+
+    let Some(fields) = line
+        .strip_prefix("#SKONTO#")
+        .and_then(|line| line.strip_suffix('#'))
+    else {
+        return false;
+    };
+    let fields: Vec<&str> = fields.split('#').collect();
+    let (days, percent, base) = match fields[..] {
+        [days, percent] => (days, percent, None),
+        [days, percent, base] => (days, percent, Some(base)),
+        _ => return false,
+    };
+    days.strip_prefix("TAGE=").is_some_and(is_number)
+        && percent.strip_prefix("PROZENT=").is_some_and(is_amount)
+        && base.is_none_or(|base| {
+            base.strip_prefix("BASISBETRAG=")
+                .is_some_and(|base| is_amount(base.strip_prefix('-').unwrap_or(base)))
+        })
+}
+
+/// Checks the rules of the German CIUS XRechnung.
+fn check_xrechnung(validator: &mut Validator, invoice: &XmlElement) {
+    let root = "/Invoice";
+    let seller = ["AccountingSupplierParty", "Party"];
+    let buyer = ["AccountingCustomerParty", "Party"];
+    let delivery_address = ["Delivery", "DeliveryLocation", "Address"];
+
+    let mandatory: [(&'static str, Vec<&str>, &str); 8] = [
+        (
+            "BR-DE-3",
+            [&seller[..], &["PostalAddress", "CityName"]].concat(),
+            "city of the seller",
+        ),
+        (
+            "BR-DE-4",
+            [&seller[..], &["PostalAddress", "PostalZone"]].concat(),
+            "post code of the seller",
+        ),
+        (
+            "BR-DE-5",
+            [&seller[..], &["Contact", "Name"]].concat(),
+            "contact point of the seller",
+        ),
+        (
+            "BR-DE-6",
+            [&seller[..], &["Contact", "Telephone"]].concat(),
+            "contact telephone number of the seller",
+        ),
+        (
+            "BR-DE-7",
+            [&seller[..], &["Contact", "ElectronicMail"]].concat(),
+            "contact email address of the seller",
+        ),
+        (
+            "BR-DE-8",
+            [&buyer[..], &["PostalAddress", "CityName"]].concat(),
+            "city of the buyer",
+        ),
+        (
+            "BR-DE-9",
+            [&buyer[..], &["PostalAddress", "PostalZone"]].concat(),
+            "post code of the buyer",
+        ),
+        ("BR-DE-15", vec!["BuyerReference"], "buyer reference"),
+    ];
+    validator.require_element(
+        "BR-DE-1",
+        invoice,
+        root,
+        &["PaymentMeans"],
+        "payment instructions",
+    );
+    validator.require_element(
+        "BR-DE-2",
+        invoice,
+        root,
+        &[&seller[..], &["Contact"]].concat(),
+        "seller contact",
+    );
+    for (rule, path, description) in &mandatory {
+        validator.require_text(rule, invoice, root, path, description);
+    }
+    if find(invoice, &delivery_address).is_some() {
+        validator.require_text(
+            "BR-DE-10",
+            invoice,
+            root,
+            &[&delivery_address[..], &["CityName"]].concat(),
+            "city of the delivery address",
+        );
+        validator.require_text(
+            "BR-DE-11",
+            invoice,
+            root,
+            &[&delivery_address[..], &["PostalZone"]].concat(),
+            "post code of the delivery address",
+        );
+    }
+
+    // the VAT breakdown
+    let categories: Vec<&XmlElement> = children(invoice, "TaxTotal")
+        .flat_map(|tax_total| children(tax_total, "TaxSubtotal"))
+        .filter_map(|subtotal| find(subtotal, &["TaxCategory"]))
+        .collect();
+    for (index, category) in categories.iter().enumerate() {
+        if text(category, &["Percent"]).is_none() {
+            validator.report(
+                "BR-DE-14",
+                format!(
+                    "{root}/TaxTotal/TaxSubtotal[{}]/TaxCategory/Percent",
+                    index + 1
+                ),
+                "The VAT category rate is missing.".to_string(),
+            );
+        }
+    }
+    let needs_vat_id = categories.iter().any(|category| {
+        text(category, &["ID"]).is_some_and(|id| CATEGORIES_WITH_SELLER_VAT_ID.contains(&id))
+    });
+    let has_vat_id = children(find(invoice, &seller).unwrap_or(invoice), "PartyTaxScheme")
+        .any(|scheme| text(scheme, &["CompanyID"]).is_some())
+        || find(invoice, &["TaxRepresentativeParty"]).is_some();
+    if needs_vat_id && !has_vat_id {
+        validator.report(
+            "BR-DE-16",
+            format!("{root}/AccountingSupplierParty/Party/PartyTaxScheme/CompanyID"),
+            "The VAT identifier or tax registration identifier of the seller (or a tax representative) is missing."
+                .to_string(),
+        );
+    }
+
+    if let Some(code) = text(invoice, &["InvoiceTypeCode"])
+        && !XRECHNUNG_TYPE_CODES.contains(&code)
+    {
+        validator.report(
+            "BR-DE-17",
+            format!("{root}/InvoiceTypeCode"),
+            format!(
+                "The invoice type code '{code}' is not allowed (allowed: {}).",
+                XRECHNUNG_TYPE_CODES.join(", ")
+            ),
+        );
+    }
+
+    for terms in children(invoice, "PaymentTerms") {
+        for line in text(terms, &["Note"]).unwrap_or_default().lines() {
+            let line = line.trim();
+            if line.starts_with('#') && !is_cash_discount(line) {
+                validator.report(
+                    "BR-DE-18",
+                    format!("{root}/PaymentTerms/Note"),
+                    format!("The cash discount '{line}' does not follow the format '#SKONTO#TAGE=n#PROZENT=n.nn#'."),
+                );
+            }
+        }
+    }
+
+    for (index, payment_means) in children(invoice, "PaymentMeans").enumerate() {
+        check_xrechnung_payment_means(
+            validator,
+            payment_means,
+            &format!("{root}/PaymentMeans[{}]", index + 1),
+        );
+    }
+}
+
+/// Checks the payment instructions of an invoice against the rules of XRechnung for credit transfers (BR-DE-19,
+/// BR-DE-23), payment cards (BR-DE-24) and direct debits (BR-DE-20, BR-DE-25).
+fn check_xrechnung_payment_means(
+    validator: &mut Validator,
+    payment_means: &XmlElement,
+    base: &str,
+) {
+    let has = |name: &str| find(payment_means, &[name]).is_some();
+    let mut report = |rule, path: &str, message: &str| {
+        validator.report(rule, format!("{base}/{path}"), message.to_string());
+    };
+
+    match text(payment_means, &["PaymentMeansCode"]) {
+        Some("30" | "58") => {
+            if !has("PayeeFinancialAccount") {
+                report(
+                    "BR-DE-23-a",
+                    "PayeeFinancialAccount",
+                    "The payment account is missing for a credit transfer.",
+                );
+            }
+            if has("CardAccount") || has("PaymentMandate") {
+                report(
+                    "BR-DE-23-b",
+                    "PaymentMeansCode",
+                    "A credit transfer must not contain payment card or direct debit information.",
+                );
+            }
+        }
+        Some("48" | "54" | "55") => {
+            if !has("CardAccount") {
+                report(
+                    "BR-DE-24-a",
+                    "CardAccount",
+                    "The payment card information is missing for a card payment.",
+                );
+            }
+            if has("PayeeFinancialAccount") || has("PaymentMandate") {
+                report(
+                    "BR-DE-24-b",
+                    "PaymentMeansCode",
+                    "A card payment must not contain credit transfer or direct debit information.",
+                );
+            }
+        }
+        Some("59") => {
+            if !has("PaymentMandate") {
+                report(
+                    "BR-DE-25-a",
+                    "PaymentMandate",
+                    "The direct debit information is missing for a direct debit.",
+                );
+            }
+            if has("PayeeFinancialAccount") || has("CardAccount") {
+                report(
+                    "BR-DE-25-b",
+                    "PaymentMeansCode",
+                    "A direct debit must not contain credit transfer or payment card information.",
+                );
+            }
+        }
+        _ => return,
+    }
+
+    // SEPA payments need valid IBANs
+    let sepa_account = match text(payment_means, &["PaymentMeansCode"]) {
+        Some("58") => Some((
+            "BR-DE-19",
+            "PayeeFinancialAccount/ID",
+            &["PayeeFinancialAccount", "ID"][..],
+        )),
+        Some("59") => Some((
+            "BR-DE-20",
+            "PaymentMandate/PayerFinancialAccount/ID",
+            &["PaymentMandate", "PayerFinancialAccount", "ID"][..],
+        )),
+        _ => None,
+    };
+    if let Some((rule, path, elements)) = sepa_account
+        && let Some(iban) = text(payment_means, elements)
+        && let Err(error) = crate::identifiers::check_iban(iban)
+    {
+        report(
+            rule,
+            path,
+            &format!("The account identifier is no valid IBAN: {error}."),
+        );
+    }
+}
+
+/// Checks the calculation of the document totals and of the VAT breakdown.
+fn check_totals(validator: &mut Validator, invoice: &XmlElement, lines: &[&XmlElement]) {
+    let total = |name: &str| number(invoice, &["LegalMonetaryTotal", name]);
+
+    let line_sum: f64 = lines
+        .iter()
+        .filter_map(|line| number(line, &["LineExtensionAmount"]))
+        .sum();
+    validator.check_amount(
+        "BR-CO-10",
+        invoice,
+        &["LegalMonetaryTotal", "LineExtensionAmount"],
+        line_sum,
+        "the sum of the invoice line net amounts",
+    );
+
+    let allowances = total("AllowanceTotalAmount").unwrap_or(0.0);
+    let charges = total("ChargeTotalAmount").unwrap_or(0.0);
+    if let Some(line_extension) = total("LineExtensionAmount") {
+        validator.check_amount(
+            "BR-CO-13",
+            invoice,
+            &["LegalMonetaryTotal", "TaxExclusiveAmount"],
+            line_extension - allowances + charges,
+            "the sum of the invoice line net amounts minus allowances plus charges",
+        );
+    }
+
+    // the VAT total in the invoice currency is the one with the breakdown
+    let tax_total = children(invoice, "TaxTotal")
+        .find(|tax_total| children(tax_total, "TaxSubtotal").next().is_some())
+        .or(children(invoice, "TaxTotal").next());
+    let tax_amount = tax_total.and_then(|tax_total| number(tax_total, &["TaxAmount"]));
+
+    if let Some(tax_total) = tax_total {
+        let subtotals: Vec<&XmlElement> = children(tax_total, "TaxSubtotal").collect();
+        if !subtotals.is_empty()
+            && let Some(tax_amount) = tax_amount
+        {
+            let sum: f64 = subtotals
+                .iter()
+                .filter_map(|subtotal| number(subtotal, &["TaxAmount"]))
+                .sum();
+            if cents(tax_amount) != cents(sum) {
+                validator.report(
+                    "BR-CO-14",
+                    "/Invoice/TaxTotal/TaxAmount".to_string(),
+                    format!(
+                        "The amount {tax_amount:.2} differs from the sum of the VAT category tax amounts ({sum:.2})."
+                    ),
+                );
+            }
+        }
+        for (index, subtotal) in subtotals.iter().enumerate() {
+            check_standard_rated_subtotal(validator, invoice, lines, subtotal, index);
+        }
+    }
+
+    if let (Some(tax_exclusive), Some(tax_amount)) = (total("TaxExclusiveAmount"), tax_amount) {
+        validator.check_amount(
+            "BR-CO-15",
+            invoice,
+            &["LegalMonetaryTotal", "TaxInclusiveAmount"],
+            tax_exclusive + tax_amount,
+            "the invoice total amount without VAT plus the VAT total amount",
+        );
+    }
+
+    if let Some(tax_inclusive) = total("TaxInclusiveAmount") {
+        let prepaid = total("PrepaidAmount").unwrap_or(0.0);
+        let rounding = total("PayableRoundingAmount").unwrap_or(0.0);
+        validator.check_amount(
+            "BR-CO-16",
+            invoice,
+            &["LegalMonetaryTotal", "PayableAmount"],
+            tax_inclusive - prepaid + rounding,
+            "the invoice total amount with VAT minus the paid amount plus the rounding amount",
+        );
+    }
+}
+
+/// Checks the taxable amount and the tax amount of a VAT breakdown for the standard rated category ("S").
+fn check_standard_rated_subtotal(
+    validator: &mut Validator,
+    invoice: &XmlElement,
+    lines: &[&XmlElement],
+    subtotal: &XmlElement,
+    index: usize,
+) {
+    if text(subtotal, &["TaxCategory", "ID"]) != Some("S") {
+        return;
+    }
+    let Some(percent) = number(subtotal, &["TaxCategory", "Percent"]) else {
+        return;
+    };
+    let base = format!("/Invoice/TaxTotal/TaxSubtotal[{}]", index + 1);
+    let has_category = |element: &XmlElement, path: &[&str]| {
+        find(element, path).is_some_and(|category| {
+            text(category, &["ID"]) == Some("S")
+                && number(category, &["Percent"]).is_some_and(|rate| cents(rate) == cents(percent))
+        })
+    };
+
+    // lines as well as document level allowances and charges of the category and rate make up the taxable amount
+    let mut expected: f64 = lines
+        .iter()
+        .filter(|line| has_category(line, &["Item", "ClassifiedTaxCategory"]))
+        .filter_map(|line| number(line, &["LineExtensionAmount"]))
+        .sum();
+    for allowance_charge in children(invoice, "AllowanceCharge") {
+        if has_category(allowance_charge, &["TaxCategory"]) {
+            let amount = number(allowance_charge, &["Amount"]).unwrap_or(0.0);
+            match text(allowance_charge, &["ChargeIndicator"]) {
+                Some("true") => expected += amount,
+                _ => expected -= amount,
+            }
+        }
+    }
+
+    if let Some(taxable) = number(subtotal, &["TaxableAmount"]) {
+        if cents(taxable) != cents(expected) {
+            validator.report(
+                "BR-S-08",
+                format!("{base}/TaxableAmount"),
+                format!(
+                    "The amount {taxable:.2} differs from the sum of the line net amounts with VAT rate {percent} % \
+                     minus allowances plus charges ({expected:.2})."
+                ),
+            );
+        }
+        if let Some(tax_amount) = number(subtotal, &["TaxAmount"]) {
+            let expected = taxable * percent / 100.0;
+            if cents(tax_amount) != cents(expected) {
+                validator.report(
+                    "BR-S-09",
+                    format!("{base}/TaxAmount"),
+                    format!(
+                        "The amount {tax_amount:.2} differs from the taxable amount multiplied by the VAT rate \
+                         ({expected:.2})."
+                    ),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::XmlElement;
+    use crate::data::{Bill, InvoiceHoursElement};
+
+    /// Creates an invoice for the example configuration.
+    fn create_invoice() -> XmlElement {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let bill = Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            None,
+            &config,
+        );
+        let invoice_hours = vec![
+            InvoiceHoursElement {
+                name: "Example Service".to_string(),
+                quantity: 7.0,
+                hourly_rate: 110.0,
+                ..Default::default()
+            },
+            InvoiceHoursElement {
+                name: "Another Service".to_string(),
+                quantity: 6.5,
+                hourly_rate: 110.0,
+                ..Default::default()
+            },
+        ];
+        crate::create(config.supplier, config.buyer, bill, invoice_hours)
+            .unwrap()
+            .0
+    }
+
+    /// Creates an invoice for the example configuration and returns it as XML document.
+    fn create_document() -> String {
+        let mut writer = quick_xml::writer::Writer::new(Vec::new());
+        create_invoice().write(&mut writer).unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    /// Returns the violations of the given document as strings.
+    fn violations(document: &str) -> Vec<String> {
+        super::validate(&XmlElement::parse(document).unwrap())
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_invoice() {
+        assert_eq!(violations(&create_document()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_comments() {
+        // comments and processing instructions are no elements of the invoice
+        let mut invoice = create_invoice();
+        let index = invoice
+            .children()
+            .iter()
+            .position(|child| child.name() == "cbc:ID")
+            .unwrap();
+        invoice.insert_item(index, XmlElement::comment(" invoice number "));
+        invoice.push_item(XmlElement::processing_instruction("review", "pending"));
+        assert_eq!(super::validate(&invoice), Vec::new());
+        assert_eq!(invoice.children()[index].name(), "cbc:ID");
+        assert!(
+            crate::to_xml_string(&invoice)
+                .unwrap()
+                .contains("<!-- invoice number -->\n    <cbc:ID>2025-0001</cbc:ID>")
+        );
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let document = create_document()
+            .replace("<cbc:ID>2025-0001</cbc:ID>", "")
+            .replace("<cbc:Name>Another Service</cbc:Name>", "")
+            .replace(" unitCode=\"HUR\"", "");
+        let violations = violations(&document);
+        assert_eq!(
+            violations,
+            vec![
+                "[BR-02] /Invoice/ID: The invoice number is missing.",
+                "[BR-23] /Invoice/InvoiceLine[1]/InvoicedQuantity/@unitCode: The unit of measure of the invoiced \
+                 quantity is missing.",
+                "[BR-25] /Invoice/InvoiceLine[2]/Item/Name: The item name is missing.",
+                "[BR-23] /Invoice/InvoiceLine[2]/InvoicedQuantity/@unitCode: The unit of measure of the invoiced \
+                 quantity is missing.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrong_totals() {
+        let document = create_document()
+            .replace(
+                "<cbc:LineExtensionAmount currencyID=\"EUR\">715.00</cbc:LineExtensionAmount>",
+                "<cbc:LineExtensionAmount currencyID=\"EUR\">715.01</cbc:LineExtensionAmount>",
+            )
+            .replace(
+                "<cbc:PayableAmount currencyID=\"EUR\">1767.15</cbc:PayableAmount>",
+                "<cbc:PayableAmount currencyID=\"EUR\">1767.00</cbc:PayableAmount>",
+            );
+        let violations = violations(&document);
+        assert_eq!(
+            violations,
+            vec![
+                "[BR-CO-10] /Invoice/LegalMonetaryTotal/LineExtensionAmount: The amount 1485.00 differs from the sum \
+                 of the invoice line net amounts (1485.01).",
+                "[BR-S-08] /Invoice/TaxTotal/TaxSubtotal[1]/TaxableAmount: The amount 1485.00 differs from the sum \
+                 of the line net amounts with VAT rate 19 % minus allowances plus charges (1485.01).",
+                "[BR-CO-16] /Invoice/LegalMonetaryTotal/PayableAmount: The amount 1767.00 differs from the invoice \
+                 total amount with VAT minus the paid amount plus the rounding amount (1767.15).",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_lines() {
+        let document = create_document();
+        let start = document.find("<cac:InvoiceLine>").unwrap();
+        let end = document.rfind("</cac:InvoiceLine>").unwrap() + "</cac:InvoiceLine>".len();
+        let document = format!("{}{}", &document[..start], &document[end..]);
+        assert!(violations(&document).contains(
+            &"[BR-16] /Invoice/InvoiceLine: The invoice has no invoice line.".to_string()
+        ));
+    }
+
+    #[test]
+    fn test_xrechnung_rules() {
+        let document = create_document()
+            .replace("<cbc:BuyerReference>12345</cbc:BuyerReference>", "")
+            .replace("<cbc:Telephone>+4932123456789</cbc:Telephone>", "")
+            .replace("DE43345678111111111100", "DE00345678111111111100")
+            .replace(
+                "Payable without deduction until 2025-02-20.",
+                "#SKONTO#TAGE=14#PROZENT=2#",
+            );
+        assert_eq!(
+            violations(&document),
+            vec![
+                "[BR-DE-6] /Invoice/AccountingSupplierParty/Party/Contact/Telephone: The contact telephone number of \
+                 the seller is missing.",
+                "[BR-DE-15] /Invoice/BuyerReference: The buyer reference is missing.",
+                "[BR-DE-18] /Invoice/PaymentTerms/Note: The cash discount '#SKONTO#TAGE=14#PROZENT=2#' does not \
+                 follow the format '#SKONTO#TAGE=n#PROZENT=n.nn#'.",
+                "[BR-DE-19] /Invoice/PaymentMeans[1]/PayeeFinancialAccount/ID: The account identifier is no valid \
+                 IBAN: 'DE00345678111111111100' has an invalid checksum.",
+            ]
+        );
+
+        // the rules of XRechnung only apply to invoices that follow its specification
+        let document = document.replace(":xrechnung_3.0", "");
+        assert_eq!(violations(&document), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_is_cash_discount() {
+        assert!(super::is_cash_discount("#SKONTO#TAGE=14#PROZENT=2.00#"));
+        assert!(super::is_cash_discount(
+            "#SKONTO#TAGE=7#PROZENT=3.00#BASISBETRAG=-1000.00#"
+        ));
+        assert!(!super::is_cash_discount("#SKONTO#TAGE=14#PROZENT=2.00"));
+        assert!(!super::is_cash_discount("#SKONTO#TAGE=14#PROZENT=2,00#"));
+        assert!(!super::is_cash_discount("#SKONTO#TAGE=#PROZENT=2.00#"));
+        assert!(!super::is_cash_discount(
+            "#SKONTO#TAGE=14#PROZENT=2.00#BETRAG=100.00#"
+        ));
+    }
+
+    #[test]
+    fn test_check() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let mut bill = Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            Some(crate::data::Period::new(
+                chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            )),
+            &config,
+        );
+        let mut lines = vec![InvoiceHoursElement::new("Development", 7.0, 110.0)];
+        let (mut supplier, mut buyer) = (config.supplier, config.buyer);
+        assert!(super::check(&bill, &supplier, &buyer, &lines).is_empty());
+
+        bill.period = Some(crate::data::Period::new(
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        ));
+        bill.tax_category = crate::data::TaxCategory::ReverseCharge;
+        supplier.phone = String::new();
+        buyer.address.country_code = "Germany".to_string();
+        buyer.tax_identification = " ".to_string();
+        lines[0].hourly_rate = -1.0;
+        lines.push(InvoiceHoursElement {
+            start_date: Some("2025-01-03".to_string()),
+            end_date: Some("2025-01-02".to_string()),
+            ..InvoiceHoursElement::new("", 1.0, 110.0)
+        });
+
+        let violations: Vec<String> = super::check(&bill, &supplier, &buyer, &lines)
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            violations,
+            vec![
+                "[BR-DE-6] supplier.phone: The contact telephone number of the seller is missing.",
+                "[BR-CL-14] buyer.address.country_code: The country code 'Germany' is no ISO 3166-1 (Alpha-2) \
+                 country code.",
+                "[BR-AE-02] buyer.tax_identification: The VAT identifier of the buyer is missing, which is needed \
+                 for the VAT category 'AE'.",
+                "[BR-29] bill.period.end: The end date 2025-01-01 of the period lies before its start date \
+                 2025-01-31.",
+                "[BR-27] lines[0].hourly_rate: The item net price -1.00 is negative.",
+                "[BR-25] lines[1].name: The item name is missing.",
+                "[BR-30] lines[1]: The period of the invoice line is invalid: Line '' ends before it starts.",
+            ]
+        );
+    }
+}
@@ -1,22 +1,26 @@
 use chrono::NaiveDate;
+use std::collections::HashSet;
 
-use crate::config::{Address, Buyer, Supplier};
-use crate::data::{Bill, InvoiceHoursElement, Period};
+use crate::config::{Address, Buyer, PaymentMeans, Supplier};
+use crate::data::{Bill, InvoiceHoursElement, Period, TaxCategory};
+use crate::diagnostics::{self, Warning};
+use crate::lines::InvoiceLineSource;
+use crate::money::{self, Money, Rounding};
+use crate::totals::{self, Totals};
 use crate::xml_writer::XmlElement;
 
-const XMLNS_UBL: &'static str = "urn:oasis:names:specification:ubl:schema:xsd:Invoice-2";
-const XMLNS_CAC: &'static str =
-    "urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2";
-const XMLNS_CBC: &'static str =
-    "urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2";
-const CUSTOMIZATION_ID: &'static str =
+const XMLNS_UBL: &str = "urn:oasis:names:specification:ubl:schema:xsd:Invoice-2";
+const XMLNS_CAC: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2";
+const XMLNS_CBC: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2";
+const CUSTOMIZATION_ID: &str =
     "urn:cen.eu:en16931:2017#compliant#urn:xeinkauf.de:kosit:xrechnung_3.0";
-const PROFILE_ID: &'static str = "urn:fdc:peppol.eu:2017:poacc:billing:01:1.0";
-const PAYMENT_MEANS_CODE: &'static str = "42"; // payment to bank account
-const ENDPOINT_SCHEME_ID: &'static str = "EM"; // use email addresses as the contact points
-const QUANTITY_UNIT_CODE: &'static str = "HUR"; // HUR is code for 'hour' from Codes for Units of Measure used in International Trade
+const PROFILE_ID: &str = "urn:fdc:peppol.eu:2017:poacc:billing:01:1.0";
+const PAYMENT_MEANS_CODE: &str = "42"; // payment to bank account
+const ENDPOINT_SCHEME_ID: &str = "EM"; // use the email address of the supplier as its contact point
+const QUANTITY_UNIT_CODE: &str = "HUR"; // HUR is code for 'hour' from Codes for Units of Measure used in International Trade
 
-/// Rounds a floating point number to two decimal places and formats it as a string.
+/// Rounds a floating point number to two decimal places and formats it as a string. It is used for the quantities and
+/// percentages, while the amounts are given as [`Money`].
 fn rounded_string(input: f32) -> String {
     format!("{:.2}", (input * 100.0).round() / 100.0)
 }
@@ -65,7 +69,7 @@ fn create_supplier_element(supplier: &Supplier) -> XmlElement {
             None,
             Some(vec![
                 create_endpoint_id_element(ENDPOINT_SCHEME_ID, &supplier.email),
-                create_address_element(&supplier.address),
+                create_address_element("cac:PostalAddress", &supplier.address),
                 create_party_tax_scheme_element(&supplier.tax_identification),
                 create_legal_entity_element(&supplier.name, &supplier.tax_identification),
                 create_contact_element(&supplier.name, &supplier.phone, &supplier.email),
@@ -74,25 +78,37 @@ fn create_supplier_element(supplier: &Supplier) -> XmlElement {
     )
 }
 
-fn create_buyer_element(buyer: &Buyer) -> XmlElement {
+fn create_buyer_element(
+    buyer: &Buyer,
+    endpoint_scheme: &str,
+    endpoint: &str,
+    tax_category: &TaxCategory,
+) -> XmlElement {
     // buyer elements with only one party
-    XmlElement::new(
-        "cac:AccountingCustomerParty",
+    let mut party = XmlElement::new(
+        "cac:Party",
         None,
-        Some(vec![XmlElement::new(
-            "cac:Party",
-            None,
-            Some(vec![
-                create_endpoint_id_element(ENDPOINT_SCHEME_ID, &buyer.email),
-                create_address_element(&buyer.address),
-                create_legal_entity_element(&buyer.name, &buyer.tax_identification),
-            ]),
-        )]),
-    )
+        Some(vec![
+            create_endpoint_id_element(endpoint_scheme, endpoint),
+            create_address_element("cac:PostalAddress", &buyer.address),
+        ]),
+    );
+    // the VAT identifier of the buyer is mandatory for reverse charge and intra-community supplies (BR-AE-02, BR-IC-02)
+    if matches!(
+        tax_category,
+        TaxCategory::ReverseCharge | TaxCategory::IntraCommunity
+    ) {
+        party.push_child(create_party_tax_scheme_element(&buyer.tax_identification));
+    }
+    party.push_child(create_legal_entity_element(
+        &buyer.name,
+        &buyer.tax_identification,
+    ));
+    XmlElement::new("cac:AccountingCustomerParty", None, Some(vec![party]))
 }
 
-fn create_delivery_element(issue_date: &NaiveDate) -> XmlElement {
-    XmlElement::new(
+fn create_delivery_element(issue_date: &NaiveDate, address: Option<&Address>) -> XmlElement {
+    let mut delivery = XmlElement::new(
         "cac:Delivery",
         None,
         Some(vec![XmlElement::new_leaf(
@@ -100,116 +116,218 @@ fn create_delivery_element(issue_date: &NaiveDate) -> XmlElement {
             None,
             &issue_date.to_string(),
         )]),
-    )
+    );
+    if let Some(address) = address {
+        delivery.push_child(XmlElement::new(
+            "cac:DeliveryLocation",
+            None,
+            Some(vec![create_address_element("cac:Address", address)]),
+        ));
+    }
+    delivery
 }
 
-fn create_payment_means_element(name: &str, iban: &str, bic: &str) -> XmlElement {
+fn create_bank_account_element(name: &str, iban: &str, bic: &str) -> XmlElement {
     XmlElement::new(
-        "cac:PaymentMeans",
+        "cac:PayeeFinancialAccount",
         None,
         Some(vec![
-            XmlElement::new_leaf("cbc:PaymentMeansCode", None, PAYMENT_MEANS_CODE),
+            XmlElement::new_leaf("cbc:ID", None, iban),
+            XmlElement::new_leaf("cbc:Name", None, name),
             XmlElement::new(
-                "cac:PayeeFinancialAccount",
+                "cac:FinancialInstitutionBranch",
                 None,
-                Some(vec![
-                    XmlElement::new_leaf("cbc:ID", None, iban),
-                    XmlElement::new_leaf("cbc:Name", None, name),
-                    XmlElement::new(
-                        "cac:FinancialInstitutionBranch",
-                        None,
-                        Some(vec![XmlElement::new_leaf("cbc:ID", None, bic)]),
-                    ),
-                ]),
+                Some(vec![XmlElement::new_leaf("cbc:ID", None, bic)]),
             ),
         ]),
     )
 }
 
-fn create_tax_total_element(bill: &Bill, value: f32) -> XmlElement {
-    // add tax amounts with only VAT
-    XmlElement::new(
-        "cac:TaxTotal",
-        None,
-        Some(vec![
-            create_element_with_currency(
-                &bill.currency,
-                "cbc:TaxAmount",
-                &rounded_string(value * (bill.vat_percent / 100.0)),
+fn create_payment_means_element(supplier: &Supplier, payment_means: &PaymentMeans) -> XmlElement {
+    let bank_account =
+        || create_bank_account_element(&supplier.name, &supplier.iban, &supplier.bic);
+    let children = match payment_means {
+        PaymentMeans::BankTransfer => vec![
+            XmlElement::new_leaf("cbc:PaymentMeansCode", None, PAYMENT_MEANS_CODE),
+            bank_account(),
+        ],
+        PaymentMeans::SepaCreditTransfer => vec![
+            XmlElement::new_leaf("cbc:PaymentMeansCode", None, "58"),
+            bank_account(),
+        ],
+        PaymentMeans::SepaInstant => vec![
+            XmlElement::new_leaf(
+                "cbc:PaymentMeansCode",
+                Some(vec![("name", "SEPA Instant Credit Transfer")]),
+                "58",
             ),
-            XmlElement::new(
-                "cac:TaxSubtotal",
+            bank_account(),
+        ],
+        PaymentMeans::Card {
+            credit,
+            number,
+            network,
+            holder,
+        } => {
+            let mut card = XmlElement::new(
+                "cac:CardAccount",
                 None,
                 Some(vec![
-                    create_element_with_currency(
-                        &bill.currency,
-                        "cbc:TaxableAmount",
-                        &rounded_string(value),
-                    ),
-                    create_element_with_currency(
-                        &bill.currency,
-                        "cbc:TaxAmount",
-                        &rounded_string(value * (bill.vat_percent / 100.0)),
-                    ),
-                    XmlElement::new(
-                        "cac:TaxCategory",
-                        None,
-                        Some(vec![
-                            XmlElement::new_leaf("cbc:ID", None, "S"),
-                            XmlElement::new_leaf(
-                                "cbc:Percent",
-                                None,
-                                &rounded_string(bill.vat_percent),
-                            ),
-                            create_tax_scheme_vat_element(),
-                        ]),
-                    ),
+                    XmlElement::new_leaf("cbc:PrimaryAccountNumberID", None, number),
+                    XmlElement::new_leaf("cbc:NetworkID", None, network),
                 ]),
+            );
+            if let Some(holder) = holder {
+                card.push_child(XmlElement::new_leaf("cbc:HolderName", None, holder));
+            }
+            vec![
+                XmlElement::new_leaf(
+                    "cbc:PaymentMeansCode",
+                    None,
+                    if *credit { "54" } else { "48" },
+                ),
+                card,
+            ]
+        }
+        PaymentMeans::Online { service, account } => vec![
+            XmlElement::new_leaf("cbc:PaymentMeansCode", Some(vec![("name", service)]), "68"),
+            XmlElement::new(
+                "cac:PayeeFinancialAccount",
+                None,
+                Some(vec![XmlElement::new_leaf("cbc:ID", None, account)]),
             ),
-        ]),
-    )
+        ],
+    };
+
+    XmlElement::new("cac:PaymentMeans", None, Some(children))
 }
 
-fn create_legal_monetary_total_element(bill: &Bill, value: f32) -> XmlElement {
+/// Returns the note with the legal information about the supplier's company (managing director, commercial register
+/// and website), if any of them is configured. The note has the subject code "REG" (regulatory information) of UNTDID
+/// 4451 and is labelled in the language of the bill.
+fn legal_note(supplier: &Supplier, language: Option<&str>) -> Option<String> {
+    let german = language == Some("de");
+    let fields = [
+        (
+            if german {
+                "Geschäftsführer"
+            } else {
+                "Managing director"
+            },
+            &supplier.managing_director,
+        ),
+        (
+            if german {
+                "Registergericht"
+            } else {
+                "Register court"
+            },
+            &supplier.register_court,
+        ),
+        (
+            if german {
+                "Registernummer"
+            } else {
+                "Register number"
+            },
+            &supplier.register_number,
+        ),
+        (
+            if german { "Webseite" } else { "Website" },
+            &supplier.website,
+        ),
+    ];
+
+    let parts: Vec<String> = fields
+        .iter()
+        .filter_map(|(label, value)| value.as_ref().map(|value| format!("{label}: {value}")))
+        .collect();
+    (!parts.is_empty()).then(|| format!("#REG#{}", parts.join("; ")))
+}
+
+fn create_payment_terms_element(note: &str) -> XmlElement {
     XmlElement::new(
-        "cac:LegalMonetaryTotal",
+        "cac:PaymentTerms",
+        None,
+        Some(vec![XmlElement::new_leaf("cbc:Note", None, note)]),
+    )
+}
+
+fn create_tax_total_element(bill: &Bill, totals: &Totals) -> XmlElement {
+    let mut tax_category = XmlElement::new(
+        "cac:TaxCategory",
         None,
         Some(vec![
-            create_element_with_currency(
-                &bill.currency,
-                "cbc:LineExtensionAmount",
-                &rounded_string(value),
-            ),
-            create_element_with_currency(
-                &bill.currency,
-                "cbc:TaxExclusiveAmount",
-                &rounded_string(value),
-            ),
-            create_element_with_currency(
-                &bill.currency,
-                "cbc:TaxInclusiveAmount",
-                &rounded_string(value * ((bill.vat_percent / 100.0) + 1.0)),
-            ),
-            create_element_with_currency(&bill.currency, "cbc:AllowanceTotalAmount", "0.00"),
-            create_element_with_currency(&bill.currency, "cbc:ChargeTotalAmount", "0.00"),
-            create_element_with_currency(&bill.currency, "cbc:PrepaidAmount", "0.00"),
-            create_element_with_currency(&bill.currency, "cbc:PayableRoundingAmount", "0.00"),
-            create_element_with_currency(
-                &bill.currency,
-                "cbc:PayableAmount",
-                &rounded_string(value * ((bill.vat_percent / 100.0) + 1.0)),
-            ),
+            XmlElement::new_leaf("cbc:ID", None, bill.tax_category.code()),
+            XmlElement::new_leaf("cbc:Percent", None, &rounded_string(bill.tax_percent())),
         ]),
+    );
+    if let Some(reason) = bill.tax_category.exemption_reason(bill.language.as_deref()) {
+        tax_category.push_child(XmlElement::new_leaf(
+            "cbc:TaxExemptionReason",
+            None,
+            &reason,
+        ));
+    }
+    tax_category.push_child(create_tax_scheme_vat_element());
+
+    // add tax amounts with only VAT
+    let mut tax_total = XmlElement::new(
+        "cac:TaxTotal",
+        None,
+        Some(vec![create_amount_element(
+            "cbc:TaxAmount",
+            &totals.tax_amount,
+        )]),
+    );
+    for subtotal in &totals.subtotals {
+        tax_total.push_child(XmlElement::new(
+            "cac:TaxSubtotal",
+            None,
+            Some(vec![
+                create_amount_element("cbc:TaxableAmount", &subtotal.taxable_amount),
+                create_amount_element("cbc:TaxAmount", &subtotal.tax_amount),
+                tax_category.clone(),
+            ]),
+        ));
+    }
+    tax_total
+}
+
+fn create_legal_monetary_total_element(totals: &Totals) -> XmlElement {
+    let amounts = [
+        ("cbc:LineExtensionAmount", &totals.line_extension_amount),
+        ("cbc:TaxExclusiveAmount", &totals.tax_exclusive_amount),
+        ("cbc:TaxInclusiveAmount", &totals.tax_inclusive_amount),
+        ("cbc:AllowanceTotalAmount", &totals.allowance_total_amount),
+        ("cbc:ChargeTotalAmount", &totals.charge_total_amount),
+        ("cbc:PrepaidAmount", &totals.prepaid_amount),
+        ("cbc:PayableRoundingAmount", &totals.payable_rounding_amount),
+        ("cbc:PayableAmount", &totals.payable_amount),
+    ];
+    XmlElement::new(
+        "cac:LegalMonetaryTotal",
+        None,
+        Some(
+            amounts
+                .iter()
+                .map(|(tag, amount)| create_amount_element(tag, amount))
+                .collect(),
+        ),
     )
 }
 
-fn create_element_with_currency(currency: &str, tag: &str, content: &str) -> XmlElement {
-    XmlElement::new_leaf(tag, Some(vec![("currencyID", currency)]), content)
+fn create_amount_element(tag: &str, amount: &Money) -> XmlElement {
+    XmlElement::new_leaf(
+        tag,
+        Some(vec![("currencyID", amount.currency().code())]),
+        &amount.decimal(),
+    )
 }
 
-fn create_address_element(address: &Address) -> XmlElement {
+fn create_address_element(tag: &str, address: &Address) -> XmlElement {
     XmlElement::new(
-        "cac:PostalAddress",
+        tag,
         None,
         Some(vec![
             XmlElement::new_leaf("cbc:StreetName", None, &address.address_line),
@@ -251,12 +369,12 @@ fn create_party_tax_scheme_element(company_id: &str) -> XmlElement {
     )
 }
 
-fn create_classified_tax_category_element(vat_percent: f32) -> XmlElement {
+fn create_classified_tax_category_element(code: &str, vat_percent: f32) -> XmlElement {
     XmlElement::new(
         "cac:ClassifiedTaxCategory",
         None,
         Some(vec![
-            XmlElement::new_leaf("cbc:ID", None, "S"),
+            XmlElement::new_leaf("cbc:ID", None, code),
             XmlElement::new_leaf("cbc:Percent", None, &rounded_string(vat_percent)),
             create_tax_scheme_vat_element(),
         ]),
@@ -286,55 +404,86 @@ fn create_contact_element(name: &str, phone: &str, email: &str) -> XmlElement {
     )
 }
 
+/// Creates a reference element (e.g., to an order or a contract) that consists of an identifier only.
+fn create_reference_element(tag: &str, id: &str) -> XmlElement {
+    XmlElement::new(
+        tag,
+        None,
+        Some(vec![XmlElement::new_leaf("cbc:ID", None, id)]),
+    )
+}
+
+fn create_order_line_reference_element(line_id: &str) -> XmlElement {
+    XmlElement::new(
+        "cac:OrderLineReference",
+        None,
+        Some(vec![XmlElement::new_leaf("cbc:LineID", None, line_id)]),
+    )
+}
+
 fn create_invoice_hours_element(
     id: &str,
-    currency: &str,
+    tax_code: &str,
     vat_percent: f32,
+    rounding: Rounding,
     element: InvoiceHoursElement,
+    amount: Money,
 ) -> Result<XmlElement, Box<dyn std::error::Error>> {
     let mut line_element = XmlElement::new(
         "cac:InvoiceLine",
         None,
-        Some(vec![
-            XmlElement::new_leaf("cbc:ID", None, id),
-            XmlElement::new_leaf(
-                "cbc:InvoicedQuantity",
-                Some(vec![("unitCode", QUANTITY_UNIT_CODE)]),
-                &rounded_string(element.quantity),
-            ),
-            create_element_with_currency(
-                currency,
-                "cbc:LineExtensionAmount",
-                &rounded_string(element.quantity * element.hourly_rate),
-            ),
-        ]),
+        Some(vec![XmlElement::new_leaf("cbc:ID", None, id)]),
     );
 
-    if element.date.is_some() {
-        let date = NaiveDate::parse_from_str(&element.date.unwrap(), "%Y-%m-%d")?;
+    if let Some(note) = &element.note {
+        line_element.push_child(XmlElement::new_leaf("cbc:Note", None, note));
+    }
 
-        line_element.push_child(create_invoice_period_element(&Period {
-            start: date,
-            end: date,
-        }));
+    line_element.push_children(vec![
+        XmlElement::new_leaf(
+            "cbc:InvoicedQuantity",
+            Some(vec![("unitCode", QUANTITY_UNIT_CODE)]),
+            &rounded_string(element.quantity),
+        ),
+        create_amount_element("cbc:LineExtensionAmount", &amount),
+    ]);
+
+    if let Some(accounting_cost) = &element.accounting_cost {
+        line_element.push_child(XmlElement::new_leaf(
+            "cbc:AccountingCost",
+            None,
+            accounting_cost,
+        ));
     }
 
-    line_element.push_child(XmlElement::new(
-        "cac:Item",
-        None,
-        Some(vec![
-            XmlElement::new_leaf("cbc:Name", None, &element.name),
-            create_classified_tax_category_element(vat_percent),
-        ]),
-    ));
+    if let Some(period) = element.period()? {
+        line_element.push_child(create_invoice_period_element(&period));
+    }
+
+    if let Some(order_line_id) = &element.order_line_id {
+        line_element.push_child(create_order_line_reference_element(order_line_id));
+    }
+
+    let mut item_element = XmlElement::new("cac:Item", None, None);
+    if let Some(description) = &element.description {
+        item_element.push_child(XmlElement::new_leaf("cbc:Description", None, description));
+    }
+    item_element.push_children(vec![
+        XmlElement::new_leaf("cbc:Name", None, &element.name),
+        create_classified_tax_category_element(tax_code, vat_percent),
+    ]);
+    line_element.push_child(item_element);
 
     line_element.push_child(XmlElement::new(
         "cac:Price",
         None,
-        Some(vec![create_element_with_currency(
-            currency,
+        Some(vec![create_amount_element(
             "cbc:PriceAmount",
-            &rounded_string(element.hourly_rate),
+            &Money::new(
+                money::decimal(element.hourly_rate),
+                amount.currency(),
+                rounding,
+            ),
         )]),
     ));
 
@@ -343,22 +492,34 @@ fn create_invoice_hours_element(
 
 /// Creates an XML structure for an invoice based on the provided supplier, buyer, bill metadata, and invoice hours.
 ///
+/// The invoice hours are read from the given [source][InvoiceLineSource], which can be a vector of the lines or any
+/// other source of invoice lines (e.g., a reader of an input format). Errors of reading the source are returned.
+///
 /// The returned XML structure can than be given to the [`write`][crate::write] function to write the invoice to a file.
+/// It is returned together with the [warnings][crate::diagnostics] about the invoice lines, e.g., lines with an amount of
+/// zero, which do not prevent creating the invoice, but are likely mistakes.
 ///
 /// * `supplier` - The supplier information (name, address, contact, bank data).
 /// * `buyer` - The buyer information (name, address, contact).
 /// * `bill` - The bill metadata (invoice number, issue date, due date, currency).
-/// * `invoice_hours` - A vector of `InvoiceHoursElement` representing the hours worked and their rates.
+/// * `invoice_hours` - The source of the `InvoiceHoursElement`s representing the hours worked and their rates.
 pub fn create(
     supplier: Supplier,
     buyer: Buyer,
     bill: Bill,
-    invoice_hours: Vec<InvoiceHoursElement>,
-) -> Result<XmlElement, Box<dyn std::error::Error>> {
-    let mut value = 0.0;
-    for line in &invoice_hours {
-        value += line.quantity * line.hourly_rate;
-    }
+    invoice_hours: impl InvoiceLineSource,
+) -> Result<(XmlElement, Vec<Warning>), Box<dyn std::error::Error>> {
+    let invoice_hours = invoice_hours.read_lines()?;
+
+    // take the identifiers from the line items, if given, or otherwise number the lines sequentially
+    let ids: Vec<String> = invoice_hours
+        .iter()
+        .enumerate()
+        .map(|(count, line)| line.id.clone().unwrap_or_else(|| (count + 1).to_string()))
+        .collect();
+    let warnings = diagnostics::check_lines(&ids, &invoice_hours);
+
+    let totals = totals::calculate(&bill, &invoice_hours);
 
     let mut root = create_root_element();
 
@@ -374,44 +535,567 @@ pub fn create(
         &bill.due_date.to_string(),
     ));
     root.push_child(XmlElement::new_leaf("cbc:InvoiceTypeCode", None, "380"));
+    for note in &buyer.notes {
+        root.push_child(XmlElement::new_leaf(
+            "cbc:Note",
+            None,
+            &bill.expand_placeholders(note, &buyer.name),
+        ));
+    }
+    // invoices without VAT have to state the reason for it
+    if let Some(reason) = bill.tax_category.exemption_reason(bill.language.as_deref()) {
+        root.push_child(XmlElement::new_leaf("cbc:Note", None, &reason));
+    }
+    if let Some(note) = legal_note(&supplier, bill.language.as_deref()) {
+        root.push_child(XmlElement::new_leaf("cbc:Note", None, &note));
+    }
     root.push_child(XmlElement::new_leaf(
         "cbc:DocumentCurrencyCode",
         None,
-        &bill.currency,
+        bill.currency.code(),
     ));
+    if let Some(accounting_cost) = &buyer.accounting_cost {
+        root.push_child(XmlElement::new_leaf(
+            "cbc:AccountingCost",
+            None,
+            accounting_cost,
+        ));
+    }
+    // public-sector buyers are identified by their Leitweg-ID as buyer reference
     root.push_child(XmlElement::new_leaf(
         "cbc:BuyerReference",
         None,
-        &buyer.reference,
+        buyer.leitweg_id.as_ref().unwrap_or(&buyer.reference),
     ));
 
-    if bill.period.is_some() {
-        root.push_child(create_invoice_period_element(
-            &bill.period.as_ref().unwrap(),
+    if let Some(period) = &bill.period {
+        root.push_child(create_invoice_period_element(period));
+    }
+    if let Some(order_reference) = &buyer.order_reference {
+        root.push_child(create_reference_element(
+            "cac:OrderReference",
+            order_reference,
+        ));
+    }
+    if let Some(contract_reference) = &buyer.contract_reference {
+        root.push_child(create_reference_element(
+            "cac:ContractDocumentReference",
+            contract_reference,
         ));
     }
 
     root.push_child(create_supplier_element(&supplier));
-    root.push_child(create_buyer_element(&buyer));
-    root.push_child(create_delivery_element(&bill.issue_date));
-    root.push_child(create_payment_means_element(
-        &supplier.name,
-        &supplier.iban,
-        &supplier.bic,
+    let (endpoint_scheme, endpoint) = buyer.endpoint()?;
+    root.push_child(create_buyer_element(
+        &buyer,
+        endpoint_scheme,
+        endpoint,
+        &bill.tax_category,
+    ));
+    // the country of delivery is mandatory for intra-community supplies (BR-IC-12), so the address of the buyer is
+    // given as delivery address
+    root.push_child(create_delivery_element(
+        &bill.issue_date,
+        (bill.tax_category == TaxCategory::IntraCommunity).then_some(&buyer.address),
     ));
-    root.push_child(create_tax_total_element(&bill, value));
-    root.push_child(create_legal_monetary_total_element(&bill, value));
+    if supplier.payment_means.is_empty() {
+        root.push_child(create_payment_means_element(
+            &supplier,
+            &PaymentMeans::BankTransfer,
+        ));
+    }
+    for payment_means in &supplier.payment_means {
+        root.push_child(create_payment_means_element(&supplier, payment_means));
+    }
+    // the discounts for early payment follow the text of the payment terms, each on a line of its own (BR-DE-18)
+    let mut payment_terms = buyer
+        .payment_terms
+        .as_ref()
+        .map(|payment_terms| bill.expand_placeholders(payment_terms, &buyer.name))
+        .unwrap_or_default();
+    for cash_discount in &bill.cash_discounts {
+        if !payment_terms.is_empty() && !payment_terms.ends_with('\n') {
+            payment_terms.push('\n');
+        }
+        payment_terms.push_str(&format!("{cash_discount}\n"));
+    }
+    if !payment_terms.is_empty() {
+        root.push_child(create_payment_terms_element(&payment_terms));
+    }
+    root.push_child(create_tax_total_element(&bill, &totals));
+    root.push_child(create_legal_monetary_total_element(&totals));
+
+    let mut line_ids = HashSet::new();
+    for ((id, mut invoice_hours_element), amount) in
+        ids.into_iter().zip(invoice_hours).zip(totals.line_amounts)
+    {
+        if !line_ids.insert(id.clone()) {
+            return Err(format!("The line identifier '{id}' is used more than once.").into());
+        }
+
+        // translate the name into the language of the buyer and fill in placeholders like "{month}" in the texts
+        if let Some(translation) = buyer.translations.get(&invoice_hours_element.name) {
+            invoice_hours_element.name = translation.clone();
+        }
+        invoice_hours_element.name =
+            bill.expand_placeholders(&invoice_hours_element.name, &buyer.name);
+        invoice_hours_element.note = invoice_hours_element
+            .note
+            .map(|note| bill.expand_placeholders(&note, &buyer.name));
+        invoice_hours_element.description = invoice_hours_element
+            .description
+            .map(|description| bill.expand_placeholders(&description, &buyer.name));
 
-    let mut count = 0;
-    for invoice_hours_element in invoice_hours {
-        count += 1;
         root.push_child(create_invoice_hours_element(
-            &count.to_string(),
-            &bill.currency,
-            bill.vat_percent,
+            &id,
+            bill.tax_category.code(),
+            bill.tax_percent(),
+            bill.amount_rounding,
             invoice_hours_element,
+            amount,
         )?);
     }
 
-    Ok(root)
+    Ok((root, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Config, PaymentMeans};
+    use crate::data::{Bill, CashDiscount, InvoiceHoursElement, TaxCategory};
+
+    /// Creates the invoice for the example configuration and returns it serialized as a string.
+    fn create_xml(invoice_hours: Vec<InvoiceHoursElement>) -> String {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        create_xml_with_config(config, invoice_hours)
+    }
+
+    /// Creates the invoice for the given configuration and returns it serialized as a string.
+    fn create_xml_with_config(config: Config, invoice_hours: Vec<InvoiceHoursElement>) -> String {
+        let bill = Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            None,
+            &config,
+        );
+        create_xml_with_bill(config, bill, invoice_hours)
+    }
+
+    /// Creates the invoice for the given configuration and bill and returns it serialized as a string.
+    fn create_xml_with_bill(
+        config: Config,
+        bill: Bill,
+        invoice_hours: Vec<InvoiceHoursElement>,
+    ) -> String {
+        let (root, _) = crate::create(config.supplier, config.buyer, bill, invoice_hours).unwrap();
+        let mut writer = quick_xml::writer::Writer::new(Vec::new());
+        root.write(&mut writer).unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    /// Returns the bill for the given configuration that is issued on 2025-01-31.
+    fn create_bill(config: &Config) -> Bill {
+        Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            None,
+            config,
+        )
+    }
+
+    #[test]
+    fn test_order_line_reference() {
+        let xml = create_xml(vec![
+            InvoiceHoursElement {
+                name: "Example Service".to_string(),
+                quantity: 7.0,
+                hourly_rate: 110.0,
+                order_line_id: Some("10".to_string()),
+                ..Default::default()
+            },
+            InvoiceHoursElement {
+                name: "Another Service".to_string(),
+                quantity: 6.5,
+                hourly_rate: 110.0,
+                ..Default::default()
+            },
+        ]);
+
+        assert!(xml.contains(
+            "<cac:OrderLineReference><cbc:LineID>10</cbc:LineID></cac:OrderLineReference>"
+        ));
+        assert_eq!(xml.matches("<cac:OrderLineReference>").count(), 1);
+    }
+
+    #[test]
+    fn test_custom_line_ids() {
+        let xml = create_xml(vec![
+            InvoiceHoursElement {
+                id: Some("TS-100".to_string()),
+                name: "Example Service".to_string(),
+                quantity: 7.0,
+                hourly_rate: 110.0,
+                ..Default::default()
+            },
+            InvoiceHoursElement {
+                name: "Another Service".to_string(),
+                quantity: 6.5,
+                hourly_rate: 110.0,
+                ..Default::default()
+            },
+        ]);
+
+        assert!(xml.contains("<cac:InvoiceLine><cbc:ID>TS-100</cbc:ID>"));
+        assert!(xml.contains("<cac:InvoiceLine><cbc:ID>2</cbc:ID>"));
+    }
+
+    #[test]
+    fn test_error_on_duplicate_line_ids() {
+        let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        let bill = Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            None,
+            &config,
+        );
+
+        let line = || InvoiceHoursElement {
+            id: Some("1".to_string()),
+            name: "Example Service".to_string(),
+            quantity: 7.0,
+            hourly_rate: 110.0,
+            ..Default::default()
+        };
+
+        assert!(crate::create(config.supplier, config.buyer, bill, vec![line(), line()]).is_err());
+    }
+
+    #[test]
+    fn test_line_period() {
+        let xml = create_xml(vec![
+            InvoiceHoursElement {
+                name: "Example Service".to_string(),
+                quantity: 30.0,
+                hourly_rate: 110.0,
+                start_date: Some("2025-01-06".to_string()),
+                end_date: Some("2025-01-10".to_string()),
+                ..Default::default()
+            },
+            InvoiceHoursElement {
+                name: "Another Service".to_string(),
+                quantity: 6.5,
+                hourly_rate: 110.0,
+                date: Some("2025-01-13".to_string()),
+                ..Default::default()
+            },
+        ]);
+
+        assert!(xml.contains("<cac:InvoicePeriod><cbc:StartDate>2025-01-06</cbc:StartDate><cbc:EndDate>2025-01-10</cbc:EndDate></cac:InvoicePeriod>"));
+        assert!(xml.contains("<cac:InvoicePeriod><cbc:StartDate>2025-01-13</cbc:StartDate><cbc:EndDate>2025-01-13</cbc:EndDate></cac:InvoicePeriod>"));
+    }
+
+    #[test]
+    fn test_error_on_invalid_line_period() {
+        let line = InvoiceHoursElement {
+            end_date: Some("2025-01-10".to_string()),
+            ..Default::default()
+        };
+        assert!(line.period().is_err());
+
+        let line = InvoiceHoursElement {
+            start_date: Some("2025-01-10".to_string()),
+            end_date: Some("2025-01-06".to_string()),
+            ..Default::default()
+        };
+        assert!(line.period().is_err());
+    }
+
+    #[test]
+    fn test_line_accounting_cost() {
+        let xml = create_xml(vec![InvoiceHoursElement {
+            name: "Example Service".to_string(),
+            quantity: 7.0,
+            hourly_rate: 110.0,
+            date: Some("2025-01-02".to_string()),
+            accounting_cost: Some("CC-4711".to_string()),
+            ..Default::default()
+        }]);
+
+        // the accounting cost needs to be placed between the line amount and the line period
+        assert!(xml.contains(
+            "</cbc:LineExtensionAmount><cbc:AccountingCost>CC-4711</cbc:AccountingCost><cac:InvoicePeriod>"
+        ));
+    }
+
+    #[test]
+    fn test_placeholders_in_lines() {
+        let xml = create_xml(vec![InvoiceHoursElement {
+            name: "Consulting services {month} {year}".to_string(),
+            quantity: 1.0,
+            hourly_rate: 110.0,
+            note: Some("Invoice {number} for {buyer}".to_string()),
+            ..Default::default()
+        }]);
+
+        assert!(xml.contains("<cbc:Name>Consulting services January 2025</cbc:Name>"));
+        assert!(xml.contains("<cbc:Note>Invoice 2025-0001 for Client Company</cbc:Note>"));
+    }
+
+    #[test]
+    fn test_line_description() {
+        let xml = create_xml(vec![InvoiceHoursElement {
+            name: "Development".to_string(),
+            description: Some("Implementation of the reporting module".to_string()),
+            quantity: 1.0,
+            hourly_rate: 110.0,
+            ..Default::default()
+        }]);
+
+        assert!(xml.contains(
+            "<cac:Item><cbc:Description>Implementation of the reporting module</cbc:Description><cbc:Name>Development</cbc:Name>"
+        ));
+    }
+
+    #[test]
+    fn test_language_of_buyer() {
+        let config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        let bill = Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 3, 31).unwrap(),
+            None,
+            &config,
+        );
+        let lines = vec![InvoiceHoursElement {
+            name: "support".to_string(),
+            note: Some("{month} {year}".to_string()),
+            quantity: 1.0,
+            hourly_rate: 75.0,
+            ..Default::default()
+        }];
+
+        let (root, _) = crate::create(config.supplier, config.buyer, bill, lines).unwrap();
+        let mut writer = quick_xml::writer::Writer::new(Vec::new());
+        root.write(&mut writer).unwrap();
+        let xml = String::from_utf8(writer.into_inner()).unwrap();
+
+        assert!(xml.contains("<cbc:Name>Wartung und Support</cbc:Name>"));
+        assert!(xml.contains("<cbc:Note>März 2025</cbc:Note>"));
+    }
+
+    #[test]
+    fn test_payment_terms() {
+        let xml = create_xml(vec![InvoiceHoursElement {
+            name: "Development".to_string(),
+            quantity: 1.0,
+            hourly_rate: 110.0,
+            ..Default::default()
+        }]);
+
+        assert!(xml.contains(
+            "</cac:PaymentMeans><cac:PaymentTerms><cbc:Note>Payable without deduction until 2025-02-20.</cbc:Note></cac:PaymentTerms><cac:TaxTotal>"
+        ));
+    }
+
+    #[test]
+    fn test_cash_discounts() {
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        config.buyer.payment_terms = Some("Payable until {due_date}.".to_string());
+        let mut bill = create_bill(&config);
+        bill.cash_discounts = vec![
+            CashDiscount {
+                percent: 3.0,
+                days: 7,
+            },
+            CashDiscount {
+                percent: 2.0,
+                days: 14,
+            },
+        ];
+        let xml = create_xml_with_bill(config, bill, vec![]);
+        assert!(xml.contains(
+            "<cac:PaymentTerms><cbc:Note>Payable until 2025-02-20.\n#SKONTO#TAGE=7#PROZENT=3.00#\n\
+             #SKONTO#TAGE=14#PROZENT=2.00#\n</cbc:Note></cac:PaymentTerms>"
+        ));
+
+        // the payment terms consist of the discounts only, if no text is configured
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        config.buyer.payment_terms = None;
+        let mut bill = create_bill(&config);
+        bill.cash_discounts = vec![CashDiscount {
+            percent: 2.0,
+            days: 10,
+        }];
+        let xml = create_xml_with_bill(config, bill, vec![]);
+        assert!(xml.contains("<cbc:Note>#SKONTO#TAGE=10#PROZENT=2.00#\n</cbc:Note>"));
+    }
+
+    #[test]
+    fn test_buyer_notes_and_references() {
+        let xml = create_xml(vec![InvoiceHoursElement {
+            name: "Development".to_string(),
+            quantity: 1.0,
+            hourly_rate: 110.0,
+            ..Default::default()
+        }]);
+
+        assert!(xml.contains(
+            "<cbc:InvoiceTypeCode>380</cbc:InvoiceTypeCode><cbc:Note>Services according to the framework agreement.</cbc:Note><cbc:DocumentCurrencyCode>EUR</cbc:DocumentCurrencyCode><cbc:AccountingCost>CC-100</cbc:AccountingCost><cbc:BuyerReference>"
+        ));
+        assert!(xml.contains(
+            "</cbc:BuyerReference><cac:OrderReference><cbc:ID>PO-2025-17</cbc:ID></cac:OrderReference><cac:ContractDocumentReference><cbc:ID>FA-2024-3</cbc:ID></cac:ContractDocumentReference><cac:AccountingSupplierParty>"
+        ));
+    }
+
+    #[test]
+    fn test_leitweg_id_as_buyer_reference() {
+        let xml = create_xml(vec![]);
+        assert!(xml.contains("<cbc:BuyerReference>12345</cbc:BuyerReference>"));
+
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        config.buyer.leitweg_id = Some("991-33333TEST-33".to_string());
+        let xml = create_xml_with_config(config, vec![]);
+        assert!(xml.contains("<cbc:BuyerReference>991-33333TEST-33</cbc:BuyerReference>"));
+    }
+
+    #[test]
+    fn test_payment_means() {
+        let xml = create_xml(vec![]);
+        assert!(xml.contains(
+            "<cac:PaymentMeans><cbc:PaymentMeansCode>58</cbc:PaymentMeansCode><cac:PayeeFinancialAccount><cbc:ID>DE43345678111111111100</cbc:ID>"
+        ));
+
+        // without configured payment means, the invoice is paid by transfer to the bank account of the supplier
+        let config = crate::config::load("examples/config.json", "Client Company").unwrap();
+        let xml = create_xml_with_config(config, vec![]);
+        assert!(xml.contains("<cac:PaymentMeans><cbc:PaymentMeansCode>42</cbc:PaymentMeansCode>"));
+
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        config.supplier.payment_means = vec![
+            PaymentMeans::SepaInstant,
+            PaymentMeans::Card {
+                credit: true,
+                number: "1234".to_string(),
+                network: "VISA".to_string(),
+                holder: None,
+            },
+            PaymentMeans::Online {
+                service: "PayPal".to_string(),
+                account: "pay@supplier.example.com".to_string(),
+            },
+        ];
+        let xml = create_xml_with_config(config, vec![]);
+
+        assert!(!xml.contains("<cbc:PaymentMeansCode>42</cbc:PaymentMeansCode>"));
+        assert!(xml.contains(
+            "<cac:PaymentMeans><cbc:PaymentMeansCode name=\"SEPA Instant Credit Transfer\">58</cbc:PaymentMeansCode><cac:PayeeFinancialAccount>"
+        ));
+        assert!(xml.contains(
+            "<cac:PaymentMeans><cbc:PaymentMeansCode>54</cbc:PaymentMeansCode><cac:CardAccount><cbc:PrimaryAccountNumberID>1234</cbc:PrimaryAccountNumberID><cbc:NetworkID>VISA</cbc:NetworkID></cac:CardAccount></cac:PaymentMeans>"
+        ));
+        assert!(xml.contains(
+            "<cac:PaymentMeans><cbc:PaymentMeansCode name=\"PayPal\">68</cbc:PaymentMeansCode><cac:PayeeFinancialAccount><cbc:ID>pay@supplier.example.com</cbc:ID></cac:PayeeFinancialAccount></cac:PaymentMeans>"
+        ));
+    }
+
+    #[test]
+    fn test_legal_note() {
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        config.supplier.managing_director = Some("Hans Muster".to_string());
+        config.supplier.register_court = Some("Amtsgericht Example City".to_string());
+        config.supplier.register_number = Some("HRB 12345".to_string());
+        let xml = create_xml_with_config(config, vec![]);
+        assert!(xml.contains(
+            "<cbc:Note>Services according to the framework agreement.</cbc:Note><cbc:Note>#REG#Managing director: Hans Muster; Register court: Amtsgericht Example City; Register number: HRB 12345</cbc:Note><cbc:DocumentCurrencyCode>"
+        ));
+
+        let mut config = crate::config::load("examples/config.toml", "Another Client").unwrap();
+        config.supplier.website = Some("https://supplier.example.com".to_string());
+        let xml = create_xml_with_config(config, vec![]);
+        assert!(xml.contains("<cbc:Note>#REG#Webseite: https://supplier.example.com</cbc:Note>"));
+
+        // no note without legal information
+        let xml = create_xml(vec![]);
+        assert!(!xml.contains("#REG#"));
+    }
+
+    #[test]
+    fn test_buyer_endpoint() {
+        let xml = create_xml(vec![]);
+        assert!(xml.contains(
+            "<cac:AccountingCustomerParty><cac:Party><cbc:EndpointID schemeID=\"EM\">mail@client1.example.com</cbc:EndpointID>"
+        ));
+
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        config.buyer.leitweg_id = Some("04011000-1234512345-06".to_string());
+        config.buyer.endpoint_scheme = Some("0204".to_string());
+        let xml = create_xml_with_config(config, vec![]);
+        assert!(xml.contains(
+            "<cac:AccountingCustomerParty><cac:Party><cbc:EndpointID schemeID=\"0204\">04011000-1234512345-06</cbc:EndpointID>"
+        ));
+
+        let mut config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+        config.buyer.endpoint_scheme = Some("0088".to_string());
+        config.buyer.endpoint = Some("4000001123452".to_string());
+        let xml = create_xml_with_config(config, vec![]);
+        assert!(xml.contains("<cbc:EndpointID schemeID=\"0088\">4000001123452</cbc:EndpointID>"));
+        // the supplier keeps its email address as endpoint
+        assert!(xml.contains(
+            "<cbc:EndpointID schemeID=\"EM\">mail@supplier.example.com</cbc:EndpointID>"
+        ));
+    }
+
+    #[test]
+    fn test_tax_category() {
+        let create_xml = |tax_category: TaxCategory| {
+            let config = crate::config::load("examples/config.toml", "Client Company").unwrap();
+            let mut bill = create_bill(&config);
+            bill.tax_category = tax_category;
+            let line = InvoiceHoursElement {
+                name: "Example Service".to_string(),
+                quantity: 10.0,
+                hourly_rate: 100.0,
+                ..Default::default()
+            };
+            create_xml_with_bill(config, bill, vec![line])
+        };
+
+        let xml = create_xml(TaxCategory::ReverseCharge);
+        assert!(xml.contains(
+            "<cbc:Note>Reverse charge: the recipient of the service is liable for the VAT</cbc:Note>"
+        ));
+        assert!(xml.contains(
+            "<cac:TaxCategory><cbc:ID>AE</cbc:ID><cbc:Percent>0.00</cbc:Percent><cbc:TaxExemptionReason>Reverse \
+             charge: the recipient of the service is liable for the VAT</cbc:TaxExemptionReason><cac:TaxScheme>"
+        ));
+        assert!(xml.contains(
+            "<cac:ClassifiedTaxCategory><cbc:ID>AE</cbc:ID><cbc:Percent>0.00</cbc:Percent>"
+        ));
+        assert!(xml.contains(
+            "<cbc:TaxInclusiveAmount currencyID=\"EUR\">1000.00</cbc:TaxInclusiveAmount>"
+        ));
+        // the buyer is given with its VAT identifier
+        assert_eq!(xml.matches("<cac:PartyTaxScheme>").count(), 2);
+        assert!(!xml.contains("<cac:DeliveryLocation>"));
+
+        let xml = create_xml(TaxCategory::Exempt(
+            "Kleinunternehmer gemäß § 19 UStG".to_string(),
+        ));
+        assert!(xml.contains("<cbc:Note>Kleinunternehmer gemäß § 19 UStG</cbc:Note>"));
+        assert!(
+            xml.contains(
+                "<cbc:ID>E</cbc:ID><cbc:Percent>0.00</cbc:Percent><cbc:TaxExemptionReason>"
+            )
+        );
+        assert_eq!(xml.matches("<cac:PartyTaxScheme>").count(), 1);
+
+        let xml = create_xml(TaxCategory::IntraCommunity);
+        assert!(xml.contains("<cbc:ID>K</cbc:ID>"));
+        assert!(xml.contains(
+            "<cac:DeliveryLocation><cac:Address><cbc:StreetName>Example Street 42</cbc:StreetName>"
+        ));
+
+        let xml = create_xml(TaxCategory::Standard);
+        assert!(xml.contains("<cbc:ID>S</cbc:ID><cbc:Percent>19.00</cbc:Percent><cac:TaxScheme>"));
+        assert!(!xml.contains("TaxExemptionReason"));
+    }
 }
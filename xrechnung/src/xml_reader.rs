@@ -0,0 +1,146 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+
+use crate::xml_writer::{XmlElement, XmlElementContent};
+
+/// Creates an element without content from the given start tag.
+fn create_element(start: &BytesStart) -> Result<XmlElement, String> {
+    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let mut attributes = Vec::new();
+    for attribute in start.attributes() {
+        let attribute =
+            attribute.map_err(|e| format!("Invalid attribute of element '{name}': {e}"))?;
+        let value = attribute
+            .unescape_value()
+            .map_err(|e| format!("Invalid attribute of element '{name}': {e}"))?;
+        attributes.push((
+            String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+            value.into_owned(),
+        ));
+    }
+    Ok(XmlElement {
+        name,
+        attributes,
+        content: XmlElementContent::Children(Vec::new()),
+        nodes: Vec::new(),
+        prolog: Vec::new(),
+    })
+}
+
+impl XmlElement {
+    /// Parses the given XML document and returns its root element, e.g., for validating an existing invoice.
+    ///
+    /// Elements without child elements get their (unescaped) text as content, whereas the text between child elements
+    /// is dropped. Comments and processing instructions are skipped.
+    ///
+    /// The function returns an error in case that the document is no well-formed XML document.
+    pub fn parse(document: &str) -> Result<XmlElement, String> {
+        let mut reader = Reader::from_str(document);
+        // the open elements with the text that was read for them so far
+        let mut open: Vec<(XmlElement, String)> = Vec::new();
+        let mut root = None;
+
+        loop {
+            let position = reader.buffer_position();
+            let event = reader.read_event().map_err(|e| {
+                format!("The document is no well-formed XML (after position {position}): {e}")
+            })?;
+
+            let element = match event {
+                Event::Start(start) => {
+                    open.push((create_element(&start)?, String::new()));
+                    continue;
+                }
+                Event::Empty(start) => {
+                    let mut element = create_element(&start)?;
+                    element.content = XmlElementContent::Content(String::new());
+                    element
+                }
+                Event::Text(text) => {
+                    let text = text.unescape().map_err(|e| {
+                        format!(
+                            "The document is no well-formed XML (after position {position}): {e}"
+                        )
+                    })?;
+                    match open.last_mut() {
+                        Some((_, content)) => content.push_str(&text),
+                        None if text.trim().is_empty() => (),
+                        None => {
+                            return Err("The document contains text outside of the root element."
+                                .to_string());
+                        }
+                    }
+                    continue;
+                }
+                Event::CData(data) => {
+                    let data = String::from_utf8_lossy(data.as_ref());
+                    if let Some((_, content)) = open.last_mut() {
+                        content.push_str(&data);
+                    }
+                    continue;
+                }
+                Event::End(_) => {
+                    let (mut element, content) = open
+                        .pop()
+                        .ok_or("The document has an unexpected end tag.")?;
+                    if element.children().is_empty() {
+                        element.content = XmlElementContent::Content(content);
+                    }
+                    element
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+
+            match open.last_mut() {
+                Some((parent, _)) => parent.push_child(element),
+                None if root.is_none() => root = Some(element),
+                None => return Err("The document has more than one root element.".to_string()),
+            }
+        }
+
+        if let Some((element, _)) = open.last() {
+            return Err(format!(
+                "The document is no well-formed XML: the element '{}' is not closed.",
+                element.name
+            ));
+        }
+        root.ok_or("The document contains no element.".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::XmlElement;
+
+    #[test]
+    fn test_parse() {
+        let root = XmlElement::parse(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<ubl:Invoice xmlns:ubl=\"urn:example\">
+    <!-- comment -->
+    <cbc:ID>A &amp; B</cbc:ID>
+    <cbc:Note/>
+    <cac:Line><cbc:Amount currencyID=\"EUR\">1.00</cbc:Amount></cac:Line>
+</ubl:Invoice>
+",
+        )
+        .unwrap();
+
+        assert_eq!(root.name(), "ubl:Invoice");
+        assert_eq!(root.attribute("xmlns:ubl"), Some("urn:example"));
+        assert_eq!(root.text(), None);
+        let children = root.children();
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[0].text(), Some("A & B"));
+        assert_eq!(children[1].text(), Some(""));
+        let amount = &children[2].children()[0];
+        assert_eq!(amount.attribute("currencyID"), Some("EUR"));
+        assert_eq!(amount.text(), Some("1.00"));
+
+        assert!(XmlElement::parse("<a><b></a>").is_err());
+        assert!(XmlElement::parse("<a>").is_err());
+        assert!(XmlElement::parse("<a/><b/>").is_err());
+        assert!(XmlElement::parse("").is_err());
+    }
+}
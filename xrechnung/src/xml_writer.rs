@@ -1,22 +1,87 @@
-use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesPI, BytesStart, BytesText, Event};
 use quick_xml::writer::Writer;
-use std::fs::File;
-use std::io::BufWriter;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-enum XmlElementContent {
+/// The number of names that are tried for the temporary file of a file that is written, before writing fails.
+const TEMPORARY_FILE_ATTEMPTS: usize = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum XmlElementContent {
     Content(String),
+    Raw(String),
     Children(Vec<XmlElement>),
 }
 
+/// An item of the content of an element, i.e., text, a child element, a comment or a processing instruction. The
+/// items other than the child elements are not returned by [`XmlElement::children`], so that they are not mistaken
+/// for elements when the children are looked up by their names.
+#[derive(Clone, Debug, PartialEq)]
+pub enum XmlContentItem {
+    /// Text, which is escaped when it is written (like the content of leaf elements).
+    Text(String),
+
+    /// A child element.
+    Element(XmlElement),
+
+    /// A comment with the given text. Writing the comment fails, if its text contains "--" or ends with "-", which is
+    /// not allowed in XML comments.
+    Comment(String),
+
+    /// A processing instruction for the given target, e.g., `<?xml-stylesheet type="text/xsl" href="invoice.xsl"?>`
+    /// with the target "xml-stylesheet". Writing the processing instruction fails, if its content contains "?>".
+    ProcessingInstruction { target: String, content: String },
+}
+
+impl XmlContentItem {
+    fn write<W: std::io::Write>(
+        &self,
+        writer: &mut Writer<W>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            XmlContentItem::Text(text) => writer.write_event(Event::Text(BytesText::new(text)))?,
+            XmlContentItem::Element(element) => element.write(writer)?,
+            XmlContentItem::Comment(text) => {
+                if text.contains("--") || text.ends_with('-') {
+                    return Err(
+                        format!("The comment '{text}' contains '--' or ends with '-'.").into(),
+                    );
+                }
+                writer.write_event(Event::Comment(BytesText::from_escaped(text.as_str())))?;
+            }
+            XmlContentItem::ProcessingInstruction { target, content } => {
+                if content.contains("?>") {
+                    return Err(
+                        format!("The processing instruction '{target}' contains '?>'.").into(),
+                    );
+                }
+                let instruction = if content.is_empty() {
+                    target.clone()
+                } else {
+                    format!("{target} {content}")
+                };
+                writer.write_event(Event::PI(BytesPI::new(instruction)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct XmlElement {
-    name: String,
-    attributes: Vec<(String, String)>,
-    content: XmlElementContent,
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) content: XmlElementContent,
+    /// The text, comments and processing instructions between the child elements, each with the number of child
+    /// elements before it (ordered by that number).
+    pub(crate) nodes: Vec<(usize, XmlContentItem)>,
+    pub(crate) prolog: Vec<XmlContentItem>,
 }
 
 impl XmlElement {
     fn to_owned_strings_vector(input: Option<Vec<(&str, &str)>>) -> Vec<(String, String)> {
-        let input = input.unwrap_or(Vec::new());
+        let input = input.unwrap_or_default();
 
         // convert string references to owned strings
         input
@@ -25,54 +90,264 @@ impl XmlElement {
             .collect()
     }
 
-    pub fn new(
+    fn with_content(
         name: &str,
         attributes: Option<Vec<(&str, &str)>>,
-        children: Option<Vec<XmlElement>>,
+        content: XmlElementContent,
     ) -> Self {
         XmlElement {
             name: name.to_string(),
             attributes: XmlElement::to_owned_strings_vector(attributes),
-            content: XmlElementContent::Children(children.unwrap_or(Vec::new())),
+            content,
+            nodes: Vec::new(),
+            prolog: Vec::new(),
         }
     }
 
+    pub fn new(
+        name: &str,
+        attributes: Option<Vec<(&str, &str)>>,
+        children: Option<Vec<XmlElement>>,
+    ) -> Self {
+        XmlElement::with_content(
+            name,
+            attributes,
+            XmlElementContent::Children(children.unwrap_or_default()),
+        )
+    }
+
+    /// Creates an element with the given text content, which is escaped when it is written (e.g., "Müller & Söhne"
+    /// becomes "Müller &amp; Söhne").
     pub fn new_leaf(name: &str, attributes: Option<Vec<(&str, &str)>>, content: &str) -> Self {
-        XmlElement {
-            name: name.to_string(),
-            attributes: XmlElement::to_owned_strings_vector(attributes),
-            content: XmlElementContent::Content(content.to_string()),
+        XmlElement::with_content(
+            name,
+            attributes,
+            XmlElementContent::Content(content.to_string()),
+        )
+    }
+
+    /// Creates an element with the given content that is already escaped, which is written as it is. The content needs
+    /// to be valid XML text, e.g., "A &amp; B" or with character references like "&#x20AC;".
+    pub fn new_raw_leaf(name: &str, attributes: Option<Vec<(&str, &str)>>, content: &str) -> Self {
+        XmlElement::with_content(
+            name,
+            attributes,
+            XmlElementContent::Raw(content.to_string()),
+        )
+    }
+
+    /// Creates an element with mixed content, i.e., with text and child elements in the given order, e.g., for text with
+    /// embedded markup or line break elements.
+    pub fn new_mixed(
+        name: &str,
+        attributes: Option<Vec<(&str, &str)>>,
+        items: Vec<XmlContentItem>,
+    ) -> Self {
+        let mut element = XmlElement::new(name, attributes, None);
+        for item in items {
+            element.push_item(item);
+        }
+        element
+    }
+
+    /// Creates a comment with the given text, which can be added to an element with
+    /// [`push_item`][XmlElement::push_item] or [`insert_item`][XmlElement::insert_item], e.g., for a comment header
+    /// with the version of the generating tool.
+    pub fn comment(text: &str) -> XmlContentItem {
+        XmlContentItem::Comment(text.to_string())
+    }
+
+    /// Creates a processing instruction for the given target with the given content, which can be added to an element
+    /// like a comment or before the root element with [`push_prolog`][XmlElement::push_prolog].
+    pub fn processing_instruction(target: &str, content: &str) -> XmlContentItem {
+        XmlContentItem::ProcessingInstruction {
+            target: target.to_string(),
+            content: content.to_string(),
         }
     }
 
     pub fn push_child(&mut self, child: XmlElement) {
         match &mut self.content {
             XmlElementContent::Children(children) => children.push(child),
-            XmlElementContent::Content(_) => {
-                panic!("Cannot push child to an element with content.")
+            _ => panic!("Cannot push child to an element with content."),
+        }
+    }
+
+    /// Appends the given item to the content of the element. An element with text content keeps its text before the
+    /// item, if the item is no child element.
+    pub fn push_item(&mut self, item: XmlContentItem) {
+        let index = self.children().len();
+        self.insert_item(index, item);
+    }
+
+    /// Inserts the given item before the child element with the given index, e.g., a comment before the "cbc:ID"
+    /// element. Items that are already before the child element with the index stay in front of an inserted child
+    /// element.
+    ///
+    /// Panics, if the index is greater than the number of child elements.
+    pub fn insert_item(&mut self, index: usize, item: XmlContentItem) {
+        assert!(
+            index <= self.children().len(),
+            "Cannot insert an item after the end of the child elements."
+        );
+        if let XmlContentItem::Element(child) = item {
+            let XmlElementContent::Children(children) = &mut self.content else {
+                panic!("Cannot push child to an element with content.");
+            };
+            children.insert(index, child);
+            for (position, _) in &mut self.nodes {
+                if *position > index {
+                    *position += 1;
+                }
             }
+            return;
+        }
+
+        // the text content becomes the first item of an element with child elements
+        match std::mem::replace(&mut self.content, XmlElementContent::Children(Vec::new())) {
+            XmlElementContent::Content(content) if !content.is_empty() => {
+                self.nodes.push((0, XmlContentItem::Text(content)))
+            }
+            XmlElementContent::Raw(content) if !content.is_empty() => {
+                let text = quick_xml::escape::unescape(&content)
+                    .map(|text| text.into_owned())
+                    .unwrap_or(content);
+                self.nodes.push((0, XmlContentItem::Text(text)))
+            }
+            XmlElementContent::Children(children) => {
+                self.content = XmlElementContent::Children(children)
+            }
+            _ => (),
+        }
+        let position = self
+            .nodes
+            .partition_point(|(position, _)| *position <= index);
+        self.nodes.insert(position, (index, item));
+    }
+
+    /// Adds the given comment or processing instruction before the element, i.e., to the prolog of the document for the
+    /// root element (e.g., a stylesheet processing instruction).
+    pub fn push_prolog(&mut self, item: XmlContentItem) {
+        match item {
+            XmlContentItem::Comment(_) | XmlContentItem::ProcessingInstruction { .. } => {
+                self.prolog.push(item)
+            }
+            _ => panic!("Cannot add text or an element to the prolog."),
+        }
+    }
+
+    /// Appends the given text to the content of the element. An element with child elements gets mixed content by it,
+    /// with the text after the child elements.
+    pub fn push_text(&mut self, text: &str) {
+        match &mut self.content {
+            XmlElementContent::Content(content) if self.nodes.is_empty() => content.push_str(text),
+            XmlElementContent::Raw(content) if self.nodes.is_empty() => {
+                content.push_str(&quick_xml::escape::escape(text))
+            }
+            _ => self.push_item(XmlContentItem::Text(text.to_string())),
+        }
+    }
+
+    pub fn push_children(&mut self, children: Vec<XmlElement>) {
+        for child in children {
+            self.push_child(child);
         }
     }
 
+    /// Returns the (qualified) name of the element, e.g., "cbc:ID".
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the value of the attribute with the given name, if the element has it.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the child elements (none for elements with text content), without the text, comments and processing
+    /// instructions between them, which are returned by [`items`][XmlElement::items] instead.
+    pub fn children(&self) -> &[XmlElement] {
+        match &self.content {
+            XmlElementContent::Children(children) => children,
+            _ => &[],
+        }
+    }
+
+    /// Returns the text content of the element, if it has no child elements. The content of elements created with
+    /// [`new_raw_leaf`][XmlElement::new_raw_leaf] is returned in escaped form.
+    pub fn text(&self) -> Option<&str> {
+        match &self.content {
+            XmlElementContent::Content(content) | XmlElementContent::Raw(content) => Some(content),
+            _ => None,
+        }
+    }
+
+    /// Returns the child elements together with the text, comments and processing instructions between them in their
+    /// order (none for elements with text content, which is returned by [`text`][XmlElement::text] instead).
+    pub fn items(&self) -> Vec<XmlContentItem> {
+        let children = self.children();
+        let mut items = Vec::with_capacity(children.len() + self.nodes.len());
+        let mut nodes = self.nodes.iter().peekable();
+        for index in 0..=children.len() {
+            while let Some((_, item)) = nodes.next_if(|(position, _)| *position <= index) {
+                items.push(item.clone());
+            }
+            if let Some(child) = children.get(index) {
+                items.push(XmlContentItem::Element(child.clone()));
+            }
+        }
+        items
+    }
+
     pub fn write<W: std::io::Write>(
         &self,
         writer: &mut Writer<W>,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        for item in &self.prolog {
+            item.write(writer)?;
+        }
+
         let mut elem = BytesStart::new(self.name.clone());
 
         for (key, value) in &self.attributes {
-            elem.push_attribute((key.as_bytes(), value.as_bytes()));
+            elem.push_attribute((key.as_str(), value.as_str()));
         }
 
         writer.write_event(Event::Start(elem))?;
 
         match &self.content {
+            XmlElementContent::Children(_)
+                if self
+                    .nodes
+                    .iter()
+                    .any(|(_, item)| matches!(item, XmlContentItem::Text(_))) =>
+            {
+                // mixed content is written without indentation, which would add whitespace to the text
+                let mut unindented = Writer::new(Vec::new());
+                for item in self.items() {
+                    item.write(&mut unindented)?;
+                }
+                let content = String::from_utf8(unindented.into_inner())?;
+                writer.write_event(Event::Text(BytesText::from_escaped(content)))?;
+            }
             XmlElementContent::Children(children) => {
-                for child in children {
-                    child.write(writer)?;
+                let mut nodes = self.nodes.iter().peekable();
+                for index in 0..=children.len() {
+                    while let Some((_, item)) = nodes.next_if(|(position, _)| *position <= index) {
+                        item.write(writer)?;
+                    }
+                    if let Some(child) = children.get(index) {
+                        child.write(writer)?;
+                    }
                 }
             }
             XmlElementContent::Content(content) => {
+                writer.write_event(Event::Text(BytesText::new(content)))?;
+            }
+            XmlElementContent::Raw(content) => {
                 writer.write_event(Event::Text(BytesText::from_escaped(content)))?;
             }
         }
@@ -85,15 +360,266 @@ impl XmlElement {
 
 /// Writes an  XRechnung XML structure to the file with the given name.
 ///
+/// The structure is first written to a temporary file next to the file, which is then renamed to the given name. So
+/// the file is either written completely or not at all, even if writing is interrupted.
+///
 /// * `file_name` - The name of the file to write the XRechnung XML structure to.
 /// * `root_element` - The root element of the XML structure as created by the [`create`][crate::create] function.
 pub fn write(file_name: &str, root_element: &XmlElement) -> Result<(), Box<dyn std::error::Error>> {
-    let file = File::create(file_name)?;
-    let mut writer: Writer<BufWriter<File>> =
-        Writer::new_with_indent(BufWriter::new(file), b' ', 4);
+    write_atomically(file_name, |file| write_to(file, root_element))
+}
+
+/// Writes the file with the given name with the given function via a temporary file, which is renamed once it is
+/// completely written and stored on the disk.
+pub(crate) fn write_atomically(
+    file_name: &str,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (temporary_file_name, file) = create_temporary_file(file_name)?;
+    let result =
+        write_file(file, write).and_then(|_| Ok(std::fs::rename(&temporary_file_name, file_name)?));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary_file_name);
+    }
+    result
+}
+
+/// Creates a new temporary file next to the file with the given name and returns it together with its name. The name
+/// is not used by any other file, so that neither an existing file is overwritten nor concurrent writes of the same
+/// file use the same temporary file.
+fn create_temporary_file(file_name: &str) -> Result<(String, File), Box<dyn std::error::Error>> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    for _ in 0..TEMPORARY_FILE_ATTEMPTS {
+        let temporary_file_name = format!(
+            "{file_name}.{}-{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temporary_file_name)
+        {
+            Ok(file) => return Ok((temporary_file_name, file)),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Err(format!("No unused name for a temporary file next to '{file_name}' was found.").into())
+}
+
+/// Writes the given file with the given function and makes sure that it is stored on the disk.
+fn write_file(
+    file: File,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = BufWriter::new(file);
+    write(&mut file)?;
+    file.into_inner()?.sync_all()?;
+    Ok(())
+}
+
+/// Writes an XRechnung XML structure to the given writer, e.g., into an HTTP response, a buffer or an archive. The
+/// document is the same as the one written to files by the [`write`] function.
+///
+/// * `writer` - The writer to write the XRechnung XML structure to.
+/// * `root_element` - The root element of the XML structure as created by the [`create`][crate::create] function.
+pub fn write_to<W: Write>(
+    writer: W,
+    root_element: &XmlElement,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = Writer::new_with_indent(writer, b' ', 4);
 
     // xml declaration
     writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
 
-    root_element.write(&mut writer)
+    root_element.write(&mut writer)?;
+    writer.into_inner().flush()?;
+    Ok(())
+}
+
+/// Returns an XRechnung XML structure as string, which is the same document as the one written to files by the
+/// [`write`] function.
+///
+/// * `root_element` - The root element of the XML structure as created by the [`create`][crate::create] function.
+pub fn to_xml_string(root_element: &XmlElement) -> Result<String, Box<dyn std::error::Error>> {
+    let mut content = Vec::new();
+    write_to(&mut content, root_element)?;
+    Ok(String::from_utf8(content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XmlElement;
+
+    #[test]
+    fn test_write() {
+        let directory =
+            std::env::temp_dir().join(format!("xrechnung-write-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let file_name = directory.join("invoice.xml").to_string_lossy().into_owned();
+        let root = XmlElement::new(
+            "Invoice",
+            None,
+            Some(vec![XmlElement::new_leaf("ID", None, "2025-0001")]),
+        );
+
+        // an existing file with the name of a temporary file is kept
+        std::fs::write(format!("{file_name}.tmp"), "kept").unwrap();
+        super::write(&file_name, &root).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_name).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Invoice>\n    <ID>2025-0001</ID>\n</Invoice>"
+        );
+        assert_eq!(
+            std::fs::read_to_string(format!("{file_name}.tmp")).unwrap(),
+            "kept"
+        );
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 2);
+
+        // concurrent writes of the same file do not interfere
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let file_name = file_name.clone();
+                let root = root.clone();
+                std::thread::spawn(move || super::write(&file_name, &root).is_ok())
+            })
+            .collect();
+        assert!(threads.into_iter().all(|thread| thread.join().unwrap()));
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 2);
+
+        // nothing is left behind, if the file cannot be written
+        let missing = directory
+            .join("missing")
+            .join("invoice.xml")
+            .to_string_lossy()
+            .into_owned();
+        assert!(super::write(&missing, &root).is_err());
+        assert!(super::write_atomically(&file_name, |_| Err("failed".into())).is_err());
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_write_to() {
+        let root = XmlElement::new(
+            "Invoice",
+            None,
+            Some(vec![XmlElement::new_leaf("ID", None, "2025-0001")]),
+        );
+        let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Invoice>\n    <ID>2025-0001</ID>\n</Invoice>";
+
+        let mut buffer = Vec::new();
+        super::write_to(&mut buffer, &root).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+        assert_eq!(super::to_xml_string(&root).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_mixed_content() {
+        use super::XmlContentItem;
+
+        let mut note = XmlElement::new_mixed(
+            "Note",
+            None,
+            vec![
+                XmlContentItem::Text("First line".to_string()),
+                XmlContentItem::Element(XmlElement::new("br", None, None)),
+            ],
+        );
+        note.push_text("Second line");
+        note.push_child(XmlElement::new_leaf("b", None, "bold"));
+        assert_eq!(note.items().len(), 4);
+        assert_eq!(note.children().len(), 2);
+        assert_eq!(note.text(), None);
+
+        // an element with child elements gets mixed content by pushing text
+        let mut text = XmlElement::new("Text", None, Some(vec![XmlElement::new("br", None, None)]));
+        text.push_text("after");
+        assert_eq!(
+            text.items(),
+            [
+                XmlContentItem::Element(XmlElement::new("br", None, None)),
+                XmlContentItem::Text("after".to_string())
+            ]
+        );
+
+        let root = XmlElement::new("Invoice", None, Some(vec![note]));
+        assert_eq!(
+            super::to_xml_string(&root).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Invoice>\n    \
+             <Note>First line<br></br>Second line<b>bold</b></Note>\n</Invoice>"
+        );
+    }
+
+    #[test]
+    fn test_comments_and_processing_instructions() {
+        let mut root = XmlElement::new(
+            "Invoice",
+            None,
+            Some(vec![XmlElement::new_leaf("ID", None, "2025-0001")]),
+        );
+        root.insert_item(0, XmlElement::comment(" generated by xrechnung "));
+        root.push_prolog(XmlElement::processing_instruction(
+            "xml-stylesheet",
+            "type=\"text/xsl\" href=\"invoice.xsl\"",
+        ));
+        assert_eq!(root.children().len(), 1);
+        assert_eq!(root.children()[0].name(), "ID");
+        assert_eq!(
+            root.items()[0],
+            XmlElement::comment(" generated by xrechnung ")
+        );
+        assert_eq!(
+            super::to_xml_string(&root).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <?xml-stylesheet type=\"text/xsl\" href=\"invoice.xsl\"?>\n<Invoice>\n    \
+             <!-- generated by xrechnung -->\n    <ID>2025-0001</ID>\n</Invoice>"
+        );
+
+        // comments and processing instructions that would end early are rejected
+        let mut invalid = XmlElement::new("Invoice", None, None);
+        invalid.push_item(XmlElement::comment("a -- b"));
+        assert!(super::to_xml_string(&invalid).is_err());
+        let mut invalid = XmlElement::new("Invoice", None, None);
+        invalid.push_prolog(XmlElement::processing_instruction("pi", "?>"));
+        assert!(super::to_xml_string(&invalid).is_err());
+
+        // the parsed document skips them
+        let parsed = XmlElement::parse(&super::to_xml_string(&root).unwrap()).unwrap();
+        assert_eq!(parsed.children().len(), 1);
+    }
+
+    #[test]
+    fn test_escaping() {
+        let mut root = XmlElement::new(
+            "Invoice",
+            None,
+            Some(vec![
+                XmlElement::new_leaf(
+                    "Name",
+                    Some(vec![("note", "\"A\" & <B>")]),
+                    "Müller & Söhne",
+                ),
+                XmlElement::new_leaf("Item", None, "Price < 100 €"),
+                XmlElement::new_raw_leaf("Raw", None, "A &amp; B &#x20AC;"),
+            ]),
+        );
+        root.push_text("R&D");
+        let xml = super::to_xml_string(&root).unwrap();
+        assert!(
+            xml.contains("<Name note=\"&quot;A&quot; &amp; &lt;B&gt;\">Müller &amp; Söhne</Name>")
+        );
+        assert!(xml.contains("<Item>Price &lt; 100 €</Item>"));
+        assert!(xml.contains("<Raw>A &amp; B &#x20AC;</Raw>"));
+        assert!(xml.contains("R&amp;D</Invoice>"));
+
+        // the parsed document has the unescaped text and attributes
+        let root = XmlElement::parse(&xml).unwrap();
+        let name = &root.children()[0];
+        assert_eq!(name.text(), Some("Müller & Söhne"));
+        assert_eq!(name.attribute("note"), Some("\"A\" & <B>"));
+    }
 }
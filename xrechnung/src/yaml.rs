@@ -0,0 +1,398 @@
+//! A minimal parser for the subset of YAML that is needed for configuration files: block mappings and sequences,
+//! flow sequences of scalars, literal (`|`) and folded (`>`) block scalars, quoted and plain scalars, and comments.
+//!
+//! Anchors, aliases, tags, multiple documents and flow mappings are not supported.
+
+use crate::json::Value;
+
+/// A line of the document without comments, with its indentation and line number.
+struct Line {
+    number: usize,
+    indent: usize,
+    content: String,
+}
+
+/// Parses the given YAML document into the same value structure as used for JSON documents.
+pub fn parse(document: &str) -> Result<Value, String> {
+    let mut lines: Vec<Line> = Vec::new();
+    for (index, line) in document.lines().enumerate() {
+        let content = strip_comment(line);
+        if content.trim().is_empty() || content.trim_end() == "---" {
+            continue;
+        }
+        if content.starts_with('\t') {
+            return Err(error(index + 1, "tabs are not allowed for indentation"));
+        }
+
+        let indent = content.len() - content.trim_start().len();
+        lines.push(Line {
+            number: index + 1,
+            indent,
+            content: content.trim().to_string(),
+        });
+    }
+
+    if lines.is_empty() {
+        return Ok(Value::Null);
+    }
+
+    let mut position = 0;
+    let indent = lines[0].indent;
+    let value = parse_block(&mut lines, &mut position, indent, document)?;
+    match lines.get(position) {
+        None => Ok(value),
+        Some(line) => Err(error(line.number, "unexpected indentation")),
+    }
+}
+
+fn error(line: usize, message: &str) -> String {
+    format!("Invalid YAML in line {line}: {message}.")
+}
+
+/// Removes a comment (starting with `#` at the beginning or after whitespace, outside of quotes) from the line.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'')
+                if previous.is_whitespace() || matches!(previous, ':' | '[' | ',') =>
+            {
+                quote = Some(c)
+            }
+            (Some(open), c) if c == open => quote = None,
+            (None, '#') if previous.is_whitespace() => return line[..index].trim_end(),
+            _ => (),
+        }
+        previous = c;
+    }
+    line.trim_end()
+}
+
+/// Returns whether the content of a line is a sequence entry.
+fn is_sequence_entry(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+/// Splits the content of a line into the key and value of a mapping entry, if it is one.
+fn split_key(content: &str) -> Option<(String, &str)> {
+    let (key, rest) = if let Some(quote @ ('"' | '\'')) = content.chars().next() {
+        let end = content[1..].find(quote)? + 1;
+        let rest = content[end + 1..].trim_start();
+        (content[1..end].to_string(), rest.strip_prefix(':')?)
+    } else {
+        let index = content
+            .match_indices(':')
+            .map(|(index, _)| index)
+            .find(|index| {
+                content[index + 1..].is_empty() || content[index + 1..].starts_with(' ')
+            })?;
+        (content[..index].trim().to_string(), &content[index + 1..])
+    };
+
+    if rest.is_empty() || rest.starts_with(' ') {
+        Some((key, rest.trim()))
+    } else {
+        None
+    }
+}
+
+/// Parses the block (mapping, sequence or scalar) that starts at the given position with the given indentation.
+fn parse_block(
+    lines: &mut Vec<Line>,
+    position: &mut usize,
+    indent: usize,
+    document: &str,
+) -> Result<Value, String> {
+    if is_sequence_entry(&lines[*position].content) {
+        parse_sequence(lines, position, indent, document)
+    } else if split_key(&lines[*position].content).is_some() {
+        parse_mapping(lines, position, indent, document)
+    } else {
+        let line = &lines[*position];
+        let value = parse_scalar(&line.content, line.number)?;
+        *position += 1;
+        Ok(value)
+    }
+}
+
+fn parse_sequence(
+    lines: &mut Vec<Line>,
+    position: &mut usize,
+    indent: usize,
+    document: &str,
+) -> Result<Value, String> {
+    let mut values = Vec::new();
+    while let Some(line) = lines.get(*position) {
+        if line.indent < indent {
+            break;
+        }
+        if line.indent > indent || !is_sequence_entry(&line.content) {
+            return Err(error(line.number, "expected a sequence entry"));
+        }
+
+        let rest = line.content[1..].trim_start().to_string();
+        if rest.is_empty() {
+            // the value of the entry follows in the next lines
+            *position += 1;
+            match lines.get(*position) {
+                Some(next) if next.indent > indent => {
+                    let next_indent = next.indent;
+                    values.push(parse_block(lines, position, next_indent, document)?);
+                }
+                _ => values.push(Value::Null),
+            }
+        } else {
+            // the entry starts on the same line, e.g., "- name: value", which is parsed like an indented block
+            let offset = line.content.len() - rest.len();
+            lines[*position].indent = indent + offset;
+            lines[*position].content = rest;
+            values.push(parse_block(lines, position, indent + offset, document)?);
+        }
+    }
+    Ok(Value::Array(values))
+}
+
+fn parse_mapping(
+    lines: &mut Vec<Line>,
+    position: &mut usize,
+    indent: usize,
+    document: &str,
+) -> Result<Value, String> {
+    let mut members: Vec<(String, Value)> = Vec::new();
+    while let Some(line) = lines.get(*position) {
+        if line.indent < indent {
+            break;
+        }
+        let number = line.number;
+        if line.indent > indent {
+            return Err(error(number, "unexpected indentation"));
+        }
+        let (key, rest) =
+            split_key(&line.content).ok_or(error(number, "expected a mapping entry"))?;
+        if members.iter().any(|(name, _)| *name == key) {
+            return Err(error(number, &format!("duplicate key '{key}'")));
+        }
+        let rest = rest.to_string();
+        *position += 1;
+
+        let value = match rest.as_str() {
+            "" => match lines.get(*position) {
+                Some(next) if next.indent > indent => {
+                    let next_indent = next.indent;
+                    parse_block(lines, position, next_indent, document)?
+                }
+                // sequences can have the same indentation as their key
+                Some(next) if next.indent == indent && is_sequence_entry(&next.content) => {
+                    parse_sequence(lines, position, indent, document)?
+                }
+                _ => Value::Null,
+            },
+            "|" | "|-" | ">" | ">-" => parse_block_scalar(lines, position, indent, &rest, document),
+            _ => parse_scalar(&rest, number)?,
+        };
+        members.push((key, value));
+    }
+    Ok(Value::Object(members))
+}
+
+/// Parses a literal (`|`) or folded (`>`) block scalar, whose lines are more indented than its key. The lines are
+/// taken from the original document, as they can contain `#` characters that are no comments.
+fn parse_block_scalar(
+    lines: &[Line],
+    position: &mut usize,
+    indent: usize,
+    style: &str,
+    document: &str,
+) -> Value {
+    let key_line = lines[*position - 1].number;
+    let mut text: Vec<&str> = Vec::new();
+    let mut block_indent = None;
+    let mut last_line = key_line;
+
+    for (index, line) in document.lines().enumerate().skip(key_line) {
+        let line_indent = line.len() - line.trim_start().len();
+        if line.trim().is_empty() {
+            text.push("");
+            continue;
+        }
+        if line_indent <= indent {
+            break;
+        }
+        let block_indent = *block_indent.get_or_insert(line_indent);
+        text.push(line.get(block_indent..).unwrap_or(line.trim_start()));
+        last_line = index + 1;
+    }
+
+    // blank lines at the end do not belong to the block
+    while text.last() == Some(&"") {
+        text.pop();
+    }
+    while lines
+        .get(*position)
+        .is_some_and(|line| line.number <= last_line)
+    {
+        *position += 1;
+    }
+
+    let mut value = if style.starts_with('|') {
+        text.join("\n")
+    } else {
+        text.join(" ")
+    };
+    if !style.ends_with('-') {
+        value.push('\n');
+    }
+    Value::String(value)
+}
+
+/// Parses a scalar or a flow sequence of scalars.
+fn parse_scalar(content: &str, line: usize) -> Result<Value, String> {
+    if let Some(inner) = content.strip_prefix('[') {
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or(error(line, "unterminated flow sequence"))?;
+        if inner.trim().is_empty() {
+            return Ok(Value::Array(Vec::new()));
+        }
+        return split_flow(inner)
+            .iter()
+            .map(|item| parse_scalar(item.trim(), line))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array);
+    }
+    if content.starts_with('{') {
+        return Err(error(line, "flow mappings are not supported"));
+    }
+
+    if content.starts_with('"') {
+        // double-quoted scalars use the same escape sequences as JSON strings
+        return crate::json::parse(content)
+            .map_err(|_| error(line, "invalid double-quoted string"));
+    }
+    if let Some(inner) = content.strip_prefix('\'') {
+        let inner = inner
+            .strip_suffix('\'')
+            .ok_or(error(line, "unterminated single-quoted string"))?;
+        return Ok(Value::String(inner.replace("''", "'")));
+    }
+
+    Ok(match content {
+        "null" | "Null" | "NULL" | "~" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Bool(true),
+        "false" | "False" | "FALSE" => Value::Bool(false),
+        _ if is_number(content) => Value::Number(content.trim_start_matches('+').to_string()),
+        _ => Value::String(content.to_string()),
+    })
+}
+
+/// Returns whether a plain scalar is a number.
+fn is_number(content: &str) -> bool {
+    content.chars().any(|c| c.is_ascii_digit())
+        && content
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        && content.parse::<f64>().is_ok()
+}
+
+/// Splits the items of a flow sequence at the commas outside of quotes.
+fn split_flow(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (index, c) in inner.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, ',') => {
+                items.push(&inner[start..index]);
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    items.push(&inner[start..]);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::Value;
+
+    fn string(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    #[test]
+    fn test_parse() {
+        let value = super::parse(
+            "# configuration
+currency: EUR
+vat_percent: 19.0 # in percent
+supplier:
+  name: 'Hans ''the'' Muster'
+  address:
+    city: \"Example City\"
+buyer:
+- name: Client Company
+  due_after_days: 20
+  tags: [a, \"b, c\"]
+-   name: Another Client
+    active: true
+    note: |
+      First line
+      # no comment
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                ("currency".to_string(), string("EUR")),
+                ("vat_percent".to_string(), Value::Number("19.0".to_string())),
+                (
+                    "supplier".to_string(),
+                    Value::Object(vec![
+                        ("name".to_string(), string("Hans 'the' Muster")),
+                        (
+                            "address".to_string(),
+                            Value::Object(vec![("city".to_string(), string("Example City"))])
+                        ),
+                    ])
+                ),
+                (
+                    "buyer".to_string(),
+                    Value::Array(vec![
+                        Value::Object(vec![
+                            ("name".to_string(), string("Client Company")),
+                            (
+                                "due_after_days".to_string(),
+                                Value::Number("20".to_string())
+                            ),
+                            (
+                                "tags".to_string(),
+                                Value::Array(vec![string("a"), string("b, c")])
+                            ),
+                        ]),
+                        Value::Object(vec![
+                            ("name".to_string(), string("Another Client")),
+                            ("active".to_string(), Value::Bool(true)),
+                            ("note".to_string(), string("First line\n# no comment\n")),
+                        ]),
+                    ])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            super::parse("a: 1\n  b: 2").unwrap_err(),
+            "Invalid YAML in line 2: unexpected indentation."
+        );
+        assert!(super::parse("a: 1\na: 2").is_err());
+        assert!(super::parse("a: {b: 1}").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Storage of invoices in an archive directory (`--archive`), with a directory per year and canonical file names
+//! `<supplier>-<buyer>-<invoice number>.xml`.
+
+use std::path::{Path, PathBuf};
+
+/// Returns the given text as part of a file name, with all characters but letters and digits replaced by dashes.
+fn slug(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// Returns the path of the given invoice in the archive directory.
+pub fn file_name(
+    directory: &str,
+    supplier: &str,
+    buyer: &str,
+    invoice_id: &str,
+    year: i32,
+) -> PathBuf {
+    Path::new(directory).join(year.to_string()).join(format!(
+        "{}-{}-{}.xml",
+        slug(supplier),
+        slug(buyer),
+        slug(invoice_id)
+    ))
+}
+
+/// Returns the invoice number of the given invoice file, if it can be read.
+fn read_invoice_id(file_name: &Path) -> Option<String> {
+    let document = std::fs::read_to_string(file_name).ok()?;
+    let root = xrechnung::XmlElement::parse(&document).ok()?;
+    root.children()
+        .iter()
+        .find(|child| child.name().rsplit(':').next() == Some("ID"))
+        .and_then(|id| id.text())
+        .map(|id| id.trim().to_string())
+}
+
+/// Returns the file of the archived invoice of the supplier with the given number in any year, if there is one.
+pub fn find(
+    directory: &str,
+    supplier: &str,
+    invoice_id: &str,
+) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    let directory = Path::new(directory);
+    if !directory.exists() {
+        return Ok(None);
+    }
+
+    // the invoice numbers in the file names are only candidates, as different numbers can have the same slug
+    let prefix = format!("{}-", slug(supplier));
+    let suffix = format!("-{}.xml", slug(invoice_id));
+    for year in std::fs::read_dir(directory)? {
+        let year = year?.path();
+        if !year.is_dir() {
+            continue;
+        }
+        for file in std::fs::read_dir(&year)? {
+            let file = file?.path();
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with(&prefix)
+                && name.ends_with(&suffix)
+                && invoice_id_matches(&file, invoice_id)
+            {
+                return Ok(Some(file));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Returns whether the given file contains the invoice with the given number.
+fn invoice_id_matches(file_name: &Path, id: &str) -> bool {
+    read_invoice_id(file_name).is_some_and(|archived| archived == id)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_file_name() {
+        assert_eq!(
+            super::file_name(
+                "archive",
+                "Hans Muster",
+                "Client Company GmbH",
+                "2025/0001",
+                2025
+            ),
+            std::path::Path::new("archive/2025/Hans-Muster-Client-Company-GmbH-2025-0001.xml")
+        );
+        assert_eq!(super::slug(" Müller & Söhne "), "Müller-Söhne");
+    }
+
+    #[test]
+    fn test_find() {
+        let directory =
+            std::env::temp_dir().join(format!("xrechnung-archive-{}", std::process::id()));
+        let directory_name = directory.to_string_lossy().into_owned();
+        assert_eq!(
+            super::find(&directory_name, "Hans Muster", "1").unwrap(),
+            None
+        );
+
+        let file_name = super::file_name(&directory_name, "Hans Muster", "Client", "2025/1", 2025);
+        std::fs::create_dir_all(file_name.parent().unwrap()).unwrap();
+        std::fs::write(
+            &file_name,
+            "<ubl:Invoice xmlns:ubl=\"x\" xmlns:cbc=\"y\"><cbc:ID>2025/1</cbc:ID></ubl:Invoice>",
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::find(&directory_name, "Hans Muster", "2025/1").unwrap(),
+            Some(file_name)
+        );
+        // same slug, but another invoice number
+        assert_eq!(
+            super::find(&directory_name, "Hans Muster", "2025-1").unwrap(),
+            None
+        );
+        assert_eq!(
+            super::find(&directory_name, "Other Supplier", "2025/1").unwrap(),
+            None
+        );
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
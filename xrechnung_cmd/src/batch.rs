@@ -0,0 +1,227 @@
+//! Creation of many invoices in one run (`batch`), as listed in a manifest file.
+//!
+//! The manifest is a TOML file with an `[[invoice]]` table per invoice, which gives the buyer, the invoice number and
+//! the invoice lines, and optionally the issue date, the output file (default: `<invoice number>.xml`) and further
+//! options of the `generate` command. The issue date and options can also be given for all invoices at the top of the
+//! manifest (see `examples/batch.toml`).
+
+use clap::{Args, FromArgMatches};
+use serde::Deserialize;
+
+use crate::InvoiceArgs;
+
+/// A manifest file with the invoices to create.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    /// The issue date of all invoices that do not have their own.
+    issue_date: Option<String>,
+
+    /// Options of the `generate` command for all invoices.
+    #[serde(default)]
+    options: Vec<String>,
+
+    /// The invoices to create.
+    invoice: Vec<Entry>,
+}
+
+/// An invoice of the manifest file.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    buyer: String,
+    invoice_id: String,
+    invoice_hours: String,
+    issue_date: Option<String>,
+    output: Option<String>,
+
+    /// Options of the `generate` command for this invoice, which are given after those for all invoices.
+    #[serde(default)]
+    options: Vec<String>,
+}
+
+/// The outcome of creating an invoice of the manifest.
+struct Outcome {
+    invoice_id: String,
+    buyer: String,
+    result: Result<String, String>,
+}
+
+/// Parses the arguments for creating the given invoice of the manifest, as if they were given to the `generate`
+/// command, and returns them together with the output file.
+fn invoice_args(manifest: &Manifest, entry: &Entry) -> Result<(InvoiceArgs, String), String> {
+    // without issue date, the invoice is issued on the last day of its month (if given in the options) or today
+    let issue_date = entry.issue_date.as_ref().or(manifest.issue_date.as_ref());
+    let args = [
+        "--invoice-id",
+        &entry.invoice_id,
+        "--buyer",
+        &entry.buyer,
+        "--invoice-hours",
+        &entry.invoice_hours,
+    ]
+    .into_iter()
+    .chain(
+        issue_date
+            .into_iter()
+            .flat_map(|date| ["--issue-date", date]),
+    )
+    .chain(manifest.options.iter().map(String::as_str))
+    .chain(entry.options.iter().map(String::as_str));
+
+    let command = InvoiceArgs::augment_args(clap::Command::new("invoice").no_binary_name(true));
+    let matches = command
+        .try_get_matches_from(args)
+        .map_err(|e| e.render().to_string().trim().to_string())?;
+    let invoice_args = InvoiceArgs::from_arg_matches(&matches).map_err(|e| e.to_string())?;
+    let output = entry
+        .output
+        .clone()
+        .unwrap_or_else(|| format!("{}.xml", entry.invoice_id));
+    Ok((invoice_args, output))
+}
+
+/// Returns the report about the created invoices, with a line per invoice and the number of successes and failures.
+fn report(outcomes: &[Outcome]) -> String {
+    let mut report = String::new();
+    for outcome in outcomes {
+        match &outcome.result {
+            Ok(output) => report.push_str(&format!(
+                "OK      {} ({}): {output}\n",
+                outcome.invoice_id, outcome.buyer
+            )),
+            Err(error) => report.push_str(&format!(
+                "FAILED  {} ({}): {}\n",
+                outcome.invoice_id,
+                outcome.buyer,
+                error.replace('\n', "\n        ")
+            )),
+        }
+    }
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| outcome.result.is_err())
+        .count();
+    report.push_str(&format!(
+        "\nCreated {} of {} invoice(s), {failed} failed.\n",
+        outcomes.len() - failed,
+        outcomes.len()
+    ));
+    report
+}
+
+/// Creates all invoices of the given manifest file and prints a report about them. All invoices are tried, even if
+/// some of them fail, and an error is returned at the end if any of them failed. Existing output files are only
+/// overwritten, if forced. The created invoices are recorded in the given registry file.
+pub fn run(
+    config_file: &str,
+    registry: &std::path::Path,
+    manifest_file: &str,
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(manifest_file)
+        .map_err(|e| format!("Could not read the manifest file '{manifest_file}': {e}"))?;
+    let manifest: Manifest = toml::from_str(&content)
+        .map_err(|e| format!("Could not parse the manifest file '{manifest_file}': {e}"))?;
+
+    let outcomes: Vec<Outcome> = manifest
+        .invoice
+        .iter()
+        .map(|entry| {
+            crate::log::info!(
+                "batch",
+                "Creating the invoice {} for '{}'.",
+                entry.invoice_id,
+                entry.buyer
+            );
+            let result = invoice_args(&manifest, entry).and_then(|(args, output)| {
+                crate::generate(config_file, registry, args, Some(&output), None, force)
+                    .map(|_| output)
+                    .map_err(|e| e.to_string())
+            });
+            Outcome {
+                invoice_id: entry.invoice_id.clone(),
+                buyer: entry.buyer.clone(),
+                result,
+            }
+        })
+        .collect();
+
+    print!("{}", report(&outcomes));
+    if outcomes.iter().any(|outcome| outcome.result.is_err()) {
+        return Err(format!(
+            "Not all invoices of the manifest '{manifest_file}' could be created."
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Manifest, Outcome};
+
+    #[test]
+    fn test_invoice_args() {
+        let manifest: Manifest =
+            toml::from_str(&std::fs::read_to_string("examples/batch.toml").unwrap()).unwrap();
+        assert_eq!(manifest.invoice.len(), 2);
+
+        let (args, output) = super::invoice_args(&manifest, &manifest.invoice[0]).unwrap();
+        assert_eq!(args.invoice_id.as_deref(), Some("2025-0001"));
+        assert_eq!(args.buyer, "Client Company");
+        assert_eq!(args.issue_date().to_string(), "2025-01-31");
+        assert_eq!(args.lines.invoice_hours, "examples/invoice-lines.csv");
+        assert!(matches!(args.period_check, crate::PeriodCheck::Fail));
+        assert!(args.aggregate.is_none());
+        assert_eq!(output, "2025-0001.xml");
+
+        let (args, output) = super::invoice_args(&manifest, &manifest.invoice[1]).unwrap();
+        assert!(args.aggregate.is_some());
+        assert_eq!(output, "2025-0002.xml");
+
+        let manifest: Manifest = toml::from_str(
+            "[[invoice]]\nbuyer = \"acme\"\ninvoice_id = \"1\"\ninvoice_hours = \"a.csv\"\noptions = [\"--unknown\"]",
+        )
+        .unwrap();
+        assert!(super::invoice_args(&manifest, &manifest.invoice[0]).is_err());
+
+        // the issue date is either given or derived from the month, or the invoice is issued today
+        let manifest: Manifest = toml::from_str(
+            "[[invoice]]\nbuyer = \"acme\"\ninvoice_id = \"1\"\ninvoice_hours = \"a.csv\"\noptions = [\"--month\", \"2025-02\"]",
+        )
+        .unwrap();
+        let (args, _) = super::invoice_args(&manifest, &manifest.invoice[0]).unwrap();
+        assert_eq!(args.issue_date().to_string(), "2025-02-28");
+        let manifest: Manifest = toml::from_str(
+            "[[invoice]]\nbuyer = \"acme\"\ninvoice_id = \"1\"\ninvoice_hours = \"a.csv\"",
+        )
+        .unwrap();
+        let (args, _) = super::invoice_args(&manifest, &manifest.invoice[0]).unwrap();
+        assert_eq!(args.issue_date(), chrono::Local::now().date_naive());
+    }
+
+    #[test]
+    fn test_report() {
+        let outcomes = vec![
+            Outcome {
+                invoice_id: "2025-0001".to_string(),
+                buyer: "Client Company".to_string(),
+                result: Ok("2025-0001.xml".to_string()),
+            },
+            Outcome {
+                invoice_id: "2025-0002".to_string(),
+                buyer: "Unknown".to_string(),
+                result: Err("Buyer not found.".to_string()),
+            },
+        ];
+        assert_eq!(
+            super::report(&outcomes),
+            "OK      2025-0001 (Client Company): 2025-0001.xml
+FAILED  2025-0002 (Unknown): Buyer not found.
+
+Created 1 of 2 invoice(s), 1 failed.
+"
+        );
+    }
+}
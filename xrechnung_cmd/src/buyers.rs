@@ -0,0 +1,335 @@
+//! Listing of the buyers in the configuration file (`buyers list`) and reading of buyers from contacts exported by
+//! other tools as CSV or vCard files (`buyers import`).
+
+use xrechnung::config::CompleteConfig;
+
+use crate::init::BuyerFields;
+
+/// Returns a table with the name, the aliases, the reference, the days until invoices are due and the VAT of all
+/// buyers in the given configuration.
+pub fn table(config: &CompleteConfig) -> String {
+    let mut rows = vec![vec![
+        "NAME".to_string(),
+        "ALIAS".to_string(),
+        "REFERENCE".to_string(),
+        "DUE DAYS".to_string(),
+        "VAT".to_string(),
+    ]];
+    for buyer in &config.buyer {
+        rows.push(vec![
+            buyer.name.clone(),
+            buyer.alias.join(", "),
+            buyer
+                .leitweg_id
+                .as_ref()
+                .unwrap_or(&buyer.reference)
+                .clone(),
+            buyer.due_after_days.to_string(),
+            format!("{} % ({})", config.vat_percent, buyer.tax_identification),
+        ]);
+    }
+    crate::registry::format_rows(&rows, &[])
+}
+
+/// Reads the contacts in the given CSV or vCard file (with the extension `.vcf` or `.vcard`) as buyers.
+pub fn read_contacts(filename: &str) -> Result<Vec<BuyerFields>, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(filename)
+        .map_err(|e| format!("Could not read the contacts file '{filename}': {e}"))?;
+    let extension = std::path::Path::new(filename)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+    let contacts = match extension.as_deref() {
+        Some("vcf" | "vcard") => parse_vcard(&content)?,
+        _ => parse_csv(&content)?,
+    };
+    match contacts.is_empty() {
+        true => Err(format!("The contacts file '{filename}' contains no contacts.").into()),
+        false => Ok(contacts),
+    }
+}
+
+/// Returns the given value, if it is not empty.
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Returns the ISO 3166-1 (Alpha-2) code for the country as given in a contact, which can be the code itself or the
+/// English or German name of a few common countries. Other countries are asked for.
+fn country_code(country: &str) -> Option<String> {
+    let country = country.trim();
+    if xrechnung::identifiers::is_country_code(&country.to_uppercase()) {
+        return Some(country.to_uppercase());
+    }
+    let code = match country.to_lowercase().as_str() {
+        "germany" | "deutschland" => "DE",
+        "austria" | "österreich" => "AT",
+        "switzerland" | "schweiz" => "CH",
+        "netherlands" | "niederlande" => "NL",
+        "france" | "frankreich" => "FR",
+        "united kingdom" | "großbritannien" => "GB",
+        "united states" | "usa" => "US",
+        _ => return None,
+    };
+    Some(code.to_string())
+}
+
+/// Parses contacts in CSV format with a header line. The columns are identified by their names (case-insensitively
+/// and ignoring other characters than letters and digits), which can be the names of the buyer fields in the
+/// configuration file or common alternatives, as used by other tools (e.g., `Company`, `E-mail`, `Zip`).
+fn parse_csv(content: &str) -> Result<Vec<BuyerFields>, Box<dyn std::error::Error>> {
+    // contacts exported by German tools often use semicolons as delimiter
+    let header = content.lines().next().unwrap_or_default();
+    let delimiter = match header.matches(';').count() > header.matches(',').count() {
+        true => b';',
+        false => b',',
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let columns: Vec<String> = reader
+        .headers()?
+        .iter()
+        .map(|column| {
+            column
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .collect();
+    let column = |names: &[&str]| {
+        columns
+            .iter()
+            .position(|column| names.contains(&column.as_str()))
+    };
+    let name = column(&["name", "company", "companyname", "organization", "organisation", "organization1name", "firma"])
+        .ok_or("The contacts file has no column with the name of the contacts (e.g., 'name' or 'company').")?;
+    let tax_identification = column(&[
+        "taxidentification",
+        "vatid",
+        "vatnumber",
+        "ustidnr",
+        "ustid",
+        "taxnumber",
+    ]);
+    let email = column(&["email", "emailaddress", "mail", "email1value"]);
+    let reference = column(&["reference"]);
+    let due_after_days = column(&["dueafterdays"]);
+    let address_line = column(&[
+        "addressline",
+        "street",
+        "streetaddress",
+        "address",
+        "address1street",
+        "straße",
+        "strasse",
+    ]);
+    let post_code = column(&[
+        "postcode",
+        "postalcode",
+        "zip",
+        "zipcode",
+        "address1postalcode",
+        "plz",
+    ]);
+    let city = column(&["city", "town", "address1city", "ort", "stadt"]);
+    let country = column(&["countrycode", "country", "address1country", "land"]);
+
+    let mut contacts = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .and_then(non_empty)
+        };
+        let Some(name) = field(Some(name)) else {
+            continue;
+        };
+        contacts.push(BuyerFields {
+            name: Some(name),
+            tax_identification: field(tax_identification),
+            email: field(email),
+            reference: field(reference),
+            due_after_days: field(due_after_days),
+            address_line: field(address_line),
+            post_code: field(post_code),
+            city: field(city),
+            country_code: field(country).and_then(|country| country_code(&country)),
+        });
+    }
+    Ok(contacts)
+}
+
+/// Splits a value of a vCard property at the unescaped separators and unescapes the parts.
+fn split_vcard_value(value: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => parts.last_mut().unwrap().push('\n'),
+                Some(escaped) => parts.last_mut().unwrap().push(escaped),
+                None => (),
+            },
+            c if c == separator => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts
+}
+
+/// Parses contacts in vCard format (versions 2.1, 3.0 and 4.0). The name of the buyer is taken from the organization
+/// (`ORG`) or, if not given, the formatted name (`FN`) of a contact, the email address from its first `EMAIL` property
+/// and the address from its first `ADR` property.
+fn parse_vcard(content: &str) -> Result<Vec<BuyerFields>, Box<dyn std::error::Error>> {
+    // lines starting with a space or tab continue the previous line
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut contacts = Vec::new();
+    let mut contact: Option<(BuyerFields, Option<String>, Option<String>)> = None;
+    for line in lines {
+        let Some((property, value)) = line.split_once(':') else {
+            continue;
+        };
+        // the property name can have a group prefix (e.g., "item1.ADR") and parameters (e.g., "ADR;TYPE=work")
+        let name = property.split(';').next().unwrap_or_default();
+        let name = name.rsplit('.').next().unwrap_or_default().to_uppercase();
+
+        match (name.as_str(), &mut contact) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VCARD") => {
+                contact = Some((BuyerFields::default(), None, None))
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VCARD") => {
+                let (mut fields, organization, formatted_name) = contact.take().unwrap();
+                fields.name = organization.or(formatted_name);
+                if fields.name.is_some() {
+                    contacts.push(fields);
+                }
+            }
+            ("ORG", Some((_, organization, _))) if organization.is_none() => {
+                *organization = split_vcard_value(value, ';')
+                    .first()
+                    .and_then(|part| non_empty(part))
+            }
+            ("FN", Some((_, _, formatted_name))) if formatted_name.is_none() => {
+                *formatted_name = non_empty(&split_vcard_value(value, ',').join(","))
+            }
+            ("EMAIL", Some((fields, _, _))) if fields.email.is_none() => {
+                fields.email = non_empty(value)
+            }
+            ("ADR", Some((fields, _, _))) if fields.address_line.is_none() => {
+                // post office box; extended address; street; locality; region; postal code; country
+                let parts = split_vcard_value(value, ';');
+                let part = |index: usize| parts.get(index).and_then(|part| non_empty(part));
+                fields.address_line = part(2).map(|street| street.replace('\n', ", "));
+                fields.city = part(3);
+                fields.post_code = part(5);
+                fields.country_code = part(6).and_then(|country| country_code(&country));
+            }
+            _ => (),
+        }
+    }
+    if contact.is_some() {
+        return Err("The vCard file ends within a contact (END:VCARD is missing).".into());
+    }
+    Ok(contacts)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_table() {
+        let config = xrechnung::config::load_all("../xrechnung/examples/config.toml").unwrap();
+        assert_eq!(
+            super::table(&config),
+            "NAME            ALIAS  REFERENCE  DUE DAYS  VAT
+Client Company  acme   12345      20        19 % (DE111111111)
+Another Client         12349      30        19 % (DE111111111)
+"
+        );
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let contacts = super::parse_csv(
+            "Firma;E-Mail;USt-IdNr.;Straße;PLZ;Ort;Land
+Client Company;mail@client.example.com;DE111111111;Example Street 42;54321;Example City;Deutschland
+;nobody@example.com;;;;;
+Other Client;;;;;Vienna;AT
+",
+        )
+        .unwrap();
+
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0].name.as_deref(), Some("Client Company"));
+        assert_eq!(
+            contacts[0].email.as_deref(),
+            Some("mail@client.example.com")
+        );
+        assert_eq!(
+            contacts[0].tax_identification.as_deref(),
+            Some("DE111111111")
+        );
+        assert_eq!(
+            contacts[0].address_line.as_deref(),
+            Some("Example Street 42")
+        );
+        assert_eq!(contacts[0].post_code.as_deref(), Some("54321"));
+        assert_eq!(contacts[0].city.as_deref(), Some("Example City"));
+        assert_eq!(contacts[0].country_code.as_deref(), Some("DE"));
+        assert_eq!(contacts[1].name.as_deref(), Some("Other Client"));
+        assert_eq!(contacts[1].email, None);
+        assert_eq!(contacts[1].country_code.as_deref(), Some("AT"));
+
+        assert!(super::parse_csv("email\nmail@client.example.com\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_vcard() {
+        let contacts = super::parse_vcard(
+            "BEGIN:VCARD\r
+VERSION:3.0\r
+FN:Erika Mustermann\r
+ORG:Client Company\\, Inc.;Accounting\r
+EMAIL;TYPE=work:mail@client.example\r
+ .com\r
+item1.ADR;TYPE=work:;;Example Street 42;Example City;;54321;Germany\r
+END:VCARD\r
+BEGIN:VCARD\r
+VERSION:4.0\r
+FN:Max Muster\r
+END:VCARD\r
+",
+        )
+        .unwrap();
+
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0].name.as_deref(), Some("Client Company, Inc."));
+        assert_eq!(
+            contacts[0].email.as_deref(),
+            Some("mail@client.example.com")
+        );
+        assert_eq!(
+            contacts[0].address_line.as_deref(),
+            Some("Example Street 42")
+        );
+        assert_eq!(contacts[0].post_code.as_deref(), Some("54321"));
+        assert_eq!(contacts[0].city.as_deref(), Some("Example City"));
+        assert_eq!(contacts[0].country_code.as_deref(), Some("DE"));
+        assert_eq!(contacts[1].name.as_deref(), Some("Max Muster"));
+        assert_eq!(contacts[1].tax_identification, None);
+
+        assert!(super::parse_vcard("BEGIN:VCARD\nFN:Max Muster\n").is_err());
+    }
+}
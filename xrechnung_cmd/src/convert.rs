@@ -0,0 +1,80 @@
+//! Conversion of invoice lines from any of the supported sources into a CSV file (`convert`), e.g., for editing the
+//! lines imported from a time tracking service before creating the invoice.
+
+use std::io::Write;
+
+use xrechnung::data::InvoiceHoursElement;
+
+/// Writes the invoice lines in CSV format with a header line, as read by the command line tool. Optional columns are
+/// only written, if any of the lines has a value for them.
+pub fn write_lines<W: Write>(
+    invoice_hours: &[InvoiceHoursElement],
+    writer: W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    type Column = (&'static str, fn(&InvoiceHoursElement) -> Option<String>);
+    let columns: [Column; 12] = [
+        ("id", |line| line.id.clone()),
+        ("date", |line| line.date.clone()),
+        ("start_date", |line| line.start_date.clone()),
+        ("end_date", |line| line.end_date.clone()),
+        ("name", |line| Some(line.name.clone())),
+        ("description", |line| line.description.clone()),
+        ("quantity", |line| Some(line.quantity.to_string())),
+        ("hourly_rate", |line| Some(line.hourly_rate.to_string())),
+        ("note", |line| line.note.clone()),
+        ("order_line_id", |line| line.order_line_id.clone()),
+        ("accounting_cost", |line| line.accounting_cost.clone()),
+        ("project", |line| line.project.clone()),
+    ];
+    let columns: Vec<&Column> = columns
+        .iter()
+        .filter(|(_, value)| invoice_hours.iter().any(|line| value(line).is_some()))
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(columns.iter().map(|(name, _)| name))?;
+    for line in invoice_hours {
+        writer.write_record(
+            columns
+                .iter()
+                .map(|(_, value)| value(line).unwrap_or_default()),
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use xrechnung::data::InvoiceHoursElement;
+
+    #[test]
+    fn test_write_lines() {
+        let invoice_hours = vec![
+            InvoiceHoursElement {
+                name: "Example Service".to_string(),
+                quantity: 7.0,
+                hourly_rate: 110.0,
+                date: Some("2025-01-02".to_string()),
+                ..Default::default()
+            },
+            InvoiceHoursElement {
+                name: "Support, remote".to_string(),
+                quantity: 1.5,
+                hourly_rate: 75.0,
+                project: Some("Website".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let mut output = Vec::new();
+        super::write_lines(&invoice_hours, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "date,name,quantity,hourly_rate,project
+2025-01-02,Example Service,7,110,
+,\"Support, remote\",1.5,75,Website
+"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Cover letters for invoices (`generate --cover-letter`), i.e., the text of an email with the invoice number, amount
+//! and due date, which can be pasted into a mail client when sending the invoice manually. The text of the emails of
+//! the mail server (`[smtp]`) is used as template, if configured, and a text in the language of the buyer otherwise.
+//! The same text is sent by the `send` command.
+
+use std::path::Path;
+
+use xrechnung::config::Config;
+use xrechnung::json::Value;
+
+/// The default text of the cover letters.
+const DEFAULT_TEXT: &str = "Dear Sir or Madam,
+
+please find attached the invoice {invoice_number} of {issue_date} about {amount}, which is due on {due_date}.
+
+Kind regards
+{supplier}
+";
+
+/// The default text of the cover letters for buyers with German language.
+const DEFAULT_TEXT_GERMAN: &str = "Sehr geehrte Damen und Herren,
+
+anbei erhalten Sie die Rechnung {invoice_number} vom {issue_date} über {amount}, fällig am {due_date}.
+
+Mit freundlichen Grüßen
+{supplier}
+";
+
+/// The format of a cover letter.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// Plain text, written as `.txt` file.
+    Text,
+
+    /// HTML document with a paragraph per block of the text, written as `.html` file.
+    Html,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Text => "txt",
+            Format::Html => "html",
+        }
+    }
+}
+
+/// Returns the template of the cover letters for the buyer of the given configuration.
+pub fn template(config: &Config) -> &str {
+    if let Some(body) = config.smtp.as_ref().and_then(|smtp| smtp.body.as_deref()) {
+        return body;
+    }
+    match config.buyer.language.as_deref() {
+        Some("de") => DEFAULT_TEXT_GERMAN,
+        _ => DEFAULT_TEXT,
+    }
+}
+
+/// Replaces the placeholders in the given template with the values of the given invoice summary (as created by
+/// [`crate::preview::summary_json`]) and the given supplier name.
+pub fn fill(template: &str, summary: &Value, supplier: &str) -> String {
+    let value = |key: &str| {
+        summary
+            .get(key)
+            .map(|value| match value {
+                Value::String(string) | Value::Number(string) => string.clone(),
+                _ => String::new(),
+            })
+            .unwrap_or_default()
+    };
+    template
+        .replace("{invoice_number}", &value("invoice_number"))
+        .replace("{issue_date}", &value("issue_date"))
+        .replace("{due_date}", &value("due_date"))
+        .replace(
+            "{amount}",
+            &format!("{} {}", value("gross"), value("currency")),
+        )
+        .replace("{buyer}", &value("buyer"))
+        .replace("{supplier}", supplier)
+}
+
+/// Returns the given text with the characters that have a special meaning in HTML escaped.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Returns the given text as HTML document, with a paragraph per block of lines (separated by empty lines).
+fn html(text: &str) -> String {
+    let paragraphs: Vec<String> = text
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| {
+            let lines: Vec<String> = paragraph.lines().map(escape_html).collect();
+            format!("<p>{}</p>\n", lines.join("<br>\n"))
+        })
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n</head>\n<body>\n{}</body>\n</html>\n",
+        paragraphs.concat()
+    )
+}
+
+/// Returns the cover letter in the given format for the given invoice summary.
+pub fn render(config: &Config, summary: &Value, format: Format) -> String {
+    let text = fill(template(config), summary, &config.supplier.name);
+    match format {
+        Format::Text => text,
+        Format::Html => html(&text),
+    }
+}
+
+/// Writes the cover letter in the given format for the given invoice next to its output file (with the extension of
+/// the format instead of `.xml`). It is written to the standard error output instead, if the invoice is not written to
+/// a file.
+pub fn write(
+    config_file: &str,
+    invoice: &xrechnung::XmlElement,
+    output: Option<&str>,
+    format: Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let summary = crate::preview::summary_json(invoice, output);
+    let buyer = summary
+        .get("buyer")
+        .and_then(Value::as_str)
+        .ok_or("The invoice has no buyer name.")?;
+    let config = xrechnung::config::load(config_file, buyer).map_err(crate::error::config)?;
+    let letter = render(&config, &summary, format);
+
+    match output.filter(|output| *output != crate::STDOUT) {
+        Some(output) => {
+            let file = Path::new(output).with_extension(format.extension());
+            std::fs::write(&file, letter).map_err(|e| {
+                format!("Could not write the cover letter '{}': {e}", file.display())
+            })?;
+            crate::log::info!("write", "Wrote the cover letter to '{}'.", file.display());
+        }
+        None => eprint!("{letter}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use xrechnung::json::Value;
+
+    #[test]
+    fn test_fill() {
+        let summary = Value::Object(vec![
+            (
+                "invoice_number".to_string(),
+                Value::String("2025-001".to_string()),
+            ),
+            (
+                "due_date".to_string(),
+                Value::String("2025-02-20".to_string()),
+            ),
+            ("currency".to_string(), Value::String("EUR".to_string())),
+            ("gross".to_string(), Value::Number("1767.15".to_string())),
+        ]);
+        assert_eq!(
+            super::fill(
+                "Invoice {invoice_number}: {amount} until {due_date} ({supplier})",
+                &summary,
+                "Hans Muster"
+            ),
+            "Invoice 2025-001: 1767.15 EUR until 2025-02-20 (Hans Muster)"
+        );
+    }
+
+    #[test]
+    fn test_template() {
+        let mut config =
+            xrechnung::config::load("../xrechnung/examples/config.toml", "Client Company").unwrap();
+        config.smtp = None;
+        assert!(super::template(&config).starts_with("Dear Sir or Madam,"));
+
+        config.buyer.language = Some("de".to_string());
+        assert!(super::template(&config).starts_with("Sehr geehrte Damen und Herren,"));
+    }
+
+    #[test]
+    fn test_html() {
+        assert_eq!(
+            super::html("Hello,\n\nthe invoice <1> & more.\n\nRegards\nMüller & Söhne\n"),
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n</head>\n<body>\n\
+             <p>Hello,</p>\n\
+             <p>the invoice &lt;1&gt; &amp; more.</p>\n\
+             <p>Regards<br>\nMüller &amp; Söhne</p>\n\
+             </body>\n</html>\n"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Categories of the errors of the commands, which determine the exit code of the application, and the output of the
+//! errors as text or as JSON objects (`--error-format`), so that scripts can react to the kind of error.
+
+use std::error::Error;
+use std::fmt;
+use std::process::ExitCode;
+
+use xrechnung::json::Value;
+
+/// The description of the exit codes for the help of the application.
+pub const EXIT_CODES: &str = "Exit codes:
+  0  Success
+  1  Other errors
+  2  Invalid command line arguments
+  3  Invalid input (e.g., invoice lines or invoice files)
+  4  Invalid or missing configuration
+  5  Invoice violates the business rules or was rejected by the validator
+  6  Reading or writing files failed";
+
+/// The kind of an error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Category {
+    Other,
+    Usage,
+    Input,
+    Config,
+    Validation,
+    Io,
+}
+
+impl Category {
+    /// Returns the exit code of the application for errors of this category.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Category::Other => 1,
+            Category::Usage => 2,
+            Category::Input => 3,
+            Category::Config => 4,
+            Category::Validation => 5,
+            Category::Io => 6,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Category::Other => "other",
+            Category::Usage => "usage",
+            Category::Input => "input",
+            Category::Config => "config",
+            Category::Validation => "validation",
+            Category::Io => "io",
+        }
+    }
+}
+
+/// How errors are written to the standard error output.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// Error messages for humans.
+    Text,
+
+    /// One JSON object per error with its category, exit code and message.
+    Json,
+}
+
+/// An error together with its category.
+#[derive(Debug)]
+struct Categorized {
+    category: Category,
+    error: Box<dyn Error>,
+}
+
+impl fmt::Display for Categorized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl Error for Categorized {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Assigns the given category to the error, unless it already has one.
+fn categorize(category: Category, error: Box<dyn Error>) -> Box<dyn Error> {
+    if error.is::<Categorized>() {
+        return error;
+    }
+    Box::new(Categorized { category, error })
+}
+
+/// Marks the error as an error of the input, e.g., of the invoice lines.
+pub fn input(error: impl Into<Box<dyn Error>>) -> Box<dyn Error> {
+    categorize(Category::Input, error.into())
+}
+
+/// Marks the error as an error of the configuration.
+pub fn config(error: impl Into<Box<dyn Error>>) -> Box<dyn Error> {
+    categorize(Category::Config, error.into())
+}
+
+/// Marks the error as a failed validation of an invoice.
+pub fn validation(error: impl Into<Box<dyn Error>>) -> Box<dyn Error> {
+    categorize(Category::Validation, error.into())
+}
+
+/// Marks the error as a failed reading or writing of a file.
+pub fn io(error: impl Into<Box<dyn Error>>) -> Box<dyn Error> {
+    categorize(Category::Io, error.into())
+}
+
+/// Returns the category of the given error. Errors of the file system and errors of the command line arguments do not
+/// need to be marked explicitly.
+pub fn category(error: &(dyn Error + 'static)) -> Category {
+    let mut current = Some(error);
+    while let Some(error) = current {
+        if let Some(categorized) = error.downcast_ref::<Categorized>() {
+            return categorized.category;
+        }
+        if error.is::<std::io::Error>() {
+            return Category::Io;
+        }
+        if error.is::<clap::Error>() {
+            return Category::Usage;
+        }
+        current = error.source();
+    }
+    Category::Other
+}
+
+/// Returns the error format given with the command line arguments, e.g., for reporting errors of the arguments
+/// themselves.
+pub fn format_of_args(args: &[String]) -> Format {
+    let json = args.iter().any(|arg| arg == "--error-format=json")
+        || args
+            .windows(2)
+            .any(|pair| pair[0] == "--error-format" && pair[1] == "json");
+    if json { Format::Json } else { Format::Text }
+}
+
+/// Returns the error as written in the given format.
+fn render(error: &(dyn Error + 'static), format: Format) -> String {
+    let category = category(error);
+    let message = error.to_string();
+    match format {
+        Format::Text if category == Category::Usage => message.trim_end().to_string(),
+        Format::Text => format!(
+            "{}: {}",
+            crate::i18n::translate("Error"),
+            crate::i18n::translate(&message)
+        ),
+        Format::Json => Value::Object(vec![
+            (
+                "category".to_string(),
+                Value::String(category.name().to_string()),
+            ),
+            (
+                "exit_code".to_string(),
+                Value::Number(category.exit_code().to_string()),
+            ),
+            (
+                "message".to_string(),
+                Value::String(message.trim_end().trim_start_matches("error: ").to_string()),
+            ),
+        ])
+        .to_string(),
+    }
+}
+
+/// Writes the error in the given format to the standard error output and returns the exit code for it.
+pub fn report(error: &(dyn Error + 'static), format: Format) -> ExitCode {
+    eprintln!("{}", render(error, format));
+    ExitCode::from(category(error).exit_code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Category, Format, category, render};
+
+    #[test]
+    fn test_category() {
+        let error = super::config("The buyer 'x' is not configured.");
+        assert_eq!(category(error.as_ref()), Category::Config);
+
+        // the first category is kept
+        let error = super::validation(super::input("Invalid line."));
+        assert_eq!(category(error.as_ref()), Category::Input);
+
+        let error: Box<dyn std::error::Error> =
+            std::io::Error::new(std::io::ErrorKind::NotFound, "missing").into();
+        assert_eq!(category(error.as_ref()), Category::Io);
+
+        let error: Box<dyn std::error::Error> = "Something went wrong.".into();
+        assert_eq!(category(error.as_ref()), Category::Other);
+    }
+
+    #[test]
+    fn test_render() {
+        let error = super::validation("Found 1 violation(s).\nDetails follow.");
+        assert_eq!(
+            render(error.as_ref(), Format::Text),
+            "Error: Found 1 violation(s).\nDetails follow."
+        );
+        assert_eq!(
+            render(error.as_ref(), Format::Json),
+            r#"{"category":"validation","exit_code":5,"message":"Found 1 violation(s).\nDetails follow."}"#
+        );
+    }
+
+    #[test]
+    fn test_format_of_args() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            super::format_of_args(&args(&["xrechnung", "--error-format", "json"])),
+            Format::Json
+        );
+        assert_eq!(
+            super::format_of_args(&args(&["xrechnung", "--error-format=json"])),
+            Format::Json
+        );
+        assert_eq!(
+            super::format_of_args(&args(&["xrechnung", "generate"])),
+            Format::Text
+        );
+    }
+}
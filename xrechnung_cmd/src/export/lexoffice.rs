@@ -0,0 +1,67 @@
+//! Export of invoices to lexoffice (<https://www.lexoffice.de/>) via its public API. The invoice file is uploaded as
+//! voucher, from which lexoffice reads the structured data of the e-invoice for the bookkeeping.
+//!
+//! The API key is read from the environment variable `LEXOFFICE_API_KEY`.
+
+use xrechnung::json::{self, Value};
+
+/// The endpoint of the lexoffice API for uploading files.
+const FILES_URL: &str = "https://api.lexoffice.io/v1/files";
+
+/// The environment variable that contains the API key.
+const KEY_VARIABLE: &str = "LEXOFFICE_API_KEY";
+
+/// Returns the identifier of the uploaded file from the response of the API.
+fn file_id(response: &[u8]) -> Option<String> {
+    json::parse(&String::from_utf8_lossy(response))
+        .ok()?
+        .get("id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Uploads the given invoice file to lexoffice as voucher and prints the identifier of the uploaded file.
+pub fn export(file: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let key = std::env::var(KEY_VARIABLE).map_err(|_| {
+        format!("The API key for lexoffice needs to be given in the environment variable {KEY_VARIABLE}.")
+    })?;
+    if !std::path::Path::new(file).is_file() {
+        return Err(format!("The invoice '{file}' does not exist.").into());
+    }
+
+    let authorization = format!("Bearer {key}");
+    let (status, body) = crate::http::post_form(
+        FILES_URL,
+        &[("type", "voucher")],
+        &[("file", file)],
+        &[
+            ("Authorization", &authorization),
+            ("Accept", "application/json"),
+        ],
+    )?;
+    if !(200..300).contains(&status) {
+        return Err(format!(
+            "lexoffice refused the invoice '{file}' with status {status}: {}",
+            String::from_utf8_lossy(&body).trim()
+        )
+        .into());
+    }
+
+    match file_id(&body) {
+        Some(id) => println!("Exported the invoice '{file}' to lexoffice (file {id})."),
+        None => println!("Exported the invoice '{file}' to lexoffice."),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_file_id() {
+        assert_eq!(
+            super::file_id(br#"{"id":"8118c402-1234-4e3b-8b2b-2a0c5d7e4c11"}"#),
+            Some("8118c402-1234-4e3b-8b2b-2a0c5d7e4c11".to_string())
+        );
+        assert_eq!(super::file_id(b""), None);
+    }
+}
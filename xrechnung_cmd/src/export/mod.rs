@@ -0,0 +1,49 @@
+//! Exporters that push created invoices into accounting services, so that the bookkeeping stays in sync with the
+//! issued invoices.
+
+#[cfg(feature = "lexoffice")]
+pub mod lexoffice;
+#[cfg(feature = "sevdesk")]
+pub mod sevdesk;
+
+/// The accounting services that invoices can be exported to.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum Service {
+    /// lexoffice (<https://www.lexoffice.de/>), as voucher file for the bookkeeping.
+    Lexoffice,
+
+    /// sevDesk (<https://sevdesk.de/>), as draft invoice for the contact of the buyer.
+    Sevdesk,
+}
+
+/// Exports the given invoice file to the given service, with the settings of the given configuration file.
+pub fn run(
+    service: Service,
+    config_file: &str,
+    file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match service {
+        Service::Lexoffice => {
+            #[cfg(feature = "lexoffice")]
+            {
+                lexoffice::export(file)
+            }
+            #[cfg(not(feature = "lexoffice"))]
+            {
+                let _ = file;
+                Err("Exporting invoices to lexoffice is not supported (feature 'lexoffice' is disabled).".into())
+            }
+        }
+        Service::Sevdesk => {
+            #[cfg(feature = "sevdesk")]
+            {
+                sevdesk::export(config_file, file)
+            }
+            #[cfg(not(feature = "sevdesk"))]
+            {
+                let _ = (config_file, file);
+                Err("Exporting invoices to sevDesk is not supported (feature 'sevdesk' is disabled).".into())
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Export of invoices to sevDesk (<https://sevdesk.de/>) via its API. The invoice is created as draft in sevDesk, with
+//! a position per invoice line, for the contact that is configured for the buyer (`sevdesk_contact`).
+//!
+//! The API token is read from the environment variable `SEVDESK_API_TOKEN`.
+
+use std::io::Write;
+
+use xrechnung::XmlElement;
+use xrechnung::config::Config;
+use xrechnung::json::{self, Value};
+
+use crate::preview::text;
+
+/// The endpoint of the sevDesk API for creating invoices with their positions.
+const SAVE_INVOICE_URL: &str = "https://my.sevdesk.de/api/v1/Invoice/Factory/saveInvoice";
+
+/// The environment variable that contains the API token.
+const TOKEN_VARIABLE: &str = "SEVDESK_API_TOKEN";
+
+/// The status of draft invoices in sevDesk.
+const DRAFT: &str = "100";
+
+/// The default unit of the invoice positions (hours).
+const DEFAULT_UNITY: &str = "9";
+
+fn string(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+fn number(value: &str) -> Value {
+    Value::Number(value.to_string())
+}
+
+/// Returns a reference to the sevDesk object of the given type with the given identifier.
+fn reference(id: &str, object_name: &str) -> Value {
+    Value::Object(vec![
+        ("id".to_string(), string(id)),
+        ("objectName".to_string(), string(object_name)),
+    ])
+}
+
+/// Converts a date of the invoice (YYYY-MM-DD) into the format of sevDesk (DD.MM.YYYY).
+fn date(value: &str) -> String {
+    match chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => date.format("%d.%m.%Y").to_string(),
+        Err(_) => value.to_string(),
+    }
+}
+
+/// Returns the request for creating the given invoice in sevDesk, for the given contact of the buyer.
+fn request(invoice: &XmlElement, config: &Config, contact: &str) -> Result<Value, String> {
+    let sevdesk = config
+        .sevdesk
+        .as_ref()
+        .ok_or("No settings for sevDesk configured (`[sevdesk]` in the configuration file).")?;
+    let tax_rate = text(
+        invoice,
+        &[
+            "cac:TaxTotal",
+            "cac:TaxSubtotal",
+            "cac:TaxCategory",
+            "cbc:Percent",
+        ],
+    );
+    let delivery_date = match text(invoice, &["cac:InvoicePeriod", "cbc:EndDate"]) {
+        "" => text(invoice, &["cbc:IssueDate"]),
+        end => end,
+    };
+
+    let positions: Vec<Value> = invoice
+        .children()
+        .iter()
+        .filter(|child| child.name() == "cac:InvoiceLine")
+        .enumerate()
+        .map(|(index, line)| {
+            Value::Object(vec![
+                ("objectName".to_string(), string("InvoicePos")),
+                ("mapAll".to_string(), Value::Bool(true)),
+                (
+                    "positionNumber".to_string(),
+                    Value::Number(index.to_string()),
+                ),
+                (
+                    "name".to_string(),
+                    string(text(line, &["cac:Item", "cbc:Name"])),
+                ),
+                (
+                    "quantity".to_string(),
+                    number(text(line, &["cbc:InvoicedQuantity"])),
+                ),
+                (
+                    "price".to_string(),
+                    number(text(line, &["cac:Price", "cbc:PriceAmount"])),
+                ),
+                (
+                    "taxRate".to_string(),
+                    number(text(
+                        line,
+                        &["cac:Item", "cac:ClassifiedTaxCategory", "cbc:Percent"],
+                    )),
+                ),
+                (
+                    "unity".to_string(),
+                    reference(sevdesk.unity.as_deref().unwrap_or(DEFAULT_UNITY), "Unity"),
+                ),
+            ])
+        })
+        .collect();
+
+    Ok(Value::Object(vec![
+        (
+            "invoice".to_string(),
+            Value::Object(vec![
+                ("objectName".to_string(), string("Invoice")),
+                ("mapAll".to_string(), Value::Bool(true)),
+                (
+                    "invoiceNumber".to_string(),
+                    string(text(invoice, &["cbc:ID"])),
+                ),
+                ("contact".to_string(), reference(contact, "Contact")),
+                (
+                    "contactPerson".to_string(),
+                    reference(&sevdesk.contact_person, "SevUser"),
+                ),
+                (
+                    "invoiceDate".to_string(),
+                    string(&date(text(invoice, &["cbc:IssueDate"]))),
+                ),
+                ("deliveryDate".to_string(), string(&date(delivery_date))),
+                (
+                    "header".to_string(),
+                    string(&format!("Invoice {}", text(invoice, &["cbc:ID"]))),
+                ),
+                ("status".to_string(), number(DRAFT)),
+                ("invoiceType".to_string(), string("RE")),
+                (
+                    "currency".to_string(),
+                    string(text(invoice, &["cbc:DocumentCurrencyCode"])),
+                ),
+                ("taxRate".to_string(), number(tax_rate)),
+                ("taxType".to_string(), string("default")),
+                ("discount".to_string(), number("0")),
+            ]),
+        ),
+        ("invoicePosSave".to_string(), Value::Array(positions)),
+        ("invoicePosDelete".to_string(), Value::Null),
+        ("takeDefaultAddress".to_string(), Value::Bool(true)),
+    ]))
+}
+
+/// Returns the identifier of the created invoice from the response of the API.
+fn invoice_id(response: &[u8]) -> Option<String> {
+    let response = json::parse(&String::from_utf8_lossy(response)).ok()?;
+    match response.get("objects")?.get("invoice")?.get("id")? {
+        Value::String(id) | Value::Number(id) => Some(id.clone()),
+        _ => None,
+    }
+}
+
+/// Creates the given invoice file as draft invoice in sevDesk and prints the identifier of the created invoice.
+pub fn export(config_file: &str, file: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let token = std::env::var(TOKEN_VARIABLE).map_err(|_| {
+        format!("The API token for sevDesk needs to be given in the environment variable {TOKEN_VARIABLE}.")
+    })?;
+    let invoice = XmlElement::parse(
+        &std::fs::read_to_string(file)
+            .map_err(|e| format!("Could not read the invoice '{file}': {e}"))?,
+    )?;
+    let buyer = text(
+        &invoice,
+        &[
+            "cac:AccountingCustomerParty",
+            "cac:Party",
+            "cac:PartyLegalEntity",
+            "cbc:RegistrationName",
+        ],
+    );
+    let config = xrechnung::config::load(config_file, buyer).map_err(crate::error::config)?;
+    let contact = config.buyer.sevdesk_contact.as_deref().ok_or_else(|| {
+        format!("No sevDesk contact configured for the buyer {buyer} (`sevdesk_contact` in the configuration file).")
+    })?;
+
+    let content = request(&invoice, &config, contact)?.to_string();
+    let (request_file, mut request) =
+        crate::http::create_temporary_file("xrechnung-sevdesk", "json")?;
+    if let Err(error) = request
+        .write_all(content.as_bytes())
+        .and_then(|_| request.flush())
+    {
+        let _ = std::fs::remove_file(&request_file);
+        return Err(error.into());
+    }
+    drop(request);
+    let response = crate::http::post_file(
+        SAVE_INVOICE_URL,
+        &request_file.to_string_lossy(),
+        "application/json",
+        &[("Authorization", &token), ("Accept", "application/json")],
+    );
+    let _ = std::fs::remove_file(&request_file);
+    let (status, body) = response?;
+    if !(200..300).contains(&status) {
+        return Err(format!(
+            "sevDesk refused the invoice '{file}' with status {status}: {}",
+            String::from_utf8_lossy(&body).trim()
+        )
+        .into());
+    }
+
+    match invoice_id(&body) {
+        Some(id) => println!("Exported the invoice '{file}' to sevDesk (invoice {id})."),
+        None => println!("Exported the invoice '{file}' to sevDesk."),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_request() {
+        let load = || {
+            xrechnung::config::load("../xrechnung/examples/config.toml", "Client Company").unwrap()
+        };
+        let config = load();
+        let bill = xrechnung::data::Bill::new(
+            "2025-001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            None,
+            &config,
+        );
+        let lines = vec![xrechnung::data::InvoiceHoursElement {
+            name: "Example Service".to_string(),
+            quantity: 7.5,
+            hourly_rate: 110.0,
+            date: Some("2025-01-02".to_string()),
+            ..Default::default()
+        }];
+        let (invoice, _) = xrechnung::create(config.supplier, config.buyer, bill, lines).unwrap();
+
+        let mut config = load();
+        assert!(super::request(&invoice, &config, "4711").is_err());
+        config.sevdesk = Some(xrechnung::config::Sevdesk {
+            contact_person: "123456".to_string(),
+            unity: None,
+        });
+        let request = super::request(&invoice, &config, "4711")
+            .unwrap()
+            .to_string();
+        assert!(request.starts_with(
+            r#"{"invoice":{"objectName":"Invoice","mapAll":true,"invoiceNumber":"2025-001","contact":{"id":"4711","objectName":"Contact"},"contactPerson":{"id":"123456","objectName":"SevUser"},"invoiceDate":"31.01.2025","deliveryDate":"31.01.2025","#
+        ));
+        assert!(
+            request.contains(r#""status":100,"invoiceType":"RE","currency":"EUR","taxRate":19"#)
+        );
+        assert!(request.contains(
+            r#""invoicePosSave":[{"objectName":"InvoicePos","mapAll":true,"positionNumber":0,"name":"Example Service","quantity":7.50,"price":110.00,"#
+        ));
+        assert!(request.ends_with(r#""unity":{"id":"9","objectName":"Unity"}}],"invoicePosDelete":null,"takeDefaultAddress":true}"#));
+    }
+
+    #[test]
+    fn test_invoice_id() {
+        assert_eq!(
+            super::invoice_id(br#"{"objects":{"invoice":{"id":"98765","objectName":"Invoice"}}}"#),
+            Some("98765".to_string())
+        );
+        assert_eq!(super::invoice_id(br#"{"objects":null}"#), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! Minimal HTTP client for the importers, remote input files and the KoSIT validator, which runs `curl` to avoid
+//! depending on a TLS stack. Emails are sent via SMTP with `curl` as well.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The number of attempts to find an unused name for a temporary file.
+const TEMPORARY_FILE_ATTEMPTS: usize = 100;
+
+/// Escapes a value for the configuration file format of curl.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Sends a GET request to the given URL with the given headers and optional basic authentication (user and password),
+/// and returns the body of the response as text.
+#[cfg_attr(not(feature = "toggl"), allow(dead_code))]
+pub fn get(
+    url: &str,
+    headers: &[(&str, &str)],
+    basic_auth: Option<(&str, &str)>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let body = request(url, headers, basic_auth)?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Sends a GET request to the given URL and returns the body of the response, following redirects. Only HTTPS URLs
+/// are accepted.
+#[cfg_attr(not(feature = "remote"), allow(dead_code))]
+pub fn download(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if !url.starts_with("https://") {
+        return Err(format!("Only HTTPS URLs can be downloaded (not '{url}').").into());
+    }
+    request(url, &[], None)
+}
+
+/// Sends a GET request with curl and returns the body of the response. The headers and credentials are passed to curl
+/// via its standard input, so that they do not show up in the process list.
+fn request(
+    url: &str,
+    headers: &[(&str, &str)],
+    basic_auth: Option<(&str, &str)>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut config = format!("url = {}\n", quote(url));
+    for (name, value) in headers {
+        config.push_str(&format!(
+            "header = {}\n",
+            quote(&format!("{name}: {value}"))
+        ));
+    }
+    if let Some((user, password)) = basic_auth {
+        config.push_str(&format!(
+            "user = {}\n",
+            quote(&format!("{user}:{password}"))
+        ));
+    }
+
+    let output = curl(
+        url,
+        &[
+            "--fail-with-body",
+            "--location",
+            "--proto",
+            "=https",
+            "--proto-redir",
+            "=https",
+        ],
+        &config,
+    )?;
+    if !output.status.success() {
+        return Err(format!(
+            "The request to '{url}' failed: {} {}",
+            String::from_utf8_lossy(&output.stderr).trim(),
+            String::from_utf8_lossy(&output.stdout).trim()
+        )
+        .into());
+    }
+
+    Ok(output.stdout)
+}
+
+/// Sends the given file in a POST request with the given content type and further headers to the given HTTP or HTTPS
+/// URL, and returns the status code and the body of the response (also for error status codes).
+#[cfg_attr(
+    not(any(feature = "kosit", feature = "peppol", feature = "sevdesk")),
+    allow(dead_code)
+)]
+pub fn post_file(
+    url: &str,
+    file_name: &str,
+    content_type: &str,
+    headers: &[(&str, &str)],
+) -> Result<(u16, Vec<u8>), Box<dyn std::error::Error>> {
+    let config = format!(
+        "header = {}\ndata-binary = {}\n",
+        quote(&format!("Content-Type: {content_type}")),
+        quote(&format!("@{file_name}"))
+    );
+    post(url, config, headers)
+}
+
+/// Sends a POST request with a multipart form of the given text fields and files (field name and file name) with
+/// further headers to the given HTTP or HTTPS URL, and returns the status code and the body of the response (also for
+/// error status codes).
+#[cfg_attr(not(feature = "lexoffice"), allow(dead_code))]
+pub fn post_form(
+    url: &str,
+    fields: &[(&str, &str)],
+    files: &[(&str, &str)],
+    headers: &[(&str, &str)],
+) -> Result<(u16, Vec<u8>), Box<dyn std::error::Error>> {
+    let mut config = String::new();
+    for (name, value) in fields {
+        config.push_str(&format!(
+            "form-string = {}\n",
+            quote(&format!("{name}={value}"))
+        ));
+    }
+    for (name, file_name) in files {
+        config.push_str(&format!(
+            "form = {}\n",
+            quote(&format!("{name}=@{file_name}"))
+        ));
+    }
+    post(url, config, headers)
+}
+
+/// Sends a POST request with the given curl configuration of its body and the given headers, and returns the status
+/// code and the body of the response.
+fn post(
+    url: &str,
+    mut config: String,
+    headers: &[(&str, &str)],
+) -> Result<(u16, Vec<u8>), Box<dyn std::error::Error>> {
+    config.push_str(&format!("url = {}\n", quote(url)));
+    for (name, value) in headers {
+        config.push_str(&format!(
+            "header = {}\n",
+            quote(&format!("{name}: {value}"))
+        ));
+    }
+    let output = curl(
+        url,
+        &["--proto", "=http,https", "--write-out", "\n%{http_code}"],
+        &config,
+    )?;
+    if !output.status.success() {
+        return Err(format!(
+            "The request to '{url}' failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    // the status code is written after the body of the response
+    let mut body = output.stdout;
+    let separator = body.iter().rposition(|&byte| byte == b'\n').unwrap_or(0);
+    let status = String::from_utf8_lossy(&body[separator..]).trim().parse()?;
+    body.truncate(separator);
+    Ok((status, body))
+}
+
+/// Sends the given email message (with all header lines) to the given recipients via the mail server with the given
+/// URL, optionally logging in with the given user and password. Plain `smtp://` connections are upgraded with STARTTLS,
+/// the message is never sent unencrypted.
+#[cfg_attr(not(feature = "smtp"), allow(dead_code))]
+pub fn send_mail(
+    url: &str,
+    login: Option<(&str, &str)>,
+    from: &str,
+    recipients: &[&str],
+    message: &[u8],
+) -> Result<(), Box<dyn std::error::Error>> {
+    // the standard input of curl passes the configuration, so the message is uploaded from a file
+    let (message_file, mut file) = create_temporary_file("xrechnung-mail", "eml")?;
+    if let Err(error) = file.write_all(message).and_then(|_| file.flush()) {
+        let _ = std::fs::remove_file(&message_file);
+        return Err(error.into());
+    }
+    drop(file);
+
+    let mut config = format!(
+        "url = {}\nmail-from = {}\nupload-file = {}\n",
+        quote(url),
+        quote(from),
+        quote(&message_file.to_string_lossy())
+    );
+    for recipient in recipients {
+        config.push_str(&format!("mail-rcpt = {}\n", quote(recipient)));
+    }
+    if let Some((user, password)) = login {
+        config.push_str(&format!(
+            "user = {}\n",
+            quote(&format!("{user}:{password}"))
+        ));
+    }
+
+    let output = curl(url, &["--ssl-reqd", "--proto", "=smtp,smtps"], &config);
+    let _ = std::fs::remove_file(&message_file);
+    let output = output?;
+    if !output.status.success() {
+        return Err(format!(
+            "Sending the email via '{url}' failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Creates a new file with a unique name with the given prefix and extension in the temporary directory, which only the
+/// current user can read. The file did not exist before, so that neither several sends of the same process share a file
+/// nor a file (or symbolic link) that was placed there by another user is written.
+pub(crate) fn create_temporary_file(
+    prefix: &str,
+    extension: &str,
+) -> Result<(PathBuf, File), Box<dyn std::error::Error>> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    for _ in 0..TEMPORARY_FILE_ATTEMPTS {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.subsec_nanos())
+            .unwrap_or_default();
+        let path = std::env::temp_dir().join(format!(
+            "{prefix}-{}-{}-{nanos}.{extension}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Err("No unused name for a temporary file was found.".into())
+}
+
+/// Runs curl with the given arguments and passes the given configuration via its standard input, so that headers and
+/// credentials do not show up in the process list.
+fn curl(
+    url: &str,
+    args: &[&str],
+    config: &str,
+) -> Result<std::process::Output, Box<dyn std::error::Error>> {
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error"])
+        .args(args)
+        .args(["--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("Could not run curl for the request to '{url}': {error}"))?;
+
+    curl.stdin
+        .take()
+        .ok_or("Could not pass the request to curl.")?
+        .write_all(config.as_bytes())?;
+
+    Ok(curl.wait_with_output()?)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_quote() {
+        assert_eq!(super::quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+    }
+
+    #[test]
+    fn test_create_temporary_file() {
+        let (first, _) = super::create_temporary_file("xrechnung-test", "eml").unwrap();
+        let (second, _) = super::create_temporary_file("xrechnung-test", "eml").unwrap();
+        assert_ne!(first, second);
+        assert!(first.exists() && second.exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+}
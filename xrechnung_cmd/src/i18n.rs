@@ -0,0 +1,503 @@
+//! Localization of the messages of the application. Prompts, summaries and error messages are shown in German with
+//! `--language de` or with a German locale (the first of `LC_ALL`, `LC_MESSAGES` and `LANG` that is set, e.g.,
+//! "de_DE.UTF-8"), and in English otherwise.
+//!
+//! The messages are written in English in the code and translated line by line with the catalog of German
+//! translations when they are shown. In the English messages of the catalog, `{}` stands for a part of the message
+//! that is kept as it is (e.g., a file name or an amount) and `{message}` for a part that is a message itself and thus
+//! translated as well. In the German translations, `{}` stands for the next part and `{0}`, `{1}`, ... for the part
+//! with the given index, for translations with a different order of the parts. A period at the end of a line is kept, if
+//! only the line without it is in the catalog.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the messages are shown in German.
+static GERMAN: AtomicBool = AtomicBool::new(false);
+
+/// The language of the messages of the application.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    /// English
+    En,
+
+    /// German
+    De,
+}
+
+/// The English messages and their German translations. Messages with more specific beginnings or endings need to come
+/// before more general ones.
+const CATALOG: &[(&str, &str)] = &[
+    // general
+    ("Error", "Fehler"),
+    ("Warning: {message}", "Warnung: {}"),
+    (
+        "Invalid value for '{}': {message}.",
+        "Ungültiger Wert für '{}': {}.",
+    ),
+    ("Invalid value: {message}", "Ungültiger Wert: {}"),
+    ("a value is required", "ein Wert ist erforderlich"),
+    (
+        "The input ended before all questions were answered.",
+        "Die Eingabe endete, bevor alle Fragen beantwortet wurden.",
+    ),
+    // configuration
+    (
+        "No configuration file given and none found in the default locations (see --help).",
+        "Keine Konfigurationsdatei angegeben und keine an den Standardorten gefunden (siehe --help).",
+    ),
+    (
+        "Could not find buyer '{}' in the configuration file (did you mean {}?).",
+        "Der Rechnungsempfänger '{}' wurde in der Konfigurationsdatei nicht gefunden (meinten Sie {}?).",
+    ),
+    (
+        "Could not find buyer '{}' in the configuration file.",
+        "Der Rechnungsempfänger '{}' wurde in der Konfigurationsdatei nicht gefunden.",
+    ),
+    (
+        "The configuration file '{}' exists already.",
+        "Die Konfigurationsdatei '{}' existiert bereits.",
+    ),
+    (
+        "Created the configuration file '{}'.",
+        "Die Konfigurationsdatei '{}' wurde erstellt.",
+    ),
+    (
+        "Added {} buyer(s) to the configuration file '{}'.",
+        "Der Konfigurationsdatei '{1}' wurden {0} Rechnungsempfänger hinzugefügt.",
+    ),
+    (
+        "The currency '{}' is no valid ISO 4217 currency code.",
+        "Die Währung '{}' ist kein gültiger Währungscode nach ISO 4217.",
+    ),
+    (
+        "The VAT percentage {} is not between 0 and 100.",
+        "Der Umsatzsteuersatz {} liegt nicht zwischen 0 und 100.",
+    ),
+    // questions of `config init`
+    ("Invoices", "Rechnungen"),
+    ("Currency", "Währung"),
+    ("VAT percentage", "Umsatzsteuersatz (Prozent)"),
+    ("Supplier (you)", "Rechnungssteller (Sie)"),
+    (
+        "VAT identification number or tax number",
+        "Umsatzsteuer-Identifikationsnummer oder Steuernummer",
+    ),
+    ("Phone number", "Telefonnummer"),
+    ("Email address", "E-Mail-Adresse"),
+    ("Bank account", "Bankverbindung"),
+    ("First buyer", "Erster Rechnungsempfänger"),
+    (
+        "Buyer reference (e.g., order number)",
+        "Käuferreferenz (z. B. Bestellnummer)",
+    ),
+    (
+        "Days until invoices are due",
+        "Tage bis zur Fälligkeit der Rechnungen",
+    ),
+    ("Street and number", "Straße und Hausnummer"),
+    ("Post code", "Postleitzahl"),
+    ("City", "Ort"),
+    ("Country code", "Ländercode"),
+    (
+        "'{}' is no ISO 3166-1 (Alpha-2) country code",
+        "'{}' ist kein Ländercode nach ISO 3166-1 (Alpha-2)",
+    ),
+    (
+        "'{}' is no ISO 4217 currency code",
+        "'{}' ist kein Währungscode nach ISO 4217",
+    ),
+    (
+        "'{}' is no percentage between 0 and 100",
+        "'{}' ist kein Prozentsatz zwischen 0 und 100",
+    ),
+    (
+        "'{}' is no number of days",
+        "'{}' ist keine Anzahl von Tagen",
+    ),
+    // questions of `interactive`
+    (
+        "Buyer (number or name)",
+        "Rechnungsempfänger (Nummer oder Name)",
+    ),
+    (
+        "Invoice lines (file, URL or time tracking service)",
+        "Rechnungspositionen (Datei, URL oder Zeiterfassungsdienst)",
+    ),
+    (
+        "Month of the invoice (YYYY-MM, empty for none)",
+        "Monat der Rechnung (JJJJ-MM, leer für keinen)",
+    ),
+    (
+        "Invoice number (empty for the next number of the scheme)",
+        "Rechnungsnummer (leer für die nächste Nummer des Nummernkreises)",
+    ),
+    ("Invoice number", "Rechnungsnummer"),
+    ("Quantity (hours)", "Menge (Stunden)"),
+    ("Hourly rate", "Stundensatz"),
+    ("Date (YYYY-MM-DD)", "Datum (JJJJ-MM-TT)"),
+    ("Command", "Befehl"),
+    ("Output file", "Ausgabedatei"),
+    (
+        "Commands: 'e <line>' edit a line, 'd <line>' delete a line, 'a' add a line, 'w' write the invoice, 'q' quit \
+         (lines are numbered from 1 in the order of the table)",
+        "Befehle: 'e <Position>' Position bearbeiten, 'd <Position>' Position löschen, 'a' Position hinzufügen, 'w' \
+         Rechnung schreiben, 'q' beenden (die Positionen sind ab 1 in der Reihenfolge der Tabelle nummeriert)",
+    ),
+    ("'{}' is no command", "'{}' ist kein Befehl"),
+    (
+        "a non-negative number is required (e.g., \"95.50\")",
+        "eine nicht-negative Zahl ist erforderlich (z. B. \"95.50\")",
+    ),
+    (
+        "a date in the format YYYY-MM-DD or nothing is required",
+        "ein Datum im Format JJJJ-MM-TT oder nichts ist erforderlich",
+    ),
+    (
+        "the invoice has {} line(s)",
+        "die Rechnung hat {} Position(en)",
+    ),
+    (
+        "No invoice was created.",
+        "Es wurde keine Rechnung erstellt.",
+    ),
+    (
+        "Created the invoice '{}'.",
+        "Die Rechnung '{}' wurde erstellt.",
+    ),
+    // summaries of invoices
+    (
+        "Invoice {} for {} (dry run, no file written)",
+        "Rechnung {} für {} (Probelauf, keine Datei geschrieben)",
+    ),
+    ("Invoice {} for {}", "Rechnung {} für {}"),
+    ("Issue date: {}", "Rechnungsdatum: {}"),
+    ("Due date: {}", "Fälligkeitsdatum: {}"),
+    ("Period: {} to {}", "Leistungszeitraum: {} bis {}"),
+    ("Invoice lines: {}", "Rechnungspositionen: {}"),
+    ("Net amount: {}", "Nettobetrag: {}"),
+    ("VAT ({}%): {}", "Umsatzsteuer ({} %): {}"),
+    ("VAT: {}", "Umsatzsteuer: {}"),
+    ("Total amount: {}", "Gesamtbetrag: {}"),
+    ("DATE", "DATUM"),
+    ("NAME", "BEZEICHNUNG"),
+    ("QUANTITY", "MENGE"),
+    ("RATE", "PREIS"),
+    ("AMOUNT", "BETRAG"),
+    // invoice lines
+    ("Found {} problem in '{}':", "{} Problem in '{}' gefunden:"),
+    (
+        "Found {} problems in '{}':",
+        "{} Probleme in '{}' gefunden:",
+    ),
+    ("line {}: {message}", "Zeile {}: {}"),
+    (
+        "Either a quantity or the start and end timestamps need to be given.",
+        "Es muss entweder eine Menge oder der Start- und Endzeitpunkt angegeben werden.",
+    ),
+    (
+        "No hourly rate given and no default hourly rate configured.",
+        "Kein Stundensatz angegeben und kein Standard-Stundensatz konfiguriert.",
+    ),
+    (
+        "Invalid date '{}' in column '{}' (expected format '{}').",
+        "Ungültiges Datum '{}' in der Spalte '{}' (erwartetes Format '{}').",
+    ),
+    (
+        "The required column '{}' is missing{}.",
+        "Die erforderliche Spalte '{}' fehlt{}.",
+    ),
+    ("Unknown column '{}'{}.", "Unbekannte Spalte '{}'{}."),
+    (
+        "Skipped {} invoice line(s) outside of the month {}.",
+        "{} Rechnungsposition(en) außerhalb des Monats {} übersprungen.",
+    ),
+    (
+        "Removed {} duplicate invoice line(s).",
+        "{} doppelte Rechnungsposition(en) entfernt.",
+    ),
+    (
+        "The invoice line {} ('{}') is identical to the invoice line {}.",
+        "Die Rechnungsposition {} ('{}') ist identisch mit der Rechnungsposition {}.",
+    ),
+    (
+        "The invoice line {} ('{}') lies outside of the billing period from {} to {}.",
+        "Die Rechnungsposition {} ('{}') liegt außerhalb des Leistungszeitraums vom {} bis {}.",
+    ),
+    // invoices
+    (
+        "The due date {} lies before the issue date {} of the invoice.",
+        "Das Fälligkeitsdatum {} liegt vor dem Rechnungsdatum {} der Rechnung.",
+    ),
+    (
+        "'{}' is no discount in the format <percent>%:<days>d (e.g., \"2%:14d\")",
+        "'{}' ist kein Skonto im Format <Prozent>%:<Tage>d (z. B. \"2%:14d\")",
+    ),
+    (
+        "the discount {} % is not between 0 and 100",
+        "das Skonto von {} % liegt nicht zwischen 0 und 100",
+    ),
+    (
+        "the discount of '{}' needs to be granted for at least one day",
+        "das Skonto von '{}' muss für mindestens einen Tag gewährt werden",
+    ),
+    (
+        "The invoice line {} ('{}') has an amount of zero.",
+        "Die Rechnungsposition {} ('{}') hat einen Betrag von null.",
+    ),
+    (
+        "The invoice line {} ('{}') has no date, unlike other lines of the invoice.",
+        "Die Rechnungsposition {} ('{}') hat im Gegensatz zu anderen Positionen der Rechnung kein Datum.",
+    ),
+    (
+        "The hourly rate {} of the invoice line {} ('{}') differs strongly from the usual rate {} of the invoice.",
+        "Der Stundensatz {} der Rechnungsposition {} ('{}') weicht stark vom üblichen Satz {} der Rechnung ab.",
+    ),
+    (
+        "Found {} warning(s) about the invoice lines (not allowed with --strict).",
+        "Es wurden {} Warnungen zu den Rechnungspositionen gefunden (mit --strict nicht erlaubt).",
+    ),
+    (
+        "The output file '{}' already exists (use --force to overwrite it).",
+        "Die Ausgabedatei '{}' existiert bereits (mit --force wird sie überschrieben).",
+    ),
+    (
+        "The invoice {} is already archived as '{}' (use --force to create it again).",
+        "Die Rechnung {} ist bereits als '{}' archiviert (mit --force wird sie erneut erstellt).",
+    ),
+    (
+        "The invoice number {} was already used on {} for {} (see the registry '{}').",
+        "Die Rechnungsnummer {} wurde bereits am {} für {} verwendet (siehe Register '{}').",
+    ),
+    (
+        "The invoice {} is not in the registry '{}'.",
+        "Die Rechnung {} ist nicht im Register '{}'.",
+    ),
+    (
+        "Found {} violation(s) of the business rules in the invoice '{}'.",
+        "In der Rechnung '{1}' wurden {0} Verstöße gegen die Geschäftsregeln gefunden.",
+    ),
+    (
+        "Found {} violation(s) of the business rules in the invoice.",
+        "In der Rechnung wurden {} Verstöße gegen die Geschäftsregeln gefunden.",
+    ),
+    (
+        "The invoice '{}' is valid.",
+        "Die Rechnung '{}' ist gültig.",
+    ),
+];
+
+/// Selects the language of the messages, which defaults to the one of the locale.
+pub fn init(language: Option<Language>) {
+    let language = language.unwrap_or_else(|| language_of_locale(|name| std::env::var(name).ok()));
+    GERMAN.store(language == Language::De, Ordering::Relaxed);
+}
+
+/// Returns the language of the locale as given by the environment variables, as read with the given function.
+fn language_of_locale(variable: impl Fn(&str) -> Option<String>) -> Language {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(variable)
+        .find(|value| !value.is_empty());
+    match locale {
+        Some(locale) if locale.starts_with("de") => Language::De,
+        _ => Language::En,
+    }
+}
+
+/// Returns the given message (which can have several lines) in the selected language.
+pub fn translate(message: &str) -> String {
+    match GERMAN.load(Ordering::Relaxed) {
+        true => to_german(message),
+        false => message.to_string(),
+    }
+}
+
+/// Returns the given message with every line translated to German, as far as the catalog has a translation.
+fn to_german(message: &str) -> String {
+    message
+        .split('\n')
+        .map(translate_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Translates a single line to German, keeping its indentation.
+fn translate_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indentation = &line[..line.len() - trimmed.len()];
+    for (english, german) in CATALOG {
+        if let Some(parts) = matches(english, trimmed) {
+            let parts: Vec<String> = parts
+                .into_iter()
+                .map(|(part, nested)| match nested {
+                    true => translate_line(part),
+                    false => part.to_string(),
+                })
+                .collect();
+            return format!("{indentation}{}", substitute(german, &parts));
+        }
+    }
+    match line.strip_suffix('.') {
+        Some(sentence)
+            if CATALOG
+                .iter()
+                .any(|(english, _)| matches(english, sentence.trim_start()).is_some()) =>
+        {
+            format!("{}.", translate_line(sentence))
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// Splits the English message of the catalog into its literal texts and returns them together with whether each
+/// placeholder between them is a message itself.
+fn split(template: &str) -> (Vec<&str>, Vec<bool>) {
+    let mut literals = Vec::new();
+    let mut nested = Vec::new();
+    let mut rest = template;
+    loop {
+        let placeholder = [("{}", false), ("{message}", true)]
+            .into_iter()
+            .filter_map(|(placeholder, is_message)| {
+                rest.find(placeholder)
+                    .map(|position| (position, placeholder.len(), is_message))
+            })
+            .min();
+        match placeholder {
+            Some((position, length, is_message)) => {
+                literals.push(&rest[..position]);
+                nested.push(is_message);
+                rest = &rest[position + length..];
+            }
+            None => {
+                literals.push(rest);
+                return (literals, nested);
+            }
+        }
+    }
+}
+
+/// Returns the parts of the line for the placeholders of the English message of the catalog (and whether they are
+/// messages themselves), if the line matches the message.
+fn matches<'a>(template: &str, line: &'a str) -> Option<Vec<(&'a str, bool)>> {
+    let (literals, nested) = split(template);
+    let mut rest = line.strip_prefix(literals[0])?;
+    let mut parts = Vec::new();
+    for (index, literal) in literals.iter().enumerate().skip(1) {
+        let part = match index == literals.len() - 1 {
+            true => std::mem::take(&mut rest).strip_suffix(literal)?,
+            false => {
+                let position = rest.find(literal)?;
+                let part = &rest[..position];
+                rest = &rest[position + literal.len()..];
+                part
+            }
+        };
+        parts.push((part, nested[index - 1]));
+    }
+    rest.is_empty().then_some(parts)
+}
+
+/// Replaces the placeholders of the German translation with the given parts.
+fn substitute(translation: &str, parts: &[String]) -> String {
+    let mut result = String::new();
+    let mut next = 0;
+    let mut rest = translation;
+    while let Some(start) = rest.find('{') {
+        let end = start + rest[start..].find('}').unwrap_or(0);
+        result.push_str(&rest[..start]);
+        let index = match rest[start + 1..end].parse() {
+            Ok(index) => index,
+            Err(_) => {
+                next += 1;
+                next - 1
+            }
+        };
+        result.push_str(parts.get(index).map(String::as_str).unwrap_or_default());
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CATALOG, Language, language_of_locale, split, to_german};
+
+    #[test]
+    fn test_to_german() {
+        assert_eq!(
+            to_german("Invoice 2025-0001 for Client Company\nPeriod: 2025-01-01 to 2025-01-31\n"),
+            "Rechnung 2025-0001 für Client Company\nLeistungszeitraum: 2025-01-01 bis 2025-01-31\n"
+        );
+        assert_eq!(
+            to_german(
+                "Found 1 problem in 'lines.csv':\n  line 2: No hourly rate given and no default hourly rate configured."
+            ),
+            "1 Problem in 'lines.csv' gefunden:\n  Zeile 2: Kein Stundensatz angegeben und kein Standard-Stundensatz konfiguriert."
+        );
+        assert_eq!(
+            to_german("Found 2 violation(s) of the business rules in the invoice 'invoice.xml'."),
+            "In der Rechnung 'invoice.xml' wurden 2 Verstöße gegen die Geschäftsregeln gefunden."
+        );
+        assert_eq!(
+            to_german(
+                "Could not find buyer 'Acme' in the configuration file (did you mean 'acme'?)."
+            ),
+            "Der Rechnungsempfänger 'Acme' wurde in der Konfigurationsdatei nicht gefunden (meinten Sie 'acme'?)."
+        );
+
+        // nested messages are translated, other parts are kept
+        assert_eq!(
+            to_german("Invalid value: 'x' is no command."),
+            "Ungültiger Wert: 'x' ist kein Befehl."
+        );
+        assert_eq!(
+            to_german("Invalid value: Could not find buyer 'x' in the configuration file."),
+            "Ungültiger Wert: Der Rechnungsempfänger 'x' wurde in der Konfigurationsdatei nicht gefunden."
+        );
+        assert_eq!(to_german("Invoice City for City"), "Rechnung City für City");
+        assert_eq!(to_german("Unknown message."), "Unknown message.");
+    }
+
+    #[test]
+    fn test_catalog() {
+        // every translation only refers to parts of its message
+        for (english, german) in CATALOG {
+            let parts = split(english).1.len();
+            let mut next = 0;
+            for placeholder in german.split('{').skip(1) {
+                let index = match &placeholder[..placeholder.find('}').unwrap()] {
+                    "" => {
+                        next += 1;
+                        next - 1
+                    }
+                    index => index.parse().unwrap(),
+                };
+                assert!(index < parts, "{english}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_language_of_locale() {
+        let locale = |variables: &'static [(&str, &str)]| {
+            language_of_locale(|name| {
+                variables
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert_eq!(locale(&[("LANG", "de_DE.UTF-8")]), Language::De);
+        assert_eq!(
+            locale(&[("LC_ALL", "en_US.UTF-8"), ("LANG", "de_DE.UTF-8")]),
+            Language::En
+        );
+        assert_eq!(
+            locale(&[("LC_ALL", ""), ("LC_MESSAGES", "de_AT")]),
+            Language::De
+        );
+        assert_eq!(locale(&[]), Language::En);
+    }
+}
@@ -0,0 +1,176 @@
+//! Import of the detailed reports of Clockify (<https://clockify.me/>), as exported in CSV format.
+//!
+//! Each time entry of the report becomes an invoice line named after its task or, if it has none, after its
+//! description or project. The names can be mapped to rates and display names with the rate table of the buyer.
+
+use crate::input::Table;
+
+/// The column of the report with the duration of a time entry in hours.
+const DURATION_COLUMN: &str = "Duration (decimal)";
+
+/// The prefix of the column of the report with the billable rate, which is followed by the currency.
+const RATE_COLUMN_PREFIX: &str = "Billable Rate";
+
+/// Returns whether the column of the report with the given name contains numbers.
+pub fn is_number_column(header: &str) -> bool {
+    header == DURATION_COLUMN || header.starts_with(RATE_COLUMN_PREFIX)
+}
+
+/// Converts a detailed report of Clockify into a table with the columns date, name, quantity, hourly_rate, project and
+/// description. Time entries that are not billable are skipped.
+pub fn convert(table: Table) -> Result<Table, String> {
+    let column = |name: &str| {
+        table
+            .headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name))
+    };
+    let date = column("Start Date").ok_or("The Clockify report has no column 'Start Date'.")?;
+    let duration = column(DURATION_COLUMN).ok_or(format!(
+        "The Clockify report has no column '{DURATION_COLUMN}'."
+    ))?;
+    let rate = table
+        .headers
+        .iter()
+        .position(|header| header.starts_with(RATE_COLUMN_PREFIX));
+    let (project, task, description, billable) = (
+        column("Project"),
+        column("Task"),
+        column("Description"),
+        column("Billable"),
+    );
+
+    let mut rows = Vec::new();
+    for (line_number, values) in table.rows {
+        let value = |index: Option<usize>| {
+            index
+                .and_then(|index| values.get(index))
+                .map_or("", |value| value.trim())
+        };
+
+        if value(billable).eq_ignore_ascii_case("no") {
+            continue;
+        }
+
+        // the task is the most specific name, with the description as item description then
+        let (name, details) = match (value(task), value(description), value(project)) {
+            ("", "", project) => (project, ""),
+            ("", description, _) => (description, ""),
+            (task, description, _) => (task, description),
+        };
+
+        let hourly_rate = match value(rate) {
+            rate if rate.parse::<f32>().is_ok_and(|rate| rate > 0.0) => rate,
+            _ => "", // use the rate table or the default rate instead
+        };
+
+        rows.push((
+            line_number,
+            [
+                value(Some(date)),
+                name,
+                value(Some(duration)),
+                hourly_rate,
+                value(project),
+                details,
+            ]
+            .map(str::to_string)
+            .to_vec(),
+        ));
+    }
+
+    Ok(Table {
+        headers: [
+            "date",
+            "name",
+            "quantity",
+            "hourly_rate",
+            "project",
+            "description",
+        ]
+        .map(str::to_string)
+        .to_vec(),
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::Table;
+
+    #[test]
+    fn test_convert() {
+        let row = |values: [&str; 7]| values.map(str::to_string).to_vec();
+        let table = Table {
+            headers: row([
+                "Project",
+                "Description",
+                "Task",
+                "Billable",
+                "Start Date",
+                "Duration (decimal)",
+                "Billable Rate (EUR)",
+            ]),
+            rows: vec![
+                (
+                    2,
+                    row([
+                        "Website",
+                        "Fix layout",
+                        "development",
+                        "Yes",
+                        "01/02/2025",
+                        "1.50",
+                        "0.00",
+                    ]),
+                ),
+                (
+                    3,
+                    row([
+                        "Website",
+                        "Team lunch",
+                        "",
+                        "No",
+                        "01/02/2025",
+                        "1.00",
+                        "0.00",
+                    ]),
+                ),
+                (
+                    4,
+                    row(["Website", "", "", "Yes", "01/03/2025", "2.00", "120.00"]),
+                ),
+            ],
+        };
+
+        let table = super::convert(table).unwrap();
+        assert_eq!(
+            table.headers,
+            vec![
+                "date",
+                "name",
+                "quantity",
+                "hourly_rate",
+                "project",
+                "description"
+            ]
+        );
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].0, 2);
+        assert_eq!(
+            table.rows[0].1,
+            vec![
+                "01/02/2025",
+                "development",
+                "1.50",
+                "",
+                "Website",
+                "Fix layout"
+            ]
+        );
+        assert_eq!(
+            table.rows[1].1,
+            vec!["01/03/2025", "Website", "2.00", "120.00", "Website", ""]
+        );
+    }
+}
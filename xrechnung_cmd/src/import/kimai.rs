@@ -0,0 +1,194 @@
+//! Import of the timesheet exports of the self-hosted time tracker Kimai (<https://www.kimai.org/>), in CSV or Excel
+//! format.
+//!
+//! Only the time entries of the customer with the name of the buyer are imported. Each time entry becomes an invoice
+//! line named after its activity or, if it has none, after its description or project. The names can be mapped to
+//! rates and display names with the rate table of the buyer.
+
+use crate::input::Table;
+
+/// The possible names of the columns with the hourly rates, depending on the version of Kimai.
+const RATE_COLUMNS: [&str; 2] = ["Hourly rate", "Hourly price"];
+
+/// Returns whether the column of the export with the given name contains numbers.
+pub fn is_number_column(header: &str) -> bool {
+    header == "Duration" || RATE_COLUMNS.contains(&header)
+}
+
+/// Converts a timesheet export of Kimai into a table with the columns date, name, quantity, hourly_rate, project and
+/// description. Time entries of other customers than the buyer and time entries that are not billable are skipped.
+pub fn convert(table: Table, buyer: &str) -> Result<Table, String> {
+    let column = |name: &str| {
+        table
+            .headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name))
+    };
+    let date = column("Date").ok_or("The Kimai export has no column 'Date'.")?;
+    let duration = column("Duration").ok_or("The Kimai export has no column 'Duration'.")?;
+    let rate = RATE_COLUMNS.iter().find_map(|name| column(name));
+    let (customer, project, activity, description, billable) = (
+        column("Customer"),
+        column("Project"),
+        column("Activity"),
+        column("Description"),
+        column("Billable"),
+    );
+
+    let mut customers: Vec<&str> = Vec::new();
+    let mut rows = Vec::new();
+    for (line_number, values) in &table.rows {
+        let value = |index: Option<usize>| {
+            index
+                .and_then(|index| values.get(index))
+                .map_or("", |value| value.trim())
+        };
+
+        let entry_customer = value(customer);
+        if customer.is_some() && !entry_customer.eq_ignore_ascii_case(buyer) {
+            if !customers.contains(&entry_customer) {
+                customers.push(entry_customer);
+            }
+            continue;
+        }
+
+        if ["no", "0", "false"].contains(&value(billable).to_lowercase().as_str()) {
+            continue;
+        }
+
+        // the activity is the most specific name, with the description as item description then
+        let (name, details) = match (value(activity), value(description), value(project)) {
+            ("", "", project) => (project, ""),
+            ("", description, _) => (description, ""),
+            (activity, description, _) => (activity, description),
+        };
+
+        let hourly_rate = match value(rate) {
+            rate if rate.parse::<f32>().is_ok_and(|rate| rate > 0.0) => rate,
+            _ => "", // use the rate table or the default rate instead
+        };
+
+        rows.push((
+            *line_number,
+            [
+                value(Some(date)),
+                name,
+                value(Some(duration)),
+                hourly_rate,
+                value(project),
+                details,
+            ]
+            .map(str::to_string)
+            .to_vec(),
+        ));
+    }
+
+    if rows.is_empty() && !customers.is_empty() {
+        return Err(format!(
+            "The Kimai export contains no time entries of the customer '{buyer}' (customers: {}).",
+            customers.join(", ")
+        ));
+    }
+
+    Ok(Table {
+        headers: [
+            "date",
+            "name",
+            "quantity",
+            "hourly_rate",
+            "project",
+            "description",
+        ]
+        .map(str::to_string)
+        .to_vec(),
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::Table;
+
+    fn table() -> Table {
+        let row = |values: [&str; 7]| values.map(str::to_string).to_vec();
+        Table {
+            headers: row([
+                "Date",
+                "Duration",
+                "Hourly rate",
+                "Customer",
+                "Project",
+                "Activity",
+                "Description",
+            ]),
+            rows: vec![
+                (
+                    2,
+                    row([
+                        "2025-01-02",
+                        "1:30",
+                        "95",
+                        "Client Company",
+                        "Website",
+                        "development",
+                        "Fix layout",
+                    ]),
+                ),
+                (
+                    3,
+                    row([
+                        "2025-01-02",
+                        "2:00",
+                        "80",
+                        "Other Company",
+                        "Shop",
+                        "support",
+                        "",
+                    ]),
+                ),
+                (
+                    4,
+                    row([
+                        "2025-01-03",
+                        "0.75",
+                        "0",
+                        "client company",
+                        "Website",
+                        "",
+                        "",
+                    ]),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let table = super::convert(table(), "Client Company").unwrap();
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(
+            table.rows[0].1,
+            vec![
+                "2025-01-02",
+                "development",
+                "1:30",
+                "95",
+                "Website",
+                "Fix layout"
+            ]
+        );
+        assert_eq!(table.rows[1].0, 4);
+        assert_eq!(
+            table.rows[1].1,
+            vec!["2025-01-03", "Website", "0.75", "", "Website", ""]
+        );
+    }
+
+    #[test]
+    fn test_error_on_other_customers() {
+        assert_eq!(
+            super::convert(table(), "Another Client").err().unwrap(),
+            "The Kimai export contains no time entries of the customer 'Another Client' (customers: Client Company, Other Company, client company)."
+        );
+    }
+}
@@ -0,0 +1,7 @@
+//! Importers that read the invoice lines from time tracking services and their export formats.
+
+pub mod clockify;
+pub mod kimai;
+pub mod timeclock;
+#[cfg(feature = "toggl")]
+pub mod toggl;
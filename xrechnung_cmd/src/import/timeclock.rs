@@ -0,0 +1,184 @@
+//! Import of timeclock files, as used by plain-text accounting tools like hledger, ledger or beancount.
+//!
+//! The files consist of clock-in entries like `i 2025/01/02 09:00:00 Client Company:development  description` and
+//! clock-out entries like `o 2025/01/02 12:30:00`. The first segment of the account of a clock-in entry is the buyer
+//! and the remaining segments are the activity, which becomes the name of the invoice line (or the description, if the
+//! account has no activity). Only the entries of the buyer of the invoice are imported.
+
+use chrono::NaiveDateTime;
+
+use crate::input::Table;
+
+/// The formats of the date and time of timeclock entries.
+const TIMESTAMP_FORMATS: [&str; 4] = [
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Parses the timestamp at the start of the given text, which is followed by the rest of the entry.
+fn parse_timestamp(text: &str) -> Result<(NaiveDateTime, &str), String> {
+    let mut parts = text.trim_start().splitn(3, ' ');
+    let date = parts.next().unwrap_or_default();
+    let time = parts.next().unwrap_or_default();
+    let rest = parts.next().unwrap_or_default();
+
+    TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&format!("{date} {time}"), format).ok())
+        .map(|timestamp| (timestamp, rest))
+        .ok_or(format!("Invalid date and time '{date} {time}'."))
+}
+
+/// Reads the clock-in and clock-out entries of the buyer from the timeclock file with the given name into a table with
+/// the columns date, name, quantity and description. The row numbers are the line numbers of the clock-in entries.
+pub fn read_table(
+    file_name: &str,
+    buyer: &str,
+    date_format: &str,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(file_name)?;
+    convert(&content, buyer, date_format).map_err(|error| format!("{file_name}, {error}").into())
+}
+
+/// Converts the content of a timeclock file, see [`read_table`].
+fn convert(content: &str, buyer: &str, date_format: &str) -> Result<Table, String> {
+    let mut rows = Vec::new();
+    let mut clock_in: Option<(u64, NaiveDateTime, String, String)> = None;
+    let mut buyers: Vec<String> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index as u64 + 1;
+        let error = |message: String| format!("line {line_number}: {message}");
+
+        let Some((code, entry)) = line.split_at_checked(1) else {
+            continue;
+        };
+        match code {
+            "i" => {
+                if clock_in.is_some() {
+                    return Err(error("Clocked in again without clocking out first.".into()));
+                }
+
+                let (start, rest) = parse_timestamp(entry).map_err(error)?;
+                let (account, description) = match rest.split_once("  ") {
+                    Some((account, description)) => (account.trim(), description.trim()),
+                    None => (rest.trim(), ""),
+                };
+                clock_in = Some((
+                    line_number,
+                    start,
+                    account.to_string(),
+                    description.to_string(),
+                ));
+            }
+            "o" | "O" => {
+                let (line_number, start, account, description) = clock_in
+                    .take()
+                    .ok_or(error("Clocked out without clocking in first.".into()))?;
+                let (end, _) = parse_timestamp(entry).map_err(error)?;
+                if end < start {
+                    return Err(error("Clocked out before clocking in.".into()));
+                }
+
+                let (entry_buyer, activity) = account.split_once(':').unwrap_or((&account, ""));
+                if !entry_buyer.eq_ignore_ascii_case(buyer) {
+                    if !buyers.iter().any(|name| name == entry_buyer) {
+                        buyers.push(entry_buyer.to_string());
+                    }
+                    continue;
+                }
+
+                let (name, details) = match (activity, description.as_str()) {
+                    ("", "") => (entry_buyer, ""),
+                    ("", description) => (description, ""),
+                    (activity, description) => (activity, description),
+                };
+
+                let hours = (end - start).num_seconds() as f64 / 3600.0;
+                rows.push((
+                    line_number,
+                    vec![
+                        start.format(date_format).to_string(),
+                        name.to_string(),
+                        hours.to_string(),
+                        details.to_string(),
+                    ],
+                ));
+            }
+            _ => (), // comments and other entries
+        }
+    }
+
+    if let Some((line_number, ..)) = clock_in {
+        return Err(format!(
+            "line {line_number}: Clocked in without clocking out."
+        ));
+    }
+
+    if rows.is_empty() && !buyers.is_empty() {
+        return Err(format!(
+            "line 1: The file contains no entries of the buyer '{buyer}' (accounts: {}).",
+            buyers.join(", ")
+        ));
+    }
+
+    Ok(Table {
+        headers: ["date", "name", "quantity", "description"]
+            .map(str::to_string)
+            .to_vec(),
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    const CONTENT: &str = "; work of January
+i 2025/01/02 09:00:00 Client Company:development  Reporting module
+o 2025/01/02 12:30:00
+i 2025-01-02 13:00 Another Client:support
+o 2025-01-02 14:00
+
+i 2025/01/03 09:15 client company  Planning meeting
+o 2025/01/03 10:00
+";
+
+    #[test]
+    fn test_convert() {
+        let table = super::convert(CONTENT, "Client Company", "%Y-%m-%d").unwrap();
+        assert_eq!(
+            table.headers,
+            vec!["date", "name", "quantity", "description"]
+        );
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].0, 2);
+        assert_eq!(
+            table.rows[0].1,
+            vec!["2025-01-02", "development", "3.5", "Reporting module"]
+        );
+        assert_eq!(table.rows[1].0, 7);
+        assert_eq!(
+            table.rows[1].1,
+            vec!["2025-01-03", "Planning meeting", "0.75", ""]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            super::convert("o 2025/01/02 12:30", "Client Company", "%Y-%m-%d").unwrap_err(),
+            "line 1: Clocked out without clocking in first."
+        );
+        assert_eq!(
+            super::convert(
+                "i 2025/01/02 12:30 Client Company",
+                "Client Company",
+                "%Y-%m-%d"
+            )
+            .unwrap_err(),
+            "line 1: Clocked in without clocking out."
+        );
+        assert!(super::convert(CONTENT, "Unknown", "%Y-%m-%d").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Import of time entries from Toggl Track (<https://toggl.com/track/>) via its API.
+//!
+//! The API token is read from the environment variable `TOGGL_API_TOKEN`.
+
+use chrono::{DateTime, Duration, Local};
+use std::collections::HashMap;
+
+use xrechnung::data::Period;
+
+use crate::input::Table;
+use xrechnung::json::{self, Value};
+
+/// The base URL of the Toggl Track API.
+const API_URL: &str = "https://api.track.toggl.com/api/v9";
+
+/// The environment variable that contains the API token.
+const TOKEN_VARIABLE: &str = "TOGGL_API_TOKEN";
+
+/// Returns the identifier of a Toggl object, which is a number in the API responses.
+fn id(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Number(id) | Value::String(id) => Some(id.clone()),
+        _ => None,
+    }
+}
+
+/// Reads the time entries of the given workspace (and optionally only of the given project) within the given period
+/// from the Toggl API, with one row per time entry.
+pub fn read_table(
+    workspace: &str,
+    project: Option<&str>,
+    period: &Period,
+    date_format: &str,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    let token = std::env::var(TOKEN_VARIABLE).map_err(|_| {
+        format!("The API token for Toggl Track needs to be given in the environment variable {TOKEN_VARIABLE}.")
+    })?;
+    let auth = Some((token.as_str(), "api_token"));
+
+    let projects = json::parse(&crate::http::get(
+        &format!("{API_URL}/workspaces/{workspace}/projects"),
+        &[],
+        auth,
+    )?)?;
+
+    // the end date of the API is exclusive, while the end of the period is inclusive
+    let entries = json::parse(&crate::http::get(
+        &format!(
+            "{API_URL}/me/time_entries?start_date={}&end_date={}",
+            period.start,
+            period.end + Duration::days(1)
+        ),
+        &[],
+        auth,
+    )?)?;
+
+    Ok(convert(
+        &entries,
+        &projects,
+        workspace,
+        project,
+        date_format,
+    )?)
+}
+
+/// Converts the time entries of the Toggl API into a table with the columns date, name, quantity and project. The name
+/// of an invoice line is the description of the time entry or, if it has none, the name of its project.
+fn convert(
+    entries: &Value,
+    projects: &Value,
+    workspace: &str,
+    project: Option<&str>,
+    date_format: &str,
+) -> Result<Table, String> {
+    let project_names: HashMap<String, String> = projects
+        .as_array()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|project| {
+            let name = project.get("name")?.as_str()?;
+            Some((id(project.get("id"))?, name.to_string()))
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    for entry in entries
+        .as_array()
+        .ok_or("Unexpected response of the Toggl API: The time entries are not an array.")?
+    {
+        let project_id = id(entry.get("project_id"));
+        if id(entry.get("workspace_id")).as_deref() != Some(workspace)
+            || project.is_some_and(|project| project_id.as_deref() != Some(project))
+        {
+            continue;
+        }
+
+        // running time entries have a negative duration
+        let seconds = entry
+            .get("duration")
+            .and_then(Value::as_f64)
+            .unwrap_or(-1.0);
+        if seconds < 0.0 {
+            continue;
+        }
+
+        let start = entry
+            .get("start")
+            .and_then(Value::as_str)
+            .ok_or("Unexpected response of the Toggl API: A time entry has no start.")?;
+        let start = DateTime::parse_from_rfc3339(start)
+            .map_err(|error| format!("Invalid start '{start}' of a Toggl time entry: {error}"))?
+            .with_timezone(&Local);
+
+        let project_name = project_id.and_then(|id| project_names.get(&id).cloned());
+        let name = match entry.get("description").and_then(Value::as_str) {
+            Some(description) if !description.trim().is_empty() => description.to_string(),
+            _ => project_name.clone().ok_or(format!(
+                "The Toggl time entry starting at {start} has neither a description nor a project."
+            ))?,
+        };
+
+        lines.push((
+            start,
+            vec![
+                start.format(date_format).to_string(),
+                name,
+                (seconds / 3600.0).to_string(),
+                project_name.unwrap_or_default(),
+            ],
+        ));
+    }
+
+    // the API returns the latest time entries first
+    lines.sort_by_key(|(start, _)| *start);
+
+    Ok(Table {
+        headers: ["date", "name", "quantity", "project"]
+            .map(str::to_string)
+            .to_vec(),
+        rows: lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, (_, values))| (index as u64 + 1, values))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use xrechnung::json;
+
+    #[test]
+    fn test_convert() {
+        let projects =
+            json::parse(r#"[{"id": 11, "name": "Website"}, {"id": 12, "name": "App"}]"#).unwrap();
+        let entries = json::parse(
+            r#"[
+                {"workspace_id": 1, "project_id": 12, "start": "2025-01-03T09:00:00+00:00", "duration": 5400, "description": "Release"},
+                {"workspace_id": 1, "project_id": 11, "start": "2025-01-02T09:00:00+00:00", "duration": 7200, "description": ""},
+                {"workspace_id": 1, "project_id": 11, "start": "2025-01-04T09:00:00+00:00", "duration": -1735700000, "description": "Running"},
+                {"workspace_id": 2, "project_id": 13, "start": "2025-01-02T10:00:00+00:00", "duration": 3600, "description": "Other workspace"}
+            ]"#,
+        )
+        .unwrap();
+
+        let table = super::convert(&entries, &projects, "1", None, "%Y-%m-%d").unwrap();
+        assert_eq!(table.headers, vec!["date", "name", "quantity", "project"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].1[1..], ["Website", "2", "Website"]);
+        assert_eq!(table.rows[1].1[1..], ["Release", "1.5", "App"]);
+
+        let table = super::convert(&entries, &projects, "1", Some("12"), "%Y-%m-%d").unwrap();
+        assert_eq!(table.rows.len(), 1);
+        assert_eq!(table.rows[0].1[1], "Release");
+    }
+}
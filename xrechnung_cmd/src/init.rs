@@ -0,0 +1,500 @@
+//! Interactive creation of a configuration file (`config init`), which asks for the data of the supplier, the bank
+//! account and a first buyer, and of additional buyer sections in an existing configuration file
+//! (`config add-buyer`).
+
+use std::io::{BufRead, Write};
+
+use xrechnung::identifiers;
+
+/// Asks questions on the given output and reads the answers from the given input.
+pub struct Prompter<R: BufRead, W: Write> {
+    pub input: R,
+    pub output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Returns the given value after checking it, or asks the question if no value is given.
+    fn value(
+        &mut self,
+        given: &Option<String>,
+        question: &str,
+        default: Option<&str>,
+        check: fn(&str) -> Result<(), String>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        match given {
+            Some(value) => match check(value) {
+                Ok(()) => Ok(value.clone()),
+                Err(message) => {
+                    Err(format!("Invalid value for '{}': {message}.", question.trim()).into())
+                }
+            },
+            None => self.ask(question, default, check),
+        }
+    }
+
+    /// Asks the given question until the answer passes the check, and returns the answer. An empty answer selects the
+    /// default value, if there is one.
+    pub fn ask(
+        &mut self,
+        question: &str,
+        default: Option<&str>,
+        check: fn(&str) -> Result<(), String>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        loop {
+            let question = crate::i18n::translate(question);
+            match default {
+                Some(default) => write!(self.output, "{question} [{default}]: ")?,
+                None => write!(self.output, "{question}: ")?,
+            }
+            self.output.flush()?;
+
+            let mut answer = String::new();
+            if self.input.read_line(&mut answer)? == 0 {
+                return Err("The input ended before all questions were answered.".into());
+            }
+            let answer = match (answer.trim(), default) {
+                ("", Some(default)) => default.to_string(),
+                (answer, _) => answer.to_string(),
+            };
+
+            match check(&answer) {
+                Ok(()) => return Ok(answer),
+                Err(message) => self.say(&format!("Invalid value: {message}."))?,
+            }
+        }
+    }
+
+    /// Writes the given message as a line in the selected language.
+    pub fn say(&mut self, message: &str) -> std::io::Result<()> {
+        writeln!(self.output, "{}", crate::i18n::translate(message))
+    }
+}
+
+pub fn not_empty(value: &str) -> Result<(), String> {
+    match value.is_empty() {
+        true => Err("a value is required".to_string()),
+        false => Ok(()),
+    }
+}
+
+fn country_code(value: &str) -> Result<(), String> {
+    match identifiers::is_country_code(value) {
+        true => Ok(()),
+        false => Err(format!("'{value}' is no ISO 3166-1 (Alpha-2) country code")),
+    }
+}
+
+fn currency(value: &str) -> Result<(), String> {
+    match xrechnung::currency::is_valid(value) {
+        true => Ok(()),
+        false => Err(format!("'{value}' is no ISO 4217 currency code")),
+    }
+}
+
+fn percentage(value: &str) -> Result<(), String> {
+    match value.parse::<f32>() {
+        Ok(value) if (0.0..=100.0).contains(&value) => Ok(()),
+        _ => Err(format!("'{value}' is no percentage between 0 and 100")),
+    }
+}
+
+fn days(value: &str) -> Result<(), String> {
+    match value.parse::<i16>() {
+        Ok(value) if value >= 0 => Ok(()),
+        _ => Err(format!("'{value}' is no number of days")),
+    }
+}
+
+fn tax_identification(value: &str) -> Result<(), String> {
+    not_empty(value).and_then(|_| identifiers::check_vat_id(value))
+}
+
+/// The data of a buyer, which can be given as command line arguments. Missing values are asked for.
+#[derive(clap::Args, Debug, Default)]
+pub struct BuyerFields {
+    /// Name of the buyer
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// VAT identification number or tax number of the buyer
+    #[arg(long)]
+    pub tax_identification: Option<String>,
+
+    /// Email address of the buyer
+    #[arg(long)]
+    pub email: Option<String>,
+
+    /// Buyer reference (e.g., order number)
+    #[arg(long)]
+    pub reference: Option<String>,
+
+    /// Days until invoices for the buyer are due
+    #[arg(long)]
+    pub due_after_days: Option<String>,
+
+    /// Street and number of the address of the buyer
+    #[arg(long)]
+    pub address_line: Option<String>,
+
+    /// Post code of the address of the buyer
+    #[arg(long)]
+    pub post_code: Option<String>,
+
+    /// City of the address of the buyer
+    #[arg(long)]
+    pub city: Option<String>,
+
+    /// Country code of the address of the buyer
+    #[arg(long)]
+    pub country_code: Option<String>,
+}
+
+/// Returns the given value as quoted TOML string.
+fn quoted(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+/// Asks for the address of the supplier or buyer and returns it as TOML table with the given name.
+fn ask_address<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    table: &str,
+    fields: &BuyerFields,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let address_line =
+        prompter.value(&fields.address_line, "  Street and number", None, not_empty)?;
+    let post_code = prompter.value(&fields.post_code, "  Post code", None, not_empty)?;
+    let city = prompter.value(&fields.city, "  City", None, not_empty)?;
+    let country_code = prompter.value(
+        &fields.country_code,
+        "  Country code",
+        Some("DE"),
+        country_code,
+    )?;
+
+    Ok(format!(
+        "[{table}]\naddress_line = {}\ncity = {}\npost_code = {}\ncountry_code = {}\n",
+        quoted(&address_line),
+        quoted(&city),
+        quoted(&post_code),
+        quoted(&country_code)
+    ))
+}
+
+/// Asks for the data of the configuration and returns it in TOML format.
+fn ask_config<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    prompter.say("Invoices")?;
+    let currency = prompter.ask("  Currency", Some("EUR"), currency)?;
+    let vat_percent = prompter.ask("  VAT percentage", Some("19"), percentage)?;
+
+    prompter.say("Supplier (you)")?;
+    let supplier_name = prompter.ask("  Name", None, not_empty)?;
+    let supplier_tax_identification = prompter.ask(
+        "  VAT identification number or tax number",
+        None,
+        tax_identification,
+    )?;
+    let phone = prompter.ask("  Phone number", None, not_empty)?;
+    let supplier_email = prompter.ask("  Email address", None, identifiers::check_email)?;
+    let supplier_address = ask_address(prompter, "supplier.address", &BuyerFields::default())?;
+
+    prompter.say("Bank account")?;
+    let iban = prompter.ask("  IBAN", None, identifiers::check_iban)?;
+    let bic = prompter.ask("  BIC", None, identifiers::check_bic)?;
+
+    prompter.say("First buyer")?;
+    let buyer = ask_buyer(prompter, &BuyerFields::default())?;
+
+    Ok(format!(
+        "currency = {}\nvat_percent = {:.1}\n\n\
+         [supplier]\nname = {}\ntax_identification = {}\nphone = {}\nemail = {}\niban = {}\nbic = {}\n\n\
+         {supplier_address}\n\
+         {buyer}",
+        quoted(&currency),
+        vat_percent.parse::<f32>()?,
+        quoted(&supplier_name),
+        quoted(&supplier_tax_identification),
+        quoted(&phone),
+        quoted(&supplier_email),
+        quoted(&iban.replace(' ', "")),
+        quoted(&bic),
+    ))
+}
+
+/// Asks for the data of a buyer that is not given in the fields and returns it as TOML table of the `buyer` array.
+fn ask_buyer<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    fields: &BuyerFields,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let name = prompter.value(&fields.name, "  Name", None, not_empty)?;
+    let tax_identification = prompter.value(
+        &fields.tax_identification,
+        "  VAT identification number or tax number",
+        None,
+        tax_identification,
+    )?;
+    let email = prompter.value(
+        &fields.email,
+        "  Email address",
+        None,
+        identifiers::check_email,
+    )?;
+    let reference = prompter.value(
+        &fields.reference,
+        "  Buyer reference (e.g., order number)",
+        Some("N/A"),
+        not_empty,
+    )?;
+    let due_after_days = prompter.value(
+        &fields.due_after_days,
+        "  Days until invoices are due",
+        Some("14"),
+        days,
+    )?;
+    let address = ask_address(prompter, "buyer.address", fields)?;
+
+    Ok(format!(
+        "[[buyer]]\nname = {}\ntax_identification = {}\nemail = {}\nreference = {}\ndue_after_days = {due_after_days}\n\n\
+         {address}",
+        quoted(&name),
+        quoted(&tax_identification),
+        quoted(&email),
+        quoted(&reference),
+    ))
+}
+
+/// Asks for the data of the configuration on the given output, reads the answers from the given input and writes the
+/// configuration file. An existing file is not overwritten.
+pub fn run<R: BufRead, W: Write>(
+    config_file: &str,
+    input: R,
+    output: W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if std::path::Path::new(config_file).exists() {
+        return Err(format!("The configuration file '{config_file}' exists already.").into());
+    }
+
+    let mut prompter = Prompter { input, output };
+    let config = ask_config(&mut prompter)?;
+
+    if let Some(directory) = std::path::Path::new(config_file).parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    std::fs::write(config_file, config)?;
+    prompter.say(&format!("Created the configuration file '{config_file}'."))?;
+    Ok(())
+}
+
+/// Appends a buyer section per given fields to the given TOML configuration file. The values that are not given in the
+/// fields are asked for on the given output and read from the given input.
+///
+/// The existing content of the file, including its formatting and comments, is kept. The file is only changed if the
+/// configuration with the new buyers can be loaded and the new buyers cause no problems (e.g., a duplicate name).
+pub fn add_buyers<R: BufRead, W: Write>(
+    config_file: &str,
+    buyers: &[BuyerFields],
+    input: R,
+    output: W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::path::Path::new(config_file);
+    if path.extension().and_then(|extension| extension.to_str()) != Some("toml") {
+        return Err(format!(
+            "Buyers can only be added to TOML configuration files, but '{config_file}' is none."
+        )
+        .into());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read the configuration file '{config_file}': {e}"))?;
+    let problems_before = xrechnung::config::validate_config(config_file)?;
+
+    let mut prompter = Prompter { input, output };
+    let mut new_content = content;
+    for (index, fields) in buyers.iter().enumerate() {
+        if buyers.len() > 1 {
+            let name = fields.name.as_deref().unwrap_or("unnamed");
+            writeln!(
+                prompter.output,
+                "Buyer {} of {}: {name}",
+                index + 1,
+                buyers.len()
+            )?;
+        }
+        let buyer = ask_buyer(&mut prompter, fields)?;
+
+        if !new_content.is_empty() && !new_content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        new_content.push('\n');
+        new_content.push_str(&buyer);
+    }
+
+    // the extended configuration is checked in a file next to the original one, as included files are relative to it
+    let new_file = path.with_extension("new.toml");
+    let new_file_name = new_file.to_string_lossy().into_owned();
+    std::fs::write(&new_file, new_content)?;
+    let problems = match xrechnung::config::validate_config(&new_file_name) {
+        Ok(problems) => problems,
+        Err(e) => {
+            std::fs::remove_file(&new_file)?;
+            return Err(e);
+        }
+    };
+    let new_problems: Vec<String> = problems
+        .iter()
+        .filter(|problem| !problems_before.contains(problem))
+        .map(|problem| problem.to_string())
+        .collect();
+    if !new_problems.is_empty() {
+        std::fs::remove_file(&new_file)?;
+        return Err(format!(
+            "The buyers were not added to '{config_file}': {}",
+            new_problems.join("; ")
+        )
+        .into());
+    }
+
+    std::fs::rename(&new_file, path)?;
+    prompter.say(&format!(
+        "Added {} buyer(s) to the configuration file '{config_file}'.",
+        buyers.len()
+    ))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_create_config() {
+        let config_file = std::env::temp_dir().join("xrechnung_init_test/config.toml");
+        let _ = std::fs::remove_file(&config_file);
+        let config_file = config_file.to_str().unwrap();
+
+        let answers = [
+            "", // currency
+            "", // VAT percentage
+            "Hans \"Hansi\" Muster",
+            "DE123456789",
+            "+4932123456789",
+            "mail@supplier.example.com",
+            "Supplier Street 1",
+            "54321",
+            "Example City",
+            "",
+            "DE03 1203 0000 0000 2020 51", // invalid checksum
+            "DE02 1203 0000 0000 2020 51",
+            "BYLADEM1001",
+            "Client Company",
+            "DE111111111",
+            "mail@client.example.com",
+            "",
+            "30",
+            "Example Street 42",
+            "54321",
+            "Example City",
+            "XX", // invalid country
+            "DE",
+        ];
+        let mut output = Vec::new();
+        super::run(config_file, answers.join("\n").as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.contains("Invalid value: 'DE03120300000000202051' has an invalid checksum.")
+        );
+        assert!(output.contains("Invalid value: 'XX' is no ISO 3166-1 (Alpha-2) country code."));
+
+        let config = xrechnung::config::load(config_file, "Client Company").unwrap();
+        assert_eq!(config.currency, "EUR");
+        assert_eq!(config.vat_percent, 19.0);
+        assert_eq!(config.supplier.name, "Hans \"Hansi\" Muster");
+        assert_eq!(config.supplier.iban, "DE02120300000000202051");
+        assert_eq!(config.buyer.reference, "N/A");
+        assert_eq!(config.buyer.due_after_days, 30);
+        assert!(
+            xrechnung::config::validate_config(config_file)
+                .unwrap()
+                .is_empty()
+        );
+
+        // an existing configuration file is not overwritten
+        assert!(super::run(config_file, "".as_bytes(), Vec::new()).is_err());
+        std::fs::remove_file(config_file).unwrap();
+    }
+
+    #[test]
+    fn test_error_on_incomplete_input() {
+        let config_file = std::env::temp_dir().join("xrechnung_init_test/incomplete.toml");
+        let result = super::run(
+            config_file.to_str().unwrap(),
+            "EUR\n19\n".as_bytes(),
+            Vec::new(),
+        );
+        assert!(result.is_err());
+        assert!(!config_file.exists());
+    }
+
+    #[test]
+    fn test_add_buyer() {
+        let directory = std::env::temp_dir().join("xrechnung_add_buyer_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        let config_file = directory.join("config.toml");
+        std::fs::copy("../xrechnung/examples/config.toml", &config_file).unwrap();
+        let original = std::fs::read_to_string(&config_file).unwrap();
+        let config_file = config_file.to_str().unwrap();
+
+        let fields = super::BuyerFields {
+            name: Some("New Client".to_string()),
+            tax_identification: Some("DE222222222".to_string()),
+            email: Some("mail@client3.example.com".to_string()),
+            country_code: Some("AT".to_string()),
+            ..Default::default()
+        };
+        let answers = ["", "", "New Street 5", "1010", "Vienna"].join("\n");
+        super::add_buyers(
+            config_file,
+            std::slice::from_ref(&fields),
+            answers.as_bytes(),
+            Vec::new(),
+        )
+        .unwrap();
+
+        // the existing content is kept unchanged
+        let content = std::fs::read_to_string(config_file).unwrap();
+        assert!(content.starts_with(&original));
+
+        let config = xrechnung::config::load(config_file, "New Client").unwrap();
+        assert_eq!(config.buyer.reference, "N/A");
+        assert_eq!(config.buyer.due_after_days, 14);
+        assert_eq!(config.buyer.address.city, "Vienna");
+        assert_eq!(config.buyer.address.country_code, "AT");
+
+        // a buyer with an existing name is not added
+        let result = super::add_buyers(
+            config_file,
+            std::slice::from_ref(&fields),
+            answers.as_bytes(),
+            Vec::new(),
+        );
+        assert!(result.unwrap_err().to_string().contains("not unique"));
+        assert_eq!(std::fs::read_to_string(config_file).unwrap(), content);
+
+        // invalid values given as arguments are rejected
+        let fields = super::BuyerFields {
+            email: Some("no email".to_string()),
+            ..Default::default()
+        };
+        assert!(
+            super::add_buyers(
+                config_file,
+                std::slice::from_ref(&fields),
+                "Other".as_bytes(),
+                Vec::new()
+            )
+            .is_err()
+        );
+        assert_eq!(std::fs::read_to_string(config_file).unwrap(), content);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
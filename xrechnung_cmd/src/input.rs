@@ -0,0 +1,1136 @@
+//! Reading of invoice lines from the input files.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Deserialize;
+use std::fs::File;
+use std::path::Path;
+
+use xrechnung::config::Config;
+use xrechnung::data::{InvoiceHoursElement, Period, parse_hours};
+use xrechnung::lines::InvoiceLineSource;
+
+use xrechnung::json;
+
+/// All columns of the invoice lines that are known, used to suggest corrections for misspelled column names.
+const KNOWN_COLUMNS: [&str; 15] = [
+    "id",
+    "name",
+    "description",
+    "quantity",
+    "hourly_rate",
+    "date",
+    "note",
+    "start_date",
+    "end_date",
+    "order_line_id",
+    "accounting_cost",
+    "project",
+    "start",
+    "end",
+    "break",
+];
+
+/// The columns that contain numbers, which need to be converted when reading files with decimal commas.
+const NUMBER_COLUMNS: [&str; 3] = ["quantity", "hourly_rate", "break"];
+
+/// The format of dates in the input files, if not configured otherwise (ISO 8601).
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The formats in which the time of the start and end timestamps of an invoice line can follow the date.
+const TIME_FORMATS: [&str; 4] = [" %H:%M", " %H:%M:%S", "T%H:%M", "T%H:%M:%S"];
+
+/// The columns that contain dates.
+#[cfg_attr(not(any(feature = "xlsx", feature = "ods")), allow(dead_code))]
+pub const DATE_COLUMNS: [&str; 3] = ["date", "start_date", "end_date"];
+
+/// The columns that contain timestamps (date and time).
+#[cfg_attr(not(any(feature = "xlsx", feature = "ods")), allow(dead_code))]
+pub const TIMESTAMP_COLUMNS: [&str; 2] = ["start", "end"];
+
+/// The layout of the table in the input files.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Layout {
+    /// One invoice line per row.
+    #[default]
+    Lines,
+
+    /// A timesheet with one row per activity and one column per day, with the dates as column names. Each non-empty
+    /// cell of a day column becomes an invoice line for that day, with the cell as quantity.
+    Matrix,
+
+    /// A detailed report of Clockify in CSV format, with one invoice line per billable time entry.
+    Clockify,
+
+    /// A timesheet export of Kimai in CSV or Excel format, with one invoice line per billable time entry of the buyer.
+    Kimai,
+}
+
+/// Options for reading the invoice lines from the input files.
+pub struct InputOptions {
+    /// The character that separates the fields of a record, e.g., ';' for German spreadsheet exports.
+    pub delimiter: u8,
+
+    /// Whether numbers are written with a decimal comma and dots as thousands separators (e.g., "1.234,50").
+    pub decimal_comma: bool,
+
+    /// The format of the dates (e.g., "%d.%m.%Y"), as accepted by [`chrono::NaiveDate::parse_from_str`].
+    pub date_format: String,
+
+    /// The name of the sheet to read from spreadsheet files. The first sheet is read, if not given.
+    #[cfg_attr(not(any(feature = "xlsx", feature = "ods")), allow(dead_code))]
+    pub sheet: Option<String>,
+
+    /// The layout of the table in the input file.
+    pub layout: Layout,
+
+    /// The period for which time entries are imported from time tracking services.
+    #[cfg_attr(not(feature = "toggl"), allow(dead_code))]
+    pub import_period: Option<Period>,
+}
+
+impl Default for InputOptions {
+    fn default() -> Self {
+        InputOptions {
+            delimiter: b',',
+            decimal_comma: false,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            sheet: None,
+            layout: Layout::default(),
+            import_period: None,
+        }
+    }
+}
+
+/// A table of strings as read from an input file, consisting of the column names and the data rows together with
+/// their line (or row) numbers in the file.
+#[derive(Debug)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<(u64, Vec<String>)>,
+}
+
+/// Converts a number with a decimal comma and dots as thousands separators (e.g., "1.234,50") to a number with a
+/// decimal point (e.g., "1234.50").
+fn normalize_decimal_comma(value: &str) -> String {
+    value.replace('.', "").replace(',', ".")
+}
+
+/// A row of the invoice lines CSV file. In contrast to the `InvoiceHoursElement`, the quantity can be omitted, if the
+/// start and end timestamps of the work are given instead.
+#[derive(Deserialize)]
+struct InvoiceLineRecord {
+    id: Option<String>,
+    name: String,
+    description: Option<String>,
+    quantity: Option<String>,
+    hourly_rate: Option<f32>,
+    date: Option<String>,
+    note: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    order_line_id: Option<String>,
+    accounting_cost: Option<String>,
+    project: Option<String>,
+
+    /// Timestamp of the start of the work, used to calculate the quantity.
+    start: Option<String>,
+
+    /// Timestamp of the end of the work, used to calculate the quantity.
+    end: Option<String>,
+
+    /// Duration of breaks that is subtracted from the time between start and end.
+    #[serde(rename = "break")]
+    break_duration: Option<String>,
+}
+
+/// Converts a date in ISO 8601 format (with optional time) into the given date format, with the time appended if
+/// requested.
+pub fn convert_iso_date(value: &str, date_format: &str, with_time: bool) -> Option<String> {
+    let timestamp = match NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        Ok(timestamp) => timestamp,
+        Err(_) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)?,
+    };
+
+    if with_time {
+        Some(
+            timestamp
+                .format(&format!("{date_format} %H:%M:%S"))
+                .to_string(),
+        )
+    } else {
+        Some(timestamp.format(date_format).to_string())
+    }
+}
+
+/// Returns the date of the given column name of a matrix timesheet, if it is a date with the given format or in ISO 8601
+/// format.
+fn parse_date_column(column: &str, date_format: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(column.trim(), date_format)
+        .or_else(|_| NaiveDate::parse_from_str(column.trim(), DEFAULT_DATE_FORMAT))
+        .ok()
+}
+
+/// Transposes a matrix timesheet with one row per activity and one column per day into a table with one row per
+/// invoice line. The columns that are no dates are copied to each invoice line of the row, while each non-empty cell of
+/// a date column becomes an invoice line with that date and the cell as quantity.
+fn transpose_matrix(table: Table, date_format: &str) -> Result<Table, String> {
+    let dates: Vec<Option<NaiveDate>> = table
+        .headers
+        .iter()
+        .map(|header| parse_date_column(header, date_format))
+        .collect();
+    if dates.iter().all(Option::is_none) {
+        return Err("The timesheet does not contain any columns with dates as names.".into());
+    }
+    if table
+        .headers
+        .iter()
+        .any(|header| header == "date" || header == "quantity")
+    {
+        return Err(
+            "A timesheet with dates as columns must not have a 'date' or 'quantity' column.".into(),
+        );
+    }
+
+    let mut headers: Vec<String> = table
+        .headers
+        .iter()
+        .zip(&dates)
+        .filter(|(_, date)| date.is_none())
+        .map(|(header, _)| header.clone())
+        .collect();
+    headers.extend(["date".to_string(), "quantity".to_string()]);
+
+    let mut rows = Vec::new();
+    for (line_number, values) in table.rows {
+        let attributes: Vec<String> = values
+            .iter()
+            .zip(&dates)
+            .filter(|(_, date)| date.is_none())
+            .map(|(value, _)| value.clone())
+            .collect();
+
+        for (value, date) in values.iter().zip(&dates) {
+            let Some(date) = date else {
+                continue;
+            };
+            if value.trim().is_empty() || parse_hours(value) == Ok(0.0) {
+                continue; // no work on that day
+            }
+
+            let mut row = attributes.clone();
+            row.resize(headers.len() - 2, String::new());
+            row.extend([date.format(date_format).to_string(), value.clone()]);
+            rows.push((line_number, row));
+        }
+    }
+
+    Ok(Table { headers, rows })
+}
+
+/// Parses a date of the given column with the given format and returns it in ISO 8601 format.
+fn normalize_date(
+    value: Option<String>,
+    column: &str,
+    date_format: &str,
+) -> Result<Option<String>, String> {
+    match value {
+        Some(value) => match NaiveDate::parse_from_str(value.trim(), date_format) {
+            Ok(date) => Ok(Some(date.to_string())),
+            Err(_) => Err(format!(
+                "Invalid date '{value}' in column '{column}' (expected format '{date_format}')."
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Parses a timestamp of the given column, consisting of a date with the given format followed by a time.
+fn parse_timestamp(value: &str, column: &str, date_format: &str) -> Result<NaiveDateTime, String> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|time_format| {
+            NaiveDateTime::parse_from_str(value.trim(), &format!("{date_format}{time_format}")).ok()
+        })
+        .ok_or(format!(
+            "Invalid timestamp '{value}' in column '{column}' (expected format '{date_format} %H:%M')."
+        ))
+}
+
+impl InvoiceLineRecord {
+    /// Converts the record into an invoice line, calculating the quantity from the start and end timestamps if needed.
+    ///
+    /// If the record has no hourly rate, the rate is taken from the buyer's rate with the name of the record or
+    /// otherwise from the default hourly rate of the configuration. The name of the line is replaced by the display
+    /// name of the matching rate, if there is one.
+    ///
+    /// All dates are parsed with the given date format and converted to ISO 8601 format.
+    fn into_invoice_line(
+        self,
+        config: &Config,
+        date_format: &str,
+    ) -> Result<InvoiceHoursElement, String> {
+        let rate = config.buyer.rates.get(&self.name);
+
+        let hourly_rate = self
+            .hourly_rate
+            .or(rate.map(|rate| rate.hourly_rate))
+            .or(config.default_hourly_rate)
+            .ok_or("No hourly rate given and no default hourly rate configured.")?;
+
+        let name = match rate.and_then(|rate| rate.name.as_ref()) {
+            Some(name) => name.clone(),
+            None => self.name,
+        };
+
+        let mut date = normalize_date(self.date, "date", date_format)?;
+        let start_date = normalize_date(self.start_date, "start_date", date_format)?;
+        let end_date = normalize_date(self.end_date, "end_date", date_format)?;
+
+        let quantity = match (self.quantity, self.start, self.end) {
+            (Some(quantity), None, None) => parse_hours(&quantity)?,
+            (None, Some(start), Some(end)) => {
+                let start = parse_timestamp(&start, "start", date_format)?;
+                let end = parse_timestamp(&end, "end", date_format)?;
+
+                let breaks = match &self.break_duration {
+                    Some(break_duration) => parse_hours(break_duration)?,
+                    None => 0.0,
+                };
+
+                let hours = (end - start).num_minutes() as f32 / 60.0 - breaks;
+                if hours < 0.0 {
+                    return Err("The end of the work lies before its start (minus breaks).".into());
+                }
+
+                // the line is dated on the day on which the work started, if no date is given explicitly
+                date = date.or(Some(start.date().to_string()));
+                hours
+            }
+            _ => {
+                return Err(
+                    "Either a quantity or the start and end timestamps need to be given.".into(),
+                );
+            }
+        };
+
+        Ok(InvoiceHoursElement {
+            id: self.id,
+            name,
+            description: self.description,
+            quantity,
+            hourly_rate,
+            date,
+            note: self.note,
+            start_date,
+            end_date,
+            order_line_id: self.order_line_id,
+            accounting_cost: self.accounting_cost,
+            project: self.project,
+        })
+    }
+}
+
+/// Reads a table from the CSV file with the given name.
+fn read_csv_table(
+    file_name: &str,
+    options: &InputOptions,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    let file = File::open(file_name)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .comment(Some(b'#'))
+        .flexible(true)
+        .trim(csv::Trim::Headers)
+        .from_reader(file);
+    let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line_number = record.position().map_or(0, |position| position.line());
+
+        let values = record
+            .iter()
+            .enumerate()
+            .map(|(column, value)| {
+                let header = headers.get(column).map_or("", String::as_str);
+                let is_number = NUMBER_COLUMNS.contains(&header)
+                    || match options.layout {
+                        Layout::Lines => false,
+                        Layout::Matrix => parse_date_column(header, &options.date_format).is_some(),
+                        Layout::Clockify => crate::import::clockify::is_number_column(header),
+                        Layout::Kimai => crate::import::kimai::is_number_column(header),
+                    };
+                match options.decimal_comma && is_number {
+                    true => normalize_decimal_comma(value),
+                    false => value.to_string(),
+                }
+            })
+            .collect();
+
+        rows.push((line_number, values));
+    }
+
+    Ok(Table { headers, rows })
+}
+
+/// Creates a table from entries of column names and values. The columns of the table are all column names in the
+/// order of their first appearance, and the row numbers are the positions of the entries.
+fn table_from_entries(entries: Vec<Vec<(String, String)>>) -> Table {
+    let mut headers: Vec<String> = Vec::new();
+    for (key, _) in entries.iter().flatten() {
+        if !headers.contains(key) {
+            headers.push(key.clone());
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let mut values = vec![String::new(); headers.len()];
+        for (key, value) in entry {
+            if let Some(column) = headers.iter().position(|header| *header == key) {
+                values[column] = value;
+            }
+        }
+        rows.push((index as u64 + 1, values));
+    }
+
+    Table { headers, rows }
+}
+
+/// Reads a table from the JSON file with the given name, which needs to contain an array of objects with the columns
+/// as keys.
+fn read_json_table(file_name: &str) -> Result<Table, Box<dyn std::error::Error>> {
+    let document = std::fs::read_to_string(file_name)?;
+    json_table(file_name, json::parse(&document)?)
+}
+
+/// Creates a table from the given JSON array of objects with the columns as keys. The source is used for error
+/// messages.
+fn json_table(file_name: &str, lines: json::Value) -> Result<Table, Box<dyn std::error::Error>> {
+    let json::Value::Array(objects) = lines else {
+        return Err(
+            format!("{file_name}: The invoice lines need to be given as a JSON array.").into(),
+        );
+    };
+
+    let mut entries = Vec::new();
+    for (index, object) in objects.into_iter().enumerate() {
+        let json::Value::Object(members) = object else {
+            return Err(format!(
+                "{file_name}, line {}: The invoice line is not an object.",
+                index + 1
+            )
+            .into());
+        };
+
+        let mut entry = Vec::new();
+        for (key, value) in members {
+            let value = match value {
+                json::Value::Null => continue,
+                json::Value::Bool(value) => value.to_string(),
+                json::Value::Number(value) | json::Value::String(value) => value,
+                json::Value::Array(_) | json::Value::Object(_) => {
+                    return Err(format!(
+                        "{file_name}, line {}: The value of '{key}' needs to be a string or a number.",
+                        index + 1
+                    )
+                    .into());
+                }
+            };
+            entry.push((key, value));
+        }
+        entries.push(entry);
+    }
+
+    Ok(table_from_entries(entries))
+}
+
+/// Reads a table from the TOML file with the given name, which needs to contain the invoice lines as an array of
+/// tables named `line`. Other keys of the file are ignored, so that the lines can be kept in the same file as other
+/// settings. Dates of the TOML file are converted to strings with the given date format.
+fn read_toml_table(
+    file_name: &str,
+    date_format: &str,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    let document: toml::Table = toml::from_str(&std::fs::read_to_string(file_name)?)?;
+    let lines = match document.get("line") {
+        Some(toml::Value::Array(lines)) => lines,
+        _ => {
+            return Err(format!(
+                "{file_name}: The invoice lines need to be given as an array of tables '[[line]]'."
+            )
+            .into());
+        }
+    };
+
+    let mut entries = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let toml::Value::Table(line) = line else {
+            return Err(format!(
+                "{file_name}, line {}: The invoice line is not a table.",
+                index + 1
+            )
+            .into());
+        };
+
+        let mut entry = Vec::new();
+        for (key, value) in line {
+            let value = match value {
+                toml::Value::String(value) => value.clone(),
+                toml::Value::Integer(value) => value.to_string(),
+                toml::Value::Float(value) => value.to_string(),
+                toml::Value::Boolean(value) => value.to_string(),
+                toml::Value::Datetime(value) => {
+                    let value = value.to_string();
+                    convert_iso_date(&value, date_format, value.contains(['T', ' ']))
+                        .unwrap_or(value)
+                }
+                toml::Value::Array(_) | toml::Value::Table(_) => {
+                    return Err(format!(
+                        "{file_name}, line {}: The value of '{key}' needs to be a string, a number or a date.",
+                        index + 1
+                    )
+                    .into());
+                }
+            };
+            entry.push((key.clone(), value));
+        }
+        entries.push(entry);
+    }
+
+    Ok(table_from_entries(entries))
+}
+
+/// A file with invoice lines (or an import from a time tracking service) as source of the lines of an invoice, which is
+/// read like by [`read_invoice_hours`].
+pub struct LineFile<'a> {
+    /// The name of the file, an HTTPS URL or an import source like `toggl:<workspace id>`.
+    pub file_name: &'a str,
+
+    /// The configuration that gives the default values of the lines.
+    pub config: &'a Config,
+
+    /// The options for reading the file.
+    pub options: &'a InputOptions,
+}
+
+impl InvoiceLineSource for LineFile<'_> {
+    fn read_lines(self) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+        read_invoice_hours(self.file_name, self.config, self.options)
+    }
+}
+
+/// A JSON array of line objects (e.g., as received by the HTTP server) as source of the lines of an invoice, which is
+/// read like by [`read_json_lines`].
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+pub struct JsonLines<'a> {
+    /// The source of the array, used for error messages.
+    pub source: &'a str,
+
+    /// The array of line objects.
+    pub lines: json::Value,
+
+    /// The configuration that gives the default values of the lines.
+    pub config: &'a Config,
+
+    /// The options for reading the lines.
+    pub options: &'a InputOptions,
+}
+
+impl InvoiceLineSource for JsonLines<'_> {
+    fn read_lines(self) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+        read_json_lines(self.source, self.lines, self.config, self.options)
+    }
+}
+
+/// Reads the invoice lines from the file with the given name, using the given configuration for default values. The
+/// format of the file is determined by its extension: Excel spreadsheets (`.xlsx`), OpenDocument spreadsheets
+/// (`.ods`), JSON arrays of line objects (`.json`), TOML files with `[[line]]` tables (`.toml`) or CSV files (any
+/// other extension).
+///
+/// Time entries of Toggl Track are imported for the import period of the options with a file name like
+/// `toggl:<workspace id>` or `toggl:<workspace id>/<project id>`. Files are downloaded, if the file name is an HTTPS
+/// URL. The entries of the buyer are imported from timeclock files (`.timeclock`).
+pub fn read_invoice_hours(
+    file_name: &str,
+    config: &Config,
+    options: &InputOptions,
+) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+    let table = match file_name {
+        #[cfg(feature = "toggl")]
+        _ if file_name.starts_with("toggl:") => {
+            let source = &file_name["toggl:".len()..];
+            let (workspace, project) = match source.split_once('/') {
+                Some((workspace, project)) => (workspace, Some(project)),
+                None => (source, None),
+            };
+            let period = options
+                .import_period
+                .as_ref()
+                .ok_or("A period is needed for importing time entries from Toggl Track.")?;
+            crate::import::toggl::read_table(workspace, project, period, &options.date_format)?
+        }
+        #[cfg(feature = "remote")]
+        _ if file_name.starts_with("https://") => read_remote_table(file_name, options)?,
+        #[cfg(not(feature = "remote"))]
+        _ if file_name.starts_with("https://") || file_name.starts_with("http://") => {
+            return Err("Remote files are not supported (feature 'remote' is disabled).".into());
+        }
+        _ if file_name.ends_with(".timeclock") => crate::import::timeclock::read_table(
+            file_name,
+            &config.buyer.name,
+            &options.date_format,
+        )?,
+        _ => read_table(file_name, options)?,
+    };
+
+    let table = match options.layout {
+        Layout::Lines => table,
+        Layout::Matrix => transpose_matrix(table, &options.date_format)
+            .map_err(|error| format!("{file_name}: {error}"))?,
+        Layout::Clockify => crate::import::clockify::convert(table)
+            .map_err(|error| format!("{file_name}: {error}"))?,
+        Layout::Kimai => crate::import::kimai::convert(table, &config.buyer.name)
+            .map_err(|error| format!("{file_name}: {error}"))?,
+    };
+    invoice_lines(file_name, table, config, options)
+}
+
+/// Reads the invoice hours from the given JSON array of line objects (with the columns of the CSV files as keys), e.g.,
+/// as received by the HTTP server. The source is used for error messages.
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+pub fn read_json_lines(
+    source: &str,
+    lines: json::Value,
+    config: &Config,
+    options: &InputOptions,
+) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+    invoice_lines(source, json_table(source, lines)?, config, options)
+}
+
+/// Converts the rows of the given table into invoice hours, collecting the errors of all rows.
+fn invoice_lines(
+    file_name: &str,
+    table: Table,
+    config: &Config,
+    options: &InputOptions,
+) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
+    let errors = check_columns(&table.headers);
+    if !errors.is_empty() {
+        return Err(format_errors(file_name, errors).into());
+    }
+
+    crate::log::debug!(
+        "input",
+        "{file_name}: {} row(s) with the columns {}.",
+        table.rows.len(),
+        table.headers.join(", ")
+    );
+    let headers = csv::StringRecord::from(table.headers);
+    let mut invoice_hours: Vec<InvoiceHoursElement> = Vec::new();
+    let mut errors = Vec::new();
+
+    for (line_number, mut values) in table.rows {
+        // blank rows (e.g., at the end of spreadsheet exports) are skipped
+        if values.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+
+        if values.len() > headers.len() {
+            errors.push(format!(
+                "line {line_number}: The row has {} fields, but there are only {} columns.",
+                values.len(),
+                headers.len()
+            ));
+            continue;
+        }
+        values.resize(headers.len(), String::new());
+
+        let record = csv::StringRecord::from(values);
+        let line = match record.deserialize::<InvoiceLineRecord>(Some(&headers)) {
+            Ok(line) => line,
+            Err(error) => {
+                errors.push(format!(
+                    "line {line_number}{}",
+                    describe_deserialize_error(&error, &headers)
+                ));
+                continue;
+            }
+        };
+
+        match line.into_invoice_line(config, &options.date_format) {
+            Ok(line) => invoice_hours.push(line),
+            Err(error) => errors.push(format!("line {line_number}: {error}")),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(format_errors(file_name, errors).into());
+    }
+
+    Ok(invoice_hours)
+}
+
+/// Returns the file extension for the data at the given URL, taken from the path of the URL or from an `output` or
+/// `format` parameter (as used by published Google Sheets). Defaults to CSV.
+#[cfg_attr(not(feature = "remote"), allow(dead_code))]
+fn remote_extension(url: &str) -> String {
+    let url = url.split('#').next().unwrap_or_default();
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+    let from_query = query.split('&').find_map(|parameter| {
+        parameter
+            .strip_prefix("output=")
+            .or(parameter.strip_prefix("format="))
+    });
+    let from_path = path
+        .rsplit('/')
+        .next()
+        .and_then(|name| Path::new(name).extension())
+        .and_then(|extension| extension.to_str());
+
+    match from_query.or(from_path).map(str::to_lowercase) {
+        Some(extension) if ["csv", "json", "ods", "toml", "xlsx"].contains(&extension.as_str()) => {
+            extension
+        }
+        _ => "csv".to_string(),
+    }
+}
+
+/// Downloads the file at the given HTTPS URL into a temporary file and reads the table from it, with the format
+/// determined by [`remote_extension`].
+#[cfg(feature = "remote")]
+fn read_remote_table(
+    url: &str,
+    options: &InputOptions,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    use std::io::Write;
+
+    let data = crate::http::download(url)?;
+
+    let (file, mut temporary) =
+        crate::http::create_temporary_file("xrechnung", &remote_extension(url))?;
+    if let Err(error) = temporary.write_all(&data).and_then(|_| temporary.flush()) {
+        let _ = std::fs::remove_file(&file);
+        return Err(error.into());
+    }
+    drop(temporary);
+
+    let table = read_table(&file.to_string_lossy(), options);
+    std::fs::remove_file(&file)?;
+    table
+}
+
+/// Reads a table from the file with the given name, with the format determined by the extension of the file.
+fn read_table(
+    file_name: &str,
+    options: &InputOptions,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+
+    Ok(match extension.as_deref() {
+        #[cfg(feature = "xlsx")]
+        Some("xlsx") => crate::spreadsheet::xlsx::read_table(
+            file_name,
+            options.sheet.as_deref(),
+            &options.date_format,
+        )?,
+        #[cfg(feature = "ods")]
+        Some("ods") => crate::spreadsheet::ods::read_table(
+            file_name,
+            options.sheet.as_deref(),
+            &options.date_format,
+        )?,
+        #[cfg(not(feature = "xlsx"))]
+        Some("xlsx") => {
+            return Err(
+                "Excel spreadsheets are not supported (feature 'xlsx' is disabled).".into(),
+            );
+        }
+        #[cfg(not(feature = "ods"))]
+        Some("ods") => {
+            return Err(
+                "OpenDocument spreadsheets are not supported (feature 'ods' is disabled).".into(),
+            );
+        }
+        Some("json") => read_json_table(file_name)?,
+        Some("toml") => read_toml_table(file_name, &options.date_format)?,
+        _ => read_csv_table(file_name, options)?,
+    })
+}
+
+/// Checks that the required columns exist, and that there are no columns that look like misspelled known columns.
+/// Returns the problems that were found, with suggestions for the correct column names.
+fn check_columns(headers: &[String]) -> Vec<String> {
+    let has_column = |column: &str| headers.iter().any(|header| header == column);
+    let unknown: Vec<&str> = headers
+        .iter()
+        .map(String::as_str)
+        .filter(|header| !KNOWN_COLUMNS.contains(header))
+        .collect();
+
+    let mut missing = vec![];
+    if !has_column("name") {
+        missing.push("name");
+    }
+    if !(has_column("quantity") || has_column("start") && has_column("end")) {
+        missing.push("quantity");
+    }
+
+    let mut errors = Vec::new();
+    for column in &missing {
+        errors.push(format!(
+            "The required column '{column}' is missing{}.",
+            did_you_mean(column, &unknown)
+        ));
+    }
+
+    for header in unknown {
+        let candidates: Vec<&str> = KNOWN_COLUMNS
+            .iter()
+            .copied()
+            .filter(|column| !has_column(column) && !missing.contains(column))
+            .collect();
+        let suggestion = did_you_mean(header, &candidates);
+        if !suggestion.is_empty() {
+            errors.push(format!("Unknown column '{header}'{suggestion}."));
+        }
+    }
+
+    errors
+}
+
+/// Returns a suggestion like " (did you mean 'quantity'?)" with the candidate that is most similar to the given name,
+/// or an empty string if no candidate is similar enough.
+pub fn did_you_mean(name: &str, candidates: &[&str]) -> String {
+    candidates
+        .iter()
+        .map(|candidate| {
+            (
+                strsim::jaro_winkler(&name.to_lowercase(), &candidate.to_lowercase()),
+                candidate,
+            )
+        })
+        .filter(|(similarity, _)| *similarity > 0.85)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, candidate)| format!(" (did you mean '{candidate}'?)"))
+        .unwrap_or_default()
+}
+
+/// Describes the error of deserializing a record, including the column in which it occurred, if known.
+fn describe_deserialize_error(error: &csv::Error, headers: &csv::StringRecord) -> String {
+    if let csv::ErrorKind::Deserialize { err, .. } = error.kind() {
+        let column = err
+            .field()
+            .and_then(|field| headers.get(field as usize).map(|header| (field, header)));
+        return match column {
+            Some((field, header)) => format!(", column {} ('{header}'): {}", field + 1, err.kind()),
+            None => format!(": {}", err.kind()),
+        };
+    }
+    format!(": {error}")
+}
+
+/// Combines all problems found in the file with the given name into one error message.
+fn format_errors(file_name: &str, errors: Vec<String>) -> String {
+    let count = errors.len();
+    let mut message = format!(
+        "Found {count} problem{} in '{file_name}':",
+        if count == 1 { "" } else { "s" }
+    );
+    for error in errors {
+        message.push_str(&format!("\n  {error}"));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_DATE_FORMAT, InvoiceLineRecord};
+    use xrechnung::config::Config;
+
+    fn config() -> Config {
+        xrechnung::config::load("../xrechnung/examples/config.toml", "Client Company").unwrap()
+    }
+
+    fn record(quantity: Option<&str>, start: Option<&str>, end: Option<&str>) -> InvoiceLineRecord {
+        InvoiceLineRecord {
+            id: None,
+            name: "Development".to_string(),
+            description: None,
+            quantity: quantity.map(str::to_string),
+            hourly_rate: None,
+            date: None,
+            note: None,
+            start_date: None,
+            end_date: None,
+            order_line_id: None,
+            accounting_cost: None,
+            project: None,
+            start: start.map(str::to_string),
+            end: end.map(str::to_string),
+            break_duration: Some("0:30".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_quantity_from_timestamps() {
+        let line = record(None, Some("2025-01-02 09:00"), Some("2025-01-02T17:15"))
+            .into_invoice_line(&config(), DEFAULT_DATE_FORMAT)
+            .unwrap();
+        assert_eq!(line.quantity, 7.75);
+        assert_eq!(line.date.as_deref(), Some("2025-01-02"));
+    }
+
+    #[test]
+    fn test_default_hourly_rate() {
+        let line = record(Some("1.0"), None, None)
+            .into_invoice_line(&config(), DEFAULT_DATE_FORMAT)
+            .unwrap();
+        assert_eq!(line.hourly_rate, 100.0);
+
+        let mut line = record(Some("1.0"), None, None);
+        line.hourly_rate = Some(80.0);
+        assert_eq!(
+            line.into_invoice_line(&config(), DEFAULT_DATE_FORMAT)
+                .unwrap()
+                .hourly_rate,
+            80.0
+        );
+
+        let mut config = config();
+        config.default_hourly_rate = None;
+        assert!(
+            record(Some("1.0"), None, None)
+                .into_invoice_line(&config, DEFAULT_DATE_FORMAT)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_error_on_missing_quantity() {
+        assert!(
+            record(None, None, None)
+                .into_invoice_line(&config(), DEFAULT_DATE_FORMAT)
+                .is_err()
+        );
+        assert!(
+            record(Some("1.0"), Some("2025-01-02 09:00"), None)
+                .into_invoice_line(&config(), DEFAULT_DATE_FORMAT)
+                .is_err()
+        );
+        assert!(
+            record(None, Some("2025-01-02 09:00"), Some("2025-01-02 09:15"))
+                .into_invoice_line(&config(), DEFAULT_DATE_FORMAT)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rate_from_rate_table() {
+        let mut line = record(Some("1.0"), None, None);
+        line.name = "development".to_string();
+
+        let line = line
+            .into_invoice_line(&config(), DEFAULT_DATE_FORMAT)
+            .unwrap();
+        assert_eq!(line.hourly_rate, 95.0);
+        assert_eq!(line.name, "Software Development");
+
+        let mut line = record(Some("1.0"), None, None);
+        line.name = "support".to_string();
+        line.hourly_rate = Some(80.0);
+
+        let line = line
+            .into_invoice_line(&config(), DEFAULT_DATE_FORMAT)
+            .unwrap();
+        assert_eq!(line.hourly_rate, 80.0);
+        assert_eq!(line.name, "support");
+    }
+
+    #[test]
+    fn test_normalize_decimal_comma() {
+        assert_eq!(super::normalize_decimal_comma("1.234,50"), "1234.50");
+        assert_eq!(super::normalize_decimal_comma("7,5"), "7.5");
+        assert_eq!(super::normalize_decimal_comma("1:30"), "1:30");
+    }
+
+    #[test]
+    fn test_date_format() {
+        let mut line = record(None, Some("02.01.2025 09:00"), Some("02.01.2025 10:30"));
+        line.end_date = Some("03.01.2025".to_string());
+
+        let line = line.into_invoice_line(&config(), "%d.%m.%Y").unwrap();
+        assert_eq!(line.quantity, 1.0);
+        assert_eq!(line.date.as_deref(), Some("2025-01-02"));
+        assert_eq!(line.end_date.as_deref(), Some("2025-01-03"));
+
+        let mut line = record(Some("1.0"), None, None);
+        line.date = Some("2025-01-02".to_string());
+        let error = line.into_invoice_line(&config(), "%d.%m.%Y").err();
+        assert_eq!(
+            error.as_deref(),
+            Some("Invalid date '2025-01-02' in column 'date' (expected format '%d.%m.%Y').")
+        );
+    }
+
+    #[test]
+    fn test_line_file() {
+        use xrechnung::lines::InvoiceLineSource;
+
+        let (config, options) = (config(), super::InputOptions::default());
+        let file = |file_name| super::LineFile {
+            file_name,
+            config: &config,
+            options: &options,
+        };
+        let lines = file("examples/invoice-lines.json").read_lines().unwrap();
+        assert_eq!(lines.len(), 3);
+        assert!(file("examples/missing.csv").read_lines().is_err());
+    }
+
+    #[test]
+    fn test_read_json() {
+        let lines = super::read_invoice_hours(
+            "examples/invoice-lines.json",
+            &config(),
+            &super::InputOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].name, "Software Development");
+        assert_eq!(lines[0].quantity, 7.0);
+        assert_eq!(lines[0].hourly_rate, 95.0);
+        assert_eq!(lines[1].quantity, 1.5);
+        assert_eq!(lines[1].note.as_deref(), Some("On-site \"workshop\""));
+        assert_eq!(lines[2].hourly_rate, 110.0);
+        assert_eq!(lines[2].date, None);
+    }
+
+    #[test]
+    fn test_read_toml() {
+        let options = super::InputOptions {
+            date_format: "%d.%m.%Y".to_string(),
+            ..Default::default()
+        };
+        let lines =
+            super::read_invoice_hours("examples/invoice-lines.toml", &config(), &options).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].name, "Monthly Maintenance");
+        assert_eq!(lines[0].quantity, 1.0);
+        assert_eq!(lines[0].hourly_rate, 450.0);
+        assert_eq!(lines[0].start_date.as_deref(), Some("2025-01-01"));
+        assert_eq!(lines[0].end_date.as_deref(), Some("2025-01-31"));
+        assert_eq!(lines[1].name, "Software Development");
+        assert_eq!(lines[1].quantity, 2.5);
+        assert_eq!(lines[1].date.as_deref(), Some("2025-01-15"));
+    }
+
+    #[test]
+    fn test_read_matrix() {
+        let options = super::InputOptions {
+            delimiter: b';',
+            decimal_comma: true,
+            date_format: "%d.%m.%Y".to_string(),
+            layout: super::Layout::Matrix,
+            ..Default::default()
+        };
+        let lines = super::read_invoice_hours("examples/timesheet-matrix.csv", &config(), &options)
+            .unwrap();
+
+        let lines: Vec<(&str, Option<&str>, f32)> = lines
+            .iter()
+            .map(|line| (line.name.as_str(), line.date.as_deref(), line.quantity))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                ("Software Development", Some("2025-01-06"), 7.5),
+                ("Software Development", Some("2025-01-08"), 8.0),
+                ("Software Development", Some("2025-01-10"), 4.0),
+                ("support", Some("2025-01-07"), 1.5),
+                ("support", Some("2025-01-10"), 0.25),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matrix_without_dates() {
+        let table = super::Table {
+            headers: vec!["name".to_string(), "Monday".to_string()],
+            rows: vec![(2, vec!["development".to_string(), "8".to_string()])],
+        };
+        assert!(super::transpose_matrix(table, DEFAULT_DATE_FORMAT).is_err());
+    }
+
+    #[test]
+    fn test_collect_all_errors() {
+        let error = super::read_invoice_hours(
+            "examples/invalid-lines.csv",
+            &config(),
+            &super::InputOptions::default(),
+        )
+        .err()
+        .unwrap()
+        .to_string();
+
+        assert_eq!(
+            error,
+            "Found 3 problems in 'examples/invalid-lines.csv':\n  \
+             line 4, column 4 ('hourly_rate'): invalid float literal\n  \
+             line 5: Either a quantity or the start and end timestamps need to be given.\n  \
+             line 6: The row has 5 fields, but there are only 4 columns."
+        );
+    }
+
+    #[test]
+    fn test_check_columns() {
+        let headers = |headers: &[&str]| -> Vec<String> {
+            headers.iter().map(|header| header.to_string()).collect()
+        };
+
+        assert!(super::check_columns(&headers(&["name", "quantity", "comment"])).is_empty());
+        assert!(super::check_columns(&headers(&["name", "start", "end"])).is_empty());
+        assert_eq!(
+            super::check_columns(&headers(&["Name", "quantity", "hourly_rte"])),
+            vec![
+                "The required column 'name' is missing (did you mean 'Name'?).",
+                "Unknown column 'hourly_rte' (did you mean 'hourly_rate'?)."
+            ]
+        );
+        assert_eq!(
+            super::check_columns(&headers(&["name", "quantitiy"])),
+            vec!["The required column 'quantity' is missing (did you mean 'quantitiy'?)."]
+        );
+    }
+
+    #[test]
+    fn test_remote_extension() {
+        assert_eq!(
+            super::remote_extension("https://example.com/sheets/lines.XLSX?download=1"),
+            "xlsx"
+        );
+        assert_eq!(
+            super::remote_extension(
+                "https://docs.google.com/spreadsheets/d/e/abc/pub?gid=0&single=true&output=csv"
+            ),
+            "csv"
+        );
+        assert_eq!(
+            super::remote_extension("https://example.com/export?format=ods#sheet"),
+            "ods"
+        );
+        assert_eq!(
+            super::remote_extension("https://example.com/lines.exe"),
+            "csv"
+        );
+    }
+}
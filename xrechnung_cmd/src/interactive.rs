@@ -0,0 +1,281 @@
+//! Interactive creation of an invoice (`interactive`), which asks for the buyer, the invoice lines and the invoice
+//! number, shows the invoice lines with the totals, lets them be edited and finally writes the invoice. This spares
+//! remembering the options of `generate` for occasional invoices.
+
+use std::io::{BufRead, Write};
+
+use clap::{Args, FromArgMatches};
+use xrechnung::data::InvoiceHoursElement;
+
+use crate::InvoiceArgs;
+use crate::init::{Prompter, not_empty};
+
+/// The commands for editing the invoice lines, as shown after the summary of the invoice.
+const COMMANDS: &str = "Commands: 'e <line>' edit a line, 'd <line>' delete a line, 'a' add a line, 'w' write the \
+                        invoice, 'q' quit (lines are numbered from 1 in the order of the table)";
+
+/// An action chosen for the invoice lines.
+#[derive(Debug, PartialEq)]
+enum Action {
+    Edit(usize),
+    Delete(usize),
+    Add,
+    Write,
+    Quit,
+}
+
+impl Action {
+    fn parse(value: &str) -> Result<Self, String> {
+        let line = |number: &str| {
+            number
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|number| *number > 0)
+                .ok_or_else(|| format!("'{number}' is no line number"))
+        };
+        match value.split_at(value.len().min(1)) {
+            ("e", number) => Ok(Action::Edit(line(number)?)),
+            ("d", number) => Ok(Action::Delete(line(number)?)),
+            ("a", "") => Ok(Action::Add),
+            ("w", "") => Ok(Action::Write),
+            ("q", "") => Ok(Action::Quit),
+            _ => Err(format!("'{value}' is no command")),
+        }
+    }
+}
+
+fn action(value: &str) -> Result<(), String> {
+    Action::parse(value).map(|_| ())
+}
+
+fn quantity(value: &str) -> Result<(), String> {
+    xrechnung::data::parse_hours(value).map(|_| ())
+}
+
+fn rate(value: &str) -> Result<(), String> {
+    match value.parse::<f32>() {
+        Ok(rate) if rate >= 0.0 => Ok(()),
+        _ => Err("a non-negative number is required (e.g., \"95.50\")".to_string()),
+    }
+}
+
+fn optional_date(value: &str) -> Result<(), String> {
+    match value.is_empty() || chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+        true => Ok(()),
+        false => Err("a date in the format YYYY-MM-DD or nothing is required".to_string()),
+    }
+}
+
+fn optional_month(value: &str) -> Result<(), String> {
+    match value.is_empty() {
+        true => Ok(()),
+        false => crate::parse_month(value).map(|_| ()),
+    }
+}
+
+/// Asks for the name, quantity, hourly rate and date of an invoice line, with the values of the given line as defaults.
+/// The other fields of the line are kept.
+fn ask_line<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    line: InvoiceHoursElement,
+) -> Result<InvoiceHoursElement, Box<dyn std::error::Error>> {
+    let name = prompter.ask(
+        "Name",
+        Some(&line.name)
+            .filter(|name| !name.is_empty())
+            .map(String::as_str),
+        not_empty,
+    )?;
+    let quantity = prompter.ask(
+        "Quantity (hours)",
+        Some(&line.quantity.to_string()),
+        quantity,
+    )?;
+    let hourly_rate = prompter.ask("Hourly rate", Some(&line.hourly_rate.to_string()), rate)?;
+    let date = prompter.ask("Date (YYYY-MM-DD)", line.date.as_deref(), optional_date)?;
+    Ok(InvoiceHoursElement {
+        name,
+        quantity: xrechnung::data::parse_hours(&quantity)?,
+        hourly_rate: hourly_rate.parse()?,
+        date: Some(date).filter(|date| !date.is_empty()),
+        ..line
+    })
+}
+
+/// Asks for the buyer until one of the given configuration file is selected by its number in the list of buyers, its
+/// name or its alias. Returns the name of the buyer.
+fn ask_buyer<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    config_file: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let config = xrechnung::config::load_all(config_file).map_err(crate::error::config)?;
+    for (index, buyer) in config.buyer.iter().enumerate() {
+        writeln!(prompter.output, "{:>3}  {}", index + 1, buyer.name)?;
+    }
+    loop {
+        let answer = prompter.ask("Buyer (number or name)", None, not_empty)?;
+        let name = match answer.parse::<usize>() {
+            Ok(number) if (1..=config.buyer.len()).contains(&number) => {
+                config.buyer[number - 1].name.clone()
+            }
+            _ => answer,
+        };
+        match xrechnung::config::load(config_file, &name) {
+            Ok(_) => return Ok(name),
+            Err(e) => prompter.say(&format!("Invalid value: {e}"))?,
+        }
+    }
+}
+
+/// Asks for the invoice on the given output and reads the answers from the given input, lets the invoice lines be
+/// edited and writes the invoice (recording it in the given registry file).
+pub fn run<R: BufRead, W: Write>(
+    config_file: &str,
+    registry: &std::path::Path,
+    input: R,
+    output: W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut prompter = Prompter { input, output };
+    let buyer = ask_buyer(&mut prompter, config_file)?;
+    let invoice_hours = prompter.ask(
+        "Invoice lines (file, URL or time tracking service)",
+        None,
+        not_empty,
+    )?;
+    let month = prompter.ask(
+        "Month of the invoice (YYYY-MM, empty for none)",
+        None,
+        optional_month,
+    )?;
+    let numbering = xrechnung::config::load(config_file, &buyer)
+        .map_err(crate::error::config)?
+        .numbering
+        .is_some();
+    let invoice_id = match numbering {
+        true => prompter.ask(
+            "Invoice number (empty for the next number of the scheme)",
+            None,
+            |_| Ok(()),
+        )?,
+        false => prompter.ask("Invoice number", None, not_empty)?,
+    };
+
+    let args = ["--buyer", &buyer, "--invoice-hours", &invoice_hours]
+        .into_iter()
+        .chain(
+            Some(["--month", &month])
+                .filter(|_| !month.is_empty())
+                .into_iter()
+                .flatten(),
+        )
+        .chain(
+            Some(["--invoice-id", &invoice_id])
+                .filter(|_| !invoice_id.is_empty())
+                .into_iter()
+                .flatten(),
+        );
+    let command = InvoiceArgs::augment_args(clap::Command::new("invoice").no_binary_name(true));
+    let args = InvoiceArgs::from_arg_matches(&command.try_get_matches_from(args)?)?;
+    let mut invoice = crate::prepare(config_file, args)?;
+
+    loop {
+        write!(
+            prompter.output,
+            "\n{}\n{}\n",
+            crate::preview::summary(&invoice.config, &invoice.bill, &invoice.invoice_hours),
+            crate::i18n::translate(COMMANDS)
+        )?;
+        let action = Action::parse(&prompter.ask("Command", Some("w"), action)?)?;
+        let lines = invoice.invoice_hours.len();
+        match action {
+            Action::Edit(line) | Action::Delete(line) if line > lines => {
+                prompter.say(&format!("Invalid value: the invoice has {lines} line(s)."))?;
+            }
+            Action::Edit(line) => {
+                let edited = std::mem::take(&mut invoice.invoice_hours[line - 1]);
+                invoice.invoice_hours[line - 1] = ask_line(&mut prompter, edited)?;
+            }
+            Action::Delete(line) => {
+                invoice.invoice_hours.remove(line - 1);
+            }
+            Action::Add => {
+                // the new line is based on the last one, as most invoices consist of similar lines
+                let last = invoice.invoice_hours.last();
+                let template = InvoiceHoursElement {
+                    hourly_rate: last.map_or(0.0, |last| last.hourly_rate),
+                    date: last.and_then(|last| last.date.clone()),
+                    ..Default::default()
+                };
+                let line = ask_line(&mut prompter, template)?;
+                invoice.invoice_hours.push(line);
+            }
+            Action::Write => break,
+            Action::Quit => {
+                prompter.say("No invoice was created.")?;
+                return Ok(());
+            }
+        }
+    }
+
+    let default_output = format!("{}.xml", invoice.bill.number);
+    let output = loop {
+        let output = prompter.ask("Output file", Some(&default_output), not_empty)?;
+        match crate::check_output(&output, false) {
+            Ok(()) => break output,
+            Err(e) => prompter.say(&format!("Invalid value: {e}"))?,
+        }
+    };
+    let (xml_root, _) = crate::create(invoice, registry, Some(&output), None, false)?;
+    crate::check_rules(&xml_root)?;
+    prompter.say(&format!("Created the invoice '{output}'."))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Action;
+
+    #[test]
+    fn test_action() {
+        assert_eq!(Action::parse("e 2"), Ok(Action::Edit(2)));
+        assert_eq!(Action::parse("d3"), Ok(Action::Delete(3)));
+        assert_eq!(Action::parse("w"), Ok(Action::Write));
+        assert!(Action::parse("e 0").is_err());
+        assert!(Action::parse("x").is_err());
+        assert!(Action::parse("").is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let directory =
+            std::env::temp_dir().join(format!("xrechnung-interactive-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let output = directory.join("invoice.xml");
+        let registry = directory.join("invoices.jsonl");
+        let input = format!(
+            "x\n1\nexamples/invoice-lines.csv\n2025-01\n2025-900\nd 2\ne 5\ne 1\n\n8\n\n\nw\n{}\n",
+            output.display()
+        );
+        let mut messages = Vec::new();
+        super::run(
+            "../xrechnung/examples/config.toml",
+            &registry,
+            input.as_bytes(),
+            &mut messages,
+        )
+        .unwrap();
+
+        let messages = String::from_utf8(messages).unwrap();
+        assert!(messages.contains("  1  Client Company\n"));
+        assert!(messages.contains("Invalid value: the invoice has 1 line(s)."));
+        assert!(messages.contains("Total amount: 1047.20 EUR"));
+        let invoice = std::fs::read_to_string(&output).unwrap();
+        assert!(invoice.contains("<cbc:ID>2025-900</cbc:ID>"));
+        assert!(
+            invoice.contains("<cbc:PayableAmount currencyID=\"EUR\">1047.20</cbc:PayableAmount>")
+        );
+        assert_eq!(crate::registry::read(&registry).unwrap().len(), 1);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,290 @@
+//! Verification of invoices with the official validator of KoSIT (Koordinierungsstelle für IT-Standards), which checks
+//! invoices against the schemas and Schematron rules of EN 16931 and XRechnung (`--verify`).
+//!
+//! The validator is either run from its jar file (with the validator configuration for XRechnung, see
+//! <https://github.com/itplr-kosit/validator-configuration-xrechnung>) or called via HTTP, if it runs in daemon mode.
+
+use std::path::Path;
+use std::process::Command;
+
+use xrechnung::XmlElement;
+
+/// The way to run the KoSIT validator.
+#[derive(Debug, PartialEq)]
+pub enum Validator {
+    /// The jar file of the validator, run with Java, and the scenario file and repository directory of the validator
+    /// configuration.
+    Jar {
+        jar: String,
+        scenarios: String,
+        repository: String,
+    },
+
+    /// The URL of the validator running in daemon mode.
+    Daemon { url: String },
+}
+
+impl Validator {
+    /// Determines how to run the validator from the given jar file or URL, scenario file and repository directory.
+    pub fn new(
+        validator: Option<&str>,
+        scenarios: Option<&str>,
+        repository: Option<&str>,
+    ) -> Result<Self, String> {
+        let validator = validator
+            .ok_or("The jar file or URL of the KoSIT validator is needed for verifying invoices (`--validator`).")?;
+        if validator.starts_with("http://") || validator.starts_with("https://") {
+            return Ok(Validator::Daemon {
+                url: validator.to_string(),
+            });
+        }
+
+        let scenarios = scenarios.ok_or(
+            "The scenario file of the validator configuration is needed for running the KoSIT validator from its jar \
+             file (`--validator-scenarios`).",
+        )?;
+        // the scenario file is usually located in the root directory of the validator configuration
+        let repository = match repository {
+            Some(repository) => repository.to_string(),
+            None => Path::new(scenarios)
+                .parent()
+                .map(|parent| parent.to_string_lossy().into_owned())
+                .filter(|parent| !parent.is_empty())
+                .unwrap_or_else(|| ".".to_string()),
+        };
+        Ok(Validator::Jar {
+            jar: validator.to_string(),
+            scenarios: scenarios.to_string(),
+            repository,
+        })
+    }
+}
+
+/// A message of the validation report, e.g., a violated rule.
+#[derive(Debug, PartialEq)]
+pub struct Message {
+    /// The level of the message ("error", "warning" or "information").
+    pub level: String,
+
+    /// The code of the message, usually the identifier of the violated rule (e.g., "BR-DE-15").
+    pub code: String,
+
+    /// The location of the element in the invoice that the message refers to, as XPath.
+    pub location: String,
+
+    /// The text of the message.
+    pub text: String,
+}
+
+/// The validation report of the KoSIT validator for an invoice.
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    /// Whether the validator recommends to accept the invoice.
+    pub accepted: bool,
+
+    /// The messages of all validation steps.
+    pub messages: Vec<Message>,
+}
+
+/// Returns the name of the element without namespace prefix.
+fn local_name(element: &XmlElement) -> &str {
+    element.name().rsplit(':').next().unwrap_or_default()
+}
+
+/// Collects the messages in the given element and all its descendants.
+fn collect_messages(element: &XmlElement, messages: &mut Vec<Message>) {
+    for child in element.children() {
+        if local_name(child) == "message" {
+            let attribute = |name| child.attribute(name).unwrap_or_default().to_string();
+            let text = child.text().unwrap_or_default();
+            messages.push(Message {
+                level: attribute("level"),
+                code: attribute("code"),
+                location: attribute("xpathLocation"),
+                text: text.trim().to_string(),
+            });
+        } else {
+            collect_messages(child, messages);
+        }
+    }
+}
+
+/// Reads the validation report of the KoSIT validator in its XML format.
+pub fn parse_report(document: &str) -> Result<Report, String> {
+    let root = XmlElement::parse(document)
+        .map_err(|e| format!("Could not read the report of the KoSIT validator: {e}"))?;
+    let assessment = root
+        .children()
+        .iter()
+        .find(|child| local_name(child) == "assessment")
+        .ok_or("The report of the KoSIT validator contains no assessment.")?;
+    let accepted = assessment
+        .children()
+        .iter()
+        .any(|child| local_name(child) == "accept");
+
+    let mut messages = Vec::new();
+    collect_messages(&root, &mut messages);
+    Ok(Report { accepted, messages })
+}
+
+/// Runs the jar file of the validator for the given invoice file and returns the content of the report.
+fn run_jar(
+    jar: &str,
+    scenarios: &str,
+    repository: &str,
+    file_name: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let output_directory =
+        std::env::temp_dir().join(format!("xrechnung-kosit-{}", std::process::id()));
+    std::fs::create_dir_all(&output_directory)?;
+
+    // the validator exits with an error for rejected invoices, so only the report tells whether it failed
+    let output = Command::new("java")
+        .args(["-jar", jar, "-s", scenarios, "-r", repository, "-o"])
+        .arg(&output_directory)
+        .arg(file_name)
+        .output()
+        .map_err(|e| format!("Could not run the KoSIT validator with Java: {e}"))?;
+
+    let stem = Path::new(file_name)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let report_file = output_directory.join(format!("{stem}-report.xml"));
+    let report = std::fs::read_to_string(&report_file);
+    let _ = std::fs::remove_dir_all(&output_directory);
+
+    report.map_err(|_| {
+        format!(
+            "The KoSIT validator did not create a report: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        )
+        .into()
+    })
+}
+
+/// Verifies the given invoice file with the KoSIT validator and returns its report.
+pub fn verify(
+    validator: &Validator,
+    file_name: &str,
+) -> Result<Report, Box<dyn std::error::Error>> {
+    let document = match validator {
+        Validator::Jar {
+            jar,
+            scenarios,
+            repository,
+        } => run_jar(jar, scenarios, repository, file_name)?,
+        Validator::Daemon { url } => {
+            // the daemon responds with the report and status 200 for accepted and 406 for rejected invoices
+            let (status, body) = crate::http::post_file(url, file_name, "application/xml", &[])?;
+            if status != 200 && status != 406 {
+                return Err(format!(
+                    "The KoSIT validator at '{url}' responded with status {status}: {}",
+                    String::from_utf8_lossy(&body).trim()
+                )
+                .into());
+            }
+            String::from_utf8_lossy(&body).into_owned()
+        }
+    };
+    Ok(parse_report(&document)?)
+}
+
+/// Verifies the given invoice file with the KoSIT validator, prints the messages of its report and returns an error, if
+/// the validator recommends to reject the invoice.
+pub fn run(validator: &Validator, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let report = verify(validator, file_name)?;
+    for message in &report.messages {
+        eprintln!(
+            "KoSIT validator: {} [{}] {}: {}",
+            message.level, message.code, message.location, message.text
+        );
+    }
+    if !report.accepted {
+        return Err(crate::error::validation(format!(
+            "The KoSIT validator rejected the invoice '{file_name}'."
+        )));
+    }
+    println!("The KoSIT validator accepted the invoice '{file_name}'.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Message, Validator};
+
+    #[test]
+    fn test_validator() {
+        assert_eq!(
+            Validator::new(Some("http://localhost:8080/"), None, None),
+            Ok(Validator::Daemon {
+                url: "http://localhost:8080/".to_string()
+            })
+        );
+        assert_eq!(
+            Validator::new(Some("validator.jar"), Some("config/scenarios.xml"), None),
+            Ok(Validator::Jar {
+                jar: "validator.jar".to_string(),
+                scenarios: "config/scenarios.xml".to_string(),
+                repository: "config".to_string(),
+            })
+        );
+        assert_eq!(
+            Validator::new(Some("validator.jar"), Some("scenarios.xml"), Some("repo")),
+            Ok(Validator::Jar {
+                jar: "validator.jar".to_string(),
+                scenarios: "scenarios.xml".to_string(),
+                repository: "repo".to_string(),
+            })
+        );
+        assert!(Validator::new(Some("validator.jar"), None, None).is_err());
+        assert!(Validator::new(None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let report = super::parse_report(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rep:report xmlns:rep="http://www.xoev.de/de/validator/varl/1" valid="false">
+    <rep:scenarioMatched>
+        <rep:validationStepResult id="val-sch.1" valid="false">
+            <rep:resource><rep:name>Schematron XRechnung</rep:name></rep:resource>
+            <rep:message id="val-sch.1.1" level="error" code="BR-DE-15" xpathLocation="/Invoice[1]">[BR-DE-15] The element &quot;Buyer reference&quot; (BT-10) must be transmitted.</rep:message>
+            <rep:message id="val-sch.1.2" level="warning" code="PEPPOL-EN16931-R001" xpathLocation="/Invoice[1]/ProfileID[1]">Business process MUST be provided.</rep:message>
+        </rep:validationStepResult>
+    </rep:scenarioMatched>
+    <rep:assessment>
+        <rep:reject><rep:explanation>Invalid</rep:explanation></rep:reject>
+    </rep:assessment>
+</rep:report>"#,
+        )
+        .unwrap();
+        assert!(!report.accepted);
+        assert_eq!(
+            report.messages,
+            vec![
+                Message {
+                    level: "error".to_string(),
+                    code: "BR-DE-15".to_string(),
+                    location: "/Invoice[1]".to_string(),
+                    text: "[BR-DE-15] The element \"Buyer reference\" (BT-10) must be transmitted."
+                        .to_string(),
+                },
+                Message {
+                    level: "warning".to_string(),
+                    code: "PEPPOL-EN16931-R001".to_string(),
+                    location: "/Invoice[1]/ProfileID[1]".to_string(),
+                    text: "Business process MUST be provided.".to_string(),
+                },
+            ]
+        );
+
+        let report = super::parse_report(
+            r#"<rep:report xmlns:rep="http://www.xoev.de/de/validator/varl/1"><rep:assessment><rep:accept/></rep:assessment></rep:report>"#,
+        )
+        .unwrap();
+        assert!(report.accepted);
+        assert!(super::parse_report("<rep:report></rep:report>").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! Logging of the processing stages of the commands (e.g., loading the configuration, reading the invoice lines,
+//! calculating the totals, validating and writing the invoice) to the standard error output, for troubleshooting batch
+//! runs. Nothing is logged by default, `-v` logs the stages and `-vv` also their details. The log is written as text or
+//! as one JSON object per line (`--log-format`).
+//!
+//! Messages are logged with the [`info!`] and [`debug!`] macros, which only format their message if the level is
+//! enabled.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use xrechnung::json::Value;
+
+/// The enabled level (0 for none).
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// Whether the log is written as JSON objects.
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// The level of a log message.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Level {
+    /// The processing stages (`-v`).
+    Info = 1,
+
+    /// The details of the processing stages (`-vv`).
+    Debug = 2,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Info => "info",
+            Level::Debug => "debug",
+        }
+    }
+}
+
+/// The format of the log.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// One line of text per message.
+    Text,
+
+    /// One JSON object per message with the time, level, stage and message.
+    Json,
+}
+
+/// Enables the logging for the given verbosity (the number of `-v` flags) and format.
+pub fn init(verbosity: u8, format: Format) {
+    LEVEL.store(verbosity.min(Level::Debug as u8), Ordering::Relaxed);
+    JSON.store(format == Format::Json, Ordering::Relaxed);
+}
+
+/// Returns whether messages of the given level are logged.
+pub fn enabled(level: Level) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level as u8
+}
+
+/// Writes the message of the given level and processing stage to the standard error output.
+pub fn write(level: Level, stage: &str, message: fmt::Arguments) {
+    let format = match JSON.load(Ordering::Relaxed) {
+        true => Format::Json,
+        false => Format::Text,
+    };
+    let time = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z");
+    eprintln!(
+        "{}",
+        line(
+            &time.to_string(),
+            level,
+            stage,
+            &message.to_string(),
+            format
+        )
+    );
+}
+
+/// Returns the line of the log for the given message.
+fn line(time: &str, level: Level, stage: &str, message: &str, format: Format) -> String {
+    match format {
+        Format::Text => format!(
+            "{time} {:<5} {stage}: {message}",
+            level.name().to_uppercase()
+        ),
+        Format::Json => Value::Object(vec![
+            ("time".to_string(), Value::String(time.to_string())),
+            ("level".to_string(), Value::String(level.name().to_string())),
+            ("stage".to_string(), Value::String(stage.to_string())),
+            ("message".to_string(), Value::String(message.to_string())),
+        ])
+        .to_string(),
+    }
+}
+
+/// Logs a processing stage, e.g., `info!("config", "Loaded '{}'.", file)`.
+macro_rules! info {
+    ($stage:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            $crate::log::write($crate::log::Level::Info, $stage, format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs a detail of a processing stage, e.g., `debug!("input", "Read the line {}.", number)`.
+macro_rules! debug {
+    ($stage:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            $crate::log::write($crate::log::Level::Debug, $stage, format_args!($($arg)*));
+        }
+    };
+}
+
+pub(crate) use {debug, info};
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, Level, line};
+
+    #[test]
+    fn test_line() {
+        let time = "2025-01-31T10:00:00.000+01:00";
+        assert_eq!(
+            line(
+                time,
+                Level::Info,
+                "config",
+                "Loaded \"config.toml\".",
+                Format::Text
+            ),
+            "2025-01-31T10:00:00.000+01:00 INFO  config: Loaded \"config.toml\"."
+        );
+        assert_eq!(
+            line(time, Level::Debug, "input", "Read 2 line(s).", Format::Json),
+            r#"{"time":"2025-01-31T10:00:00.000+01:00","level":"debug","stage":"input","message":"Read 2 line(s)."}"#
+        );
+    }
+}
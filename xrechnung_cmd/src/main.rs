@@ -1,86 +1,1377 @@
-/// A command line tool to create an XRechnung invoice from a CSV file with invoice hours.
+/// A command line tool to create XRechnung invoices from files with invoice hours.
 ///
 /// ```
 ///
 /// ```
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
 use chrono::{Datelike, NaiveDate};
 use clap::Parser;
-use csv;
-use std::fs::File;
 
-use xrechnung::data::{Bill, InvoiceHoursElement, Period};
+use xrechnung::attachments::Attachment;
+use xrechnung::config::Config;
+use xrechnung::data::{Bill, InvoiceHoursElement, Period, TaxCategory};
+use xrechnung::lines::{Aggregation, InvoiceLineSource};
+
+mod archive;
+mod batch;
+mod buyers;
+mod convert;
+mod cover;
+mod error;
+mod export;
+#[cfg(any(
+    feature = "remote",
+    feature = "toggl",
+    feature = "kosit",
+    feature = "smtp",
+    feature = "peppol",
+    feature = "lexoffice",
+    feature = "sevdesk"
+))]
+mod http;
+mod i18n;
+mod import;
+mod init;
+mod input;
+mod interactive;
+#[cfg(feature = "kosit")]
+mod kosit;
+mod log;
+#[cfg(feature = "peppol")]
+mod peppol;
+mod preview;
+mod registry;
+mod report;
+#[cfg(feature = "smtp")]
+mod send;
+#[cfg(feature = "serve")]
+mod serve;
+mod sha256;
+#[cfg(any(feature = "xlsx", feature = "ods"))]
+mod spreadsheet;
+mod submit;
+mod validate;
+
+/// The output file name for writing to the standard output.
+const STDOUT: &str = "-";
+
+/// How to handle invoice lines that lie outside of the billing period.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum PeriodCheck {
+    /// Abort with an error.
+    Fail,
+
+    /// Print a warning and create the invoice anyway.
+    Warn,
+
+    /// Do not check the dates of the invoice lines.
+    Ignore,
+}
+
+/// How to handle invoice lines that are identical to an earlier line.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Duplicates {
+    /// Abort with an error.
+    Fail,
+
+    /// Print a warning and keep the duplicates.
+    Warn,
+
+    /// Remove the duplicates, keeping the first of the identical lines.
+    Remove,
+}
 
-/// Command line tool to create an XRechnung invoice from a CSV file with invoice hours.
+/// Command line tool to create XRechnung invoices from files with invoice hours.
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
-struct Args {
-    /// The unique number of the invoice
-    #[arg(short, long)]
-    invoice_id: String,
+#[command(version, about, long_about = None, after_help = error::EXIT_CODES)]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Options that are shared by all commands.
+#[derive(clap::Args, Debug)]
+struct GlobalArgs {
+    /// Config file (TOML, YAML or JSON) that provides supplier and buyer information (default:
+    /// `$XDG_CONFIG_HOME/xrechnung/config.toml`, `~/.config/xrechnung/config.toml` or `xrechnung.toml` in the current
+    /// directory, whichever exists first)
+    #[arg(short, long, global = true)]
+    config: Option<String>,
+
+    /// Registry file (JSON Lines) that records every created invoice (default: `invoices.jsonl` next to the config
+    /// file)
+    #[arg(long, global = true)]
+    registry: Option<String>,
+
+    /// Format of the error messages (JSON objects with the category, exit code and message of the errors for scripts)
+    #[arg(long, global = true, value_enum, default_value_t = error::Format::Text)]
+    error_format: error::Format,
+
+    /// Language of the prompts, summaries and error messages (default: German for German locales as given by `LC_ALL`,
+    /// `LC_MESSAGES` or `LANG`, English otherwise)
+    #[arg(long, global = true, value_enum)]
+    language: Option<i18n::Language>,
+
+    /// Log the processing stages to the standard error output (`-vv` for their details)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Format of the log (JSON objects with the time, level, stage and message for log processors)
+    #[arg(long, global = true, value_enum, default_value_t = log::Format::Text)]
+    log_format: log::Format,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Create an XRechnung invoice from the invoice lines
+    Generate {
+        #[command(flatten)]
+        invoice: Box<InvoiceArgs>,
+
+        #[command(flatten)]
+        verify: VerifyArgs,
+
+        /// Create and validate the invoice and show its totals, but do not write it
+        #[arg(long, conflicts_with = "verify")]
+        dry_run: bool,
+
+        /// Print a JSON object with the invoice number, buyer, period, totals and output file after creating the
+        /// invoice (e.g., for recording the invoice in other systems)
+        #[arg(long)]
+        summary_json: bool,
+
+        /// Overwrite the output file, if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Output XML file for the invoice to be written ("-" for the standard output)
+        #[arg(short, long, required_unless_present_any = ["dry_run", "archive"])]
+        output: Option<String>,
 
-    /// Config file that provides supplier and buyer information
+        /// Archive directory to store the invoice in, as `<year>/<supplier>-<buyer>-<invoice id>.xml` (instead of the
+        /// output file). Invoice numbers that are already archived are refused.
+        #[arg(long, conflicts_with = "output")]
+        archive: Option<String>,
+
+        /// Accounting service to export the created invoice to
+        #[arg(long, value_enum)]
+        export: Option<export::Service>,
+
+        /// Write a cover letter with the invoice number, amount and due date next to the invoice (e.g.,
+        /// `invoice.txt` for `invoice.xml`), for pasting it into an email
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        cover_letter: Option<cover::Format>,
+    },
+
+    /// Check that an XML file is an invoice in UBL syntax according to EN 16931 (e.g., an XRechnung) and that it
+    /// satisfies the business rules of the standard
+    Validate {
+        /// XML file with the invoice
+        file: String,
+
+        #[command(flatten)]
+        verify: VerifyArgs,
+    },
+
+    /// Convert the invoice lines from any supported source (e.g., a spreadsheet or a time tracking service) into a
+    /// CSV file, which can be edited before creating the invoice
+    Convert {
+        /// Buyer of the invoice lines (name or alias as given in the config file), whose rates are applied
+        #[arg(short, long)]
+        buyer: String,
+
+        #[command(flatten)]
+        lines: Box<LinesArgs>,
+
+        /// Output CSV file for the invoice lines to be written
+        #[arg(short, long)]
+        output: String,
+    },
+
+    /// Create the invoices that are listed in a manifest file (TOML with an `[[invoice]]` table per invoice, giving the
+    /// buyer, invoice number, invoice lines and optionally issue date, output file and options of `generate`)
+    Batch {
+        /// Manifest file with the invoices
+        manifest: String,
+
+        /// Overwrite the output files of the invoices, if they already exist
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Create an invoice by answering questions about the buyer, the invoice lines and the invoice number, with a
+    /// summary of the invoice lines and totals, in which the invoice lines can be edited before writing the invoice
+    Interactive,
+
+    /// Show the invoice lines and totals of an invoice without creating it
+    Preview {
+        #[command(flatten)]
+        invoice: Box<InvoiceArgs>,
+    },
+
+    /// Work with the configuration file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Work with the buyers in the configuration file
+    Buyers {
+        #[command(subcommand)]
+        command: BuyersCommand,
+    },
+
+    /// Work with the registry of the created invoices
+    Registry {
+        #[command(subcommand)]
+        command: RegistryCommand,
+    },
+
+    /// Send an invoice by email to the buyer (`invoice_email` or `email` of the buyer in the config file) via the mail
+    /// server of the config file (`[smtp]`), and record it in the registry
+    Send {
+        /// XML file with the invoice
+        file: String,
+
+        /// Further file to attach to the email (e.g., a PDF version of the invoice)
+        #[arg(long)]
+        attach: Vec<String>,
+
+        /// Email address to send the invoice to, instead of the one of the buyer
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Print the email instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Submit an invoice to the buyer via the channel configured for the buyer (`submission`) and record it in the
+    /// registry: via the Peppol access point of the config file (`[peppol]`, the default) or by email (`[smtp]`), e.g.,
+    /// to the invoicing portals ZRE and OZG-RE for public-sector buyers
+    Submit {
+        /// XML file with the invoice
+        file: String,
+
+        /// Print the document that would be submitted (the Standard Business Document or the email) instead of
+        /// submitting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export an invoice to an accounting service: lexoffice (with the API key in the environment variable
+    /// `LEXOFFICE_API_KEY`) or sevDesk (with the API token in `SEVDESK_API_TOKEN` and the contact of the buyer as
+    /// `sevdesk_contact` in the config file)
+    Export {
+        /// Accounting service to export the invoice to
+        #[arg(value_enum)]
+        service: export::Service,
+
+        /// XML file with the invoice
+        file: String,
+    },
+
+    /// Sum up the net amounts, VAT and gross amounts of the created invoices (as recorded in the registry) per month or
+    /// quarter and per buyer, e.g., for the advance VAT returns
+    Report {
+        /// Period to sum up the invoices for
+        #[arg(long, value_enum, default_value_t = report::Interval::Month)]
+        per: report::Interval,
+
+        /// Only report the invoices issued in this year
+        #[arg(long)]
+        year: Option<i32>,
+    },
+
+    /// Run an HTTP server that creates invoices for JSON requests (`POST /invoice` with the options of `generate` and
+    /// the invoice lines as JSON object) and responds with their XML, e.g., as internal service of a team
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum RegistryCommand {
+    /// List the created invoices with their numbers, issue dates, buyers, totals and files
+    List {
+        /// Only list the invoices of this buyer (name as given in the config file)
+        #[arg(short, long)]
+        buyer: Option<String>,
+
+        /// Only list the invoices issued in this year
+        #[arg(long)]
+        year: Option<i32>,
+    },
+
+    /// Show a created invoice and whether its file was changed since it was created
+    Show {
+        /// Number of the invoice
+        invoice_id: String,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum BuyersCommand {
+    /// Append buyers to the configuration file (TOML only) from contacts exported as CSV or vCard file, asking for all
+    /// values that are missing in the contacts (e.g., the VAT identification numbers)
+    Import {
+        /// CSV file with a header line (e.g., with the columns `name`, `email`, `tax_identification`, `street`,
+        /// `post_code`, `city` and `country`) or vCard file (`.vcf`) with the contacts
+        contacts: String,
+    },
+
+    /// List the buyers with their names and aliases (as accepted by `--buyer`), references, due days and VAT
+    List,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum ConfigCommand {
+    /// Create a configuration file (the one given with `--config`, or `$XDG_CONFIG_HOME/xrechnung/config.toml` or
+    /// `~/.config/xrechnung/config.toml`) by answering questions about the supplier, the bank account and a first
+    /// buyer
+    Init,
+
+    /// Append a buyer to the configuration file (TOML only), asking for all values not given as arguments
+    AddBuyer {
+        #[command(flatten)]
+        buyer: Box<init::BuyerFields>,
+    },
+
+    /// Check the configuration file for problems like invalid IBANs, VAT identification numbers or email addresses
+    Check,
+}
+
+/// Arguments for reading the invoice lines.
+#[derive(clap::Args, Debug)]
+struct LinesArgs {
+    /// CSV file (or Excel / OpenDocument spreadsheet, JSON array of line objects or TOML file with `[[line]]` tables)
+    /// that contains the invoice lines, HTTPS URL to download such a file from, or time tracking service to import them
+    /// from (e.g., `toggl:<workspace>/<project>`)
+    #[arg(short = 'l', long, alias = "invoice-lines")]
+    invoice_hours: String,
+
+    /// Sheet of the spreadsheet that contains the invoice lines (default: the first sheet)
+    #[arg(long)]
+    sheet: Option<String>,
+
+    /// Character that separates the fields in the CSV file (e.g., ';' for German spreadsheet exports)
+    #[arg(long, default_value_t = ',')]
+    delimiter: char,
+
+    /// Read numbers in the CSV file with a decimal comma and dots as thousands separators (e.g., "1.234,50")
+    #[arg(long)]
+    decimal_comma: bool,
+
+    /// Format of the dates in the CSV file (e.g., "%d.%m.%Y")
+    #[arg(long, default_value = input::DEFAULT_DATE_FORMAT)]
+    date_format: String,
+
+    /// First day of the time entries to import from time tracking services (default: first day of the month of the
+    /// invoice or of the issue date)
+    #[arg(long)]
+    from: Option<NaiveDate>,
+
+    /// Last day of the time entries to import from time tracking services (default: last day of the month of the
+    /// invoice or the issue date)
+    #[arg(long)]
+    to: Option<NaiveDate>,
+
+    /// Layout of the invoice lines: one line per row, a timesheet with one row per activity and the days as columns, or
+    /// the export of a time tracking service
+    #[arg(long, value_enum, default_value_t = input::Layout::Lines)]
+    layout: input::Layout,
+}
+
+/// Arguments for verifying invoices with the validator of KoSIT.
+#[derive(clap::Args, Debug)]
+struct VerifyArgs {
+    /// Verify the invoice with the official validator of KoSIT (Koordinierungsstelle für IT-Standards) and show its
+    /// report
+    #[arg(long)]
+    verify: bool,
+
+    /// Jar file of the KoSIT validator (run with Java) or URL of the validator running in daemon mode (e.g.,
+    /// "http://localhost:8080/")
+    #[arg(long)]
+    validator: Option<String>,
+
+    /// Scenario file of the validator configuration for XRechnung (needed for running the jar file)
+    #[arg(long)]
+    validator_scenarios: Option<String>,
+
+    /// Directory of the validator configuration for XRechnung (default: the directory of the scenario file)
+    #[arg(long)]
+    validator_repository: Option<String>,
+}
+
+/// Arguments for the creation of an invoice.
+#[derive(clap::Args, Debug)]
+struct InvoiceArgs {
+    /// The unique number of the invoice (default: the next number of the numbering scheme in the config file)
     #[arg(short, long)]
-    config: String,
+    invoice_id: Option<String>,
 
-    /// Buyer of the invoice
+    /// Buyer of the invoice (name or alias as given in the config file)
     #[arg(short, long)]
     buyer: String,
 
-    /// Issue date of the invoice
+    /// Issue date of the invoice (default: the last day of the month of the invoice, or today without month)
     #[arg(short = 'd', long)]
-    issue_date: NaiveDate,
+    issue_date: Option<NaiveDate>,
 
-    /// CSV file that contains the invoice lines
-    #[arg(short = 'l', long)]
-    invoice_hours: String,
+    /// Month of the invoice (e.g., "2025-01"), which is the billing period and the import period of time tracking
+    /// services. Invoice lines of other months are skipped.
+    #[arg(long, value_parser = parse_month)]
+    month: Option<NaiveDate>,
 
-    /// Output XML file for the invoice to be written
-    #[arg(short, long)]
-    output: String,
+    #[command(flatten)]
+    lines: LinesArgs,
+
+    /// Merge the invoice lines into one line per day, per item name or per project [possible values: day, name,
+    /// project]
+    #[arg(long)]
+    aggregate: Option<Aggregation>,
+
+    /// Sort the invoice lines by date and name, and let the billing period span from the first to the last date of
+    /// the invoice lines
+    #[arg(long)]
+    sort: bool,
+
+    /// Keep the invoice lines of each project together, ordered by the first appearance of the projects
+    #[arg(long)]
+    group_by_project: bool,
+
+    /// What to do with invoice lines that have the same date, name, quantity and rate as an earlier line
+    #[arg(long, value_enum, default_value_t = Duplicates::Warn)]
+    duplicates: Duplicates,
+
+    /// What to do with invoice lines whose dates lie outside of the billing period
+    #[arg(long, value_enum, default_value_t = PeriodCheck::Warn)]
+    period_check: PeriodCheck,
+
+    /// Treat the warnings about the invoice lines (e.g., lines with an amount of zero, without a date or with an
+    /// unusual rate) as errors
+    #[arg(long)]
+    strict: bool,
+
+    /// Note for this invoice (e.g., "Leistung gemäß Angebot 2024-17"), added after the configured notes of the buyer.
+    /// Can be given several times.
+    #[arg(long)]
+    note: Vec<String>,
+
+    /// File to attach to this invoice (PDF, PNG, JPEG, CSV, XLSX or ODS, e.g., a timesheet), optionally with a
+    /// description after the last colon (only if the part before it is an existing file, so that file names with
+    /// colons like `C:\docs\timesheet.pdf` work). Can be given several times.
+    #[arg(long, value_name = "FILE[:DESCRIPTION]")]
+    attach: Vec<String>,
+
+    /// VAT percentage for this invoice, overriding the configured one
+    #[arg(long)]
+    vat_percent: Option<f32>,
+
+    /// Charge no VAT for this invoice, as the buyer is liable for it (reverse charge), and add the note required for it
+    #[arg(long, conflicts_with_all = ["vat_exempt", "intra_community"])]
+    reverse_charge: bool,
+
+    /// Charge no VAT for this invoice, as it is exempt for the given reason (e.g., "Kleinunternehmer gemäß § 19 UStG"),
+    /// which is added as note
+    #[arg(long, value_name = "REASON", conflicts_with = "intra_community")]
+    vat_exempt: Option<String>,
+
+    /// Charge no VAT for this invoice, as it is an intra-community supply to a company in another EU member state, and
+    /// add the note required for it
+    #[arg(long)]
+    intra_community: bool,
+
+    /// Currency of this invoice as ISO 4217 code (e.g., "CHF"), overriding the configured one
+    #[arg(long)]
+    currency: Option<String>,
+
+    /// Number of days after the issue date that this invoice is due, overriding the configured one
+    #[arg(long)]
+    due_after_days: Option<i16>,
+
+    /// Due date of this invoice (e.g., for extended payment terms), instead of the one calculated from the number of
+    /// days after the issue date
+    #[arg(long, conflicts_with = "due_after_days")]
+    due_date: Option<NaiveDate>,
+
+    /// Discounts for early payment of this invoice as comma-separated list of percentages and days after the issue
+    /// date (e.g., "3%:7d,2%:14d"), which are added to the payment terms
+    #[arg(long, value_name = "DISCOUNTS")]
+    skonto: Option<String>,
+
+    /// Name of the bank account of the supplier (as given in the config file) for the payment of this invoice
+    #[arg(long)]
+    account: Option<String>,
+
+    /// IBAN of the bank account for the payment of this invoice, overriding the configured one
+    #[arg(long)]
+    iban: Option<String>,
+
+    /// BIC of the bank account for the payment of this invoice, overriding the configured one
+    #[arg(long)]
+    bic: Option<String>,
 }
 
-fn read_invoice_hours(
-    file_name: &str,
-) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>> {
-    let mut invoice_hours: Vec<InvoiceHoursElement> = Vec::new();
-    let file = File::open(file_name)?;
+/// Overrides the values of the configuration with the ones given on the command line for this invoice.
+fn apply_overrides(
+    args: &InvoiceArgs,
+    config: &mut Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(vat_percent) = args.vat_percent {
+        if !(0.0..=100.0).contains(&vat_percent) {
+            return Err(
+                format!("The VAT percentage {vat_percent} is not between 0 and 100.").into(),
+            );
+        }
+        config.vat_percent = vat_percent;
+    }
+    if let Some(currency) = &args.currency {
+        config.currency = currency.parse().map_err(|_| {
+            format!("The currency '{currency}' is no valid ISO 4217 currency code.")
+        })?;
+    }
+    if let Some(due_after_days) = args.due_after_days {
+        config.buyer.due_after_days = due_after_days;
+    }
+    if let Some(account) = &args.account {
+        config.select_account(account)?;
+    }
+    if let Some(iban) = &args.iban {
+        config.supplier.iban = iban.clone();
+    }
+    if let Some(bic) = &args.bic {
+        config.supplier.bic = bic.clone();
+    }
+    config.buyer.notes.extend(args.note.iter().cloned());
+    Ok(())
+}
+
+impl InvoiceArgs {
+    /// Returns the issue date of the invoice, which defaults to the last day of the month of the invoice, or to the
+    /// current date if no month is given.
+    fn issue_date(&self) -> NaiveDate {
+        self.issue_date
+            .or(self.month.map(|month| Period::month_of(month).end))
+            .unwrap_or_else(|| chrono::Local::now().date_naive())
+    }
+
+    /// Returns the VAT category of the invoice, which is standard rated unless given otherwise.
+    fn tax_category(&self) -> TaxCategory {
+        if self.reverse_charge {
+            TaxCategory::ReverseCharge
+        } else if self.intra_community {
+            TaxCategory::IntraCommunity
+        } else if let Some(reason) = &self.vat_exempt {
+            TaxCategory::Exempt(reason.clone())
+        } else {
+            TaxCategory::Standard
+        }
+    }
+}
+
+/// Parses a month in the format "YYYY-MM" (e.g., "2025-01") and returns its first day.
+fn parse_month(month: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+        .map_err(|_| format!("'{month}' is no month in the format YYYY-MM (e.g., \"2025-01\")."))
+}
+
+/// Returns the given configuration file, or the one in the default locations if none is given.
+fn config_file(config: &Option<String>) -> Result<String, Box<dyn std::error::Error>> {
+    match config {
+        Some(config_file) => Ok(config_file.clone()),
+        None => Ok(xrechnung::config::find_default()
+            .ok_or_else(|| {
+                error::config(
+                    "No configuration file given and none found in the default locations (see --help).",
+                )
+            })?
+            .to_string_lossy()
+            .into_owned()),
+    }
+}
+
+/// Returns the given registry file, or the one next to the configuration file if none is given.
+fn registry_file(registry: &Option<String>, config_file: &str) -> PathBuf {
+    match registry {
+        Some(registry) => PathBuf::from(registry),
+        None => registry::default_file(config_file),
+    }
+}
+
+/// Checks the configuration file and prints all problems that were found.
+fn check_config(config: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let config_file = config_file(config)?;
+    let problems = xrechnung::config::validate_config(&config_file)?;
+    if problems.is_empty() {
+        println!("No problems found in '{config_file}'.");
+        return Ok(());
+    }
+
+    for problem in &problems {
+        eprintln!("{problem}");
+    }
+    Err(format!(
+        "Found {} problem(s) in the configuration file '{config_file}'.",
+        problems.len()
+    )
+    .into())
+}
+
+/// Verifies the given invoice file with the KoSIT validator, if requested by the given arguments.
+fn verify(args: &VerifyArgs, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if !args.verify {
+        return Ok(());
+    }
+
+    #[cfg(feature = "kosit")]
+    {
+        let validator = kosit::Validator::new(
+            args.validator.as_deref(),
+            args.validator_scenarios.as_deref(),
+            args.validator_repository.as_deref(),
+        )?;
+        kosit::run(&validator, file_name)
+    }
+    #[cfg(not(feature = "kosit"))]
+    {
+        let _ = file_name;
+        Err("Verifying invoices with the KoSIT validator is not supported (feature 'kosit' is disabled).".into())
+    }
+}
+
+fn main() -> ExitCode {
+    // parse command line arguments, with errors of the arguments in the requested format as well
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(error) if error.use_stderr() => {
+            let args: Vec<String> = std::env::args().collect();
+            return error::report(&error, error::format_of_args(&args));
+        }
+        Err(error) => error.exit(),
+    };
+
+    log::init(cli.global.verbose, cli.global.log_format);
+    i18n::init(cli.global.language);
+    let format = cli.global.error_format;
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => error::report(error.as_ref(), format),
+    }
+}
+
+/// Runs the command given with the command line arguments.
+fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let config = cli.global.config;
+    let registry = cli.global.registry;
+    match cli.command {
+        Command::Generate {
+            invoice,
+            verify: verify_args,
+            dry_run,
+            summary_json,
+            force,
+            output,
+            archive,
+            export,
+            cover_letter,
+        } => {
+            let output = output.filter(|_| !dry_run);
+            let archive = archive.filter(|_| !dry_run);
+            if verify_args.verify && output.as_deref() == Some(STDOUT) {
+                return Err("Invoices written to the standard output cannot be verified.".into());
+            }
+            if export.is_some() && output.as_deref() == Some(STDOUT) {
+                return Err("Invoices written to the standard output cannot be exported.".into());
+            }
+            let config_file = config_file(&config)?;
+            let (invoice, output) = generate(
+                &config_file,
+                &registry_file(&registry, &config_file),
+                *invoice,
+                output.as_deref(),
+                archive.as_deref(),
+                force,
+            )?;
+            match &output {
+                Some(output) => {
+                    verify(&verify_args, output)?;
+                    if let Some(service) = export {
+                        export::run(service, &config_file, output)?;
+                    }
+                }
+                None => {
+                    if !summary_json {
+                        print!("{}", preview::totals(&invoice));
+                    }
+                    check_rules(&invoice)?;
+                }
+            }
+            if let Some(format) = cover_letter {
+                cover::write(&config_file, &invoice, output.as_deref(), format)?;
+            }
+            if summary_json {
+                let summary = preview::summary_json(&invoice, output.as_deref());
+                // the summary must not get mixed up with the invoice
+                match output.as_deref() {
+                    Some(STDOUT) => eprintln!("{summary}"),
+                    _ => println!("{summary}"),
+                }
+            }
+            Ok(())
+        }
+        Command::Validate {
+            file,
+            verify: verify_args,
+        } => {
+            validate::run(&file)?;
+            verify(&verify_args, &file)
+        }
+        Command::Convert {
+            buyer,
+            lines,
+            output,
+        } => {
+            let config =
+                xrechnung::config::load(&config_file(&config)?, &buyer).map_err(error::config)?;
+            let today = chrono::Local::now().date_naive();
+            let invoice_hours = input::LineFile {
+                file_name: &lines.invoice_hours,
+                config: &config,
+                options: &input_options(&lines, today)?,
+            }
+            .read_lines()
+            .map_err(error::input)?;
+            convert::write_lines(&invoice_hours, std::fs::File::create(&output)?)
+        }
+        Command::Batch { manifest, force } => {
+            let config_file = config_file(&config)?;
+            batch::run(
+                &config_file,
+                &registry_file(&registry, &config_file),
+                &manifest,
+                force,
+            )
+        }
+        Command::Interactive => {
+            let config_file = config_file(&config)?;
+            interactive::run(
+                &config_file,
+                &registry_file(&registry, &config_file),
+                std::io::stdin().lock(),
+                std::io::stdout(),
+            )
+        }
+        Command::Preview { invoice } => {
+            let invoice = prepare(&config_file(&config)?, *invoice)?;
+            print!(
+                "{}",
+                preview::summary(&invoice.config, &invoice.bill, &invoice.invoice_hours)
+            );
+            Ok(())
+        }
+        Command::Config {
+            command: ConfigCommand::Init,
+        } => {
+            let config_file = match config {
+                Some(config_file) => config_file,
+                None => xrechnung::config::default_location()
+                    .ok_or("No configuration file given and no default location known (HOME is not set).")?
+                    .to_string_lossy()
+                    .into_owned(),
+            };
+            init::run(&config_file, std::io::stdin().lock(), std::io::stdout())
+        }
+        Command::Config {
+            command: ConfigCommand::AddBuyer { buyer },
+        } => init::add_buyers(
+            &config_file(&config)?,
+            std::slice::from_ref(&*buyer),
+            std::io::stdin().lock(),
+            std::io::stdout(),
+        ),
+        Command::Config {
+            command: ConfigCommand::Check,
+        } => check_config(&config),
+        Command::Buyers {
+            command: BuyersCommand::Import { contacts },
+        } => init::add_buyers(
+            &config_file(&config)?,
+            &buyers::read_contacts(&contacts)?,
+            std::io::stdin().lock(),
+            std::io::stdout(),
+        ),
+        Command::Buyers {
+            command: BuyersCommand::List,
+        } => {
+            let config =
+                xrechnung::config::load_all(&config_file(&config)?).map_err(error::config)?;
+            print!("{}", buyers::table(&config));
+            Ok(())
+        }
+        Command::Registry {
+            command: RegistryCommand::List { buyer, year },
+        } => registry::list(
+            &registry_file(&registry, &config_file(&config)?),
+            buyer.as_deref(),
+            year,
+        ),
+        Command::Registry {
+            command: RegistryCommand::Show { invoice_id },
+        } => registry::show(
+            &registry_file(&registry, &config_file(&config)?),
+            &invoice_id,
+        ),
+        Command::Send {
+            file,
+            attach,
+            to,
+            dry_run,
+        } => {
+            #[cfg(feature = "smtp")]
+            {
+                let config_file = config_file(&config)?;
+                send::run(
+                    &config_file,
+                    &registry_file(&registry, &config_file),
+                    &file,
+                    &attach,
+                    to.as_deref(),
+                    dry_run,
+                )
+            }
+            #[cfg(not(feature = "smtp"))]
+            {
+                let _ = (file, attach, to, dry_run);
+                Err(
+                    "Sending invoices by email is not supported (feature 'smtp' is disabled)."
+                        .into(),
+                )
+            }
+        }
+        Command::Submit { file, dry_run } => {
+            let config_file = config_file(&config)?;
+            submit::run(
+                &config_file,
+                &registry_file(&registry, &config_file),
+                &file,
+                dry_run,
+            )
+        }
+        Command::Export { service, file } => export::run(service, &config_file(&config)?, &file),
+        Command::Report { per, year } => {
+            report::run(&registry_file(&registry, &config_file(&config)?), per, year)
+        }
+        Command::Serve { listen } => {
+            #[cfg(feature = "serve")]
+            {
+                serve::run(&config_file(&config)?, &listen)
+            }
+            #[cfg(not(feature = "serve"))]
+            {
+                let _ = listen;
+                Err("The HTTP server is not supported (feature 'serve' is disabled).".into())
+            }
+        }
+    }
+}
+
+/// Returns the options for reading the invoice lines. Time entries are imported from the first day of the month of
+/// the given date up to the date, if not given otherwise.
+fn input_options(
+    lines: &LinesArgs,
+    date: NaiveDate,
+) -> Result<input::InputOptions, Box<dyn std::error::Error>> {
+    if !lines.delimiter.is_ascii() {
+        return Err("The delimiter of the CSV file needs to be an ASCII character.".into());
+    }
+
+    Ok(input::InputOptions {
+        delimiter: lines.delimiter as u8,
+        decimal_comma: lines.decimal_comma,
+        date_format: lines.date_format.clone(),
+        sheet: lines.sheet.clone(),
+        layout: lines.layout,
+        import_period: Some(Period {
+            start: lines.from.unwrap_or(Period::month_of(date).start),
+            end: lines.to.unwrap_or(date),
+        }),
+    })
+}
+
+/// Creates an invoice as given by the command line arguments and writes it to the output file or archive directory (if
+/// any, which is not the case for a dry run) and records it in the registry. An existing output file, archived invoice
+/// or invoice number of the registry is only used again, if forced. Returns the XML structure of the invoice and the
+/// file it was written to.
+fn generate(
+    config_file: &str,
+    registry: &std::path::Path,
+    args: InvoiceArgs,
+    output: Option<&str>,
+    archive: Option<&str>,
+    force: bool,
+) -> Result<(xrechnung::XmlElement, Option<String>), Box<dyn std::error::Error>> {
+    if let Some(output) = output {
+        check_output(output, force)?;
+    }
+    let invoice = prepare(config_file, args)?;
+    create(invoice, registry, output, archive, force)
+}
+
+/// Returns an error, if the given output file already exists and overwriting it is not forced.
+fn check_output(output: &str, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+    // an existing file might be an invoice that was already sent and must be kept for the records
+    if output != STDOUT && !force && std::path::Path::new(output).exists() {
+        return Err(format!(
+            "The output file '{output}' already exists (use --force to overwrite it)."
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Creates the prepared invoice and writes it to the output file or archive directory (if any) and records it in the
+/// registry. Returns the XML structure of the invoice and the file it was written to.
+fn create(
+    invoice: PreparedInvoice,
+    registry: &std::path::Path,
+    output: Option<&str>,
+    archive: Option<&str>,
+    force: bool,
+) -> Result<(xrechnung::XmlElement, Option<String>), Box<dyn std::error::Error>> {
+    let PreparedInvoice {
+        config,
+        bill,
+        invoice_hours,
+        attachments,
+        strict,
+        numbering,
+    } = invoice;
+
+    if !force {
+        registry::check_unused(registry, &bill.number)?;
+    }
+
+    // the invoice numbers must be unique, so an archived invoice is never created again
+    let output = match archive {
+        Some(directory) => {
+            if !force
+                && let Some(archived) =
+                    archive::find(directory, &config.supplier.name, &bill.number)?
+            {
+                return Err(format!(
+                    "The invoice {} is already archived as '{}' (use --force to create it again).",
+                    bill.number,
+                    archived.display()
+                )
+                .into());
+            }
+            let file_name = archive::file_name(
+                directory,
+                &config.supplier.name,
+                &config.buyer.name,
+                &bill.number,
+                bill.issue_date.year(),
+            );
+            if let Some(parent) = file_name.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            Some(file_name.to_string_lossy().into_owned())
+        }
+        None => output.map(str::to_string),
+    };
+
+    // create XML structure for the invoice from the supplier, buyer, invoice metadata and invoice hours
+    let (mut xml_root, warnings) =
+        xrechnung::create(config.supplier, config.buyer, bill, invoice_hours)?;
+    check_warnings(&warnings, strict)?;
+    xrechnung::attachments::embed(&mut xml_root, &attachments);
+    log::info!(
+        "totals",
+        "Calculated the totals of the invoice {}: net {}, VAT {}, gross {} {}.",
+        preview::text(&xml_root, &["cbc:ID"]),
+        preview::text(
+            &xml_root,
+            &["cac:LegalMonetaryTotal", "cbc:TaxExclusiveAmount"]
+        ),
+        preview::text(&xml_root, &["cac:TaxTotal", "cbc:TaxAmount"]),
+        preview::text(
+            &xml_root,
+            &["cac:LegalMonetaryTotal", "cbc:TaxInclusiveAmount"]
+        ),
+        preview::text(&xml_root, &["cbc:DocumentCurrencyCode"])
+    );
+
+    // finally write the XML structure to a file and record it together with the hash of the written content
+    let content = match output.as_deref() {
+        Some(STDOUT) => Some(write_stdout(&xml_root)?),
+        Some(output) => {
+            xrechnung::write(output, &xml_root)?;
+            Some(std::fs::read(output)?)
+        }
+        None => None,
+    };
+    if let Some(content) = content {
+        log::info!(
+            "write",
+            "Wrote the invoice ({} bytes) to '{}'.",
+            content.len(),
+            output.as_deref().unwrap_or_default()
+        );
+        let summary = preview::summary_json(&xml_root, output.as_deref());
+        registry::record(registry, &summary, &content)?;
+        log::debug!(
+            "write",
+            "Recorded the invoice in the registry '{}'.",
+            registry.display()
+        );
+    }
+
+    // the allocated invoice number is only used up by a created invoice (not by a dry run)
+    if output.is_some()
+        && let Some((state_file, counter, _lock)) = &numbering
+    {
+        xrechnung::numbering::write_counter(state_file, counter)?;
+        log::debug!(
+            "numbering",
+            "Stored the invoice number in the state file '{}'.",
+            state_file.display()
+        );
+    }
+    Ok((xml_root, output))
+}
+
+/// Writes the XML structure of an invoice to the standard output, e.g., for piping it into other tools. Returns the
+/// written content.
+fn write_stdout(root: &xrechnung::XmlElement) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut content = xrechnung::to_xml_string(root)?.into_bytes();
+    content.push(b'\n');
+    std::io::stdout().lock().write_all(&content)?;
+    Ok(content)
+}
+
+/// Prints the given warnings about an invoice. They are errors, if the invoice is created strictly.
+fn check_warnings(
+    warnings: &[xrechnung::diagnostics::Warning],
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for warning in warnings {
+        eprintln!("{}", i18n::translate(&format!("Warning: {warning}")));
+    }
+    if strict && !warnings.is_empty() {
+        return Err(error::validation(format!(
+            "Found {} warning(s) about the invoice lines (not allowed with --strict).",
+            warnings.len()
+        )));
+    }
+    Ok(())
+}
 
-    for result in csv::Reader::from_reader(file).deserialize() {
-        invoice_hours.push(result?);
+/// Validates the given invoice against the business rules and prints all violations that were found.
+fn check_rules(invoice: &xrechnung::XmlElement) -> Result<(), Box<dyn std::error::Error>> {
+    let violations = xrechnung::validate::validate(invoice);
+    log::info!(
+        "validation",
+        "Validated the invoice against the business rules: {} violation(s).",
+        violations.len()
+    );
+    for violation in &violations {
+        eprintln!("{violation}");
     }
+    if !violations.is_empty() {
+        return Err(error::validation(format!(
+            "Found {} violation(s) of the business rules in the invoice.",
+            violations.len()
+        )));
+    }
+    Ok(())
+}
 
-    Ok(invoice_hours)
+/// An invoice as prepared from the command line arguments, ready to be created.
+struct PreparedInvoice {
+    config: Config,
+    bill: Bill,
+    invoice_hours: Vec<InvoiceHoursElement>,
+    attachments: Vec<Attachment>,
+
+    /// Whether warnings about the invoice lines are treated as errors.
+    strict: bool,
+
+    /// The state file and the new state of the invoice numbering, if the invoice number was allocated automatically.
+    /// The state is only stored once the invoice was created, so that no numbers are skipped. The state file stays
+    /// locked until then, so that concurrent runs do not allocate the same number.
+    numbering: Option<(
+        PathBuf,
+        xrechnung::numbering::Counter,
+        xrechnung::numbering::Lock,
+    )>,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // parse command line arguments and load configuration based on them
-    let args = Args::parse();
-    let config = xrechnung::config::load(&args.config, &args.buyer)?;
+/// Loads the configuration and reads the invoice lines for the invoice as given by the command line arguments, and
+/// returns them together with the metadata of the invoice.
+fn prepare(
+    config_file: &str,
+    args: InvoiceArgs,
+) -> Result<PreparedInvoice, Box<dyn std::error::Error>> {
+    let file_name = args.lines.invoice_hours.clone();
+    prepare_with(config_file, args, |config, options| {
+        input::LineFile {
+            file_name: &file_name,
+            config,
+            options,
+        }
+        .read_lines()
+    })
+}
+
+/// Prepares the invoice as given by the command line arguments like [`prepare`], but with the invoice lines read by
+/// the given function (from the configuration of the buyer and the input options) instead of from the given file.
+fn prepare_with(
+    config_file: &str,
+    args: InvoiceArgs,
+    read_lines: impl FnOnce(
+        &Config,
+        &input::InputOptions,
+    ) -> Result<Vec<InvoiceHoursElement>, Box<dyn std::error::Error>>,
+) -> Result<PreparedInvoice, Box<dyn std::error::Error>> {
+    // load configuration based on the command line arguments
+    let mut config = xrechnung::config::load(config_file, &args.buyer).map_err(error::config)?;
+    apply_overrides(&args, &mut config)?;
+    log::info!(
+        "config",
+        "Loaded the configuration of the buyer '{}' from '{config_file}'.",
+        config.buyer.name
+    );
+    log::debug!(
+        "config",
+        "Currency {}, VAT {}%, due {} day(s) after the issue date.",
+        config.currency,
+        config.vat_percent,
+        config.buyer.due_after_days
+    );
+
+    let issue_date = args.issue_date();
+    if let Some(due_date) = args.due_date
+        && due_date < issue_date
+    {
+        return Err(format!(
+            "The due date {due_date} lies before the issue date {issue_date} of the invoice."
+        )
+        .into());
+    }
+
+    // without invoice number, the next number of the configured numbering scheme is allocated
+    let (invoice_id, numbering) = match &args.invoice_id {
+        Some(invoice_id) => (invoice_id.clone(), None),
+        None => {
+            let numbering = config.numbering.as_ref().ok_or(
+                "No invoice number given (--invoice-id) and no automatic numbering configured (`[numbering]` in the \
+                 configuration file).",
+            )?;
+            let state_file = numbering.state_file(config_file);
+            let lock = xrechnung::numbering::lock(&state_file)?;
+            let last = xrechnung::numbering::read_counter(&state_file)?;
+            let (invoice_id, counter) = numbering.next(last.as_ref(), issue_date)?;
+            log::info!("numbering", "Allocated the invoice number {invoice_id}.");
+            (invoice_id, Some((state_file, counter, lock)))
+        }
+    };
 
     // read the invoice hours from the given CSV file
-    let invoice_hours = read_invoice_hours(&args.invoice_hours)?;
+    let mut options = input_options(&args.lines, issue_date)?;
+    if let Some(month) = args.month {
+        let month = Period::month_of(month);
+        options.import_period = Some(Period {
+            start: args.lines.from.unwrap_or(month.start),
+            end: args.lines.to.unwrap_or(month.end),
+        });
+    }
+    let mut invoice_hours = read_lines(&config, &options).map_err(error::input)?;
+    log::info!(
+        "input",
+        "Read {} invoice line(s) from '{}'.",
+        invoice_hours.len(),
+        args.lines.invoice_hours
+    );
+
+    // only the lines of the month of the invoice are billed
+    if let Some(month) = args.month {
+        let outside = xrechnung::lines::outside_period(&invoice_hours, &Period::month_of(month))?;
+        if !outside.is_empty() {
+            eprintln!(
+                "{}",
+                i18n::translate(&format!(
+                    "Skipped {} invoice line(s) outside of the month {}.",
+                    outside.len(),
+                    month.format("%Y-%m")
+                ))
+            );
+            let mut index = 0;
+            invoice_hours.retain(|_| {
+                index += 1;
+                !outside.contains(&(index - 1))
+            });
+        }
+    }
+
+    // lines of projects get the references that are configured for the project
+    xrechnung::lines::apply_project_settings(&mut invoice_hours, &config.buyer.projects);
+
+    // identical lines usually come from exporting the same time entries twice
+    match args.duplicates {
+        Duplicates::Remove => {
+            let removed = xrechnung::lines::remove_duplicates(&mut invoice_hours);
+            if removed > 0 {
+                eprintln!(
+                    "{}",
+                    i18n::translate(&format!("Removed {removed} duplicate invoice line(s)."))
+                );
+            }
+        }
+        check => {
+            let problems: Vec<String> = xrechnung::lines::find_duplicates(&invoice_hours)
+                .iter()
+                .map(|(index, original)| {
+                    format!(
+                        "The invoice line {} ('{}') is identical to the invoice line {}.",
+                        index + 1,
+                        invoice_hours[*index].name,
+                        original + 1
+                    )
+                })
+                .collect();
+
+            match check {
+                Duplicates::Fail if !problems.is_empty() => return Err(problems.join("\n").into()),
+                _ => problems.iter().for_each(|problem| {
+                    eprintln!("{}", i18n::translate(&format!("Warning: {problem}")))
+                }),
+            }
+        }
+    }
+
+    // round the quantities to the billing increments agreed with the buyer
+    if let Some(rounding) = &config.buyer.rounding {
+        xrechnung::lines::round_quantities(&mut invoice_hours, rounding)?;
+    }
+
+    // raise quantities below the minimum billable quantity agreed with the buyer
+    if let Some(minimum) = config.buyer.minimum_quantity {
+        xrechnung::lines::apply_minimum_quantity(
+            &mut invoice_hours,
+            minimum,
+            config.buyer.note_minimum_quantity,
+            config.buyer.language.as_deref(),
+        );
+    }
 
-    // the start of the billing period is either the first date of the invoice hours, or if that does not exist
-    // then the billing period is the first day of the month of the issue date of the bill
-    let start = if invoice_hours.iter().count() > 0 && invoice_hours[0].date.is_some() {
-        NaiveDate::parse_from_str(&invoice_hours[0].date.as_ref().unwrap(), "%Y-%m-%d").unwrap()
+    // optionally merge the raw time entries into fewer invoice lines
+    if let Some(aggregation) = args.aggregate {
+        invoice_hours = xrechnung::lines::aggregate(invoice_hours, aggregation)?;
+    }
+
+    // the billing period is the month of the invoice, if given. Otherwise, it either spans the dates of the sorted
+    // invoice lines, or it starts at the first date of the invoice hours (or if that does not exist, on the first day
+    // of the month of the issue date) and ends on the issue date of the bill
+    let period = if args.sort {
+        xrechnung::lines::sort_by_date(&mut invoice_hours)?;
+        xrechnung::lines::date_range(&invoice_hours)?
     } else {
-        args.issue_date.with_day(1).unwrap() // billing period starts on first day of the month of the issue date
+        None
     };
 
-    let bill = Bill::new(
-        args.invoice_id,
-        args.issue_date,
-        Some(Period {
-            start: start,
-            end: args.issue_date, // billing period ends on the issue date
-        }),
-        &config,
+    let period = match (args.month, period) {
+        (Some(month), _) => Period::month_of(month),
+        (None, Some(period)) => period,
+        (None, None) => {
+            let first_period = match invoice_hours.first() {
+                Some(line) => line.period()?,
+                None => None,
+            };
+            // billing period starts on the first date of the invoice hours (or on the first day of the month of the
+            // issue date) and ends on the issue date
+            let start = first_period.unwrap_or(Period::month_of(issue_date)).start;
+            Period::new(start, issue_date)
+        }
+    };
+
+    // keep the lines of each project together, in the (possibly sorted) order of the lines within the project
+    if args.group_by_project {
+        xrechnung::lines::group_by_project(&mut invoice_hours);
+    }
+
+    // catch invoice lines of other billing periods, e.g., entries of the last month that were copied by mistake
+    if !matches!(args.period_check, PeriodCheck::Ignore) {
+        let outside = xrechnung::lines::outside_period(&invoice_hours, &period)?;
+        let problems: Vec<String> = outside
+            .iter()
+            .map(|index| {
+                let line = &invoice_hours[*index];
+                format!(
+                    "The invoice line {} ('{}') lies outside of the billing period from {} to {}.",
+                    index + 1,
+                    line.name,
+                    period.start,
+                    period.end
+                )
+            })
+            .collect();
+
+        match args.period_check {
+            PeriodCheck::Fail if !problems.is_empty() => return Err(problems.join("\n").into()),
+            _ => problems.iter().for_each(|problem| {
+                eprintln!("{}", i18n::translate(&format!("Warning: {problem}")))
+            }),
+        }
+    }
+
+    log::info!(
+        "lines",
+        "Prepared {} invoice line(s) for the billing period from {} to {}.",
+        invoice_hours.len(),
+        period.start,
+        period.end
     );
+    let mut bill = Bill::new(invoice_id, issue_date, Some(period), &config);
+    bill.tax_category = args.tax_category();
+    if let Some(skonto) = &args.skonto {
+        bill.cash_discounts = xrechnung::data::parse_cash_discounts(skonto)
+            .map_err(|e| format!("Invalid value for '--skonto': {e}."))?;
+    }
 
-    // create XML structure for the invoice from the supplier, buyer, invoice metadata and invoice hours
-    let xml_root = xrechnung::create(config.supplier, config.buyer, bill, invoice_hours)?;
+    // a negotiated due date replaces the one calculated from the configuration
+    if let Some(due_date) = args.due_date {
+        bill.due_date = due_date;
+    }
+    // the attached files are read right away, so that missing files are noticed before the invoice is created
+    let attachments = args
+        .attach
+        .iter()
+        .map(|attach| match attach.rsplit_once(':') {
+            // the colon might also be part of the file name (e.g., a drive letter on Windows)
+            Some((file_name, description)) if std::path::Path::new(file_name).is_file() => {
+                Attachment::from_file(file_name, Some(description))
+            }
+            _ => Attachment::from_file(attach, None),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(error::input)?;
 
-    // finally write the XML structure to a file
-    xrechnung::write(&args.output, &xml_root)
+    Ok(PreparedInvoice {
+        config,
+        bill,
+        invoice_hours,
+        attachments,
+        strict: args.strict,
+        numbering,
+    })
 }
@@ -0,0 +1,323 @@
+//! Submission of invoices to the Peppol network (`submit`) via the REST API of an access point (`[peppol]` in the
+//! configuration). The invoice is wrapped in a Standard Business Document (SBD), whose header gives the participant
+//! identifiers of the sender and the receiver and the document type and process of the invoice.
+
+use std::io::Write;
+use std::path::Path;
+
+use xrechnung::XmlElement;
+use xrechnung::config::Peppol;
+use xrechnung::json::{self, Value};
+
+/// The namespace of the Standard Business Document Header.
+const SBDH_NAMESPACE: &str =
+    "http://www.unece.org/cefact/namespaces/StandardBusinessDocumentHeader";
+
+/// The scheme of the Peppol participant identifiers.
+const PARTICIPANT_SCHEME: &str = "iso6523-actorid-upis";
+
+/// The document type of UBL invoices, which is completed with the customization of the invoice.
+const UBL_INVOICE: &str = "urn:oasis:names:specification:ubl:schema:xsd:Invoice-2";
+
+/// The names of the fields of the response in which access points usually return the identifier of the transmission.
+const TRANSMISSION_ID_FIELDS: [&str; 4] = ["transmissionId", "transmission_id", "messageId", "id"];
+
+/// Returns the text of the element at the given path of (qualified) names below the given element, if it exists.
+fn text<'a>(element: &'a XmlElement, path: &[&str]) -> Option<&'a str> {
+    path.iter()
+        .try_fold(element, |element, name| {
+            element
+                .children()
+                .iter()
+                .find(|child| child.name() == *name)
+        })
+        .and_then(XmlElement::text)
+}
+
+/// Returns the Peppol participant identifier (`<scheme>:<identifier>`) of the receiver of the given invoice, which is
+/// the electronic address of the buyer.
+fn receiver(invoice: &XmlElement) -> Result<String, String> {
+    let endpoint = ["cac:AccountingCustomerParty", "cac:Party", "cbc:EndpointID"];
+    let element = endpoint
+        .iter()
+        .try_fold(invoice, |element, name| {
+            element
+                .children()
+                .iter()
+                .find(|child| child.name() == *name)
+        })
+        .ok_or("The invoice has no electronic address of the buyer.")?;
+    match (element.attribute("schemeID"), element.text()) {
+        (Some(scheme), Some(identifier)) if scheme.chars().all(|c| c.is_ascii_digit()) => {
+            Ok(format!("{scheme}:{identifier}"))
+        }
+        (scheme, _) => Err(format!(
+            "The electronic address of the buyer (scheme '{}') is no Peppol participant identifier (set \
+             `endpoint_scheme` and `endpoint` of the buyer in the configuration file).",
+            scheme.unwrap_or_default()
+        )),
+    }
+}
+
+/// Returns the Standard Business Document with the given invoice, sent by the given participant at the given time.
+fn envelope(
+    invoice: XmlElement,
+    sender: &str,
+    created: chrono::DateTime<chrono::FixedOffset>,
+) -> Result<XmlElement, String> {
+    let receiver = receiver(&invoice)?;
+    let customization = text(&invoice, &["cbc:CustomizationID"])
+        .unwrap_or_default()
+        .to_string();
+    let profile = text(&invoice, &["cbc:ProfileID"])
+        .unwrap_or_default()
+        .to_string();
+    let invoice_number = text(&invoice, &["cbc:ID"]).unwrap_or_default();
+    let country = text(
+        &invoice,
+        &[
+            "cac:AccountingSupplierParty",
+            "cac:Party",
+            "cac:PostalAddress",
+            "cac:Country",
+            "cbc:IdentificationCode",
+        ],
+    )
+    .unwrap_or_default()
+    .to_string();
+
+    // the identifier of the document instance is unique per invoice and submission
+    let digest = crate::sha256::hex_digest(
+        format!("{sender}{invoice_number}{}", created.to_rfc3339()).as_bytes(),
+    );
+    let instance = format!(
+        "{}-{}-{}-{}-{}",
+        &digest[0..8],
+        &digest[8..12],
+        &digest[12..16],
+        &digest[16..20],
+        &digest[20..32]
+    );
+
+    let identifier = |name: &str, participant: &str| {
+        XmlElement::new(
+            name,
+            None,
+            Some(vec![XmlElement::new_leaf(
+                "Identifier",
+                Some(vec![("Authority", PARTICIPANT_SCHEME)]),
+                participant,
+            )]),
+        )
+    };
+    let scope = |scope_type: &str, instance: &str, identifier: Option<&str>| {
+        let mut children = vec![
+            XmlElement::new_leaf("Type", None, scope_type),
+            XmlElement::new_leaf("InstanceIdentifier", None, instance),
+        ];
+        children.extend(
+            identifier.map(|identifier| XmlElement::new_leaf("Identifier", None, identifier)),
+        );
+        XmlElement::new("Scope", None, Some(children))
+    };
+
+    let header = XmlElement::new(
+        "StandardBusinessDocumentHeader",
+        None,
+        Some(vec![
+            XmlElement::new_leaf("HeaderVersion", None, "1.0"),
+            identifier("Sender", sender),
+            identifier("Receiver", &receiver),
+            XmlElement::new(
+                "DocumentIdentification",
+                None,
+                Some(vec![
+                    XmlElement::new_leaf("Standard", None, UBL_INVOICE),
+                    XmlElement::new_leaf("TypeVersion", None, "2.1"),
+                    XmlElement::new_leaf("InstanceIdentifier", None, &instance),
+                    XmlElement::new_leaf("Type", None, "Invoice"),
+                    XmlElement::new_leaf(
+                        "CreationDateAndTime",
+                        None,
+                        &created.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+                    ),
+                ]),
+            ),
+            XmlElement::new(
+                "BusinessScope",
+                None,
+                Some(vec![
+                    scope(
+                        "DOCUMENTID",
+                        &format!("{UBL_INVOICE}::Invoice##{customization}::2.1"),
+                        Some("busdox-docid-qns"),
+                    ),
+                    scope("PROCESSID", &profile, Some("cenbii-procid-ubl")),
+                    scope("COUNTRY_C1", &country, None),
+                ]),
+            ),
+        ]),
+    );
+    Ok(XmlElement::new(
+        "StandardBusinessDocument",
+        Some(vec![("xmlns", SBDH_NAMESPACE)]),
+        Some(vec![header, invoice]),
+    ))
+}
+
+/// Returns the identifier of the transmission from the response of the access point, if it contains one.
+fn transmission_id(response: &[u8]) -> Option<String> {
+    let response = json::parse(&String::from_utf8_lossy(response)).ok()?;
+    TRANSMISSION_ID_FIELDS
+        .iter()
+        .find_map(|field| match response.get(field)? {
+            Value::String(id) | Value::Number(id) => Some(id.clone()),
+            _ => None,
+        })
+}
+
+/// Submits the given invoice file to the Peppol network via the access point of the configuration and records it in the
+/// given registry file. With `dry_run`, the Standard Business Document is only printed.
+pub fn run(
+    config_file: &str,
+    registry: &Path,
+    file: &str,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let invoice = XmlElement::parse(
+        &std::fs::read_to_string(file)
+            .map_err(|e| format!("Could not read the invoice '{file}': {e}"))?,
+    )?;
+    let invoice_number = text(&invoice, &["cbc:ID"]).unwrap_or_default().to_string();
+    let config = xrechnung::config::load_all(config_file).map_err(crate::error::config)?;
+    let Peppol { url, token, sender } = config
+        .peppol
+        .ok_or("No Peppol access point configured for submitting invoices (`[peppol]` in the configuration file).")?;
+
+    let document = envelope(invoice, &sender, chrono::Local::now().fixed_offset())?;
+    let content = xrechnung::to_xml_string(&document)?;
+    if dry_run {
+        println!("{content}");
+        return Ok(());
+    }
+
+    let (envelope_file, mut file) = crate::http::create_temporary_file("xrechnung-peppol", "xml")?;
+    if let Err(error) = file
+        .write_all(content.as_bytes())
+        .and_then(|_| file.flush())
+    {
+        let _ = std::fs::remove_file(&envelope_file);
+        return Err(error.into());
+    }
+    drop(file);
+    let authorization = token.map(|token| format!("Bearer {token}"));
+    let headers: Vec<(&str, &str)> = authorization
+        .iter()
+        .map(|authorization| ("Authorization", authorization.as_str()))
+        .collect();
+    let response = crate::http::post_file(
+        &url,
+        &envelope_file.to_string_lossy(),
+        "application/xml",
+        &headers,
+    );
+    let _ = std::fs::remove_file(&envelope_file);
+    let (status, body) = response?;
+    if !(200..300).contains(&status) {
+        return Err(format!(
+            "The access point at '{url}' refused the invoice with status {status}: {}",
+            String::from_utf8_lossy(&body).trim()
+        )
+        .into());
+    }
+
+    let receiver = text(
+        &document,
+        &["StandardBusinessDocumentHeader", "Receiver", "Identifier"],
+    )
+    .unwrap_or_default()
+    .to_string();
+    let transmission = transmission_id(&body).unwrap_or_else(|| "unknown".to_string());
+    crate::registry::record_sent(
+        registry,
+        &invoice_number,
+        &format!("Peppol participant {receiver} (transmission {transmission})"),
+    )?;
+    println!(
+        "Submitted the invoice {invoice_number} to {receiver} (transmission ID {transmission})."
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use xrechnung::XmlElement;
+
+    fn invoice(scheme: &str, endpoint: &str) -> XmlElement {
+        XmlElement::parse(&format!(
+            "<ubl:Invoice xmlns:ubl=\"urn:oasis:names:specification:ubl:schema:xsd:Invoice-2\" \
+             xmlns:cac=\"cac\" xmlns:cbc=\"cbc\">\
+             <cbc:CustomizationID>urn:cen.eu:en16931:2017</cbc:CustomizationID>\
+             <cbc:ProfileID>urn:fdc:peppol.eu:2017:poacc:billing:01:1.0</cbc:ProfileID>\
+             <cbc:ID>2025-001</cbc:ID>\
+             <cac:AccountingSupplierParty><cac:Party><cac:PostalAddress><cac:Country>\
+             <cbc:IdentificationCode>DE</cbc:IdentificationCode></cac:Country></cac:PostalAddress></cac:Party>\
+             </cac:AccountingSupplierParty>\
+             <cac:AccountingCustomerParty><cac:Party>\
+             <cbc:EndpointID schemeID=\"{scheme}\">{endpoint}</cbc:EndpointID></cac:Party></cac:AccountingCustomerParty>\
+             </ubl:Invoice>"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_envelope() {
+        let created = chrono::DateTime::parse_from_rfc3339("2025-01-31T10:00:00+01:00").unwrap();
+        let document = super::envelope(
+            invoice("0088", "4000001123452"),
+            "9930:DE123456789",
+            created,
+        )
+        .unwrap();
+        let xml = xrechnung::to_xml_string(&document).unwrap();
+        assert!(xml.contains("<Sender>\n            <Identifier Authority=\"iso6523-actorid-upis\">9930:DE123456789</Identifier>"));
+        assert!(xml.contains(
+            "<Identifier Authority=\"iso6523-actorid-upis\">0088:4000001123452</Identifier>"
+        ));
+        assert!(xml.contains(
+            "<InstanceIdentifier>urn:oasis:names:specification:ubl:schema:xsd:Invoice-2::Invoice##urn:cen.eu:en16931:2017::2.1</InstanceIdentifier>"
+        ));
+        assert!(xml.contains(
+            "<InstanceIdentifier>urn:fdc:peppol.eu:2017:poacc:billing:01:1.0</InstanceIdentifier>"
+        ));
+        assert!(
+            xml.contains("<CreationDateAndTime>2025-01-31T10:00:00+01:00</CreationDateAndTime>")
+        );
+        assert!(xml.contains(
+            "<Type>COUNTRY_C1</Type>\n                <InstanceIdentifier>DE</InstanceIdentifier>"
+        ));
+        assert!(xml.contains("<cbc:ID>2025-001</cbc:ID>"));
+
+        assert!(
+            super::envelope(
+                invoice("EM", "mail@client1.example.com"),
+                "9930:DE123456789",
+                created
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_transmission_id() {
+        assert_eq!(
+            super::transmission_id(br#"{"transmissionId":"abc-123"}"#),
+            Some("abc-123".to_string())
+        );
+        assert_eq!(
+            super::transmission_id(br#"{"id":42}"#),
+            Some("42".to_string())
+        );
+        assert_eq!(super::transmission_id(b"accepted"), None);
+    }
+}
@@ -0,0 +1,244 @@
+//! Textual preview of an invoice (`preview`), which shows the invoice lines and totals without creating the invoice.
+
+use xrechnung::XmlElement;
+use xrechnung::config::Config;
+use xrechnung::data::{Bill, InvoiceHoursElement};
+use xrechnung::json::Value;
+
+/// Returns a summary of the invoice with its metadata, a table of the invoice lines and the totals.
+pub fn summary(config: &Config, bill: &Bill, invoice_hours: &[InvoiceHoursElement]) -> String {
+    let mut summary = format!(
+        "Invoice {} for {}\nIssue date: {}\nDue date: {}\n",
+        bill.number, config.buyer.name, bill.issue_date, bill.due_date
+    );
+    if let Some(period) = &bill.period {
+        summary.push_str(&format!("Period: {} to {}\n", period.start, period.end));
+    }
+    summary.push('\n');
+
+    // the amounts are the ones that are written into the invoice
+    let totals = xrechnung::totals::calculate(bill, invoice_hours);
+    let mut rows = vec![
+        ["DATE", "NAME", "QUANTITY", "RATE", "AMOUNT"]
+            .map(crate::i18n::translate)
+            .to_vec(),
+    ];
+    for (line, amount) in invoice_hours.iter().zip(&totals.line_amounts) {
+        rows.push(vec![
+            line.start_date
+                .as_ref()
+                .or(line.date.as_ref())
+                .cloned()
+                .unwrap_or_default(),
+            line.name.clone(),
+            format!("{:.2}", line.quantity),
+            format!("{:.2}", line.hourly_rate),
+            amount.decimal(),
+        ]);
+    }
+
+    // the numbers are aligned to the right
+    summary.push_str(&crate::registry::format_rows(&rows, &[2, 3, 4]));
+
+    summary.push_str(&format!(
+        "\nNet amount: {}\nVAT ({}%): {}\nTotal amount: {}\n",
+        totals.tax_exclusive_amount,
+        bill.tax_percent(),
+        totals.tax_amount,
+        totals.payable_amount
+    ));
+    crate::i18n::translate(&summary)
+}
+
+/// Returns the text of the element at the given path of (qualified) names below the given element, or an empty string.
+pub fn text<'a>(element: &'a XmlElement, path: &[&str]) -> &'a str {
+    path.iter()
+        .try_fold(element, |element, name| {
+            element
+                .children()
+                .iter()
+                .find(|child| child.name() == *name)
+        })
+        .and_then(XmlElement::text)
+        .unwrap_or_default()
+}
+
+/// Returns the period, number of invoice lines and totals of the given invoice as created by
+/// [`xrechnung::create`], for checking the numbers of an invoice before writing it (`--dry-run`).
+pub fn totals(invoice: &XmlElement) -> String {
+    let currency = text(invoice, &["cbc:DocumentCurrencyCode"]);
+    let buyer = text(
+        invoice,
+        &[
+            "cac:AccountingCustomerParty",
+            "cac:Party",
+            "cac:PartyLegalEntity",
+            "cbc:RegistrationName",
+        ],
+    );
+    let mut totals = format!(
+        "Invoice {} for {buyer} (dry run, no file written)\n",
+        text(invoice, &["cbc:ID"])
+    );
+    let start = text(invoice, &["cac:InvoicePeriod", "cbc:StartDate"]);
+    if !start.is_empty() {
+        totals.push_str(&format!(
+            "Period: {start} to {}\n",
+            text(invoice, &["cac:InvoicePeriod", "cbc:EndDate"])
+        ));
+    }
+    let lines = invoice
+        .children()
+        .iter()
+        .filter(|child| child.name() == "cac:InvoiceLine")
+        .count();
+    totals.push_str(&format!(
+        "Invoice lines: {lines}\nNet amount: {} {currency}\nVAT: {} {currency}\nTotal amount: {} {currency}\n",
+        text(invoice, &["cac:LegalMonetaryTotal", "cbc:TaxExclusiveAmount"]),
+        text(invoice, &["cac:TaxTotal", "cbc:TaxAmount"]),
+        text(invoice, &["cac:LegalMonetaryTotal", "cbc:PayableAmount"]),
+    ));
+    crate::i18n::translate(&totals)
+}
+
+/// Returns a JSON object with the invoice number, buyer, dates, period, totals and output file of the given invoice as
+/// created by [`xrechnung::create`] (`--summary-json`).
+pub fn summary_json(invoice: &XmlElement, output: Option<&str>) -> Value {
+    let string = |path: &[&str]| match text(invoice, path) {
+        "" => Value::Null,
+        text => Value::String(text.to_string()),
+    };
+    let number = |path: &[&str]| Value::Number(text(invoice, path).to_string());
+    let period = match string(&["cac:InvoicePeriod", "cbc:StartDate"]) {
+        Value::Null => Value::Null,
+        start => Value::Object(vec![
+            ("start".to_string(), start),
+            (
+                "end".to_string(),
+                string(&["cac:InvoicePeriod", "cbc:EndDate"]),
+            ),
+        ]),
+    };
+
+    Value::Object(vec![
+        ("invoice_number".to_string(), string(&["cbc:ID"])),
+        (
+            "buyer".to_string(),
+            string(&[
+                "cac:AccountingCustomerParty",
+                "cac:Party",
+                "cac:PartyLegalEntity",
+                "cbc:RegistrationName",
+            ]),
+        ),
+        ("issue_date".to_string(), string(&["cbc:IssueDate"])),
+        ("due_date".to_string(), string(&["cbc:DueDate"])),
+        ("period".to_string(), period),
+        (
+            "currency".to_string(),
+            string(&["cbc:DocumentCurrencyCode"]),
+        ),
+        (
+            "net".to_string(),
+            number(&["cac:LegalMonetaryTotal", "cbc:TaxExclusiveAmount"]),
+        ),
+        (
+            "vat".to_string(),
+            number(&["cac:TaxTotal", "cbc:TaxAmount"]),
+        ),
+        (
+            "gross".to_string(),
+            number(&["cac:LegalMonetaryTotal", "cbc:PayableAmount"]),
+        ),
+        (
+            "output".to_string(),
+            output.map_or(Value::Null, |output| Value::String(output.to_string())),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use xrechnung::data::{Bill, InvoiceHoursElement};
+
+    #[test]
+    fn test_summary() {
+        let config =
+            xrechnung::config::load("../xrechnung/examples/config.toml", "Client Company").unwrap();
+        let bill = Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            None,
+            &config,
+        );
+        let invoice_hours = vec![
+            InvoiceHoursElement {
+                name: "Example Service".to_string(),
+                quantity: 7.0,
+                hourly_rate: 110.0,
+                date: Some("2025-01-02".to_string()),
+                ..Default::default()
+            },
+            InvoiceHoursElement {
+                name: "Support".to_string(),
+                quantity: 1.5,
+                hourly_rate: 75.0,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            super::summary(&config, &bill, &invoice_hours),
+            "Invoice 2025-0001 for Client Company
+Issue date: 2025-01-31
+Due date: 2025-02-20
+
+DATE        NAME             QUANTITY    RATE  AMOUNT
+2025-01-02  Example Service      7.00  110.00  770.00
+            Support              1.50   75.00  112.50
+
+Net amount: 882.50 EUR
+VAT (19%): 167.68 EUR
+Total amount: 1050.18 EUR
+"
+        );
+    }
+
+    #[test]
+    fn test_totals() {
+        let config =
+            xrechnung::config::load("../xrechnung/examples/config.toml", "Client Company").unwrap();
+        let bill = Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            Some(xrechnung::data::Period {
+                start: chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                end: chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            }),
+            &config,
+        );
+        let invoice_hours = vec![InvoiceHoursElement {
+            name: "Support".to_string(),
+            quantity: 1.5,
+            hourly_rate: 75.0,
+            ..Default::default()
+        }];
+        let (invoice, _) =
+            xrechnung::create(config.supplier, config.buyer, bill, invoice_hours).unwrap();
+
+        assert_eq!(
+            super::totals(&invoice),
+            "Invoice 2025-0001 for Client Company (dry run, no file written)
+Period: 2025-01-01 to 2025-01-31
+Invoice lines: 1
+Net amount: 112.50 EUR
+VAT: 21.38 EUR
+Total amount: 133.88 EUR
+"
+        );
+        assert_eq!(
+            super::summary_json(&invoice, Some("invoice.xml")).to_string(),
+            r#"{"invoice_number":"2025-0001","buyer":"Client Company","issue_date":"2025-01-31","due_date":"2025-02-20","period":{"start":"2025-01-01","end":"2025-01-31"},"currency":"EUR","net":112.50,"vat":21.38,"gross":133.88,"output":"invoice.xml"}"#
+        );
+    }
+}
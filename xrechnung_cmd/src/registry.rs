@@ -0,0 +1,391 @@
+//! Registry of the issued invoices (`registry`), an append-only file with a JSON object per line for every created
+//! invoice: its number, buyer, dates, totals, file and the SHA-256 hash of the file.
+//!
+//! The registry prevents that invoice numbers are used twice and is the basis for the reporting of the issued invoices.
+//! It is stored as `invoices.jsonl` next to the configuration file, if not given otherwise with `--registry`.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use xrechnung::json::{self, Value};
+
+/// The default name of the registry file, next to the configuration file.
+const DEFAULT_FILE_NAME: &str = "invoices.jsonl";
+
+/// An issued invoice as recorded in the registry.
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub invoice_number: String,
+    pub buyer: String,
+    pub issue_date: String,
+    pub currency: String,
+    pub net: f64,
+    pub vat: f64,
+    pub gross: f64,
+
+    /// The file the invoice was written to ("-" for the standard output).
+    pub output: String,
+
+    /// The SHA-256 hash of the invoice as written.
+    pub sha256: String,
+
+    /// The time the invoice was created.
+    pub created: String,
+
+    /// The times and recipients the invoice was sent to, as recorded in separate lines of the registry.
+    pub sent: Vec<String>,
+}
+
+impl Entry {
+    /// Reads an entry from the JSON object of a line of the registry.
+    fn from_value(value: &Value) -> Result<Self, String> {
+        let string = |name: &str| {
+            value
+                .get(name)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| format!("the field '{name}' is missing"))
+        };
+        let number = |name: &str| {
+            value
+                .get(name)
+                .and_then(Value::as_f64)
+                .ok_or_else(|| format!("the amount '{name}' is missing"))
+        };
+        Ok(Entry {
+            invoice_number: string("invoice_number")?,
+            buyer: string("buyer")?,
+            issue_date: string("issue_date")?,
+            currency: string("currency")?,
+            net: number("net")?,
+            vat: number("vat")?,
+            gross: number("gross")?,
+            output: string("output")?,
+            sha256: string("sha256")?,
+            created: string("created")?,
+            sent: Vec::new(),
+        })
+    }
+}
+
+/// Returns the registry file for the given configuration file, if no registry file is given explicitly.
+pub fn default_file(config_file: &str) -> PathBuf {
+    Path::new(config_file)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(DEFAULT_FILE_NAME)
+}
+
+/// Reads all entries of the given registry file, which does not exist before the first invoice.
+pub fn read(registry: &Path) -> Result<Vec<Entry>, Box<dyn std::error::Error>> {
+    if !registry.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(registry)?;
+    let mut entries: Vec<Entry> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = json::parse(line).and_then(|value| match value.get("sent") {
+            // sending an invoice is recorded in a line of its own, as the registry is never rewritten
+            Some(sent) => {
+                let invoice_number = value.get("invoice_number").and_then(Value::as_str);
+                let entry = entries
+                    .iter_mut()
+                    .rfind(|entry| Some(entry.invoice_number.as_str()) == invoice_number)
+                    .ok_or("the sent invoice is not in the registry")?;
+                entry.sent.push(format!(
+                    "{} to {}",
+                    sent.as_str().unwrap_or_default(),
+                    value.get("to").and_then(Value::as_str).unwrap_or_default()
+                ));
+                Ok(None)
+            }
+            None => Entry::from_value(&value).map(Some),
+        });
+        match entry {
+            Ok(entry) => entries.extend(entry),
+            Err(e) => {
+                return Err(format!(
+                    "Invalid entry in line {} of the registry '{}': {e}",
+                    index + 1,
+                    registry.display()
+                )
+                .into());
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Returns an error, if an invoice with the given number is already recorded in the given registry file.
+pub fn check_unused(
+    registry: &Path,
+    invoice_number: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(entry) = read(registry)?
+        .iter()
+        .find(|entry| entry.invoice_number == invoice_number)
+    {
+        return Err(format!(
+            "The invoice number {invoice_number} was already used on {} for {} (see the registry '{}').",
+            entry.issue_date,
+            entry.buyer,
+            registry.display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Appends the invoice with the given summary (as created by [`crate::preview::summary_json`]) and content to the
+/// given registry file.
+pub fn record(
+    registry: &Path,
+    summary: &Value,
+    content: &[u8],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Value::Object(members) = summary else {
+        return Err("The summary of the invoice is no JSON object.".into());
+    };
+    let mut members = members.clone();
+    members.push((
+        "sha256".to_string(),
+        Value::String(crate::sha256::hex_digest(content)),
+    ));
+    members.push(("created".to_string(), Value::String(now())));
+    append(registry, Value::Object(members))
+}
+
+/// Records in the given registry file that the invoice with the given number was sent to the given recipient.
+#[cfg_attr(not(feature = "smtp"), allow(dead_code))]
+pub fn record_sent(
+    registry: &Path,
+    invoice_number: &str,
+    to: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    append(
+        registry,
+        Value::Object(vec![
+            (
+                "invoice_number".to_string(),
+                Value::String(invoice_number.to_string()),
+            ),
+            ("sent".to_string(), Value::String(now())),
+            ("to".to_string(), Value::String(to.to_string())),
+        ]),
+    )
+}
+
+/// Returns the current time as recorded in the registry.
+fn now() -> String {
+    chrono::Local::now()
+        .format("%Y-%m-%dT%H:%M:%S%:z")
+        .to_string()
+}
+
+/// Appends the given value as line to the given registry file.
+fn append(registry: &Path, value: Value) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(registry)
+        .map_err(|e| format!("Could not open the registry '{}': {e}", registry.display()))?;
+    writeln!(file, "{value}")?;
+    Ok(())
+}
+
+/// Returns a table of the given entries with number, issue date, buyer, totals and file.
+pub fn table(entries: &[&Entry]) -> String {
+    let mut rows = vec![vec![
+        "NUMBER".to_string(),
+        "ISSUE DATE".to_string(),
+        "BUYER".to_string(),
+        "NET".to_string(),
+        "VAT".to_string(),
+        "GROSS".to_string(),
+        "FILE".to_string(),
+    ]];
+    for entry in entries {
+        rows.push(vec![
+            entry.invoice_number.clone(),
+            entry.issue_date.clone(),
+            entry.buyer.clone(),
+            format!("{:.2} {}", entry.net, entry.currency),
+            format!("{:.2} {}", entry.vat, entry.currency),
+            format!("{:.2} {}", entry.gross, entry.currency),
+            entry.output.clone(),
+        ]);
+    }
+    format_rows(&rows, &[3, 4, 5])
+}
+
+/// Formats the given rows (the first one being the header) as table with aligned columns. The columns with the given
+/// indices (e.g., amounts) are aligned to the right.
+pub fn format_rows(rows: &[Vec<String>], right_aligned: &[usize]) -> String {
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut table = String::new();
+    for row in rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if right_aligned.contains(&column) {
+                    format!("{cell:>width$}")
+                } else {
+                    format!("{cell:width$}")
+                }
+            })
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// Prints the invoices of the registry, optionally only those of the given buyer and year.
+pub fn list(
+    registry: &Path,
+    buyer: Option<&str>,
+    year: Option<i32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = read(registry)?;
+    let year = year.map(|year| format!("{year}-"));
+    let entries: Vec<&Entry> = entries
+        .iter()
+        .filter(|entry| buyer.is_none_or(|buyer| entry.buyer == buyer))
+        .filter(|entry| {
+            year.as_ref()
+                .is_none_or(|year| entry.issue_date.starts_with(year))
+        })
+        .collect();
+    print!("{}", table(&entries));
+    Ok(())
+}
+
+/// Prints the entry of the invoice with the given number and checks that its file was not changed since.
+pub fn show(registry: &Path, invoice_number: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = read(registry)?;
+    let entry = entries
+        .iter()
+        .find(|entry| entry.invoice_number == invoice_number)
+        .ok_or_else(|| {
+            format!(
+                "The invoice {invoice_number} is not in the registry '{}'.",
+                registry.display()
+            )
+        })?;
+
+    let file_state = match std::fs::read(&entry.output) {
+        Ok(content) if crate::sha256::hex_digest(&content) == entry.sha256 => "unchanged",
+        Ok(_) => "CHANGED since the invoice was created",
+        Err(_) => "not found",
+    };
+    println!(
+        "Invoice: {}\nBuyer: {}\nIssue date: {}\nNet amount: {:.2} {currency}\nVAT: {:.2} {currency}\n\
+         Total amount: {:.2} {currency}\nFile: {} ({file_state})\nSHA-256: {}\nCreated: {}",
+        entry.invoice_number,
+        entry.buyer,
+        entry.issue_date,
+        entry.net,
+        entry.vat,
+        entry.gross,
+        entry.output,
+        entry.sha256,
+        entry.created,
+        currency = entry.currency
+    );
+    for sent in &entry.sent {
+        println!("Sent: {sent}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use xrechnung::json::Value;
+
+    fn summary(number: &str, buyer: &str, issue_date: &str) -> Value {
+        Value::Object(vec![
+            (
+                "invoice_number".to_string(),
+                Value::String(number.to_string()),
+            ),
+            ("buyer".to_string(), Value::String(buyer.to_string())),
+            (
+                "issue_date".to_string(),
+                Value::String(issue_date.to_string()),
+            ),
+            ("due_date".to_string(), Value::Null),
+            ("period".to_string(), Value::Null),
+            ("currency".to_string(), Value::String("EUR".to_string())),
+            ("net".to_string(), Value::Number("100.00".to_string())),
+            ("vat".to_string(), Value::Number("19.00".to_string())),
+            ("gross".to_string(), Value::Number("119.00".to_string())),
+            ("output".to_string(), Value::String(format!("{number}.xml"))),
+        ])
+    }
+
+    #[test]
+    fn test_registry() {
+        let directory =
+            std::env::temp_dir().join(format!("xrechnung-registry-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let registry = directory.join("invoices.jsonl");
+        assert!(super::read(&registry).unwrap().is_empty());
+        assert!(super::check_unused(&registry, "2025-001").is_ok());
+
+        super::record(
+            &registry,
+            &summary("2025-001", "Client Company", "2025-01-31"),
+            b"abc",
+        )
+        .unwrap();
+        super::record(
+            &registry,
+            &summary("2025-002", "Another Client", "2025-02-28"),
+            b"",
+        )
+        .unwrap();
+
+        let entries = super::read(&registry).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].invoice_number, "2025-001");
+        assert_eq!(entries[0].gross, 119.0);
+        assert_eq!(
+            entries[0].sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(super::check_unused(&registry, "2025-001").is_err());
+        assert!(super::check_unused(&registry, "2025-003").is_ok());
+
+        super::record_sent(&registry, "2025-002", "mail@client2.example.com").unwrap();
+        let entries = super::read(&registry).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].sent.is_empty());
+        assert_eq!(entries[1].sent.len(), 1);
+        assert!(entries[1].sent[0].ends_with(" to mail@client2.example.com"));
+
+        assert_eq!(
+            super::table(&entries.iter().collect::<Vec<_>>()),
+            "NUMBER    ISSUE DATE  BUYER                  NET        VAT       GROSS  FILE
+2025-001  2025-01-31  Client Company  100.00 EUR  19.00 EUR  119.00 EUR  2025-001.xml
+2025-002  2025-02-28  Another Client  100.00 EUR  19.00 EUR  119.00 EUR  2025-002.xml
+"
+        );
+
+        std::fs::write(&registry, "{\"invoice_number\":\"1\"}\n").unwrap();
+        assert!(super::read(&registry).is_err());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
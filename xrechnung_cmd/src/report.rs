@@ -0,0 +1,185 @@
+//! Revenue report (`report`) of the invoices in the registry, with the net amounts, VAT and gross amounts summed up per
+//! month or quarter and per buyer, e.g., for preparing the advance VAT returns (Umsatzsteuervoranmeldung).
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::registry::{self, Entry};
+
+/// The periods to sum up the invoices for.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Interval {
+    /// Calendar months (e.g., "2025-01").
+    Month,
+
+    /// Calendar quarters (e.g., "2025-Q1").
+    Quarter,
+}
+
+/// The sums of the invoices of a period, buyer and currency (amounts in cents).
+#[derive(Default)]
+struct Sums {
+    invoices: usize,
+    net: i64,
+    vat: i64,
+    gross: i64,
+}
+
+impl Sums {
+    fn add(&mut self, entry: &Entry) {
+        self.invoices += 1;
+        self.net += cents(entry.net);
+        self.vat += cents(entry.vat);
+        self.gross += cents(entry.gross);
+    }
+
+    fn row(&self, period: &str, buyer: &str, currency: &str) -> Vec<String> {
+        let amount = |cents: i64| format!("{:.2} {currency}", cents as f64 / 100.0);
+        vec![
+            period.to_string(),
+            buyer.to_string(),
+            self.invoices.to_string(),
+            amount(self.net),
+            amount(self.vat),
+            amount(self.gross),
+        ]
+    }
+}
+
+/// Returns the given amount in cents, so that it can be summed up without rounding errors.
+fn cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+/// Returns the name of the period of the given interval that contains the given date.
+fn period(date: NaiveDate, interval: Interval) -> String {
+    match interval {
+        Interval::Month => date.format("%Y-%m").to_string(),
+        Interval::Quarter => format!("{}-Q{}", date.year(), date.month0() / 3 + 1),
+    }
+}
+
+/// Returns the report of the given invoices, optionally only of the given year. For each period, there is a line per
+/// buyer and currency and a line with the totals of the period. The totals of all periods are given at the end.
+pub fn report(
+    entries: &[Entry],
+    interval: Interval,
+    year: Option<i32>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    // sorted by period, buyer and currency
+    let mut buyers: BTreeMap<(String, String, String), Sums> = BTreeMap::new();
+    let mut periods: BTreeMap<(String, String), Sums> = BTreeMap::new();
+    let mut totals: BTreeMap<String, Sums> = BTreeMap::new();
+    for entry in entries {
+        let issue_date =
+            NaiveDate::parse_from_str(&entry.issue_date, "%Y-%m-%d").map_err(|_| {
+                format!(
+                    "The invoice {} has the invalid issue date '{}'.",
+                    entry.invoice_number, entry.issue_date
+                )
+            })?;
+        if year.is_some_and(|year| issue_date.year() != year) {
+            continue;
+        }
+        let period = period(issue_date, interval);
+        buyers
+            .entry((period.clone(), entry.buyer.clone(), entry.currency.clone()))
+            .or_default()
+            .add(entry);
+        periods
+            .entry((period, entry.currency.clone()))
+            .or_default()
+            .add(entry);
+        totals.entry(entry.currency.clone()).or_default().add(entry);
+    }
+
+    let mut rows = vec![
+        ["PERIOD", "BUYER", "INVOICES", "NET", "VAT", "GROSS"]
+            .map(str::to_string)
+            .to_vec(),
+    ];
+    for ((period, currency), sums) in &periods {
+        rows.extend(
+            buyers
+                .iter()
+                .filter(|((p, _, c), _)| p == period && c == currency)
+                .map(|((_, buyer, _), sums)| sums.row(period, buyer, currency)),
+        );
+        rows.push(sums.row(period, "Total", currency));
+    }
+    let label = year.map_or("Total".to_string(), |year| year.to_string());
+    for (currency, sums) in &totals {
+        rows.push(sums.row(&label, "Total", currency));
+    }
+    Ok(registry::format_rows(&rows, &[2, 3, 4, 5]))
+}
+
+/// Prints the report of the invoices in the given registry file.
+pub fn run(
+    registry: &std::path::Path,
+    interval: Interval,
+    year: Option<i32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    print!("{}", report(&registry::read(registry)?, interval, year)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Interval;
+    use crate::registry::Entry;
+
+    fn entry(
+        invoice_number: &str,
+        buyer: &str,
+        issue_date: &str,
+        net: f64,
+        currency: &str,
+    ) -> Entry {
+        Entry {
+            invoice_number: invoice_number.to_string(),
+            buyer: buyer.to_string(),
+            issue_date: issue_date.to_string(),
+            currency: currency.to_string(),
+            net,
+            vat: net * 0.19,
+            gross: net * 1.19,
+            output: format!("{invoice_number}.xml"),
+            sha256: String::new(),
+            created: String::new(),
+            sent: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let entries = vec![
+            entry("2024-012", "Client Company", "2024-12-31", 500.0, "EUR"),
+            entry("2025-001", "Client Company", "2025-01-31", 1000.0, "EUR"),
+            entry("2025-002", "Another Client", "2025-01-31", 200.0, "CHF"),
+            entry("2025-003", "Client Company", "2025-02-28", 100.1, "EUR"),
+            entry("2025-004", "Client Company", "2025-04-30", 100.0, "EUR"),
+        ];
+        assert_eq!(
+            super::report(&entries, Interval::Quarter, Some(2025)).unwrap(),
+            "PERIOD   BUYER           INVOICES          NET         VAT        GROSS
+2025-Q1  Another Client         1   200.00 CHF   38.00 CHF   238.00 CHF
+2025-Q1  Total                  1   200.00 CHF   38.00 CHF   238.00 CHF
+2025-Q1  Client Company         2  1100.10 EUR  209.02 EUR  1309.12 EUR
+2025-Q1  Total                  2  1100.10 EUR  209.02 EUR  1309.12 EUR
+2025-Q2  Client Company         1   100.00 EUR   19.00 EUR   119.00 EUR
+2025-Q2  Total                  1   100.00 EUR   19.00 EUR   119.00 EUR
+2025     Total                  1   200.00 CHF   38.00 CHF   238.00 CHF
+2025     Total                  3  1200.10 EUR  228.02 EUR  1428.12 EUR
+"
+        );
+
+        let report = super::report(&entries, Interval::Month, None).unwrap();
+        assert!(report.contains("\n2024-12  Total                  1   500.00 EUR"));
+        assert!(report.contains("\n2025-02  Client Company         1   100.10 EUR"));
+        assert!(report.ends_with(
+            "\nTotal    Total                  4  1700.10 EUR  323.02 EUR  2023.12 EUR\n"
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+//! Sending of invoices by email (`send`) to the buyer via the mail server of the configuration (`[smtp]`), with the
+//! invoice and further documents (e.g., a PDF version of the invoice) as attachments. The text of the email is the
+//! cover letter of the invoice (see [`crate::cover`]). The email is sent with `curl`.
+
+use std::path::Path;
+
+use xrechnung::config::{Config, Smtp};
+use xrechnung::json::Value;
+
+use crate::cover::fill;
+use crate::registry;
+
+/// The default subject of the emails.
+const DEFAULT_SUBJECT: &str = "Invoice {invoice_number}";
+
+/// A file attached to an email.
+struct Attachment {
+    file_name: String,
+    content_type: &'static str,
+    content: Vec<u8>,
+}
+
+impl Attachment {
+    /// Reads the attachment from the given file, with the content type derived from its extension.
+    fn read(file: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = Path::new(file);
+        let content_type = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("xml") => "application/xml",
+            Some("pdf") => "application/pdf",
+            Some("txt") => "text/plain",
+            Some("csv") => "text/csv",
+            _ => "application/octet-stream",
+        };
+        Ok(Attachment {
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.to_string()),
+            content_type,
+            content: std::fs::read(path)
+                .map_err(|e| format!("Could not read the file '{file}': {e}"))?,
+        })
+    }
+}
+
+/// Returns the given header value, encoded as MIME encoded-word (RFC 2047) if it is not plain ASCII.
+fn header_value(value: &str) -> String {
+    match value.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+        true => value.to_string(),
+        false => format!(
+            "=?UTF-8?B?{}?=",
+            xrechnung::base64::encode(value.as_bytes())
+        ),
+    }
+}
+
+/// Returns the given data Base64 encoded in lines of 76 characters, as required for MIME bodies.
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = xrechnung::base64::encode(data);
+    let lines: Vec<&str> = encoded
+        .as_bytes()
+        .chunks(76)
+        .map(|line| std::str::from_utf8(line).unwrap_or_default())
+        .collect();
+    lines.join("\r\n")
+}
+
+/// An email with the invoice.
+struct Mail {
+    from_name: String,
+    from: String,
+    to: String,
+    subject: String,
+    body: String,
+    attachments: Vec<Attachment>,
+}
+
+impl Mail {
+    /// Returns the complete message (RFC 5322) with the text and the attachments as MIME multipart message, sent at
+    /// the given time.
+    fn message(&self, date: chrono::DateTime<chrono::FixedOffset>) -> String {
+        let domain = self.from.rsplit('@').next().unwrap_or("localhost");
+        let boundary = format!("=_xrechnung_{}", date.timestamp());
+        let mut message = format!(
+            "From: {} <{}>\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}.{}@{domain}>\r\nMIME-Version: 1.0\r\n\
+             Content-Type: multipart/mixed; boundary=\"{boundary}\"\r\n\r\n",
+            header_value(&self.from_name),
+            self.from,
+            self.to,
+            header_value(&self.subject),
+            date.to_rfc2822(),
+            date.timestamp(),
+            std::process::id()
+        );
+        message.push_str(&format!(
+            "--{boundary}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}\r\n",
+            base64_lines(self.body.replace("\r\n", "\n").replace('\n', "\r\n").as_bytes())
+        ));
+        for attachment in &self.attachments {
+            let name = header_value(&attachment.file_name).replace('"', "");
+            message.push_str(&format!(
+                "--{boundary}\r\nContent-Type: {}; name=\"{name}\"\r\nContent-Disposition: attachment; \
+                 filename=\"{name}\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{}\r\n",
+                attachment.content_type,
+                base64_lines(&attachment.content)
+            ));
+        }
+        message.push_str(&format!("--{boundary}--\r\n"));
+        message
+    }
+}
+
+/// Returns the email for the given invoice file with its summary and further attachments.
+fn mail(
+    config: &Config,
+    smtp: &Smtp,
+    summary: &Value,
+    file: &str,
+    attachments: &[String],
+    to: Option<&str>,
+) -> Result<Mail, Box<dyn std::error::Error>> {
+    let supplier = &config.supplier.name;
+    Ok(Mail {
+        from_name: supplier.clone(),
+        from: smtp
+            .from
+            .clone()
+            .unwrap_or_else(|| config.supplier.email.clone()),
+        to: to
+            .or(config.buyer.invoice_email.as_deref())
+            .unwrap_or(&config.buyer.email)
+            .to_string(),
+        subject: fill(
+            smtp.subject.as_deref().unwrap_or(DEFAULT_SUBJECT),
+            summary,
+            supplier,
+        ),
+        body: fill(crate::cover::template(config), summary, supplier),
+        attachments: std::iter::once(file)
+            .chain(attachments.iter().map(String::as_str))
+            .map(Attachment::read)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Sends the given invoice file with the given further attachments to the buyer of the invoice (or the given address)
+/// and records it in the given registry file. With `dry_run`, the email is only printed.
+pub fn run(
+    config_file: &str,
+    registry: &Path,
+    file: &str,
+    attachments: &[String],
+    to: Option<&str>,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let invoice = xrechnung::XmlElement::parse(
+        &std::fs::read_to_string(file)
+            .map_err(|e| format!("Could not read the invoice '{file}': {e}"))?,
+    )?;
+    let summary = crate::preview::summary_json(&invoice, Some(file));
+    let invoice_number = summary
+        .get("invoice_number")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let buyer = summary
+        .get("buyer")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("The invoice '{file}' has no buyer name."))?;
+
+    let config = xrechnung::config::load(config_file, buyer).map_err(crate::error::config)?;
+    let smtp = config.smtp.as_ref().ok_or(
+        "No mail server configured for sending invoices (`[smtp]` in the configuration file).",
+    )?;
+    let mail = mail(&config, smtp, &summary, file, attachments, to)?;
+    let message = mail.message(chrono::Local::now().fixed_offset());
+    if dry_run {
+        print!("{message}");
+        return Ok(());
+    }
+
+    let login = match (&smtp.user, &smtp.password) {
+        (Some(user), Some(password)) => Some((user.as_str(), password.as_str())),
+        (Some(user), None) => Some((user.as_str(), "")),
+        _ => None,
+    };
+    crate::http::send_mail(
+        &smtp.url,
+        login,
+        &mail.from,
+        &[&mail.to],
+        message.as_bytes(),
+    )?;
+    registry::record_sent(registry, invoice_number, &mail.to)?;
+    println!("Sent the invoice {invoice_number} to {}.", mail.to);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_message() {
+        let mail = super::Mail {
+            from_name: "Hans Müller".to_string(),
+            from: "mail@supplier.example.com".to_string(),
+            to: "mail@client1.example.com".to_string(),
+            subject: "Invoice 2025-001".to_string(),
+            body: "Hello\n".to_string(),
+            attachments: vec![super::Attachment {
+                file_name: "2025-001.xml".to_string(),
+                content_type: "application/xml",
+                content: b"<Invoice/>".to_vec(),
+            }],
+        };
+        let date = chrono::DateTime::parse_from_rfc3339("2025-01-31T10:00:00+01:00").unwrap();
+        assert_eq!(
+            mail.message(date),
+            "From: =?UTF-8?B?SGFucyBNw7xsbGVy?= <mail@supplier.example.com>\r\n\
+             To: mail@client1.example.com\r\n\
+             Subject: Invoice 2025-001\r\n\
+             Date: Fri, 31 Jan 2025 10:00:00 +0100\r\n"
+                .to_string()
+                + &format!(
+                    "Message-ID: <1738314000.{}@supplier.example.com>\r\n",
+                    std::process::id()
+                )
+                + "MIME-Version: 1.0\r\n\
+                   Content-Type: multipart/mixed; boundary=\"=_xrechnung_1738314000\"\r\n\r\n\
+                   --=_xrechnung_1738314000\r\n\
+                   Content-Type: text/plain; charset=utf-8\r\n\
+                   Content-Transfer-Encoding: base64\r\n\r\n\
+                   SGVsbG8NCg==\r\n\
+                   --=_xrechnung_1738314000\r\n\
+                   Content-Type: application/xml; name=\"2025-001.xml\"\r\n\
+                   Content-Disposition: attachment; filename=\"2025-001.xml\"\r\n\
+                   Content-Transfer-Encoding: base64\r\n\r\n\
+                   PEludm9pY2UvPg==\r\n\
+                   --=_xrechnung_1738314000--\r\n"
+        );
+    }
+}
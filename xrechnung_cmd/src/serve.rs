@@ -0,0 +1,483 @@
+//! HTTP server mode (`serve`), so that invoices can be generated by other services, e.g., as a small internal service
+//! of a team. Invoices are created with `POST /invoice` and a JSON object as request body, whose members are the
+//! options of the `generate` command (with underscores, e.g., `"invoice_id"` or `"month"`, and with arrays for options
+//! that can be given several times) and whose `lines` member is the array of invoice lines with the columns of the CSV
+//! files as keys:
+//!
+//! ```json
+//! {
+//!     "buyer": "acme",
+//!     "invoice_id": "2025-0001",
+//!     "month": "2025-01",
+//!     "lines": [{ "date": "2025-01-02", "name": "Example Service", "quantity": 7, "hourly_rate": 110 }]
+//! }
+//! ```
+//!
+//! The response contains the XRechnung XML of the invoice. Nothing is written or recorded by the server, so the
+//! invoice number always needs to be given.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use clap::{Args, FromArgMatches};
+use xrechnung::json::{self, Value};
+use xrechnung::lines::InvoiceLineSource;
+
+use crate::InvoiceArgs;
+
+/// The maximum size of request bodies (in bytes).
+const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// The maximum size of the request line and headers of requests together (in bytes).
+const MAX_HEADER_SIZE: u64 = 16 * 1024;
+
+/// The maximum number of headers of requests.
+const MAX_HEADERS: usize = 100;
+
+/// The time to wait for the data of a request before giving up on it.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Options of the `generate` command that are about reading files (the invoice lines and attachments), and that are
+/// thus not supported for requests.
+const FILE_OPTIONS: [&str; 9] = [
+    "attach",
+    "invoice_hours",
+    "invoice_lines",
+    "sheet",
+    "delimiter",
+    "decimal_comma",
+    "from",
+    "to",
+    "layout",
+];
+
+/// A received HTTP request.
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+/// An HTTP response to be sent.
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    allow: Option<&'static str>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn text(status: u16, message: impl Into<String>) -> Self {
+        let mut body = message.into().into_bytes();
+        body.push(b'\n');
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            allow: None,
+            body,
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Content Too Large",
+            422 => "Unprocessable Content",
+            431 => "Request Header Fields Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len()
+        )?;
+        if let Some(allow) = self.allow {
+            write!(writer, "Allow: {allow}\r\n")?;
+        }
+        writer.write_all(b"\r\n")?;
+        writer.write_all(&self.body)?;
+        writer.flush()
+    }
+}
+
+/// Reads a line of the request line and headers, which may use at most the given remaining size of the header section.
+/// The remaining size is reduced by the size of the line. Returns the response to send instead, if the line cannot be
+/// read or exceeds the remaining size.
+fn read_header_line(reader: &mut impl BufRead, remaining: &mut u64) -> Result<String, Response> {
+    let mut line = String::new();
+    let size = Read::take(&mut *reader, *remaining)
+        .read_line(&mut line)
+        .map_err(|_| Response::text(400, "The request could not be read."))?;
+    *remaining -= size as u64;
+    if *remaining == 0 && !line.ends_with('\n') {
+        return Err(Response::text(
+            431,
+            format!("The request line and headers are larger than {MAX_HEADER_SIZE} bytes."),
+        ));
+    }
+    Ok(line)
+}
+
+/// Reads an HTTP request (with its body as given by the `Content-Length` header). Returns the response to send
+/// instead, if the request is malformed.
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let malformed = |_| Response::text(400, "The request could not be read.");
+
+    // the request line and headers are limited, so that requests cannot use up the memory
+    let mut remaining = MAX_HEADER_SIZE;
+    let line = read_header_line(reader, &mut remaining)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(Response::text(400, "The request line is malformed."));
+    };
+    let path = target.split('?').next().unwrap_or_default();
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        body: Vec::new(),
+    };
+
+    let mut length = 0;
+    for count in 0.. {
+        let line = read_header_line(reader, &mut remaining)?;
+        if line.is_empty() {
+            return Err(Response::text(400, "The request headers are incomplete."));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if count == MAX_HEADERS {
+            return Err(Response::text(
+                431,
+                format!("The request has more than {MAX_HEADERS} headers."),
+            ));
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse().map_err(|_| {
+                Response::text(
+                    400,
+                    format!("The content length '{}' is invalid.", value.trim()),
+                )
+            })?;
+        }
+    }
+
+    if length > MAX_BODY_SIZE {
+        return Err(Response::text(
+            413,
+            format!("The request body is larger than {MAX_BODY_SIZE} bytes."),
+        ));
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body).map_err(malformed)?;
+    Ok(request)
+}
+
+/// Returns the command line arguments of the `generate` command for the given request object, together with its
+/// invoice lines.
+fn arguments(request: Value) -> Result<(Vec<String>, Value), String> {
+    let Value::Object(members) = request else {
+        return Err("The request body needs to be a JSON object.".to_string());
+    };
+
+    let mut args = vec!["--invoice-hours".to_string(), "lines".to_string()];
+    let mut lines = None;
+    for (key, value) in members {
+        if key == "lines" {
+            lines = Some(value);
+            continue;
+        }
+        if FILE_OPTIONS.contains(&key.as_str()) {
+            return Err(format!(
+                "'{key}' is not supported for requests, as it refers to files."
+            ));
+        }
+
+        let option = format!("--{}", key.replace('_', "-"));
+        match value {
+            Value::Null | Value::Bool(false) => {}
+            Value::Bool(true) => args.push(option),
+            Value::Number(value) | Value::String(value) => args.extend([option, value]),
+            Value::Array(values) => {
+                for value in values {
+                    let (Value::Number(value) | Value::String(value)) = value else {
+                        return Err(format!(
+                            "The values of '{key}' need to be strings or numbers."
+                        ));
+                    };
+                    args.extend([option.clone(), value]);
+                }
+            }
+            Value::Object(_) => {
+                return Err(format!(
+                    "The value of '{key}' needs to be a string, a number, a boolean or an array."
+                ));
+            }
+        }
+    }
+
+    if !args.iter().any(|arg| arg == "--invoice-id") {
+        return Err("The request has no invoice number ('invoice_id').".to_string());
+    }
+    let lines = lines.ok_or("The request has no invoice lines ('lines').")?;
+    Ok((args, lines))
+}
+
+/// Creates the invoice for the given request body and returns its XML document.
+fn invoice(config_file: &str, body: &[u8]) -> Result<Vec<u8>, Response> {
+    let body = std::str::from_utf8(body)
+        .map_err(|_| Response::text(400, "The request body is no UTF-8 text."))?;
+    let request = json::parse(body).map_err(|error| {
+        Response::text(400, format!("The request body is no valid JSON: {error}"))
+    })?;
+    let (args, lines) = arguments(request).map_err(|error| Response::text(400, error))?;
+
+    // the options are checked like the ones of the command line, but the first line of the error is enough here
+    let command = InvoiceArgs::augment_args(clap::Command::new("invoice").no_binary_name(true));
+    let args = command
+        .try_get_matches_from(args)
+        .and_then(|matches| InvoiceArgs::from_arg_matches(&matches))
+        .map_err(|error| {
+            let error = error.to_string();
+            let message = error.lines().next().unwrap_or_default();
+            Response::text(400, message.trim_start_matches("error: "))
+        })?;
+
+    let invoice = crate::prepare_with(config_file, args, |config, options| {
+        crate::input::JsonLines {
+            source: "lines",
+            lines,
+            config,
+            options,
+        }
+        .read_lines()
+    })
+    .map_err(|error| Response::text(400, error.to_string()))?;
+    let (root, warnings) = xrechnung::create(
+        invoice.config.supplier,
+        invoice.config.buyer,
+        invoice.bill,
+        invoice.invoice_hours,
+    )
+    .map_err(|error| Response::text(500, error.to_string()))?;
+    if invoice.strict && !warnings.is_empty() {
+        let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+        return Err(Response::text(422, warnings.join("\n")));
+    }
+
+    let violations = xrechnung::validate::validate(&root);
+    if !violations.is_empty() {
+        let violations: Vec<String> = violations.iter().map(ToString::to_string).collect();
+        return Err(Response::text(422, violations.join("\n")));
+    }
+    xrechnung::to_xml_string(&root)
+        .map(String::into_bytes)
+        .map_err(|error| Response::text(500, error.to_string()))
+}
+
+/// Returns the response to the given request.
+fn handle(config_file: &str, request: &Request) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/invoice") => match invoice(config_file, &request.body) {
+            Ok(body) => Response {
+                status: 200,
+                content_type: "application/xml",
+                allow: None,
+                body,
+            },
+            Err(response) => response,
+        },
+        (_, "/invoice") => Response {
+            allow: Some("POST"),
+            ..Response::text(405, "Invoices are created with POST requests.")
+        },
+        (_, path) => Response::text(404, format!("There is no resource '{path}'.")),
+    }
+}
+
+/// Answers the request of the given connection.
+fn answer(config_file: &str, stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let (request, response) = match read_request(&mut reader) {
+        Ok(request) => {
+            let response = handle(config_file, &request);
+            (format!("{} {}", request.method, request.path), response)
+        }
+        Err(response) => ("Malformed request".to_string(), response),
+    };
+    eprintln!("{request}: {}", response.status);
+    response.write(&mut &stream)
+}
+
+/// Listens on the given address (e.g., "127.0.0.1:8080") and creates invoices for the buyers of the given
+/// configuration file. The requests are answered one after another.
+pub fn run(config_file: &str, listen: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind(listen)
+        .map_err(|error| format!("Could not listen on '{listen}': {error}"))?;
+    eprintln!(
+        "Listening on http://{} for invoices (POST /invoice).",
+        listener.local_addr()?
+    );
+
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| answer(config_file, stream));
+        if let Err(error) = result {
+            eprintln!("Could not answer a request: {error}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Request, handle, read_request};
+
+    const CONFIG: &str = "../xrechnung/examples/config.toml";
+
+    fn post(body: &str) -> Request {
+        Request {
+            method: "POST".to_string(),
+            path: "/invoice".to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_read_request() {
+        let request = read_request(
+            &mut "POST /invoice?pretty HTTP/1.1\r\nHost: localhost\r\ncontent-length: 2\r\n\r\n{}"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/invoice");
+        assert_eq!(request.body, b"{}");
+
+        let request = read_request(&mut "GET / HTTP/1.1\r\n\r\n".as_bytes()).unwrap();
+        assert!(request.body.is_empty());
+
+        let error = |request: &str| read_request(&mut request.as_bytes()).unwrap_err().status;
+        assert_eq!(error("GET /\r\n\r\n"), 400);
+        assert_eq!(error("GET / HTTP/1.1\r\nHost: localhost\r\n"), 400);
+        assert_eq!(error("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\n{}"), 400);
+        assert_eq!(
+            error("POST / HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n"),
+            413
+        );
+
+        // the request line and headers are limited in size and number
+        let long_header = format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", "a".repeat(20_000));
+        assert_eq!(error(&long_header), 431);
+        let endless_line = "a".repeat(20_000);
+        assert_eq!(error(&endless_line), 431);
+        let many_headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Header: 1\r\n".repeat(101));
+        assert_eq!(error(&many_headers), 431);
+        let headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Header: 1\r\n".repeat(100));
+        assert!(read_request(&mut headers.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_response() {
+        let mut written = Vec::new();
+        super::Response {
+            allow: Some("POST"),
+            ..super::Response::text(405, "Not allowed.")
+        }
+        .write(&mut written)
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "HTTP/1.1 405 Method Not Allowed\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: 13\r\n\
+             Connection: close\r\nAllow: POST\r\n\r\nNot allowed.\n"
+        );
+    }
+
+    #[test]
+    fn test_handle() {
+        let response = handle(
+            CONFIG,
+            &post(
+                r#"{"buyer": "acme", "invoice_id": "2025-0042", "month": "2025-01", "sort": true,
+                    "note": ["Leistung gemäß Angebot 2024-17", "Invoice {number}"], "lines": [
+                    {"date": "2025-01-03", "name": "Another Service", "quantity": 6.5, "hourly_rate": 110},
+                    {"date": "2025-01-02", "name": "Example Service", "quantity": "7", "hourly_rate": 110}
+                ]}"#,
+            ),
+        );
+        let body = String::from_utf8(response.body).unwrap();
+        assert_eq!(response.status, 200, "{body}");
+        assert_eq!(response.content_type, "application/xml");
+        assert!(body.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(body.contains("<cbc:ID>2025-0042</cbc:ID>"));
+        assert!(body.contains("<cbc:Note>Leistung gemäß Angebot 2024-17</cbc:Note>"));
+        assert!(body.contains("<cbc:Note>Invoice 2025-0042</cbc:Note>"));
+        assert!(body.find("Example Service").unwrap() < body.find("Another Service").unwrap());
+
+        let status = |body: &str| handle(CONFIG, &post(body)).status;
+        assert_eq!(status("no json"), 400);
+        assert_eq!(status("[]"), 400);
+        assert_eq!(status(r#"{"buyer": "acme", "lines": []}"#), 400);
+        assert_eq!(
+            status(r#"{"buyer": "acme", "invoice_id": null, "lines": []}"#),
+            400
+        );
+        assert_eq!(status(r#"{"buyer": "acme", "invoice_id": "1"}"#), 400);
+        assert_eq!(
+            status(r#"{"buyer": "acme", "invoice_id": "1", "layout": "matrix", "lines": []}"#),
+            400
+        );
+        assert_eq!(
+            status(r#"{"buyer": "acme", "invoice_id": "1", "colour": "red", "lines": []}"#),
+            400
+        );
+        assert_eq!(
+            status(r#"{"buyer": "nobody", "invoice_id": "1", "lines": []}"#),
+            400
+        );
+        assert_eq!(
+            status(r#"{"buyer": "acme", "invoice_id": "1", "lines": [{"name": "Service"}]}"#),
+            400
+        );
+        let zero_line = r#""lines": [{"name": "Service", "quantity": 0, "hourly_rate": 110}]"#;
+        assert_eq!(
+            status(&format!(
+                r#"{{"buyer": "acme", "invoice_id": "1", {zero_line}}}"#
+            )),
+            200
+        );
+        assert_eq!(
+            status(&format!(
+                r#"{{"buyer": "acme", "invoice_id": "1", "strict": true, {zero_line}}}"#
+            )),
+            422
+        );
+
+        let get = Request {
+            method: "GET".to_string(),
+            ..post("")
+        };
+        let response = handle(CONFIG, &get);
+        assert_eq!((response.status, response.allow), (405, Some("POST")));
+        let other = Request {
+            path: "/".to_string(),
+            ..post("")
+        };
+        assert_eq!(handle(CONFIG, &other).status, 404);
+    }
+}
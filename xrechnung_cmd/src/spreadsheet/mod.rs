@@ -0,0 +1,23 @@
+//! Reading of invoice lines from spreadsheet files, which are ZIP archives containing XML documents.
+
+use quick_xml::events::BytesStart;
+
+#[cfg(feature = "ods")]
+pub mod ods;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
+mod zip;
+
+/// Returns the value of the attribute with the given (local) name of the element, if it exists.
+fn attribute(
+    element: &BytesStart,
+    name: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    for attribute in element.attributes() {
+        let attribute = attribute?;
+        if attribute.key.local_name().as_ref() == name.as_bytes() {
+            return Ok(Some(attribute.unescape_value()?.into_owned()));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,246 @@
+//! Reading of invoice lines from OpenDocument spreadsheets (LibreOffice, `.ods`).
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+
+use super::attribute;
+use super::zip::Archive;
+use crate::input::{DATE_COLUMNS, TIMESTAMP_COLUMNS, Table, convert_iso_date};
+
+/// The content of a cell that is currently read, together with the number of times it is repeated.
+struct Cell {
+    repeated: usize,
+    value: Option<String>,
+    text: String,
+    paragraphs: usize,
+}
+
+/// Returns the number of repetitions given by the attribute with the given name (defaults to one).
+fn repetitions(element: &BytesStart, name: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    Ok(attribute(element, name)?
+        .and_then(|repeated| repeated.parse().ok())
+        .unwrap_or(1))
+}
+
+/// Converts a duration like "PT01H30M00S" into hours and minutes like "1:30".
+fn duration_to_hours(duration: &str) -> Option<String> {
+    let mut rest = duration.strip_prefix("PT")?;
+    let mut seconds = 0.0;
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+        let value: f64 = rest[..end].parse().ok()?;
+        seconds += match &rest[end..end + 1] {
+            "H" => value * 3600.0,
+            "M" => value * 60.0,
+            "S" => value,
+            _ => return None,
+        };
+        rest = &rest[end + 1..];
+    }
+
+    let minutes = (seconds / 60.0).round() as u64;
+    Some(format!("{}:{:02}", minutes / 60, minutes % 60))
+}
+
+/// Returns the value of a cell as string, by its value type. Dates are returned in ISO 8601 format and converted to
+/// the configured format once the column is known.
+fn cell_value(element: &BytesStart) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    Ok(match attribute(element, "value-type")?.as_deref() {
+        Some("float") | Some("percentage") | Some("currency") => attribute(element, "value")?,
+        Some("date") => attribute(element, "date-value")?,
+        Some("time") => attribute(element, "time-value")?
+            .as_deref()
+            .and_then(duration_to_hours),
+        Some("boolean") => attribute(element, "boolean-value")?,
+        _ => None,
+    })
+}
+
+/// Adds the given row to the rows, unless it is empty. Repeated rows are added multiple times.
+fn push_row(
+    rows: &mut Vec<(u64, Vec<String>)>,
+    number: u64,
+    repeated: usize,
+    mut values: Vec<String>,
+) {
+    while values.last().is_some_and(|value| value.is_empty()) {
+        values.pop();
+    }
+    if values.is_empty() {
+        return;
+    }
+
+    for repetition in 0..repeated {
+        rows.push((number + repetition as u64, values.clone()));
+    }
+}
+
+/// Reads a table from the given sheet (or the first sheet) of the OpenDocument spreadsheet with the given file name.
+/// The first row needs to contain the column names.
+///
+/// Dates in the spreadsheet are converted to strings with the given date format.
+pub fn read_table(
+    file_name: &str,
+    sheet: Option<&str>,
+    date_format: &str,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    let archive = Archive::open(file_name)?;
+    let xml = archive
+        .read_string("content.xml")?
+        .ok_or("The spreadsheet does not contain 'content.xml'.")?;
+
+    let mut names = Vec::new();
+    let mut in_table = false;
+    let mut found = false;
+
+    let mut rows: Vec<(u64, Vec<String>)> = Vec::new();
+    let mut row: Option<(usize, Vec<String>)> = None; // repetitions and values of the current row
+    let mut row_number = 1;
+    let mut cell: Option<Cell> = None;
+    let mut in_paragraph = false;
+
+    let mut reader = Reader::from_str(&xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) if !found && element.local_name().as_ref() == b"table" => {
+                let name = attribute(&element, "name")?.unwrap_or_default();
+                in_table = sheet.is_none() || sheet == Some(name.as_str());
+                found = in_table;
+                names.push(name);
+            }
+            Event::End(element) if in_table && element.local_name().as_ref() == b"table" => {
+                break;
+            }
+            Event::Start(element) if in_table => match element.local_name().as_ref() {
+                b"table-row" => {
+                    row = Some((repetitions(&element, "number-rows-repeated")?, Vec::new()));
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    cell = Some(Cell {
+                        repeated: repetitions(&element, "number-columns-repeated")?,
+                        value: cell_value(&element)?,
+                        text: String::new(),
+                        paragraphs: 0,
+                    });
+                }
+                b"p" => {
+                    if let Some(cell) = cell.as_mut() {
+                        if cell.paragraphs > 0 {
+                            cell.text.push('\n');
+                        }
+                        cell.paragraphs += 1;
+                    }
+                    in_paragraph = true;
+                }
+                _ => (),
+            },
+            Event::Empty(element) if in_table => match element.local_name().as_ref() {
+                b"table-row" => row_number += repetitions(&element, "number-rows-repeated")? as u64,
+                b"table-cell" | b"covered-table-cell" => {
+                    let value = cell_value(&element)?.unwrap_or_default();
+                    if let Some((_, values)) = row.as_mut() {
+                        let repeated = repetitions(&element, "number-columns-repeated")?;
+                        values.extend(std::iter::repeat_n(value, repeated));
+                    }
+                }
+                b"s" if in_paragraph => {
+                    if let Some(cell) = cell.as_mut() {
+                        let count = repetitions(&element, "c")?;
+                        cell.text.extend(std::iter::repeat_n(' ', count));
+                    }
+                }
+                _ => (),
+            },
+            Event::End(element) if in_table => match element.local_name().as_ref() {
+                b"table-row" => {
+                    if let Some((repeated, values)) = row.take() {
+                        push_row(&mut rows, row_number, repeated, values);
+                        row_number += repeated as u64;
+                    }
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    if let (Some(cell), Some((_, values))) = (cell.take(), row.as_mut()) {
+                        let value = cell.value.unwrap_or(cell.text);
+                        values.extend(std::iter::repeat_n(value, cell.repeated));
+                    }
+                }
+                b"p" => in_paragraph = false,
+                _ => (),
+            },
+            Event::Text(text) if in_table && in_paragraph => {
+                if let Some(cell) = cell.as_mut() {
+                    cell.text.push_str(&text.unescape()?);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    if !found {
+        return Err(format!(
+            "Could not find the sheet '{}' in the spreadsheet (available sheets: {}).",
+            sheet.unwrap_or_default(),
+            names.join(", ")
+        )
+        .into());
+    }
+
+    if rows.is_empty() {
+        return Err(format!("The sheet in '{file_name}' is empty.").into());
+    }
+    let (_, headers) = rows.remove(0);
+
+    // dates are stored in ISO 8601 format and need to be converted to the configured date format
+    for (_, values) in rows.iter_mut() {
+        for (header, value) in headers.iter().zip(values.iter_mut()) {
+            let is_date = DATE_COLUMNS.contains(&header.as_str());
+            let is_timestamp = TIMESTAMP_COLUMNS.contains(&header.as_str());
+
+            if (is_date || is_timestamp)
+                && let Some(timestamp) = convert_iso_date(value, date_format, is_timestamp)
+            {
+                *value = timestamp;
+            }
+        }
+    }
+
+    Ok(Table { headers, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_read_table() {
+        let table = super::read_table("examples/invoice-lines.ods", None, "%d.%m.%Y").unwrap();
+        assert_eq!(
+            table.headers,
+            vec!["date", "name", "quantity", "hourly_rate"]
+        );
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(table.rows[0].0, 2);
+        assert_eq!(
+            table.rows[0].1,
+            vec!["02.01.2025", "Example Service", "7", "110"]
+        );
+        assert_eq!(
+            table.rows[1].1,
+            vec!["03.01.2025", "Another Service", "1:30", "110"]
+        );
+        assert_eq!(table.rows[2].0, 5);
+    }
+
+    #[test]
+    fn test_error_on_missing_sheet() {
+        assert!(
+            super::read_table("examples/invoice-lines.ods", Some("Missing"), "%Y-%m-%d").is_err()
+        );
+    }
+
+    #[test]
+    fn test_duration_to_hours() {
+        assert_eq!(super::duration_to_hours("PT01H30M00S").unwrap(), "1:30");
+        assert_eq!(super::duration_to_hours("PT7H").unwrap(), "7:00");
+        assert!(super::duration_to_hours("1:30").is_none());
+    }
+}
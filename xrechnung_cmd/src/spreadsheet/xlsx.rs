@@ -0,0 +1,245 @@
+//! Reading of invoice lines from Excel spreadsheets (Office Open XML, `.xlsx`).
+
+use chrono::{Duration, NaiveDate};
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+
+use super::attribute;
+use super::zip::Archive;
+use crate::input::{DATE_COLUMNS, TIMESTAMP_COLUMNS, Table};
+
+/// Converts a serial number of a date in a spreadsheet (days since 1899-12-30, with the time as fraction) to a date
+/// and time with the given date format.
+fn serial_to_timestamp(serial: f64, date_format: &str, with_time: bool) -> Option<String> {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let timestamp = epoch + Duration::seconds((serial * 86400.0).round() as i64);
+
+    if with_time {
+        Some(
+            timestamp
+                .format(&format!("{date_format} %H:%M:%S"))
+                .to_string(),
+        )
+    } else {
+        Some(timestamp.format(date_format).to_string())
+    }
+}
+
+/// Returns the zero-based column index of a cell reference like "C12".
+fn column_index(reference: &str) -> usize {
+    reference
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .fold(0, |index, c| {
+            index * 26 + (c.to_ascii_uppercase() as usize - 'A' as usize + 1)
+        })
+        .saturating_sub(1)
+}
+
+/// Reads the shared strings table of the workbook, which contains the content of all text cells.
+fn read_shared_strings(archive: &Archive) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let Some(xml) = archive.read_string("xl/sharedStrings.xml")? else {
+        return Ok(Vec::new());
+    };
+
+    let mut reader = Reader::from_str(&xml);
+    let mut strings = Vec::new();
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"si" => strings.push(String::new()),
+                b"t" => in_text = true,
+                _ => (),
+            },
+            Event::End(element) if element.local_name().as_ref() == b"t" => in_text = false,
+            Event::Text(text) if in_text => {
+                if let Some(string) = strings.last_mut() {
+                    string.push_str(&text.unescape()?);
+                }
+            }
+            Event::Eof => return Ok(strings),
+            _ => (),
+        }
+    }
+}
+
+/// Returns the path of the worksheet with the given name in the archive, or of the first worksheet if no name is given.
+fn find_sheet(
+    archive: &Archive,
+    sheet: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let workbook = archive
+        .read_string("xl/workbook.xml")?
+        .ok_or("The spreadsheet does not contain a workbook.")?;
+
+    // find the relationship id of the sheet in the workbook
+    let mut reader = Reader::from_str(&workbook);
+    let mut names = Vec::new();
+    let mut relationship = None;
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"sheet" =>
+            {
+                let name = attribute(&element, "name")?.unwrap_or_default();
+                if sheet.is_none() || sheet == Some(name.as_str()) {
+                    relationship = attribute(&element, "id")?;
+                    break;
+                }
+                names.push(name);
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    let relationship = relationship.ok_or(format!(
+        "Could not find the sheet '{}' in the spreadsheet (available sheets: {}).",
+        sheet.unwrap_or_default(),
+        names.join(", ")
+    ))?;
+
+    // resolve the relationship id to the path of the sheet in the archive
+    let relationships = archive
+        .read_string("xl/_rels/workbook.xml.rels")?
+        .ok_or("The spreadsheet does not contain workbook relationships.")?;
+    let mut reader = Reader::from_str(&relationships);
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"Relationship"
+                    && attribute(&element, "Id")?.as_ref() == Some(&relationship) =>
+            {
+                let target = attribute(&element, "Target")?.unwrap_or_default();
+                return Ok(match target.strip_prefix('/') {
+                    Some(absolute) => absolute.to_string(),
+                    None => format!("xl/{target}"),
+                });
+            }
+            Event::Eof => return Err("Could not resolve the sheet in the spreadsheet.".into()),
+            _ => (),
+        }
+    }
+}
+
+/// Reads a table from the given sheet (or the first sheet) of the Excel spreadsheet with the given file name. The
+/// first row needs to contain the column names.
+///
+/// Dates that are stored as numbers in the spreadsheet are converted to strings with the given date format.
+pub fn read_table(
+    file_name: &str,
+    sheet: Option<&str>,
+    date_format: &str,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    let archive = Archive::open(file_name)?;
+    let shared_strings = read_shared_strings(&archive)?;
+    let sheet_path = find_sheet(&archive, sheet)?;
+    let xml = archive
+        .read_string(&sheet_path)?
+        .ok_or(format!("The spreadsheet does not contain '{sheet_path}'."))?;
+
+    let mut rows: Vec<(u64, Vec<String>)> = Vec::new();
+    let mut cell: Option<(usize, Option<String>)> = None; // column index and type of the current cell
+    let mut in_value = false;
+
+    let mut reader = Reader::from_str(&xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"row" => {
+                    let number = attribute(&element, "r")?
+                        .and_then(|number| number.parse().ok())
+                        .unwrap_or(rows.len() as u64 + 1);
+                    rows.push((number, Vec::new()));
+                }
+                b"c" => {
+                    let column = match attribute(&element, "r")? {
+                        Some(reference) => column_index(&reference),
+                        None => rows.last().map_or(0, |(_, values)| values.len()),
+                    };
+                    cell = Some((column, attribute(&element, "t")?));
+                }
+                b"v" | b"t" => in_value = true,
+                _ => (),
+            },
+            Event::End(element) => match element.local_name().as_ref() {
+                b"c" => cell = None,
+                b"v" | b"t" => in_value = false,
+                _ => (),
+            },
+            Event::Text(text) if in_value => {
+                if let (Some((column, cell_type)), Some((_, values))) = (&cell, rows.last_mut()) {
+                    let text = text.unescape()?;
+                    let value = match cell_type.as_deref() {
+                        Some("s") => text
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| shared_strings.get(index))
+                            .cloned()
+                            .unwrap_or_default(),
+                        _ => text.into_owned(),
+                    };
+
+                    if values.len() <= *column {
+                        values.resize(column + 1, String::new());
+                    }
+                    values[*column].push_str(&value);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    if rows.is_empty() {
+        return Err(format!("The sheet in '{file_name}' is empty.").into());
+    }
+    let (_, headers) = rows.remove(0);
+
+    // dates are usually stored as serial numbers and need to be converted to the configured date format
+    for (_, values) in rows.iter_mut() {
+        for (header, value) in headers.iter().zip(values.iter_mut()) {
+            let is_date = DATE_COLUMNS.contains(&header.as_str());
+            let is_timestamp = TIMESTAMP_COLUMNS.contains(&header.as_str());
+
+            if let (true, Ok(serial)) = (is_date || is_timestamp, value.parse::<f64>())
+                && let Some(timestamp) = serial_to_timestamp(serial, date_format, is_timestamp)
+            {
+                *value = timestamp;
+            }
+        }
+    }
+
+    Ok(Table { headers, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_read_table() {
+        let table = super::read_table("examples/invoice-lines.xlsx", None, "%Y-%m-%d").unwrap();
+        assert_eq!(
+            table.headers,
+            vec!["date", "name", "quantity", "hourly_rate"]
+        );
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].0, 2);
+        assert_eq!(
+            table.rows[0].1,
+            vec!["2025-01-02", "Example Service", "7", "110"]
+        );
+        assert_eq!(
+            table.rows[1].1,
+            vec!["2025-01-03", "Another Service", "6.5", "110"]
+        );
+    }
+
+    #[test]
+    fn test_error_on_missing_sheet() {
+        assert!(
+            super::read_table("examples/invoice-lines.xlsx", Some("Missing"), "%Y-%m-%d").is_err()
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! Minimal reader for ZIP archives as used by spreadsheet file formats. Only the parts needed to extract single files
+//! are implemented: the central directory and the "stored" and "deflate" compression methods.
+
+use std::fs;
+
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+const CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x02014b50;
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+
+/// The maximum size of the uncompressed content of a file in an archive (in bytes), so that small archives that are
+/// compressed extremely well (e.g., downloaded ones) cannot exhaust the memory.
+const MAX_UNCOMPRESSED_SIZE: usize = 256 * 1024 * 1024;
+
+/// An entry of the central directory of a ZIP archive.
+struct Entry {
+    name: String,
+    method: u16,
+    compressed_size: usize,
+    local_header_offset: usize,
+}
+
+/// A ZIP archive that is completely loaded into memory.
+pub struct Archive {
+    data: Vec<u8>,
+    entries: Vec<Entry>,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or("Unexpected end of the ZIP archive.".to_string())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or("Unexpected end of the ZIP archive.".to_string())
+}
+
+impl Archive {
+    /// Loads the ZIP archive with the given file name and reads its central directory.
+    pub fn open(file_name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let data = fs::read(file_name)?;
+
+        // the end of central directory record is at the end of the file, only followed by a comment
+        let end = (0..data.len().saturating_sub(21))
+            .rev()
+            .find(|&offset| read_u32(&data, offset) == Ok(END_OF_CENTRAL_DIRECTORY_SIGNATURE))
+            .ok_or(format!("The file '{file_name}' is not a ZIP archive."))?;
+
+        let entry_count = read_u16(&data, end + 10)? as usize;
+        let mut offset = read_u32(&data, end + 16)? as usize;
+
+        let mut entries = Vec::with_capacity(entry_count);
+        for _ in 0..entry_count {
+            if read_u32(&data, offset)? != CENTRAL_DIRECTORY_SIGNATURE {
+                return Err(format!("Invalid central directory in the file '{file_name}'.").into());
+            }
+
+            let name_length = read_u16(&data, offset + 28)? as usize;
+            let extra_length = read_u16(&data, offset + 30)? as usize;
+            let comment_length = read_u16(&data, offset + 32)? as usize;
+            let name = data
+                .get(offset + 46..offset + 46 + name_length)
+                .ok_or("Unexpected end of the ZIP archive.")?;
+
+            entries.push(Entry {
+                name: String::from_utf8_lossy(name).into_owned(),
+                method: read_u16(&data, offset + 10)?,
+                compressed_size: read_u32(&data, offset + 20)? as usize,
+                local_header_offset: read_u32(&data, offset + 42)? as usize,
+            });
+
+            offset += 46 + name_length + extra_length + comment_length;
+        }
+
+        Ok(Archive { data, entries })
+    }
+
+    /// Returns the uncompressed content of the file with the given name in the archive, or `None` if the archive does
+    /// not contain such a file.
+    pub fn read(&self, name: &str) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+        let Some(entry) = self.entries.iter().find(|entry| entry.name == name) else {
+            return Ok(None);
+        };
+
+        let offset = entry.local_header_offset;
+        if read_u32(&self.data, offset)? != LOCAL_HEADER_SIGNATURE {
+            return Err(format!("Invalid local header for '{name}' in the ZIP archive.").into());
+        }
+
+        // the sizes of name and extra field can differ from the ones in the central directory
+        let start = offset
+            + 30
+            + read_u16(&self.data, offset + 26)? as usize
+            + read_u16(&self.data, offset + 28)? as usize;
+        let compressed = self
+            .data
+            .get(start..start + entry.compressed_size)
+            .ok_or("Unexpected end of the ZIP archive.")?;
+
+        match entry.method {
+            0 => Ok(Some(compressed.to_vec())),
+            8 => Ok(Some(inflate(compressed, MAX_UNCOMPRESSED_SIZE)?)),
+            method => {
+                Err(format!("Unsupported compression method {method} in the ZIP archive.").into())
+            }
+        }
+    }
+
+    /// Returns the content of the file with the given name in the archive as string, or `None` if the archive does
+    /// not contain such a file.
+    pub fn read_string(&self, name: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        match self.read(name)? {
+            Some(content) => Ok(Some(String::from_utf8(content)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Reader for the bits of a DEFLATE stream, starting with the least significant bit of each byte.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    bit: u8,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, count: u8) -> Result<u32, String> {
+        let mut value = 0;
+        for index in 0..count {
+            let byte = self
+                .data
+                .get(self.position)
+                .ok_or("Unexpected end of the compressed data.")?;
+            value |= (((byte >> self.bit) & 1) as u32) << index;
+
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.position += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    fn align_to_byte(&mut self) {
+        if self.bit != 0 {
+            self.bit = 0;
+            self.position += 1;
+        }
+    }
+}
+
+/// Canonical Huffman code as defined by the code lengths of its symbols.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+        for length in 1..16 {
+            offsets[length] = offsets[length - 1] + counts[length - 1];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, String> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[length] as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("Invalid Huffman code in the compressed data.".to_string())
+    }
+}
+
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// The order in which the code lengths of the code length alphabet are stored in dynamic blocks.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompresses a raw DEFLATE stream (RFC 1951). The function returns an error in case that the decompressed data
+/// would be larger than the given limit (in bytes).
+fn inflate(data: &[u8], limit: usize) -> Result<Vec<u8>, String> {
+    let mut reader = BitReader {
+        data,
+        position: 0,
+        bit: 0,
+    };
+    let mut output = Vec::new();
+
+    loop {
+        let last_block = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                // stored block, not compressed
+                reader.align_to_byte();
+                let start = reader.position + 4;
+                let length = data
+                    .get(reader.position..start)
+                    .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+                    .ok_or("Unexpected end of the compressed data.")?;
+                if output.len() + length > limit {
+                    return Err(too_large(limit));
+                }
+                output.extend_from_slice(
+                    data.get(start..start + length)
+                        .ok_or("Unexpected end of the compressed data.")?,
+                );
+                reader.position = start + length;
+            }
+            1 => {
+                // block compressed with the fixed Huffman codes
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let literals = Huffman::new(&lengths);
+                let distances = Huffman::new(&[5; 30]);
+                inflate_block(&mut reader, &mut output, limit, &literals, &distances)?;
+            }
+            2 => {
+                // block compressed with dynamic Huffman codes that are defined at the start of the block
+                let literal_count = reader.bits(5)? as usize + 257;
+                let distance_count = reader.bits(5)? as usize + 1;
+                let code_length_count = reader.bits(4)? as usize + 4;
+
+                let mut code_lengths = [0u8; 19];
+                for &index in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+                    code_lengths[index] = reader.bits(3)? as u8;
+                }
+                let code_lengths = Huffman::new(&code_lengths);
+
+                let mut lengths = Vec::with_capacity(literal_count + distance_count);
+                while lengths.len() < literal_count + distance_count {
+                    let (value, repeat) = match code_lengths.decode(&mut reader)? {
+                        symbol @ 0..=15 => (symbol as u8, 1),
+                        16 => {
+                            let previous = *lengths.last().ok_or("Invalid code lengths.")?;
+                            (previous, 3 + reader.bits(2)?)
+                        }
+                        17 => (0, 3 + reader.bits(3)?),
+                        _ => (0, 11 + reader.bits(7)?),
+                    };
+                    lengths.extend(std::iter::repeat_n(value, repeat as usize));
+                }
+
+                let literals = Huffman::new(&lengths[..literal_count]);
+                let distances = Huffman::new(&lengths[literal_count..]);
+                inflate_block(&mut reader, &mut output, limit, &literals, &distances)?;
+            }
+            _ => return Err("Invalid block type in the compressed data.".to_string()),
+        }
+
+        if last_block {
+            return Ok(output);
+        }
+    }
+}
+
+/// Returns the error for decompressed data that is larger than the given limit.
+fn too_large(limit: usize) -> String {
+    format!("The decompressed data is larger than {limit} bytes.")
+}
+
+/// Decompresses the symbols of a Huffman-compressed block until the end of the block, with at most the given size of
+/// the output.
+fn inflate_block(
+    reader: &mut BitReader,
+    output: &mut Vec<u8>,
+    limit: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), String> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        match symbol {
+            0..=255 if output.len() == limit => return Err(too_large(limit)),
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                // a length and distance pair that repeats earlier output
+                let index = symbol - 257;
+                if index >= LENGTH_BASES.len() {
+                    return Err("Invalid length in the compressed data.".to_string());
+                }
+                let length =
+                    LENGTH_BASES[index] as usize + reader.bits(LENGTH_EXTRA_BITS[index])? as usize;
+
+                let index = distances.decode(reader)? as usize;
+                if index >= DISTANCE_BASES.len() {
+                    return Err("Invalid distance in the compressed data.".to_string());
+                }
+                let distance = DISTANCE_BASES[index] as usize
+                    + reader.bits(DISTANCE_EXTRA_BITS[index])? as usize;
+
+                if distance > output.len() {
+                    return Err("Invalid distance in the compressed data.".to_string());
+                }
+                if output.len() + length > limit {
+                    return Err(too_large(limit));
+                }
+                let start = output.len() - distance;
+                for offset in 0..length {
+                    output.push(output[start + offset]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inflate;
+
+    #[test]
+    fn test_inflate_limit() {
+        // a stored block with "abc"
+        let stored = [0x01, 3, 0, 0xfc, 0xff, b'a', b'b', b'c'];
+        assert_eq!(inflate(&stored, 3).unwrap(), b"abc");
+        assert!(inflate(&stored, 2).is_err());
+
+        // 1000 times "a", compressed with the fixed Huffman codes
+        let compressed = [75, 76, 28, 5, 163, 96, 20, 12, 119, 0, 0];
+        assert_eq!(inflate(&compressed, 1000).unwrap(), [b'a'; 1000]);
+        assert_eq!(
+            inflate(&compressed, 999),
+            Err("The decompressed data is larger than 999 bytes.".to_string())
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Submission of invoices to their buyers (`submit`) via the channel configured for the buyer (`submission`): via the
+//! Peppol network (the default) or by email.
+//!
+//! Public-sector buyers in Germany receive their invoices via the invoicing portals of the federal administration (ZRE
+//! and OZG-RE). Both accept invoices via Peppol, addressed to the Leitweg-ID of the buyer (endpoint scheme "0204"), and
+//! via their email gateways, whose address is given as `invoice_email` of the buyer.
+
+use std::path::Path;
+
+use xrechnung::config::Submission;
+use xrechnung::json::Value;
+
+/// Submits the given invoice file to its buyer via the configured channel and records it in the given registry file.
+/// With `dry_run`, the document that would be submitted is only printed.
+pub fn run(
+    config_file: &str,
+    registry: &Path,
+    file: &str,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let invoice = xrechnung::XmlElement::parse(
+        &std::fs::read_to_string(file)
+            .map_err(|e| format!("Could not read the invoice '{file}': {e}"))?,
+    )?;
+    let summary = crate::preview::summary_json(&invoice, Some(file));
+    let buyer = summary
+        .get("buyer")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("The invoice '{file}' has no buyer name."))?;
+    let config = xrechnung::config::load(config_file, buyer).map_err(crate::error::config)?;
+
+    match config.buyer.submission.unwrap_or(Submission::Peppol) {
+        Submission::Peppol => {
+            #[cfg(feature = "peppol")]
+            {
+                crate::peppol::run(config_file, registry, file, dry_run)
+            }
+            #[cfg(not(feature = "peppol"))]
+            {
+                let _ = (registry, dry_run);
+                Err("Submitting invoices via Peppol is not supported (feature 'peppol' is disabled).".into())
+            }
+        }
+        Submission::Email => {
+            #[cfg(feature = "smtp")]
+            {
+                crate::send::run(config_file, registry, file, &[], None, dry_run)
+            }
+            #[cfg(not(feature = "smtp"))]
+            {
+                let _ = (registry, dry_run);
+                Err(
+                    "Sending invoices by email is not supported (feature 'smtp' is disabled)."
+                        .into(),
+                )
+            }
+        }
+    }
+}
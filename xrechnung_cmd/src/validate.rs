@@ -0,0 +1,135 @@
+//! Validation of existing invoice files (`validate`).
+
+use quick_xml::events::Event;
+
+const XMLNS_UBL: &[u8] = b"urn:oasis:names:specification:ubl:schema:xsd:Invoice-2";
+
+/// Checks that the given document is a well-formed XML document with an invoice in UBL syntax according to EN 16931
+/// (e.g., an XRechnung).
+///
+/// The function returns an error describing the first problem that was found in the structure of the document.
+/// Otherwise, the invoice is validated against the business rules of EN 16931 and the violations are returned.
+pub fn check(document: &str) -> Result<Vec<xrechnung::validate::Violation>, String> {
+    let mut reader = quick_xml::NsReader::from_str(document);
+    let mut path: Vec<String> = Vec::new();
+    let mut customization_id = None;
+    let mut root_checked = false;
+
+    loop {
+        let position = reader.buffer_position();
+        let event = reader.read_resolved_event().map_err(|e| {
+            format!("The document is no well-formed XML (after position {position}): {e}")
+        })?;
+        match event {
+            (namespace, Event::Start(element)) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+                if !root_checked {
+                    let is_ubl = matches!(namespace, quick_xml::name::ResolveResult::Bound(ns) if ns.as_ref() == XMLNS_UBL);
+                    if name != "Invoice" || !is_ubl {
+                        return Err(format!(
+                            "The root element '{name}' is no invoice in UBL syntax."
+                        ));
+                    }
+                    root_checked = true;
+                }
+                path.push(name);
+            }
+            (_, Event::Empty(_)) if !root_checked => {
+                return Err("The root element is no invoice in UBL syntax.".to_string());
+            }
+            (_, Event::Text(text)) if path.len() == 2 && path[1] == "CustomizationID" => {
+                let text = text
+                    .unescape()
+                    .map_err(|e| format!("The document is no well-formed XML: {e}"))?;
+                customization_id = Some(text.trim().to_string());
+            }
+            (_, Event::End(_)) => {
+                path.pop();
+            }
+            (_, Event::Eof) if !path.is_empty() => {
+                return Err(format!(
+                    "The document is no well-formed XML: the element '{}' is not closed.",
+                    path.last().unwrap()
+                ));
+            }
+            (_, Event::Eof) => break,
+            _ => (),
+        }
+    }
+
+    if !root_checked {
+        return Err("The document contains no invoice.".to_string());
+    }
+    match customization_id {
+        Some(id) if id.starts_with("urn:cen.eu:en16931:2017") => {
+            let invoice = xrechnung::XmlElement::parse(document)?;
+            Ok(xrechnung::validate::validate(&invoice))
+        }
+        Some(id) => Err(format!(
+            "The specification identifier (CustomizationID) '{id}' does not refer to EN 16931."
+        )),
+        None => Err("The specification identifier (CustomizationID) is missing.".to_string()),
+    }
+}
+
+/// Validates the invoice in the given file and prints the result.
+pub fn run(file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let document = std::fs::read_to_string(file_name).map_err(|e| {
+        crate::error::io(format!(
+            "Could not read the invoice file '{file_name}': {e}"
+        ))
+    })?;
+    let violations = check(&document)
+        .map_err(|message| crate::error::input(format!("{file_name}: {message}")))?;
+    if !violations.is_empty() {
+        for violation in &violations {
+            eprintln!("{file_name}: {violation}");
+        }
+        return Err(crate::error::validation(format!(
+            "Found {} violation(s) of the business rules in the invoice '{file_name}'.",
+            violations.len()
+        )));
+    }
+    println!(
+        "{}",
+        crate::i18n::translate(&format!("The invoice '{file_name}' is valid."))
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_check() {
+        let config =
+            xrechnung::config::load("../xrechnung/examples/config.toml", "Client Company").unwrap();
+        let bill = xrechnung::data::Bill::new(
+            "2025-0001".to_string(),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            None,
+            &config,
+        );
+        let invoice_hours = vec![xrechnung::data::InvoiceHoursElement {
+            name: "Example Service".to_string(),
+            quantity: 7.0,
+            hourly_rate: 110.0,
+            ..Default::default()
+        }];
+        let (root, _) =
+            xrechnung::create(config.supplier, config.buyer, bill, invoice_hours).unwrap();
+        let mut writer = quick_xml::writer::Writer::new(Vec::new());
+        root.write(&mut writer).unwrap();
+        let document = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(super::check(&document), Ok(vec![]));
+
+        let violations =
+            super::check(&document.replace("<cbc:Name>Example Service</cbc:Name>", "")).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, "BR-25");
+
+        assert!(super::check("<ubl:Invoice xmlns:ubl=\"x\"></ubl:Invoice>").is_err());
+        assert!(super::check(&document.replace("urn:cen.eu:en16931:2017", "urn:other")).is_err());
+        assert!(super::check(&document.replace("</ubl:Invoice>", "")).is_err());
+        assert!(super::check("").is_err());
+    }
+}